@@ -9,35 +9,32 @@ use rhdl_fpga::dsp::lerp::fixed::lerp_unsigned;
 // inputs of the `lerp` function need to be put into a single
 // struct.
 #[derive(PartialEq, Digital)]
-pub struct LerpIn<N, M>
+pub struct LerpIn<I, F, M>
 where
-    N: BitWidth,
+    I: BitWidth,
+    F: BitWidth,
     M: BitWidth,
 {
-    pub lower_value: Bits<N>,
-    pub upper_value: Bits<N>,
-    pub factor: Bits<M>,
+    pub lower_value: Fixed<I, F>,
+    pub upper_value: Fixed<I, F>,
+    pub factor: Fixed<U1, M>,
 }
 
 // An wrapper function to call the `lerp_unsigned`
 #[kernel]
-pub fn wrap_lerp<N, M>(_cr: ClockReset, i: LerpIn<N, M>) -> Bits<N>
-where
-    N: BitWidth,
-    M: BitWidth,
-{
-    lerp_unsigned::<N, M>(i.lower_value, i.upper_value, i.factor)
+pub fn wrap_lerp(_cr: ClockReset, i: LerpIn<U4, U4, U4>) -> Fixed<U4, U4> {
+    lerp_unsigned::<U4, U4, U4>(i.lower_value, i.upper_value, i.factor)
 }
 
 fn main() -> Result<(), RHDLError> {
     // The [Func] wrapper gives us a core we can simulate
-    let uut: Func<LerpIn<U8, U4>, Bits<U8>> = Func::try_new::<wrap_lerp<U8, U4>>()?;
+    let uut: Func<LerpIn<U4, U4, U4>, Fixed<U4, U4>> = Func::try_new::<wrap_lerp>()?;
     // Simulate a ramp
-    let ramp = (0..15)
+    let ramp = (0..=16)
         .map(|x| LerpIn {
-            upper_value: bits(255),
-            lower_value: bits(0),
-            factor: bits(x),
+            upper_value: fixed(15.0),
+            lower_value: fixed(0.0),
+            factor: fixed(x as f64 / 16.0),
         })
         .without_reset()
         .clock_pos_edge(100);
@@ -153,6 +153,7 @@ where
     type I = In<W, R>;
     type O = Out<R, N>;
     type Kernel = cross_counter_kernel<W, R, N>;
+    const SYNCHRONIZER: Option<&'static str> = Some("CrossCounter");
 }
 
 #[kernel]
@@ -196,6 +196,7 @@ impl<W: Domain, R: Domain> CircuitIO for Sync1Bit<W, R> {
     type I = In<W, R>;
     type O = Signal<bool, R>;
     type Kernel = NoKernel2<Self::I, (), (Self::O, ())>;
+    const SYNCHRONIZER: Option<&'static str> = Some("Sync1Bit");
 }
 
 #[derive(PartialEq, Debug, Digital)]
@@ -257,7 +258,9 @@ impl<W: Domain, R: Domain> Circuit for Sync1Bit<W, R> {
             q_kind: Kind::Empty,
             children: Default::default(),
             rtl: None,
+            synchronizer: Self::SYNCHRONIZER,
             ntl: rhdl::core::ntl::builder::circuit_black_box(self, name)?,
+            ..Default::default()
         })
     }
 
@@ -382,6 +385,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_cdc_report_looks_inside_wrappers() -> miette::Result<()> {
+        // An array of circuits has no kernel of its own, so the crossings
+        // are found in its elements
+        let uut = [Sync1Bit::<Red, Blue>::default(), Sync1Bit::default()];
+        let report = rhdl::core::circuit::cdc::cdc_report(&uut.descriptor("top")?);
+        assert!(report.unprotected().next().is_none());
+        assert_eq!(report.crossings.len(), 2);
+        Ok(())
+    }
+
     #[test]
     fn test_synchronizer_performance() -> miette::Result<()> {
         let uut = Sync1Bit::<Red, Blue>::default();
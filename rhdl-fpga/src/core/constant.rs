@@ -93,6 +93,7 @@ impl<T: Digital> Synchronous for Constant<T> {
             children: Default::default(),
             rtl: None,
             ntl: constant(&self.value, name)?,
+            ..Default::default()
         })
     }
 }
@@ -159,7 +159,7 @@ impl<T: Digital> Synchronous for DFF<T> {
     }
 
     fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError> {
-        let ntl = rhdl::core::ntl::builder::synchronous_black_box(self, name)?;
+        let ntl = rhdl::core::ntl::builder::register_black_box(self, name, self.reset)?;
         Ok(CircuitDescriptor {
             unique_name: name.to_string(),
             input_kind: Self::I::static_kind(),
@@ -169,6 +169,7 @@ impl<T: Digital> Synchronous for DFF<T> {
             children: Default::default(),
             ntl,
             rtl: None,
+            ..Default::default()
         })
     }
 }
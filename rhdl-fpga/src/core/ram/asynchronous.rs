@@ -253,6 +253,7 @@ impl<T: Digital, W: Domain, R: Domain, N: BitWidth> Circuit for AsyncBRAM<T, W,
             children: Default::default(),
             rtl: None,
             ntl: circuit_black_box(self, name)?,
+            ..Default::default()
         })
     }
 
@@ -397,6 +398,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_ram_as_vhdl() -> miette::Result<()> {
+        let uut = AsyncBRAM::<Bits<U8>, Red, Green, U2>::new(
+            (0..4).map(|ndx| (bits(ndx), bits(15 - ndx))),
+        );
+        let module = uut.hdl("top")?.as_module();
+        let expect = expect_file!["ram.vhd.expect"];
+        expect.assert_eq(&module.as_vhdl()?);
+        Ok(())
+    }
+
     #[test]
     fn test_ram_as_verilog() -> miette::Result<()> {
         let uut = AsyncBRAM::<Bits<U8>, Red, Green, U4>::new(
@@ -205,6 +205,7 @@ impl<T: Digital, N: BitWidth> Synchronous for SyncBRAM<T, N> {
             children: Default::default(),
             rtl: None,
             ntl: synchronous_black_box(self, name)?,
+            ..Default::default()
         })
     }
 
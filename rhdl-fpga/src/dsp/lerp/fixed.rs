@@ -7,55 +7,31 @@
 //! the interpolation factor.  
 //!
 //!# Internal Details
-//! There are various subtleties at play here.  
-//! Given A: Bits<N>, B: Bits<N> and a factor: Bits<M>,
-//!
+//! The values and the factor are fixed point values (see [Fixed]
+//! and [SignedFixed]), so the binary points are tracked in the types.
+//! Given `A, B: Fixed<I, F>` and a `factor: Fixed<U1, M>`,
 //! we want to compute
 //!
-//!> A * (1 - delta) + B * delta = Y
-//!
-//! where
-//!
-//!> 0 <= delta = factor / 2^M < 1
-//!
-//! The `< 1` part is important, since with `M` bits, it is
-//! not possible to represent `2^M`.  The largest value that
-//! `delta` can take is `2^(M-1)/2^M`.  Normally, when
-//! linear interpolation is used, this limitation is not a problem.
-//! However, you should be aware, in case you need the function
-//! to be able to handle the case of `delta = 1`.  
-//!
+//!> A * (1 - factor) + B * factor = Y
 //!
-//! Substituting delta, we get
+//! where `0 <= factor <= 1`.  The factor has an integer bit so that it
+//! can reach `1`, and the output can equal `B`.  To get this into a
+//! single multiplication, we rewrite it as
 //!
-//!> A * ( 1 - factor / 2^M) + B * factor / 2^M = Y
-//!
-//! Multiplying out by 2^M, we get
-//!
-//!> A * 2^M - A * factor + B * factor = Y * 2^M
-//!
-//! To get this into a single multiplication, we need
-//!
-//!> A * 2^M + (B - A) * factor = Y * 2^M
+//!> A + (B - A) * factor = Y
 //!
 //! Even if `B` and `A` are unsigned, the `B - A` term is
-//! signed, so we need to promote the factor to be signed as well
-//!
-//!> A * 2^M + Diff * signed_factor = Y * 2^M
-//!
-//! Here `signed_factor`` will be `M+1`` bits wide, and `Diff` will be `N+1`
-//! bits wide
-//!
-//! The product will thus be `M+N+2` bits wide.  The factor `A * 2^M`
-//! will be `N+M` bits wide,
-//! and is unsigned.  So we need to convert it to a signed value (which adds 1 bit) and
-//! then extend it (signed) by a bit.
-//!
-//! We can (after adding it), right shift by `M` bits to retrieve `Y`, and then
-//! truncate the value to `N` bits, and safely cast as unsigned.  Because the
-//! output is guaranteed to be unsigned and the number of bits cannot increase
-//! (ignoring fractional bits, of course), this operation can be safely
-//! carried out with a truncation operation.
+//! signed, so we promote both values (and the factor) to be signed,
+//! with one more integer bit.  The product then has `I + 3` integer
+//! bits and `F + M` fractional bits.  `A` is extended to the same
+//! type before it is added.
+//!
+//! The sum is then rounded back to `F` fractional bits (with
+//! [Rounding::HalfUp]) using `quantize`, and brought back to `I`
+//! integer bits using `saturate`.  As long as the factor is in range,
+//! the result lies between `A` and `B`, so the saturation never kicks in.
+//! For the unsigned version, the result is not negative, so it can be
+//! safely cast as unsigned first.
 //!
 //!# Example
 //!
@@ -70,62 +46,64 @@
 //!
 #![doc = include_str!("../../../doc/lerp.md")]
 //!
+use std::ops::Add;
+
 use rhdl::prelude::*;
 
 #[kernel]
 /// Linearly interpolate between unsigned values
 ///
-/// Interpolates between values `lower_value` and `upper_value` with a factor
-/// of `factor/2^M` where `M` is the number of bits in `factor`.  Note that
-/// `factor/2^M < 1`, so the output cannot equal `upper_value`.  This core is
-/// just a function since it has no state.  It _does_ require a multiplier.
-pub fn lerp_unsigned<N, M>(lower_value: Bits<N>, upper_value: Bits<N>, factor: Bits<M>) -> Bits<N>
+/// Interpolates between values `lower_value` and `upper_value` with a
+/// `factor` between 0 and 1.  The result is rounded to the nearest value
+/// (with ties rounded up).  This core is just a function since it has no
+/// state.  It _does_ require a multiplier.
+pub fn lerp_unsigned<I, F, M>(
+    lower_value: Fixed<I, F>,
+    upper_value: Fixed<I, F>,
+    factor: Fixed<U1, M>,
+) -> Fixed<I, F>
 where
-    N: BitWidth,
+    I: BitWidth + Add<U1>,
+    F: BitWidth + Add<M>,
     M: BitWidth,
+    op!(I + U1): BitWidth + Add<U2>,
+    op!(I + U1 + U2): BitWidth,
+    op!(F + M): BitWidth,
 {
-    // Convert them to DynBits so we can manipulate them
-    let lower_value = lower_value.dyn_bits(); // Size N
-    let upper_value = upper_value.dyn_bits(); // Size N
-    let factor = factor.dyn_bits(); // Size M
-    let signed_factor = factor.xsgn(); // Size M + 1
-    let diff = upper_value.xsub(lower_value); // Size N + 1
-    let correction = signed_factor.xmul(diff); // Size N + M + 2
-    let lower_value = lower_value.xshl::<M>(); // Size N + M
-    let lower_value = lower_value.xsgn(); // Size N + M + 1
-    let y = lower_value.xadd(correction); // Size N + M + 3
-    let y = y.xshr::<M>(); // Size N + 3
-    let y = y.as_unsigned().resize::<N>(); // Size N
-    y.as_bits()
+    let lower_value = lower_value.xsgn(); // I + 1 integer bits
+    let diff = upper_value.xsgn() - lower_value; // I + 1 integer bits
+    let correction = diff.xmul(factor.xsgn()); // I + 3 integer bits, F + M fractional bits
+    let y = lower_value.xext::<U2>().xshl::<M>() + correction;
+    y.quantize::<F>(Rounding::HalfUp)
+        .as_unsigned()
+        .saturate::<I>()
 }
 
 #[kernel]
 /// Linearly interpolate between signed values
 ///
-/// Interpolates between `lower_value` and `upper_value` with a factor
-/// of `factor/2^M` where `M` is the number of bits in `factor`.  Note that
-/// `factor/2^M < 1`, so the output cannot equal `upper_value`.  This core is
-/// just a function since it has no state.  It _does_ require a multiplier.
-pub fn lerp_signed<N, M>(
-    lower_value: SignedBits<N>,
-    upper_value: SignedBits<N>,
-    factor: Bits<M>,
-) -> SignedBits<N>
+/// Interpolates between `lower_value` and `upper_value` with a
+/// `factor` between 0 and 1.  The result is rounded to the nearest value
+/// (with ties rounded up).  This core is just a function since it has no
+/// state.  It _does_ require a multiplier.
+pub fn lerp_signed<I, F, M>(
+    lower_value: SignedFixed<I, F>,
+    upper_value: SignedFixed<I, F>,
+    factor: Fixed<U1, M>,
+) -> SignedFixed<I, F>
 where
-    N: BitWidth,
+    I: BitWidth + Add<U1>,
+    F: BitWidth + Add<M>,
     M: BitWidth,
+    op!(I + U1): BitWidth + Add<U2>,
+    op!(I + U1 + U2): BitWidth,
+    op!(F + M): BitWidth,
 {
-    let lower_value = lower_value.dyn_bits(); // Size N
-    let upper_value = upper_value.dyn_bits(); // Size N
-    let factor = factor.dyn_bits(); // Size M
-    let signed_factor = factor.xsgn(); // Size M + 1
-    let diff = upper_value.xsub(lower_value); // Size N + 1
-    let correction = signed_factor.xmul(diff); // Size N + M + 2
-    let lower_value = lower_value.xshl::<M>(); // Size N + M
-    let y = lower_value.xadd(correction); // Size N + M + 3
-    let y = y.xshr::<M>(); // Size N + 3
-    let y = y.resize::<N>(); // Size N
-    y.as_signed_bits()
+    let lower_value = lower_value.xext::<U1>(); // I + 1 integer bits
+    let diff = upper_value.xext::<U1>() - lower_value; // I + 1 integer bits
+    let correction = diff.xmul(factor.xsgn()); // I + 3 integer bits, F + M fractional bits
+    let y = lower_value.xext::<U2>().xshl::<M>() + correction;
+    y.quantize::<F>(Rounding::HalfUp).saturate::<I>()
 }
 
 #[cfg(test)]
@@ -134,23 +112,27 @@ mod tests {
 
     use super::*;
 
+    // The raw values are scaled by 2^F, and the factor by 2^shift, so
+    // we round the scaled result back with a (floor) shift
     fn lerp_i32(a: i32, b: i32, f: i32, shift: u8) -> i32 {
-        ((a << shift) + (b - a) * f) >> shift
+        ((a << shift) + (b - a) * f + (1 << (shift - 1))) >> shift
+    }
+
+    fn factors() -> impl Iterator<Item = Fixed<U1, U5>> + Clone {
+        (0..=32).map(|f| Fixed::from_bits(b6(f)))
     }
 
     #[test]
     fn test_lerp_signed_exhaustive() {
-        for a in -8..7 {
-            for b in -8..7 {
-                for factor in 0..32 {
-                    let x = s4(a);
-                    let y = s4(b);
-                    let f = b5(factor);
+        for a in -8..8 {
+            for b in -8..8 {
+                for factor in factors() {
+                    let x = SignedFixed::<U2, U2>::from_signed_bits(s4(a));
+                    let y = SignedFixed::<U2, U2>::from_signed_bits(s4(b));
                     // Compute the "right answer", but use integer arithmetic, not floating point.
-                    let expected = lerp_i32(a as i32, b as i32, factor as i32, 5) as u128;
-                    let expected = expected as i128;
+                    let expected = lerp_i32(a as i32, b as i32, factor.raw() as i32, 5) as i128;
                     assert_eq!(
-                        lerp_signed(x, y, f).raw(),
+                        lerp_signed(x, y, factor).raw(),
                         expected,
                         "{a} {b} {factor}"
                     );
@@ -163,15 +145,13 @@ mod tests {
     fn test_lerp_exhaustive() {
         for a in 0..16 {
             for b in 0..16 {
-                for factor in 0..32 {
-                    let x = b4(a);
-                    let y = b4(b);
-                    let f = b5(factor);
+                for factor in factors() {
+                    let x = Fixed::<U2, U2>::from_bits(b4(a));
+                    let y = Fixed::<U2, U2>::from_bits(b4(b));
                     // Compute the "right answer", but use integer arithmetic, not floating point.
-                    let expected = lerp_i32(a as i32, b as i32, factor as i32, 5) as u128;
-                    let expected = expected as u128;
+                    let expected = lerp_i32(a as i32, b as i32, factor.raw() as i32, 5) as u128;
                     assert_eq!(
-                        lerp_unsigned(x, y, f).raw(),
+                        lerp_unsigned(x, y, factor).raw(),
                         expected,
                         "{a} {b} {factor}"
                     );
@@ -179,14 +159,27 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_lerp_reaches_upper_value() {
+        let x: Fixed<U4, U4> = fixed(1.25);
+        let y: Fixed<U4, U4> = fixed(14.5);
+        assert_eq!(lerp_unsigned(x, y, fixed::<U1, U4>(1.0)), y);
+        assert_eq!(lerp_unsigned(x, y, fixed::<U1, U4>(0.5)).to_f64(), 7.875);
+        let x: SignedFixed<U4, U4> = signed_fixed(-7.5);
+        let y: SignedFixed<U4, U4> = signed_fixed(6.25);
+        assert_eq!(lerp_signed(x, y, fixed::<U1, U4>(1.0)), y);
+        assert_eq!(lerp_signed(y, x, fixed::<U1, U4>(0.75)).to_f64(), -4.0625);
+    }
+
     #[test]
     fn test_lerp_kernel() -> miette::Result<()> {
         let vals = (0..16)
-            .map(b4)
-            .flat_map(|x| (0..16).map(move |y| (x, b4(y))))
-            .flat_map(|(x, y)| (0..32).map(move |f| (x, y, b5(f))))
+            .map(|x| Fixed::<U2, U2>::from_bits(b4(x)))
+            .flat_map(|x| (0..16).map(move |y| (x, Fixed::from_bits(b4(y)))))
+            .flat_map(|(x, y)| factors().map(move |f| (x, y, f)))
             .collect::<Vec<_>>();
-        test_kernel_vm_and_verilog_synchronous::<lerp_unsigned<U4, U5>, _, _, _>(
+        test_kernel_vm_and_verilog_synchronous::<lerp_unsigned<U2, U2, U5>, _, _, _>(
             lerp_unsigned,
             vals.into_iter(),
         )?;
@@ -195,12 +188,12 @@ mod tests {
 
     #[test]
     fn test_signed_lerp_kernel() -> miette::Result<()> {
-        let vals = (-8..7)
-            .map(s4)
-            .flat_map(|x| (-8..7).map(move |y| (x, s4(y))))
-            .flat_map(|(x, y)| (0..32).map(move |f| (x, y, b5(f))))
+        let vals = (-8..8)
+            .map(|x| SignedFixed::<U2, U2>::from_signed_bits(s4(x)))
+            .flat_map(|x| (-8..8).map(move |y| (x, SignedFixed::from_signed_bits(s4(y)))))
+            .flat_map(|(x, y)| factors().map(move |f| (x, y, f)))
             .collect::<Vec<_>>();
-        test_kernel_vm_and_verilog_synchronous::<lerp_signed<U4, U5>, _, _, _>(
+        test_kernel_vm_and_verilog_synchronous::<lerp_signed<U2, U2, U5>, _, _, _>(
             lerp_signed,
             vals.into_iter(),
         )?;
@@ -114,6 +114,7 @@ where
     type I = In<T, W, R>;
     type O = Out<T, W, R>;
     type Kernel = async_fifo_kernel<T, W, R, N>;
+    const SYNCHRONIZER: Option<&'static str> = Some("AsyncFIFO");
 }
 
 #[kernel]
@@ -190,6 +191,30 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_async_fifo_cdc_report() -> miette::Result<()> {
+        let uut = AsyncFIFO::<Bits<U8>, Red, Blue, 3>::default();
+        let descriptor = uut.descriptor("top")?;
+        let report = cdc::cdc_report(&descriptor);
+        let expect = expect![[r#"
+            circuit  inputs    outputs   synchronizer
+            top      Blue,Red  Blue,Red  AsyncFIFO
+        "#]];
+        expect.assert_eq(&report.to_string());
+        // Without recognizing the FIFO, the report shows the crossings inside it
+        let mut descriptor = descriptor.clone();
+        descriptor.synchronizer = None;
+        let report = cdc::cdc_report(&descriptor);
+        let expect = expect![[r#"
+            circuit                         inputs    outputs  synchronizer
+            top.ram.inner                   Blue,Red  Blue     UNPROTECTED
+            top.read_count_for_write_logic  Blue,Red  Red      CrossCounter
+            top.write_count_for_read_logic  Blue,Red  Blue     CrossCounter
+        "#]];
+        expect.assert_eq(&report.to_string());
+        Ok(())
+    }
+
     #[test]
     fn basic_write_test() -> miette::Result<()> {
         let write = (0..16)
@@ -122,6 +122,7 @@ impl<W: Domain, R: Domain> CircuitIO for ResetConditioner<W, R> {
     type I = In<W, R>;
     type O = Signal<Reset, R>;
     type Kernel = NoKernel2<Self::I, (), (Self::O, ())>;
+    const SYNCHRONIZER: Option<&'static str> = Some("ResetConditioner");
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -192,7 +193,9 @@ impl<W: Domain, R: Domain> Circuit for ResetConditioner<W, R> {
             q_kind: Kind::Empty,
             children: Default::default(),
             rtl: None,
+            synchronizer: Self::SYNCHRONIZER,
             ntl: circuit_black_box(self, name)?,
+            ..Default::default()
         })
     }
 
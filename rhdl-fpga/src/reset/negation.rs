@@ -96,6 +96,7 @@ impl<C: Domain> Circuit for ResetNegation<C> {
             children: Default::default(),
             rtl: None,
             ntl: rhdl::core::ntl::builder::circuit_black_box(self, name)?,
+            ..Default::default()
         })
     }
 
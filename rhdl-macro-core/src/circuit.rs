@@ -13,14 +13,18 @@ fn define_descriptor_fn(field_set: &FieldSet) -> TokenStream {
     let component_name = &field_set.component_name;
     quote! {
         fn descriptor(&self, name: &str) -> Result<rhdl::core::CircuitDescriptor, rhdl::core::RHDLError> {
+            self.descriptor_with(name, &rhdl::core::compiler::NtlPipeline::default())
+        }
+        fn descriptor_with(&self, name: &str, pipeline: &rhdl::core::compiler::NtlPipeline) -> Result<rhdl::core::CircuitDescriptor, rhdl::core::RHDLError> {
             use std::collections::BTreeMap;
             let mut children : BTreeMap<String, rhdl::core::CircuitDescriptor> = BTreeMap::new();
             #(children.insert(stringify!(#component_name).to_string(),
-                self.#component_name.descriptor(
-                    &format!("{name}_{}", stringify!(#component_name))
+                self.#component_name.descriptor_with(
+                    &format!("{name}_{}", stringify!(#component_name)),
+                    pipeline
                 )?
             );)*
-            rhdl::core::build_descriptor::<Self>(name, children)
+            rhdl::core::build_descriptor_with::<Self>(name, children, pipeline)
         }
     }
 }
@@ -149,6 +153,13 @@ mod test {
                 fn descriptor(
                     &self,
                     name: &str,
+                ) -> Result<rhdl::core::CircuitDescriptor, rhdl::core::RHDLError> {
+                    self.descriptor_with(name, &rhdl::core::compiler::NtlPipeline::default())
+                }
+                fn descriptor_with(
+                    &self,
+                    name: &str,
+                    pipeline: &rhdl::core::compiler::NtlPipeline,
                 ) -> Result<rhdl::core::CircuitDescriptor, rhdl::core::RHDLError> {
                     use std::collections::BTreeMap;
                     let mut children: BTreeMap<String, rhdl::core::CircuitDescriptor> =
@@ -156,14 +167,14 @@ mod test {
                     children.insert(
                         stringify!(strobe).to_string(),
                         self.strobe
-                            .descriptor(&format!("{name}_{}", stringify!(strobe)))?,
+                            .descriptor_with(&format!("{name}_{}", stringify!(strobe)), pipeline)?,
                     );
                     children.insert(
                         stringify!(value).to_string(),
                         self.value
-                            .descriptor(&format!("{name}_{}", stringify!(value)))?,
+                            .descriptor_with(&format!("{name}_{}", stringify!(value)), pipeline)?,
                     );
-                    rhdl::core::build_descriptor::<Self>(name, children)
+                    rhdl::core::build_descriptor_with::<Self>(name, children, pipeline)
                 }
                 fn hdl(
                     &self,
@@ -245,35 +256,42 @@ mod test {
             fn descriptor(
                 &self,
                 name: &str,
+            ) -> Result<rhdl::core::CircuitDescriptor, rhdl::core::RHDLError> {
+                self.descriptor_with(name, &rhdl::core::compiler::NtlPipeline::default())
+            }
+            fn descriptor_with(
+                &self,
+                name: &str,
+                pipeline: &rhdl::core::compiler::NtlPipeline,
             ) -> Result<rhdl::core::CircuitDescriptor, rhdl::core::RHDLError> {
                 use std::collections::BTreeMap;
                 let mut children: BTreeMap<String, rhdl::core::CircuitDescriptor> = BTreeMap::new();
                 children.insert(
                     stringify!(strobe).to_string(),
                     self.strobe
-                        .descriptor(&format!("{name}_{}", stringify!(strobe)))?,
+                        .descriptor_with(&format!("{name}_{}", stringify!(strobe)), pipeline)?,
                 );
                 children.insert(
                     stringify!(value).to_string(),
                     self.value
-                        .descriptor(&format!("{name}_{}", stringify!(value)))?,
+                        .descriptor_with(&format!("{name}_{}", stringify!(value)), pipeline)?,
                 );
                 children.insert(
                     stringify!(buf_z).to_string(),
                     self.buf_z
-                        .descriptor(&format!("{name}_{}", stringify!(buf_z)))?,
+                        .descriptor_with(&format!("{name}_{}", stringify!(buf_z)), pipeline)?,
                 );
                 children.insert(
                     stringify!(side).to_string(),
                     self.side
-                        .descriptor(&format!("{name}_{}", stringify!(side)))?,
+                        .descriptor_with(&format!("{name}_{}", stringify!(side)), pipeline)?,
                 );
                 children.insert(
                     stringify!(latch).to_string(),
                     self.latch
-                        .descriptor(&format!("{name}_{}", stringify!(latch)))?,
+                        .descriptor_with(&format!("{name}_{}", stringify!(latch)), pipeline)?,
                 );
-                rhdl::core::build_descriptor::<Self>(name, children)
+                rhdl::core::build_descriptor_with::<Self>(name, children, pipeline)
             }
             fn hdl(&self, name: &str) -> Result<rhdl::core::HDLDescriptor, rhdl::core::RHDLError> {
                 use std::collections::BTreeMap;
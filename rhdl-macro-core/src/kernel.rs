@@ -3,8 +3,8 @@ use std::collections::HashSet;
 use inflections::Inflect;
 use quote::{format_ident, quote};
 use syn::{
-    parse::Parser, punctuated::Punctuated, spanned::Spanned, token::Comma, FnArg, Ident, Pat,
-    PatType, Path, ReturnType, Token,
+    parse::Parser, parse_quote, punctuated::Punctuated, spanned::Spanned, token::Comma,
+    visit_mut::VisitMut, FnArg, Ident, Pat, PatType, Path, ReturnType, Token,
 };
 
 // use crate::suffix::CustomSuffix;
@@ -39,6 +39,8 @@ struct Scope {
 pub struct Context {
     scopes: Vec<Scope>,
     active_scope: ScopeId,
+    // The type of `self` when building the kernel for a method.
+    self_ty: Option<syn::Type>,
 }
 
 impl Default for Context {
@@ -46,10 +48,142 @@ impl Default for Context {
         Context {
             scopes: vec![Default::default()],
             active_scope: Default::default(),
+            self_ty: None,
         }
     }
 }
 
+const KNOWN_METHODS: &[&str] = &[
+    "any",
+    "all",
+    "xor",
+    "as_signed",
+    "as_unsigned",
+    "val",
+    "resize",
+    "wide",
+    "narrow",
+    "raw",
+    "xadd",
+    "xsub",
+    "xmul",
+    "saturating_add",
+    "saturating_sub",
+    "saturating_mul",
+    "overflowing_add",
+    "overflowing_sub",
+    "overflowing_mul",
+    "count_ones",
+    "leading_zeros",
+    "trailing_zeros",
+    "reverse_bits",
+    "swap_bytes",
+    "rotate_left",
+    "rotate_right",
+    "xneg",
+    "xext",
+    "xshl",
+    "xshr",
+    "xsgn",
+    "dyn_bits",
+    "as_bits",
+    "as_signed_bits",
+];
+
+// The fixed point methods need the binary point of the receiver, which
+// (like the type of a user method receiver) is found with a probe.
+const FIXED_POINT_METHODS: &[&str] = &["quantize", "saturate"];
+
+const METHOD_PROBE_ATTR: &str = "rhdl_method_probe";
+
+// Calls to methods that are not built in are resolved to the kernels of
+// user defined methods.  The macro cannot see the type of the receiver,
+// so each such call is tagged with the index of a probe.  A copy of the
+// function body (that is never run) binds each receiver to its probe, and
+// the Rust type checker then tells us which type the method belongs to.
+#[derive(Default)]
+struct MethodCallTagger {
+    count: usize,
+}
+
+impl VisitMut for MethodCallTagger {
+    fn visit_expr_method_call_mut(&mut self, node: &mut syn::ExprMethodCall) {
+        if !KNOWN_METHODS.contains(&node.method.to_string().as_str()) {
+            let ndx = syn::Index::from(self.count);
+            node.attrs.push(parse_quote!(#[rhdl_method_probe = #ndx]));
+            self.count += 1;
+        }
+        syn::visit_mut::visit_expr_method_call_mut(self, node);
+    }
+}
+
+fn method_probe(attrs: &[syn::Attribute]) -> Option<Ident> {
+    attrs.iter().find_map(|attr| {
+        let syn::Meta::NameValue(meta) = &attr.meta else {
+            return None;
+        };
+        if !meta.path.is_ident(METHOD_PROBE_ATTR) {
+            return None;
+        }
+        let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(ndx),
+            ..
+        }) = &meta.value
+        else {
+            return None;
+        };
+        Some(format_ident!("__rhdl_probe_{}", ndx.base10_digits()))
+    })
+}
+
+// Rewrites a tagged function body into the copy that binds the
+// receivers of method calls to their probes.  Since `self` cannot
+// be the name of a closure argument, it is renamed.
+struct MethodProbeBinder;
+
+impl VisitMut for MethodProbeBinder {
+    fn visit_expr_mut(&mut self, node: &mut syn::Expr) {
+        syn::visit_mut::visit_expr_mut(self, node);
+        if let syn::Expr::Path(path) = node
+            && path.path.is_ident("self")
+        {
+            *node = parse_quote!(__rhdl_self);
+        } else if let syn::Expr::MethodCall(call) = node
+            && let Some(probe) = method_probe(&call.attrs)
+        {
+            call.attrs.clear();
+            let receiver = &call.receiver;
+            *node = parse_quote!({
+                #probe.bind(&(#receiver));
+                #call
+            });
+        }
+    }
+}
+
+// Replaces `Self` in a method with the type of the impl block, since
+// the kernel for the method is built outside of the impl block.
+struct ReplaceSelfType<'a>(&'a syn::TypePath);
+
+impl VisitMut for ReplaceSelfType<'_> {
+    fn visit_receiver_mut(&mut self, _node: &mut syn::Receiver) {}
+    fn visit_path_mut(&mut self, node: &mut Path) {
+        if node.leading_colon.is_none() && node.segments.first().is_some_and(|x| x.ident == "Self")
+        {
+            let mut segments = self.0.path.segments.clone();
+            for segment in segments.iter_mut() {
+                if let syn::PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                    args.colon2_token = Some(Default::default());
+                }
+            }
+            segments.extend(node.segments.iter().skip(1).cloned());
+            node.segments = segments;
+            node.leading_colon = self.0.path.leading_colon;
+        }
+        syn::visit_mut::visit_path_mut(self, node);
+    }
+}
+
 fn ident_starts_with_capital_letter(i: &syn::Ident) -> bool {
     i.to_string()
         .chars()
@@ -72,6 +206,20 @@ fn pattern_has_bindings(pat: &syn::Pat) -> bool {
     }
 }
 
+// Patterns that always match the value, and (possibly) destructure it,
+// like `x`, `(a, b)` or `[first, .., last]`.
+fn pattern_is_irrefutable(pat: &syn::Pat) -> bool {
+    match pat {
+        Pat::Ident(x) => x.subpat.is_none() && x.ident.to_string().is_snake_case(),
+        Pat::Wild(_) | Pat::Rest(_) => true,
+        Pat::Paren(pat) => pattern_is_irrefutable(&pat.pat),
+        Pat::Type(ty) => pattern_is_irrefutable(&ty.pat),
+        Pat::Tuple(tuple) => tuple.elems.iter().all(pattern_is_irrefutable),
+        Pat::Slice(slice) => slice.elems.iter().all(pattern_is_irrefutable),
+        _ => false,
+    }
+}
+
 //
 // This is a kludge.  I do not know of any way to determine if
 // an expression like j = Foo::Bar(3) is a function named Bar
@@ -99,6 +247,32 @@ fn path_is_enum_tuple_struct_by_convention(path: &Path) -> bool {
         && ident_starts_with_capital_letter(&second_to_last.ident)
 }
 
+// A similar kludge is used for associated functions.  If the path
+// is of the form Foo::bar or Foo::<T>::bar, with Foo capitalized and
+// bar not, then we assume that it is a call to the associated function
+// bar of the type Foo (which includes generic types like T::bar).
+// Qualified paths like <T as Trait>::bar are always associated functions.
+fn path_is_associated_fn_by_convention(path: &syn::ExprPath) -> bool {
+    if path.qself.is_some() {
+        return true;
+    }
+    let segments = &path.path.segments;
+    if segments.len() < 2 {
+        return false;
+    }
+    let last = &segments[segments.len() - 1];
+    let second_to_last = &segments[segments.len() - 2];
+    !ident_starts_with_capital_letter(&last.ident)
+        && (ident_starts_with_capital_letter(&second_to_last.ident)
+            || !second_to_last.arguments.is_empty())
+}
+
+// The name of the hidden method that returns the kernel for a method
+// of a `#[kernel]` impl block or trait.
+fn kernel_lookup_name(method: &Ident) -> Ident {
+    format_ident!("__rhdl_kernel_{}", method)
+}
+
 fn split_path_into_base_and_variant(path: &Path) -> Result<(Path, Ident)> {
     let base = path
         .segments
@@ -298,7 +472,7 @@ impl Context {
                 }
             }
             Pat::Type(pat) => self.add_scoped_binding(&pat.pat)?,
-            Pat::Wild(_) | Pat::Path(_) | Pat::Const(_) | Pat::Lit(_) => {}
+            Pat::Wild(_) | Pat::Rest(_) | Pat::Path(_) | Pat::Const(_) | Pat::Lit(_) => {}
             _ => {
                 return Err(syn::Error::new(
                     pat.span(),
@@ -316,9 +490,243 @@ impl Context {
 pub fn hdl_kernel(attrs: TS, input: TS) -> Result<TS> {
     let parser = Punctuated::<Ident, Token![,]>::parse_terminated;
     let attrs = parser.parse(attrs.into())?;
-    let input = syn::parse::<syn::ItemFn>(input.into())?;
-    let mut context = Context::default();
-    context.function(&attrs, input)
+    match syn::parse::<syn::Item>(input.into())? {
+        syn::Item::Fn(function) => Context::default().function(&attrs, function),
+        syn::Item::Impl(item_impl) => kernel_impl(&attrs, item_impl),
+        syn::Item::Trait(item_trait) => kernel_trait(item_trait),
+        item => Err(syn::Error::new(
+            item.span(),
+            "The kernel attribute can only be applied to functions, impl blocks and traits",
+        )),
+    }
+}
+
+// The lints that can be named in `#[rhdl(allow(..))]` and `#[rhdl(deny(..))]`,
+// along with the variant of `rhdl::core::ast::Lint` they map to.
+const LINTS: &[(&str, &str)] = &[
+    ("unwritten_d", "UnwrittenD"),
+    ("unread_q", "UnreadQ"),
+    ("lossy_resize", "LossyResize"),
+    ("unreachable_arm", "UnreachableArm"),
+    ("constant_output", "ConstantOutput"),
+];
+
+// The flags for a kernel come from the arguments to the `#[kernel]` attribute,
+// and from any `#[rhdl(..)]` attributes on the function itself, e.g.
+// `#[rhdl(allow_weak_partial, allow(lossy_resize), deny(unread_q))]`.
+fn kernel_flags(attrs: &Punctuated<Ident, Token![,]>, fn_attrs: &[syn::Attribute]) -> Result<TS> {
+    let mut flags = vec![];
+    if attrs.iter().any(|x| x == "allow_weak_partial") {
+        flags.push(quote!(rhdl::core::ast::KernelFlags::AllowWeakPartial));
+    }
+    for attr in fn_attrs.iter().filter(|attr| attr.path().is_ident("rhdl")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("allow_weak_partial") {
+                flags.push(quote!(rhdl::core::ast::KernelFlags::AllowWeakPartial));
+                return Ok(());
+            }
+            let level = if meta.path.is_ident("allow") {
+                format_ident!("Allow")
+            } else if meta.path.is_ident("deny") {
+                format_ident!("Deny")
+            } else {
+                return Err(meta.error("Unknown rhdl kernel attribute"));
+            };
+            meta.parse_nested_meta(|lint| {
+                let Some((_, variant)) = LINTS.iter().find(|(name, _)| lint.path.is_ident(name))
+                else {
+                    return Err(lint.error(format!(
+                        "Unknown rhdl lint (expected one of {})",
+                        LINTS
+                            .iter()
+                            .map(|(name, _)| *name)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )));
+                };
+                let variant = format_ident!("{}", variant);
+                flags.push(quote!(
+                    rhdl::core::ast::KernelFlags::#level(rhdl::core::ast::Lint::#variant)
+                ));
+                Ok(())
+            })
+        })?;
+    }
+    Ok(quote!(vec![#(#flags),*]))
+}
+
+// The `#[rhdl(..)]` attributes are consumed by the `#[kernel]` attribute,
+// and are not valid on the function that is emitted.
+fn strip_rhdl_attrs(attrs: &mut Vec<syn::Attribute>) {
+    attrs.retain(|attr| !attr.path().is_ident("rhdl"));
+}
+
+fn phantom_fields(generics: &syn::Generics) -> Vec<TS> {
+    generics
+        .params
+        .iter()
+        .enumerate()
+        .filter_map(|(ndx, param)| {
+            let ident = format_ident!("__phantom_{}", ndx);
+            if let syn::GenericParam::Type(ty) = param {
+                let ty_name = &ty.ident;
+                Some(quote! {#ident: std::marker::PhantomData<#ty_name>})
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// A `#[kernel]` attribute on an impl block turns each of the methods into
+// a kernel.  Each kernel gets a hidden type that implements `DigitalFn`, and
+// a hidden method (named by `kernel_lookup_name`) that returns the kernel.
+// Method calls (and calls to associated functions) in kernels are resolved
+// by the Rust type checker to the hidden method of the right type.
+fn kernel_impl(attrs: &Punctuated<Ident, Token![,]>, mut item_impl: syn::ItemImpl) -> Result<TS> {
+    let trait_path = match &item_impl.trait_ {
+        Some((Some(bang), _, _)) => {
+            return Err(syn::Error::new(
+                bang.span(),
+                "Unsupported negative impl in rhdl kernel",
+            ));
+        }
+        Some((None, path, _)) => Some(path.clone()),
+        None => None,
+    };
+    let syn::Type::Path(self_ty) = item_impl.self_ty.as_ref().clone() else {
+        return Err(syn::Error::new(
+            item_impl.self_ty.span(),
+            "Unsupported self type in rhdl kernel impl block",
+        ));
+    };
+    let Some(type_name) = self_ty.path.segments.last().map(|x| x.ident.clone()) else {
+        return Err(syn::Error::new(
+            self_ty.span(),
+            "Empty path in rhdl kernel impl block",
+        ));
+    };
+    // Different impl blocks can share the name of the type (e.g. `Bits<U8>` and
+    // `Bits<U16>`), so the names of the hidden types include a hash of the impl.
+    // An FNV-1a hash is used so that the names are stable across compilers.
+    let impl_hash = quote!(#self_ty #trait_path)
+        .to_string()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+    let generics = item_impl.generics.clone();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let phantom_fields = phantom_fields(&generics);
+    let mut kernels = vec![];
+    let mut lookups: Vec<syn::ImplItemFn> = vec![];
+    for item in &item_impl.items {
+        let syn::ImplItem::Fn(method) = item else {
+            continue;
+        };
+        if !method.sig.generics.params.is_empty() {
+            return Err(syn::Error::new(
+                method.sig.generics.span(),
+                "Unsupported generic method in rhdl kernel impl block",
+            ));
+        }
+        let flags = kernel_flags(attrs, &method.attrs)?;
+        let mut function = syn::ItemFn {
+            attrs: method.attrs.clone(),
+            vis: syn::Visibility::Inherited,
+            sig: method.sig.clone(),
+            block: Box::new(method.block.clone()),
+        };
+        strip_rhdl_attrs(&mut function.attrs);
+        ReplaceSelfType(&self_ty).visit_item_fn_mut(&mut function);
+        let name = &method.sig.ident;
+        let hidden_type = format_ident!("__rhdl_{}_{}_{:x}", type_name, name, impl_hash);
+        let mut context = Context {
+            self_ty: Some(syn::Type::Path(self_ty.clone())),
+            ..Default::default()
+        };
+        let kernel_fn = context.kernel_fn(&function, &flags, quote!(#hidden_type #ty_generics))?;
+        kernels.push(quote! {
+            #[allow(non_camel_case_types)]
+            #[allow(dead_code)]
+            #[doc(hidden)]
+            struct #hidden_type #impl_generics {#(#phantom_fields,)*}
+
+            impl #impl_generics rhdl::core::digital_fn::DigitalFn for #hidden_type #ty_generics #where_clause {
+                fn kernel_fn() -> Option<rhdl::core::digital_fn::KernelFnKind> {
+                    #kernel_fn
+                }
+            }
+        });
+        let lookup = kernel_lookup_name(name);
+        let vis = if trait_path.is_some() {
+            syn::Visibility::Inherited
+        } else {
+            method.vis.clone()
+        };
+        lookups.push(parse_quote! {
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            #vis fn #lookup(self) -> Option<rhdl::core::digital_fn::KernelFnKind> {
+                <#hidden_type #ty_generics as rhdl::core::digital_fn::DigitalFn>::kernel_fn()
+            }
+        });
+    }
+    for item in &mut item_impl.items {
+        if let syn::ImplItem::Fn(method) = item {
+            strip_rhdl_attrs(&mut method.attrs);
+        }
+    }
+    if trait_path.is_some() {
+        item_impl
+            .items
+            .extend(lookups.into_iter().map(syn::ImplItem::Fn));
+        Ok(quote! {
+            #item_impl
+
+            #(#kernels)*
+        })
+    } else {
+        Ok(quote! {
+            #item_impl
+
+            impl #impl_generics #self_ty #where_clause {
+                #(#lookups)*
+            }
+
+            #(#kernels)*
+        })
+    }
+}
+
+// A `#[kernel]` attribute on a trait adds the hidden methods that return
+// the kernels for the methods of the trait.  These are provided by the
+// `#[kernel]` impl blocks for the trait.
+fn kernel_trait(mut item_trait: syn::ItemTrait) -> Result<TS> {
+    let mut lookups: Vec<syn::TraitItem> = vec![];
+    for item in &item_trait.items {
+        let syn::TraitItem::Fn(method) = item else {
+            continue;
+        };
+        if let Some(default) = &method.default {
+            return Err(syn::Error::new(
+                default.span(),
+                "Unsupported default method body in rhdl kernel trait",
+            ));
+        }
+        let lookup = kernel_lookup_name(&method.sig.ident);
+        lookups.push(parse_quote! {
+            #[doc(hidden)]
+            fn #lookup(self) -> Option<rhdl::core::digital_fn::KernelFnKind>
+            where
+                Self: Sized,
+            {
+                None
+            }
+        });
+    }
+    item_trait.items.extend(lookups);
+    Ok(quote! {#item_trait})
 }
 
 // Convert a pattern that would appear in a function argument into an expression.
@@ -526,49 +934,61 @@ impl Context {
     fn function(
         &mut self,
         attrs: &Punctuated<Ident, Token![,]>,
-        function: syn::ItemFn,
+        mut function: syn::ItemFn,
     ) -> Result<TS> {
-        let orig_name = &function.sig.ident;
+        let flags = kernel_flags(attrs, &function.attrs)?;
+        strip_rhdl_attrs(&mut function.attrs);
         let vis = &function.vis;
         let (impl_generics, ty_generics, where_clause) = function.sig.generics.split_for_impl();
-        let weak_partial_flag = attrs.iter().any(|x| x == "allow_weak_partial");
-        let flags = if !weak_partial_flag {
-            quote!(vec![])
-        } else {
-            quote!(vec![rhdl::core::ast::KernelFlags::AllowWeakPartial])
-        };
-        let phantom_fields = function
-            .sig
-            .generics
-            .params
-            .iter()
-            .enumerate()
-            .filter_map(|(ndx, param)| {
-                let ident = format_ident!("__phantom_{}", ndx);
-                if let syn::GenericParam::Type(ty) = param {
-                    let ty_name = &ty.ident;
-                    Some(quote! {#ident: std::marker::PhantomData<#ty_name>})
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
+        let phantom_fields = phantom_fields(&function.sig.generics);
         let name = &function.sig.ident;
+        let kernel_fn = self.kernel_fn(&function, &flags, quote!(#name #ty_generics))?;
+        let wrapped_function = trace_wrap_function(&function)?;
+        let digital_fnk_impl = impl_digital_fnk_trait(&function)?;
+        Ok(quote! {
+            #wrapped_function
+
+            #[allow(non_camel_case_types)]
+            #[doc(hidden)]
+            #vis struct #name #impl_generics {#(#phantom_fields,)*}
+
+            #digital_fnk_impl
+
+            impl #impl_generics rhdl::core::digital_fn::DigitalFn for #name #ty_generics #where_clause {
+                fn kernel_fn() -> Option<rhdl::core::digital_fn::KernelFnKind> {
+                    #kernel_fn
+                }
+            }
+        })
+    }
+
+    // Build the body of the `kernel_fn` method of `DigitalFn` for the given function.
+    // The `fn_id` is the type used to identify the kernel.
+    fn kernel_fn(&mut self, function: &syn::ItemFn, flags: &TS, fn_id: TS) -> Result<TS> {
+        let orig_name = &function.sig.ident;
         // Put the function arguments into the current scope
         for arg in function.sig.inputs.iter() {
             match arg {
+                syn::FnArg::Receiver(receiver) if self.accepts_receiver(receiver) => {
+                    self.scopes[self.active_scope.0]
+                        .bindings
+                        .insert(receiver.self_token.into());
+                }
                 syn::FnArg::Receiver(_) => {
                     return Err(syn::Error::new(
                         arg.span(),
                         "Unsupported receiver in rhdl kernel function",
-                    ))
+                    ));
                 }
                 syn::FnArg::Typed(pat) => {
                     self.add_scoped_binding(&pat.pat)?;
                 }
             }
         }
-        let block = self.block_inner(&function.block)?;
+        let mut tagged = function.block.clone();
+        let mut tagger = MethodCallTagger::default();
+        tagger.visit_block_mut(&mut tagged);
+        let block = self.block_inner(&tagged)?;
         let ret = match &function.sig.output {
             syn::ReturnType::Default => quote! {rhdl::core::Kind::Empty},
             syn::ReturnType::Type(_, ty) => {
@@ -582,10 +1002,12 @@ impl Context {
             .inputs
             .iter()
             .map(|arg| match arg {
-                syn::FnArg::Receiver(_) => Err(syn::Error::new(
-                    arg.span(),
-                    "Unsupported receiver in rhdl kernel function",
-                )),
+                syn::FnArg::Receiver(receiver) => {
+                    let mutability = receiver.mutability.is_some();
+                    let self_ty = &self.self_ty;
+                    let kind = quote! {<#self_ty as rhdl::core::Digital>::static_kind()};
+                    Ok(quote! { bob.type_pat(bob.ident_pat("self", #mutability), #kind)})
+                }
                 syn::FnArg::Typed(pat) => {
                     let ty = &pat.ty;
                     let pat = self.pat(&pat.pat)?;
@@ -594,8 +1016,11 @@ impl Context {
                 }
             })
             .collect::<Result<Vec<_>>>()?;
-        let wrapped_function = trace_wrap_function(&function)?;
-        let digital_fnk_impl = impl_digital_fnk_trait(&function)?;
+        let probes = if tagger.count == 0 {
+            quote! {}
+        } else {
+            self.method_probes(function, tagged, tagger.count)
+        };
         let file = syn::File {
             shebang: None,
             attrs: vec![],
@@ -603,30 +1028,51 @@ impl Context {
         };
         let text = prettyplease::unparse(&file).to_string();
         Ok(quote! {
-            #wrapped_function
-
-            #[allow(non_camel_case_types)]
-            #[doc(hidden)]
-            #vis struct #name #impl_generics {#(#phantom_fields,)*}
-
-            #digital_fnk_impl
+            #probes
+            let bob = rhdl::core::ast::builder::ASTBuilder::default();
+            Some(bob.kernel_fn(
+                stringify!(#orig_name),
+                vec!{#(#args),*},
+                #ret,
+                #block,
+                std::any::TypeId::of::<#fn_id>(),
+                #text,
+                concat!(file!(), ":", line!()),
+                #flags
+            ))
+        })
+    }
 
-            impl #impl_generics rhdl::core::digital_fn::DigitalFn for #name #ty_generics #where_clause {
-                fn kernel_fn() -> Option<rhdl::core::digital_fn::KernelFnKind> {
-                    let bob = rhdl::core::ast::builder::ASTBuilder::default();
-                    Some(bob.kernel_fn(
-                        stringify!(#orig_name),
-                        vec!{#(#args),*},
-                        #ret,
-                        #block,
-                        std::any::TypeId::of::<#name #ty_generics>(),
-                        #text,
-                        concat!(file!(), ":", line!()),
-                        #flags
-                    ))
-                }
+    // Methods can take `self`, `mut self` or `&self` (but not `&mut self`),
+    // and only when building the kernel for a method.
+    fn accepts_receiver(&self, receiver: &syn::Receiver) -> bool {
+        self.self_ty.is_some() && (receiver.reference.is_none() || receiver.mutability.is_none())
+    }
+
+    // Declare the probes for the method calls in the (tagged) body of the
+    // function, and bind them in a closure that is never called.
+    fn method_probes(
+        &self,
+        function: &syn::ItemFn,
+        mut tagged: Box<syn::Block>,
+        count: usize,
+    ) -> TS {
+        MethodProbeBinder.visit_block_mut(&mut tagged);
+        let probes = (0..count).map(|ndx| format_ident!("__rhdl_probe_{}", ndx));
+        let self_ty = &self.self_ty;
+        let params = function.sig.inputs.iter().map(|arg| match arg {
+            FnArg::Receiver(receiver) => {
+                let mutability = receiver.mutability;
+                quote!(#mutability __rhdl_self: #self_ty)
             }
-        })
+            FnArg::Typed(pat) => quote!(#pat),
+        });
+        let ret = &function.sig.output;
+        quote! {
+            #(let #probes = rhdl::core::digital_fn::MethodProbe::default();)*
+            #[allow(unused, clippy::all)]
+            let _ = |#(#params),*| #ret #tagged;
+        }
     }
 
     fn block(&mut self, block: &syn::Block) -> Result<TS> {
@@ -711,6 +1157,12 @@ impl Context {
                 })
             }
             syn::Pat::TupleStruct(tuple) => {
+                if let Some(rest) = tuple.elems.iter().find(|x| matches!(x, syn::Pat::Rest(_))) {
+                    return Err(syn::Error::new(
+                        rest.span(),
+                        "Unsupported rest pattern in a tuple struct in rhdl kernel function - only array patterns can use `..`",
+                    ));
+                }
                 let path = self.path_inner(&tuple.path)?;
                 let elems = tuple
                     .elems
@@ -722,6 +1174,12 @@ impl Context {
                 })
             }
             syn::Pat::Tuple(tuple) => {
+                if let Some(rest) = tuple.elems.iter().find(|x| matches!(x, syn::Pat::Rest(_))) {
+                    return Err(syn::Error::new(
+                        rest.span(),
+                        "Unsupported rest pattern in a tuple in rhdl kernel function - only array patterns can use `..`",
+                    ));
+                }
                 let elems = tuple
                     .elems
                     .iter()
@@ -782,6 +1240,9 @@ impl Context {
             syn::Pat::Wild(_) => Ok(quote! {
                 bob.wild_pat()
             }),
+            syn::Pat::Rest(_) => Ok(quote! {
+                bob.rest_pat()
+            }),
             _ => Err(syn::Error::new(pat.span(), "Unsupported pattern type")),
         }
     }
@@ -821,6 +1282,7 @@ impl Context {
             syn::Expr::Index(expr) => self.index(expr),
             syn::Expr::MethodCall(expr) => self.method_call(expr),
             syn::Expr::Cast(expr) => self.cast(expr),
+            syn::Expr::Const(expr) => self.const_block(expr),
             _ => Err(syn::Error::new(
                 expr.span(),
                 format!(
@@ -832,6 +1294,13 @@ impl Context {
     }
 
     fn cast(&mut self, expr: &syn::ExprCast) -> Result<TS> {
+        // A cast of a constant (like `N::BITS as u128`) is itself a constant
+        if self.is_const_expr(&expr.expr) {
+            let value = self.const_value(quote!(#expr));
+            return Ok(quote! {
+                bob.const_expr(bob.path(vec![]), #value, stringify!(#expr))
+            });
+        }
         let ty = &expr.ty;
         let len = quote! {<#ty as rhdl::core::Digital>::BITS};
         let expr = self.expr(&expr.expr)?;
@@ -841,28 +1310,13 @@ impl Context {
     }
 
     fn method_call(&mut self, expr: &syn::ExprMethodCall) -> Result<TS> {
-        const KNOWN_METHODS: &[&str] = &[
-            "any",
-            "all",
-            "xor",
-            "as_signed",
-            "as_unsigned",
-            "val",
-            "resize",
-            "raw",
-            "xadd",
-            "xsub",
-            "xmul",
-            "xneg",
-            "xext",
-            "xshl",
-            "xshr",
-            "xsgn",
-            "dyn_bits",
-            "as_bits",
-            "as_signed_bits",
-        ];
-        const TURBO_METHODS: &[&str] = &["resize", "xext", "xshl", "xshr"];
+        if let Some(probe) = method_probe(&expr.attrs) {
+            if FIXED_POINT_METHODS.contains(&expr.method.to_string().as_str()) {
+                return self.fixed_point_call(expr, probe);
+            }
+            return self.user_method_call(expr, probe);
+        }
+        const TURBO_METHODS: &[&str] = &["resize", "wide", "narrow", "xext", "xshl", "xshr"];
 
         let receiver = self.expr(&expr.receiver)?;
         let args = expr
@@ -887,8 +1341,11 @@ impl Context {
                     format!("Unsupported turbofish in rhdl kernel function - only {TURBO_METHODS:?} are supported")
                 ));
             }
-            let x = x.args.iter().next().unwrap();
-            quote!(Some(<#x as rhdl::bits::BitWidth>::BITS))
+            // The wide types take their width as a const generic
+            match x.args.iter().next().unwrap() {
+                syn::GenericArgument::Const(len) => quote!(Some(#len)),
+                x => quote!(Some(<#x as rhdl::bits::BitWidth>::BITS)),
+            }
         } else {
             quote!(None)
         };
@@ -897,6 +1354,133 @@ impl Context {
         })
     }
 
+    // A call to `quantize` or `saturate` on a fixed point value.  The target
+    // width is given by the turbofish, and the rounding mode of `quantize`
+    // must be a constant path (like `Rounding::HalfEven`), as it is evaluated
+    // when the kernel is built.
+    fn fixed_point_call(&mut self, expr: &syn::ExprMethodCall, probe: Ident) -> Result<TS> {
+        let method = &expr.method;
+        let turbo = match &expr.turbofish {
+            Some(x) if x.args.len() == 1 => {
+                let x = x.args.iter().next().unwrap();
+                quote!(Some(<#x as rhdl::bits::BitWidth>::BITS))
+            }
+            Some(x) => {
+                return Err(syn::Error::new(
+                    x.span(),
+                    format!("Unsupported turbofish in rhdl kernel function - {method} takes a single width"),
+                ));
+            }
+            None => quote!(None),
+        };
+        let receiver = self.expr(&expr.receiver)?;
+        let (args, rounding) = match (method == "quantize", expr.args.first()) {
+            (true, Some(syn::Expr::Path(mode))) if expr.args.len() == 1 => {
+                let path = self.path_inner(&mode.path)?;
+                (quote!(vec![bob.path_expr(#path)]), quote!(#mode))
+            }
+            (false, None) => (quote!(vec![]), quote!(Default::default())),
+            _ => {
+                return Err(syn::Error::new(
+                    expr.span(),
+                    "Unsupported fixed point call in rhdl kernel function - use a.quantize::<F>(Rounding::HalfUp) or a.saturate::<I>()",
+                ));
+            }
+        };
+        Ok(quote! {
+            {
+                let point = #probe.binary_point();
+                bob.fixed_point_expr(#receiver, #args, stringify!(#method), #turbo, point, #rounding)
+            }
+        })
+    }
+
+    // A call to a method of a user type is lowered to a call to the kernel of
+    // the method, with the receiver as the first argument.
+    fn user_method_call(&mut self, expr: &syn::ExprMethodCall, probe: Ident) -> Result<TS> {
+        if let Some(turbo) = &expr.turbofish {
+            return Err(syn::Error::new(
+                turbo.span(),
+                "Unsupported turbofish on a method call in rhdl kernel function",
+            ));
+        }
+        let receiver = self.expr(&expr.receiver)?;
+        let args = expr
+            .args
+            .iter()
+            .map(|x| self.expr(x))
+            .collect::<Result<Vec<_>>>()?;
+        let method = &expr.method;
+        let lookup = kernel_lookup_name(method);
+        Ok(quote! {
+            {
+                let code = #probe.receiver().#lookup();
+                bob.call_expr(
+                    bob.path(vec![bob.path_segment(stringify!(#method), bob.path_arguments_none())]),
+                    vec![#receiver, #(#args),*],
+                    rhdl::core::digital_fn::kernel_signature(&code),
+                    code
+                )
+            }
+        })
+    }
+
+    // A call to an associated function like `T::mul(a, b)` is lowered to a
+    // call to the kernel of the function, which is found through the hidden
+    // lookup method of the type.
+    fn associated_fn_call(
+        &mut self,
+        expr: &syn::ExprCall,
+        func_path: &syn::ExprPath,
+    ) -> Result<TS> {
+        let mut lookup_path = func_path.clone();
+        let Some(last) = lookup_path.path.segments.last_mut() else {
+            return Err(syn::Error::new(
+                func_path.span(),
+                "Empty path in rhdl kernel function",
+            ));
+        };
+        if !last.arguments.is_empty() {
+            return Err(syn::Error::new(
+                last.arguments.span(),
+                "Unsupported generic arguments on an associated function in rhdl kernel function",
+            ));
+        }
+        let method = last.ident.clone();
+        last.ident = kernel_lookup_name(&method);
+        let receiver_ty = match &func_path.qself {
+            Some(qself) => {
+                let ty = &qself.ty;
+                quote!(#ty)
+            }
+            None => {
+                let segments = func_path
+                    .path
+                    .segments
+                    .iter()
+                    .take(func_path.path.segments.len() - 1);
+                let leading_colon = &func_path.path.leading_colon;
+                quote!(#leading_colon #(#segments)::*)
+            }
+        };
+        let args = expr
+            .args
+            .iter()
+            .map(|x| self.expr(x))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(quote! {
+            {
+                let code = #lookup_path(<#receiver_ty as rhdl::core::Digital>::dont_care());
+                bob.call_expr(
+                    bob.path(vec![bob.path_segment(stringify!(#method), bob.path_arguments_none())]),
+                    vec![#(#args),*],
+                    rhdl::core::digital_fn::kernel_signature(&code),
+                    code
+                )
+            }
+        })
+    }
+
     fn index(&mut self, expr: &syn::ExprIndex) -> Result<TS> {
         let index = self.expr(&expr.index)?;
         let expr = self.expr(&expr.expr)?;
@@ -920,6 +1504,8 @@ impl Context {
         let special_cases = [
             ("bits", quote!(bob.expr_bits)),
             ("signed", quote!(bob.expr_signed)),
+            ("wide_bits", quote!(bob.expr_bits)),
+            ("wide_signed", quote!(bob.expr_signed)),
             ("Ok", quote!(bob.expr_ok)),
             ("Err", quote!(bob.expr_err)),
             ("Some", quote!(bob.expr_some)),
@@ -942,8 +1528,8 @@ impl Context {
         if !name.arguments.is_empty() || expr.args.len() != 1 {
             return Ok(None);
         }
-        // Check for the name being equal to `b{num}` where num is a number from 1 to 128.  If that is the
-        // case, then capture the number, and use the expr_bits_with_length function.
+        // Check for the name being equal to `b{num}` where num is a number (like `b8` or `b256`).
+        // If that is the case, then capture the number, and use the expr_bits_with_length function.
         if let Some(num) = name.ident.to_string().strip_prefix("b") {
             if let Ok(num) = num.parse::<usize>() {
                 let args = self.expr(&expr.args[0])?;
@@ -981,6 +1567,13 @@ impl Context {
             ));
         };
 
+        if self.is_const_call(expr) {
+            let value = self.const_value(quote!(#expr));
+            return Ok(quote! {
+                bob.const_expr(bob.path(vec![]), #value, stringify!(#expr))
+            });
+        }
+
         if let Some(special_case) = self.special_case_call(expr)? {
             return Ok(special_case);
         }
@@ -1032,6 +1625,9 @@ impl Context {
                 }
             }
         }
+        if path_is_associated_fn_by_convention(func_path) {
+            return self.associated_fn_call(expr, func_path);
+        }
         let code = if !path_is_enum_tuple_struct_by_convention(&func_path.path) {
             // This is a function call
             self.get_code(&func_path.path)?
@@ -1092,12 +1688,29 @@ impl Context {
         })
     }
 
+    // While loops are unrolled by the compiler, so the trip count must be
+    // known at compile time.  That check happens in the MIR compiler, where
+    // we know the values of the literals involved.
     fn while_loop(&mut self, expr: &syn::ExprWhile) -> Result<TS> {
-        // In version 2.0...
-        Err(syn::Error::new(
-            expr.span(),
-            "Unsupported while loop in rhdl kernel function",
-        ))
+        if let Some(label) = &expr.label {
+            return Err(syn::Error::new(
+                label.span(),
+                "Unsupported loop label in rhdl kernel function",
+            ));
+        }
+        if let syn::Expr::Let(_) = expr.cond.as_ref() {
+            return Err(syn::Error::new(
+                expr.cond.span(),
+                "Unsupported while let loop in rhdl kernel function",
+            ));
+        }
+        let cond = self.expr(&expr.cond)?;
+        self.new_scope();
+        let body = self.block_inner(&expr.body)?;
+        self.end_scope();
+        Ok(quote! {
+            bob.while_expr(#cond, #body)
+        })
     }
 
     fn repeat(&mut self, expr: &syn::ExprRepeat) -> Result<TS> {
@@ -1184,30 +1797,32 @@ impl Context {
         let arms = expr
             .arms
             .iter()
-            .map(|x| self.arm(&x.pat, &x.body))
+            .map(|x| self.arm(x))
             .collect::<Result<Vec<_>>>()?;
         let expr = self.expr(&expr.expr)?;
         Ok(quote! {
             bob.match_expr(#expr, vec![#(#arms),*])
         })
     }
-    fn arm(&mut self, pat: &syn::Pat, body: &syn::Expr) -> Result<TS> {
+    fn arm(&mut self, arm: &syn::Arm) -> Result<TS> {
+        let pat = &arm.pat;
         let option_or_result_discriminant = get_pattern_option_or_result_discriminant(pat);
         self.new_scope();
-        let arm = if !pattern_has_bindings(pat) && option_or_result_discriminant.is_none() {
-            let body = self.expr(body)?;
-            let kind = if let syn::Pat::Wild(_) = &pat {
+        let kind = if !pattern_has_bindings(pat) && option_or_result_discriminant.is_none() {
+            if let syn::Pat::Wild(_) = &pat {
                 quote! {bob.arm_kind_wild()}
             } else if pat_is_none(pat) {
                 quote! {bob.arm_kind_none()}
             } else {
                 let pat = rewrite_pattern_as_typed_bits(pat)?;
                 quote! {bob.arm_kind_constant(#pat)}
-            };
-            quote! {bob.arm(#kind, #body)}
+            }
+        } else if pattern_is_irrefutable(pat) {
+            self.add_scoped_binding(pat)?;
+            let inner = self.pat(pat)?;
+            quote! {bob.arm_kind_binding(#inner)}
         } else {
             self.add_scoped_binding(pat)?;
-            let body = self.expr(body)?;
             let mut discriminant = option_or_result_discriminant.map(|x| quote!(#x.typed_bits()));
             if discriminant.is_none() {
                 let pat_as_expr = rewrite_pattern_to_use_dont_care_for_bindings(pat);
@@ -1216,15 +1831,23 @@ impl Context {
             if let Pat::Tuple(_) = pat {
                 return Err(syn::Error::new(
                     pat.span(),
-                    "Tuple patterns are not currently supported in match arms",
+                    "Tuple patterns that are not irrefutable are not currently supported in match arms",
                 ));
             }
             let inner = self.pat(pat)?;
-            let kind = quote! {bob.arm_kind_enum(#inner, #discriminant)};
-            quote! {bob.arm(#kind, #body)}
+            quote! {bob.arm_kind_enum(#inner, #discriminant)}
         };
+        let guard = arm
+            .guard
+            .as_ref()
+            .map(|(_, guard)| self.expr(guard))
+            .transpose()?;
+        let body = self.expr(&arm.body)?;
         self.end_scope();
-        Ok(arm)
+        Ok(match guard {
+            Some(guard) => quote! {bob.guarded_arm(#kind, #guard, #body)},
+            None => quote! {bob.arm(#kind, #body)},
+        })
     }
 
     fn let_ex(&mut self, expr: &syn::ExprLet) -> Result<TS> {
@@ -1258,6 +1881,12 @@ impl Context {
                 quote! {bob.arm_kind_constant(#pat)}
             };
             (kind, body)
+        } else if pattern_is_irrefutable(pat) {
+            self.add_scoped_binding(pat)?;
+            let body = self.block_inner(then_branch)?;
+            let inner = self.pat(pat)?;
+            let kind = quote! {bob.arm_kind_binding(#inner)};
+            (kind, body)
         } else {
             self.add_scoped_binding(pat)?;
             let body = self.block_inner(then_branch)?;
@@ -1384,8 +2013,9 @@ impl Context {
         // Check for a locally defined path
         let inner = self.path_inner(path)?;
         if !self.is_scoped_binding(path) {
+            let value = self.const_value(quote!(#path));
             return Ok(quote! {
-                bob.expr_typed_bits(#inner, rhdl::core::Digital::typed_bits(#path), stringify!(#path))
+                bob.const_expr(#inner, #value, stringify!(#path))
             });
         }
         Ok(quote! {
@@ -1393,6 +2023,74 @@ impl Context {
         })
     }
 
+    // Constants are evaluated when the kernel is built.  Integer constants
+    // become untyped literals, and everything else must be Digital.
+    fn const_value(&self, value: TS) -> TS {
+        quote! {
+            {
+                use rhdl::core::ast::kernel_const::{DigitalConst, IntegerConst};
+                (&rhdl::core::ast::kernel_const::KernelConst(#value)).const_value()
+            }
+        }
+    }
+
+    fn is_const_expr(&self, expr: &syn::Expr) -> bool {
+        match expr {
+            syn::Expr::Path(path) => {
+                !path.path.is_ident("None") && !self.is_scoped_binding(&path.path)
+            }
+            syn::Expr::Const(_) => true,
+            syn::Expr::Paren(paren) => self.is_const_expr(&paren.expr),
+            syn::Expr::Group(group) => self.is_const_expr(&group.expr),
+            syn::Expr::Cast(cast) => self.is_const_expr(&cast.expr),
+            syn::Expr::Call(call) => self.is_const_call(call),
+            _ => false,
+        }
+    }
+
+    // A call like `log2_ceil(N::BITS)`, where every argument is a constant,
+    // is evaluated when the kernel is built.  The macro cannot tell a
+    // `const fn` from a kernel, but a kernel called with constants gives
+    // the same value either way.  Builtins like `bits` and `signal`, and
+    // constructors and associated functions, are left alone.
+    fn is_const_call(&self, expr: &syn::ExprCall) -> bool {
+        const BUILTINS: &[&str] = &[
+            "bits",
+            "signed",
+            "wide_bits",
+            "wide_signed",
+            "trace",
+            "default",
+            "dont_care",
+            "signal",
+        ];
+        let syn::Expr::Path(func_path) = expr.func.as_ref() else {
+            return false;
+        };
+        let Some(name) = func_path.path.segments.last() else {
+            return false;
+        };
+        let ident = name.ident.to_string();
+        let sized_builtin = ident
+            .strip_prefix(['b', 's'])
+            .is_some_and(|num| num.parse::<usize>().is_ok());
+        !expr.args.is_empty()
+            && !sized_builtin
+            && !BUILTINS.contains(&ident.as_str())
+            && !ident_starts_with_capital_letter(&name.ident)
+            && !path_is_associated_fn_by_convention(func_path)
+            && expr.args.iter().all(|arg| self.is_const_expr(arg))
+    }
+
+    // A `const` block can hold any constant expression (like
+    // `const { N::BITS > 4 }`), and rustc checks that it is constant.
+    fn const_block(&mut self, expr: &syn::ExprConst) -> Result<TS> {
+        let value = self.const_value(quote!(#expr));
+        Ok(quote! {
+            bob.const_expr(bob.path(vec![]), #value, stringify!(#expr))
+        })
+    }
+
     fn path_inner(&mut self, path: &syn::Path) -> Result<TS> {
         let segments = path
             .segments
@@ -1514,6 +2212,8 @@ impl Context {
             syn::BinOp::Add(_) => quote!(rhdl::core::ast::builder::BinOp::Add),
             syn::BinOp::Sub(_) => quote!(rhdl::core::ast::builder::BinOp::Sub),
             syn::BinOp::Mul(_) => quote!(rhdl::core::ast::builder::BinOp::Mul),
+            syn::BinOp::Div(_) => quote!(rhdl::core::ast::builder::BinOp::Div),
+            syn::BinOp::Rem(_) => quote!(rhdl::core::ast::builder::BinOp::Rem),
             syn::BinOp::And(_) => quote!(rhdl::core::ast::builder::BinOp::And),
             syn::BinOp::Or(_) => quote!(rhdl::core::ast::builder::BinOp::Or),
             syn::BinOp::BitXor(_) => quote!(rhdl::core::ast::builder::BinOp::BitXor),
@@ -1530,6 +2230,8 @@ impl Context {
             syn::BinOp::AddAssign(_) => quote!(rhdl::core::ast::builder::BinOp::AddAssign),
             syn::BinOp::SubAssign(_) => quote!(rhdl::core::ast::builder::BinOp::SubAssign),
             syn::BinOp::MulAssign(_) => quote!(rhdl::core::ast::builder::BinOp::MulAssign),
+            syn::BinOp::DivAssign(_) => quote!(rhdl::core::ast::builder::BinOp::DivAssign),
+            syn::BinOp::RemAssign(_) => quote!(rhdl::core::ast::builder::BinOp::RemAssign),
             syn::BinOp::BitXorAssign(_) => quote!(rhdl::core::ast::builder::BinOp::BitXorAssign),
             syn::BinOp::BitAndAssign(_) => quote!(rhdl::core::ast::builder::BinOp::BitAndAssign),
             syn::BinOp::BitOrAssign(_) => quote!(rhdl::core::ast::builder::BinOp::BitOrAssign),
@@ -1603,6 +2305,40 @@ mod test {
         expect.assert_eq(&result);
     }
 
+    #[test]
+    fn test_lint_attributes() {
+        let test_code = quote! {
+            #[rhdl(allow(lossy_resize, unread_q), deny(unreachable_arm))]
+            fn update(a: b8) -> b8 {
+                a
+            }
+        };
+        let function = syn::parse2::<syn::ItemFn>(test_code).unwrap();
+        let item = Context::default()
+            .function(&Punctuated::default(), function)
+            .unwrap()
+            .to_string();
+        assert!(!item.contains("# [rhdl"));
+        for flag in [
+            "Allow (rhdl :: core :: ast :: Lint :: LossyResize)",
+            "Allow (rhdl :: core :: ast :: Lint :: UnreadQ)",
+            "Deny (rhdl :: core :: ast :: Lint :: UnreachableArm)",
+        ] {
+            assert!(item.contains(flag));
+        }
+        let test_code = quote! {
+            #[rhdl(allow(no_such_lint))]
+            fn update(a: b8) -> b8 {
+                a
+            }
+        };
+        let function = syn::parse2::<syn::ItemFn>(test_code).unwrap();
+        let err = Context::default()
+            .function(&Punctuated::default(), function)
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Unknown rhdl lint"));
+    }
+
     #[test]
     fn test_generic_kernel() {
         let test_code = quote! {
@@ -1620,6 +2356,64 @@ mod test {
         expect.assert_eq(&result);
     }
 
+    #[test]
+    fn test_kernel_impl() {
+        let test_code = quote! {
+            impl Counter {
+                fn next(self, step: b4) -> Self {
+                    Self {
+                        count: self.count + step,
+                    }
+                }
+                fn is_zero(&self) -> bool {
+                    self.next(bits(0)).count == 0
+                }
+            }
+        };
+        let item_impl = syn::parse2::<syn::ItemImpl>(test_code).unwrap();
+        let item = kernel_impl(&Punctuated::default(), item_impl).unwrap();
+        let new_code = quote! {#item};
+        let result = prettyplease::unparse(&syn::parse2::<syn::File>(new_code).unwrap());
+        let expect = expect_file!["expect/kernel_impl.expect"];
+        expect.assert_eq(&result);
+    }
+
+    #[test]
+    fn test_kernel_trait() {
+        let test_code = quote! {
+            trait MyArith: Digital {
+                fn mul(a: Self, b: Self) -> Self;
+                fn mac(self, b: Self, c: Self) -> Self;
+            }
+        };
+        let item_trait = syn::parse2::<syn::ItemTrait>(test_code).unwrap();
+        let item = kernel_trait(item_trait).unwrap();
+        let new_code = quote! {#item};
+        let result = prettyplease::unparse(&syn::parse2::<syn::File>(new_code).unwrap());
+        let expect = expect_file!["expect/kernel_trait.expect"];
+        expect.assert_eq(&result);
+    }
+
+    #[test]
+    fn test_kernel_trait_impl() {
+        let test_code = quote! {
+            impl<N: BitWidth> MyArith for Bits<N> {
+                fn mul(a: Self, b: Self) -> Self {
+                    a * b
+                }
+                fn mac(self, b: Self, c: Self) -> Self {
+                    self + Self::mul(b, c)
+                }
+            }
+        };
+        let item_impl = syn::parse2::<syn::ItemImpl>(test_code).unwrap();
+        let item = kernel_impl(&Punctuated::default(), item_impl).unwrap();
+        let new_code = quote! {#item};
+        let result = prettyplease::unparse(&syn::parse2::<syn::File>(new_code).unwrap());
+        let expect = expect_file!["expect/kernel_trait_impl.expect"];
+        expect.assert_eq(&result);
+    }
+
     #[test]
     fn test_basic_block() {
         let test_code = quote! {
@@ -1813,6 +2607,24 @@ mod test {
         expect.assert_eq(&result);
     }
 
+    #[test]
+    fn test_const_values() {
+        let test_code = quote! {
+            fn update<N: BitWidth>(a: Bits<N>) -> Bits<N> {
+                let shift = const { log2_ceil(N::BITS) as u128 };
+                (a >> shift) + (a >> log2_ceil(N::BITS) as u128) + N::BITS as u128 + OFFSET
+            }
+        };
+        let function = syn::parse2::<syn::ItemFn>(test_code).unwrap();
+        let item = Context::default()
+            .function(&Punctuated::default(), function)
+            .unwrap();
+        let new_code = quote! {#item};
+        let result = prettyplease::unparse(&syn::parse2::<syn::File>(new_code).unwrap());
+        let expect = expect_file!["expect/test_const_values.expect"];
+        expect.assert_eq(&result);
+    }
+
     #[test]
     fn test_match_arm_pattern() {
         let test_code = quote! {
@@ -13,14 +13,18 @@ fn define_descriptor_fn(field_set: &FieldSet) -> TokenStream {
     let component_name = &field_set.component_name;
     quote! {
         fn descriptor(&self, name: &str) -> Result<rhdl::core::CircuitDescriptor, rhdl::core::RHDLError> {
+            self.descriptor_with(name, &rhdl::core::compiler::NtlPipeline::default())
+        }
+        fn descriptor_with(&self, name: &str, pipeline: &rhdl::core::compiler::NtlPipeline) -> Result<rhdl::core::CircuitDescriptor, rhdl::core::RHDLError> {
             use std::collections::BTreeMap;
             let mut children: BTreeMap<String, CircuitDescriptor> = BTreeMap::new();
             #(children.insert(stringify!(#component_name).to_string(),
-                self.#component_name.descriptor(
-                    &format!("{name}_{}", stringify!(#component_name))
+                self.#component_name.descriptor_with(
+                    &format!("{name}_{}", stringify!(#component_name)),
+                    pipeline
                 )?
             );)*
-            rhdl::core::build_synchronous_descriptor::<Self>(name, children)
+            rhdl::core::build_synchronous_descriptor_with::<Self>(name, children, pipeline)
         }
     }
 }
@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 
+use internment::Intern;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, Hash)]
@@ -16,6 +17,9 @@ pub enum TraceType {
     Enum(Enum),
     Bits(usize),
     Signed(usize),
+    // Fixed point values, with the number of integer and fraction bits
+    Fixed(usize, usize),
+    SignedFixed(usize, usize),
     Signal(Box<TraceType>, Color),
     Clock,
     Reset,
@@ -88,6 +92,32 @@ pub enum Color {
     Blue,
     Indigo,
     Violet,
+    /// A named domain, holding the full path of the domain.  Use
+    /// [Color::named] to build one.
+    Named(Intern<String>),
+}
+
+impl Color {
+    /// A named domain, given the full path of the type that declares it
+    pub fn named(path: &str) -> Self {
+        Color::Named(Intern::from_ref(path))
+    }
+    /// The short name of the color or domain, for display
+    pub fn name(&self) -> &'static str {
+        match self {
+            Color::Red => "Red",
+            Color::Orange => "Orange",
+            Color::Yellow => "Yellow",
+            Color::Green => "Green",
+            Color::Blue => "Blue",
+            Color::Indigo => "Indigo",
+            Color::Violet => "Violet",
+            Color::Named(path) => {
+                let path = Intern::as_ref(*path).as_str();
+                path.rsplit("::").next().unwrap_or(path)
+            }
+        }
+    }
 }
 
 pub fn make_array(base: TraceType, size: usize) -> TraceType {
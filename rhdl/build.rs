@@ -1,4 +1,5 @@
 use std::env;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::io;
 use std::io::Write;
 use std::path::Path;
@@ -507,8 +508,37 @@ fn typenum_impls() {
     write_typenum_impl_func("Min", "min", |x, y| (x != y).then(|| x.min(y)));
 }
 
+fn hash_sources(dir: &Path, hasher: &mut DefaultHasher) {
+    let mut entries = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            hash_sources(&path, hasher);
+        } else {
+            path.hash(hasher);
+            std::fs::read(&path).unwrap().hash(hasher);
+        }
+    }
+}
+
+// The compiled kernel cache needs to know when the compiler has changed,
+// so we fingerprint the sources of the crate.
+fn compiler_fingerprint() {
+    let mut hasher = DefaultHasher::new();
+    hash_sources(Path::new("src"), &mut hasher);
+    println!(
+        "cargo:rustc-env=RHDL_COMPILER_FINGERPRINT={:016x}",
+        hasher.finish()
+    );
+    println!("cargo:rerun-if-changed=src");
+}
+
 fn main() {
     bit_tests();
     typenum_impls();
+    compiler_fingerprint();
     println!("cargo:rerun-if-changed=build.rs");
 }
@@ -1,12 +1,22 @@
 pub use crate::const_max;
+pub use crate::domain;
 pub use crate::rhdl_bits::BitWidth;
 pub use crate::rhdl_bits::Bits;
 pub use crate::rhdl_bits::Const;
+pub use crate::rhdl_bits::Fixed;
+pub use crate::rhdl_bits::Rounding;
 pub use crate::rhdl_bits::SignedBits;
+pub use crate::rhdl_bits::SignedFixed;
+pub use crate::rhdl_bits::SignedWideBits;
+pub use crate::rhdl_bits::WideBits;
 pub use crate::rhdl_bits::alias::*;
 pub use crate::rhdl_bits::bits;
 pub use crate::rhdl_bits::consts::*;
+pub use crate::rhdl_bits::fixed;
 pub use crate::rhdl_bits::signed;
+pub use crate::rhdl_bits::signed_fixed;
+pub use crate::rhdl_bits::wide_bits;
+pub use crate::rhdl_bits::wide_signed;
 pub use crate::rhdl_core::CircuitDQ;
 pub use crate::rhdl_core::ClockReset;
 pub use crate::rhdl_core::CompilationMode;
@@ -17,6 +27,7 @@ pub use crate::rhdl_core::circuit::circuit_impl::Circuit;
 pub use crate::rhdl_core::circuit::circuit_impl::CircuitIO;
 pub use crate::rhdl_core::circuit::func::Func;
 pub use crate::rhdl_core::circuit::hdl_descriptor::HDLDescriptor;
+pub use crate::rhdl_core::circuit::pipelined::Pipelined;
 pub use crate::rhdl_core::circuit::synchronous::Synchronous;
 pub use crate::rhdl_core::circuit::synchronous::SynchronousDQ;
 pub use crate::rhdl_core::circuit::synchronous::SynchronousIO;
@@ -27,6 +38,7 @@ pub use crate::rhdl_core::hdl::ast::{Direction, Events, HDLKind, non_blocking_as
 pub use crate::rhdl_core::hdl::ast::{
     always, assign, bit_string, continuous_assignment, id, if_statement, initial, port,
 };
+pub use crate::rhdl_core::hdl::systemverilog;
 pub use crate::rhdl_core::rhdl_trace_type as rtt;
 pub use crate::rhdl_core::rhif::spec::OpCode;
 pub use crate::rhdl_core::rtl::Object;
@@ -90,6 +102,7 @@ pub use crate::rhdl_core::BitX;
 pub use crate::rhdl_core::bitx::bitx_parse;
 pub use crate::rhdl_core::bitx::bitx_string;
 pub use crate::rhdl_core::bitx_vec;
+pub use crate::rhdl_core::circuit::cdc;
 pub use crate::rhdl_core::circuit::drc;
 pub use crate::rhdl_core::circuit::fixture::Driver;
 pub use crate::rhdl_core::circuit::fixture::ExportError;
@@ -97,6 +110,8 @@ pub use crate::rhdl_core::circuit::fixture::Fixture;
 pub use crate::rhdl_core::circuit::fixture::MountPoint;
 pub use crate::rhdl_core::circuit::fixture::passthrough_input_driver;
 pub use crate::rhdl_core::circuit::fixture::passthrough_output_driver;
+pub use crate::rhdl_core::circuit::resources;
+pub use crate::rhdl_core::circuit::timing;
 pub use crate::rhdl_core::sim::clock_pos_edge::ClockPosEdgeExt;
 pub use crate::rhdl_core::sim::merge::MergeExt;
 pub use crate::rhdl_core::sim::merge::merge;
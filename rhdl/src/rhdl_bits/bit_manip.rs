@@ -0,0 +1,122 @@
+use super::{BitWidth, Bits, bits, bits_impl::bits_masked};
+
+impl<N: BitWidth> Bits<N> {
+    /// Count the number of bits that are set.  The count is returned
+    /// as a value of the same width, which is always wide enough to hold it.
+    /// ```
+    /// # use rhdl::bits::alias::*;
+    /// assert_eq!(b8(0b1011_0010).count_ones(), 4);
+    /// ```
+    pub fn count_ones(self) -> Self {
+        bits(self.val.count_ones() as u128)
+    }
+    /// Count the number of zeros above the most significant set bit.
+    /// ```
+    /// # use rhdl::bits::alias::*;
+    /// assert_eq!(b8(0b0001_0110).leading_zeros(), 3);
+    /// assert_eq!(b8(0).leading_zeros(), 8);
+    /// ```
+    pub fn leading_zeros(self) -> Self {
+        bits((self.val.leading_zeros() as usize - (128 - N::BITS)) as u128)
+    }
+    /// Count the number of zeros below the least significant set bit.
+    /// ```
+    /// # use rhdl::bits::alias::*;
+    /// assert_eq!(b8(0b0001_0100).trailing_zeros(), 2);
+    /// assert_eq!(b8(0).trailing_zeros(), 8);
+    /// ```
+    pub fn trailing_zeros(self) -> Self {
+        bits((self.val.trailing_zeros() as usize).min(N::BITS) as u128)
+    }
+    /// Reverse the order of the bits, so that the LSB becomes the MSB.
+    /// ```
+    /// # use rhdl::bits::alias::*;
+    /// assert_eq!(b8(0b0001_0110).reverse_bits(), b8(0b0110_1000));
+    /// ```
+    pub fn reverse_bits(self) -> Self {
+        bits(self.val.reverse_bits() >> (128 - N::BITS))
+    }
+    /// Reverse the order of the bytes.  The width must be a whole
+    /// number of bytes.
+    /// ```
+    /// # use rhdl::bits::alias::*;
+    /// assert_eq!(b24(0x12_34_56).swap_bytes(), b24(0x56_34_12));
+    /// ```
+    pub fn swap_bytes(self) -> Self {
+        const {
+            assert!(
+                N::BITS % 8 == 0,
+                "swap_bytes requires a whole number of bytes"
+            )
+        };
+        bits(self.val.swap_bytes() >> (128 - N::BITS))
+    }
+    /// Rotate the bits toward the MSB by `amount` (modulo the width),
+    /// with the bits that fall off the top wrapping around to the bottom.
+    /// ```
+    /// # use rhdl::bits::alias::*;
+    /// assert_eq!(b8(0b1000_0110).rotate_left(b3(2)), b8(0b0001_1010));
+    /// ```
+    pub fn rotate_left<M: BitWidth>(self, amount: Bits<M>) -> Self {
+        let shift = (amount.val % N::BITS as u128) as u32;
+        if shift == 0 {
+            return self;
+        }
+        bits_masked((self.val << shift) | (self.val >> (N::BITS as u32 - shift)))
+    }
+    /// Rotate the bits toward the LSB by `amount` (modulo the width),
+    /// with the bits that fall off the bottom wrapping around to the top.
+    /// ```
+    /// # use rhdl::bits::alias::*;
+    /// assert_eq!(b8(0b1000_0110).rotate_right(b3(2)), b8(0b1010_0001));
+    /// ```
+    pub fn rotate_right<M: BitWidth>(self, amount: Bits<M>) -> Self {
+        let shift = (amount.val % N::BITS as u128) as u32;
+        if shift == 0 {
+            return self;
+        }
+        bits_masked((self.val >> shift) | (self.val << (N::BITS as u32 - shift)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rhdl_bits::alias::*;
+    use crate::rhdl_bits::bitwidth::*;
+    use crate::rhdl_bits::{Bits, bits};
+
+    #[test]
+    fn test_matches_u8() {
+        for i in 0..=u8::MAX {
+            let x = b8(i as u128);
+            assert_eq!(x.count_ones(), i.count_ones() as u128);
+            assert_eq!(x.leading_zeros(), i.leading_zeros() as u128);
+            assert_eq!(x.trailing_zeros(), i.trailing_zeros() as u128);
+            assert_eq!(x.reverse_bits(), i.reverse_bits() as u128);
+            for j in 0..16 {
+                assert_eq!(x.rotate_left(b4(j)), i.rotate_left(j as u32) as u128);
+                assert_eq!(x.rotate_right(b4(j)), i.rotate_right(j as u32) as u128);
+            }
+        }
+    }
+
+    #[test]
+    fn test_odd_widths() {
+        let x = b5(0b00110);
+        assert_eq!(x.leading_zeros(), 2);
+        assert_eq!(x.reverse_bits(), b5(0b01100));
+        assert_eq!(x.rotate_left(b3(4)), b5(0b00011));
+        assert_eq!(x.rotate_right(b3(6)), b5(0b00011));
+        assert_eq!(b1(0).leading_zeros(), 1);
+        assert_eq!(b1(1).count_ones(), 1);
+    }
+
+    #[test]
+    fn test_full_width() {
+        let x = bits::<U128>(0x0123_4567_89ab_cdef_0011_2233_4455_6677);
+        assert_eq!(x.swap_bytes().raw(), x.raw().swap_bytes());
+        assert_eq!(x.leading_zeros(), x.raw().leading_zeros() as u128);
+        assert_eq!(x.rotate_left(b8(68)).raw(), x.raw().rotate_left(68));
+        assert_eq!(Bits::<U128>::MAX.count_ones(), 128);
+    }
+}
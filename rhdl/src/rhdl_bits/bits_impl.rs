@@ -7,13 +7,11 @@ use seq_macro::seq;
 /// The [Bits] type is a fixed-sized bit vector.  It is meant to
 /// imitate the behavior of bit vectors in hardware.  Due to the
 /// design of the [Bits] type, you can only create a [Bits] type of
-/// up to 128 bits in length for now.  However, you can easily express
-/// larger constructs in hardware using arrays, tuples and structs.
-/// The only real limitation of the [Bits] type being 128 bits is that
-/// you cannot perform arbitrary arithmetic on longer bit values in your
-/// hardware designs.  I don't think this is a significant issue, but
-/// the [Bits] design of the `rust-hdl` crate was much slower and harder
-/// to maintain and use.  I think this is a good trade-off.
+/// up to 128 bits in length.  For wider values, use the
+/// [WideBits](super::WideBits) type, which is slower, but can be of
+/// any width.  The [Bits] design of the `rust-hdl` crate was much
+/// slower and harder to maintain and use, so keeping the common case
+/// in a `u128` is a good trade-off.
 ///
 /// Note that the [Bits] type implements 2's complement arithmetic.
 /// See <https://en.wikipedia.org/wiki/Two%27s_complement> for more
@@ -127,6 +125,10 @@ impl<N: BitWidth> Bits<N> {
     /// assert_eq!(bits, 0xFF);
     /// ```
     pub const fn mask() -> Self {
+        assert!(
+            N::BITS <= 128,
+            "Bits is limited to 128 bits - use WideBits instead"
+        );
         Self {
             marker: std::marker::PhantomData,
             val: u128::MAX >> (128 - N::BITS),
@@ -0,0 +1,57 @@
+use std::ops::Div;
+use std::ops::DivAssign;
+
+use crate::impl_assign_op;
+use crate::impl_assigned_signed_op;
+use crate::impl_binop;
+use crate::impl_signed_binop;
+
+use super::BitWidth;
+use super::bits_impl::Bits;
+use super::bits_impl::bits_masked;
+use super::dyn_bits::DynBits;
+use super::signed_bits_impl::SignedBits;
+use super::signed_bits_impl::signed_wrapped;
+use super::signed_dyn_bits::SignedDynBits;
+
+impl_binop!(Div, div, u128::wrapping_div);
+impl_assign_op!(DivAssign, div_assign, u128::wrapping_div);
+impl_signed_binop!(Div, div, i128::wrapping_div);
+impl_assigned_signed_op!(DivAssign, div_assign, i128::wrapping_div);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{rhdl_bits::bitwidth::*, test_binop};
+
+    #[test]
+    fn test_div() {
+        for i in 1..=255 {
+            for j in 1..=255 {
+                test_binop!(/, u128::wrapping_div, i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn test_div_i8() {
+        for i in i8::MIN..=i8::MAX {
+            for j in (i8::MIN..=i8::MAX).filter(|j| *j != 0) {
+                let signed_i: SignedBits<U8> = (i as i128).into();
+                let signed_j: SignedBits<U8> = (j as i128).into();
+                assert_eq!(signed_i / signed_j, i.wrapping_div(j) as i128);
+                assert_eq!(signed_i / (j as i128), i.wrapping_div(j) as i128);
+            }
+        }
+    }
+
+    #[test]
+    fn test_divassign_bits() {
+        let mut bits: Bits<U8> = 0b1101_1010.into();
+        bits /= 10;
+        assert_eq!(bits.val, 218 / 10);
+        let mut bits: SignedBits<U8> = (-100).into();
+        bits /= SignedBits::<U8>::from(7);
+        assert_eq!(bits.val, -100 / 7);
+    }
+}
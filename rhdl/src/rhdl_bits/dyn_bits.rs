@@ -2,6 +2,12 @@ use crate::rhdl_bits::bits_impl::bits_masked;
 
 use super::{signed_dyn_bits::SignedDynBits, BitWidth, Bits};
 
+/// A bit vector whose width is tracked at run time, rather than in
+/// the type.  Like [Bits], it is backed by a `u128`, so it is
+/// limited to 128 bits (unlike [WideBits](super::WideBits)).  The width
+/// growing operations ([DynBits::xext] and [DynBits::xshl]) panic if the
+/// result would be wider than that.  To work with wider values, move
+/// to [WideBits](super::WideBits) with `as_bits` and then `wide`.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DynBits {
     pub val: u128,
@@ -25,7 +31,10 @@ impl DynBits {
         u128::MAX >> (128 - self.bits)
     }
     pub const fn xext<M: BitWidth>(self) -> DynBits {
-        assert!((M::BITS + self.bits) <= 128);
+        assert!(
+            M::BITS + self.bits <= 128,
+            "DynBits is limited to 128 bits - use WideBits instead"
+        );
         DynBits {
             val: self.val,
             bits: M::BITS + self.bits,
@@ -39,7 +48,10 @@ impl DynBits {
         }
     }
     pub const fn xshl<M: BitWidth>(self) -> DynBits {
-        assert!((M::BITS + self.bits) <= 128);
+        assert!(
+            M::BITS + self.bits <= 128,
+            "DynBits is limited to 128 bits - use WideBits instead"
+        );
         DynBits {
             val: self.val << M::BITS,
             bits: self.bits + M::BITS,
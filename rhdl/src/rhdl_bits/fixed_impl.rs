@@ -0,0 +1,435 @@
+use std::ops::{Add, Sub};
+
+use super::{
+    BitWidth, Bits, bits, signed_fixed_impl::SignedFixed, xadd::XAdd, xmul::XMul, xneg::XNeg,
+    xsgn::XSgn, xsub::XSub,
+};
+use crate::rhdl_typenum::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The rounding mode used when discarding fractional bits
+/// from a fixed point value with
+/// [quantize](Fixed::quantize).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Rounding {
+    /// Drop the extra bits.  For 2's complement values, this
+    /// rounds towards negative infinity.  It is also free in hardware.
+    #[default]
+    Truncate,
+    /// Round towards zero.  The same as [Rounding::Truncate] for
+    /// unsigned values.
+    TowardZero,
+    /// Round to the nearest value, with ties rounded up (towards
+    /// positive infinity).
+    HalfUp,
+    /// Round to the nearest value, with ties rounded to the
+    /// nearest even value (convergent rounding).
+    HalfEven,
+}
+
+/// The [Fixed] type is an unsigned fixed point value with `I`
+/// integer bits and `F` fractional bits.  It is stored as a
+/// `I + F` bit unsigned value `raw`, and represents the real number
+/// `raw / 2^F`.  As with [Bits], the total width is limited to 128
+/// bits, and both `I` and `F` must be at least 1.
+///
+/// The `+` and `-` operators wrap, just like [Bits].  To grow the
+/// value so that the result is exact, use [xadd](XAdd::xadd),
+/// [xsub](XSub::xsub) and [xmul](XMul::xmul), which track
+/// the binary point in the type:
+/// ```
+/// # use rhdl::bits::{Fixed, fixed, consts::*, xmul::XMul};
+/// let a: Fixed<U4, U4> = fixed(2.5);
+/// let b: Fixed<U2, U6> = fixed(1.25);
+/// let c: Fixed<U6, U10> = a.xmul(b);
+/// assert_eq!(c.to_f64(), 3.125);
+/// ```
+/// To go back to a narrower type, use [quantize](Fixed::quantize) to
+/// drop fractional bits (with the given [Rounding] mode), and
+/// [saturate](Fixed::saturate) to drop integer bits.
+/// ```
+/// # use rhdl::bits::{Fixed, fixed, consts::*, Rounding};
+/// let a: Fixed<U6, U10> = fixed(19.3125);
+/// let b: Fixed<U4, U4> = a.quantize::<U4>(Rounding::HalfEven).saturate::<U4>();
+/// assert_eq!(b.to_f64(), 15.9375);
+/// ```
+///
+/// In hardware, a [Fixed] value is just a [Bits] value of `I + F` bits,
+/// so the exact operations (`+`, `-`, `xadd`, `xsub`, `xmul`, `xsgn`,
+/// `xneg`, `xext`, `xshl`, `xshr`, comparisons and `as_signed`) can be
+/// used directly in kernels.  So can [quantize](Fixed::quantize) and
+/// [saturate](Fixed::saturate), as long as the target width is given
+/// with a turbofish and the rounding mode is a constant (like
+/// `Rounding::HalfEven`).
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct Fixed<I, F> {
+    pub(crate) marker: std::marker::PhantomData<(I, F)>,
+    pub(crate) val: u128,
+}
+
+/// Construct a [Fixed] value from a floating point number.  The
+/// value is rounded to the nearest representable value.  Panics
+/// if the value is out of range.
+/// ```
+/// # use rhdl::bits::{Fixed, fixed, consts::*};
+/// let x: Fixed<U4, U2> = fixed(3.25);
+/// assert_eq!(x.raw(), 13);
+/// ```
+pub fn fixed<I: BitWidth, F: BitWidth>(val: f64) -> Fixed<I, F> {
+    Fixed::from_f64(val)
+}
+
+// Drop `shift` bits from the bottom of `val`, rounding as requested.
+pub(crate) const fn round_shr_u128(val: u128, shift: usize, mode: Rounding) -> u128 {
+    if shift == 0 {
+        return val;
+    }
+    let quotient = val >> shift;
+    let remainder = val & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    let round_up = match mode {
+        Rounding::Truncate | Rounding::TowardZero => false,
+        Rounding::HalfUp => remainder >= half,
+        Rounding::HalfEven => remainder > half || (remainder == half && quotient & 1 == 1),
+    };
+    quotient + round_up as u128
+}
+
+impl<I: BitWidth, F: BitWidth> Fixed<I, F> {
+    /// The number of integer bits.
+    pub const INTEGER_BITS: usize = I::BITS;
+    /// The number of fractional bits.
+    pub const FRACTION_BITS: usize = F::BITS;
+    pub const ZERO: Self = Self::from_raw(0);
+    /// The largest value that can be represented (`2^I - 2^-F`).
+    pub const MAX: Self = Self::from_raw(Self::mask());
+    pub const MIN: Self = Self::ZERO;
+    /// The smallest non-zero value that can be represented (`2^-F`).
+    pub const EPSILON: Self = Self::from_raw(1);
+    const fn width() -> usize {
+        assert!(
+            I::BITS + F::BITS <= 128,
+            "Fixed is limited to 128 bits in total"
+        );
+        I::BITS + F::BITS
+    }
+    const fn mask() -> u128 {
+        u128::MAX >> (128 - Self::width())
+    }
+    pub(crate) const fn from_raw(val: u128) -> Self {
+        Self {
+            marker: std::marker::PhantomData,
+            val: val & Self::mask(),
+        }
+    }
+    /// Construct a [Fixed] value from the raw bits, which are
+    /// interpreted as a value of `bits / 2^F`.
+    /// ```
+    /// # use rhdl::bits::{Fixed, consts::*, alias::*};
+    /// let x = Fixed::<U4, U4>::from_bits(b8(0x18));
+    /// assert_eq!(x.to_f64(), 1.5);
+    /// ```
+    pub const fn from_bits(val: Bits<op!(I + F)>) -> Self
+    where
+        I: Add<F>,
+        op!(I + F): BitWidth,
+    {
+        Self::from_raw(val.val)
+    }
+    /// Return the raw bits that make up this value.
+    pub const fn as_bits(self) -> Bits<op!(I + F)>
+    where
+        I: Add<F>,
+        op!(I + F): BitWidth,
+    {
+        bits(self.val)
+    }
+    /// Extract the raw `u128` behind the [Fixed] value.
+    pub const fn raw(self) -> u128 {
+        self.val
+    }
+    /// Convert a floating point value to the nearest [Fixed] value.
+    /// Panics if the value is out of range.
+    pub fn from_f64(val: f64) -> Self {
+        let scaled = (val * (F::BITS as f64).exp2()).round();
+        assert!(
+            scaled >= 0.0 && scaled <= Self::mask() as f64,
+            "value {val} is out of range for Fixed<{}, {}>",
+            I::BITS,
+            F::BITS
+        );
+        Self::from_raw(scaled as u128)
+    }
+    /// Convert the value to a floating point number.  This is exact
+    /// as long as the value fits in the mantissa of an `f64`.
+    pub fn to_f64(self) -> f64 {
+        self.val as f64 / (F::BITS as f64).exp2()
+    }
+    /// Reinterpret the bits of the value as a [SignedFixed] value.
+    pub const fn as_signed(self) -> SignedFixed<I, F> {
+        SignedFixed::from_raw(self.val as i128)
+    }
+    /// Add `M` integer bits to the value.  This is exact.
+    pub const fn xext<M>(self) -> Fixed<op!(I + M), F>
+    where
+        I: Add<M>,
+        op!(I + M): BitWidth,
+    {
+        Fixed::from_raw(self.val)
+    }
+    /// Add `M` fractional bits to the value.  This is exact.
+    pub const fn xshl<M>(self) -> Fixed<I, op!(F + M)>
+    where
+        F: Add<M>,
+        M: BitWidth,
+        op!(F + M): BitWidth,
+    {
+        Fixed::from_raw(self.val << M::BITS)
+    }
+    /// Drop `M` fractional bits from the value.  This truncates the
+    /// value.  See [quantize](Fixed::quantize) for other rounding modes.
+    pub const fn xshr<M>(self) -> Fixed<I, op!(F - M)>
+    where
+        F: Sub<M>,
+        M: BitWidth,
+        op!(F - M): BitWidth,
+    {
+        Fixed::from_raw(self.val >> M::BITS)
+    }
+    /// Change the number of fractional bits to `F2`.  If bits
+    /// are dropped, the value is rounded using the given mode.
+    /// The integer part wraps if rounding overflows it, so
+    /// use [xext](Fixed::xext) first if that matters.
+    /// ```
+    /// # use rhdl::bits::{Fixed, fixed, consts::*, Rounding};
+    /// let x: Fixed<U4, U4> = fixed(2.625);
+    /// assert_eq!(x.quantize::<U2>(Rounding::Truncate).to_f64(), 2.5);
+    /// assert_eq!(x.quantize::<U2>(Rounding::HalfUp).to_f64(), 2.75);
+    /// assert_eq!(x.quantize::<U2>(Rounding::HalfEven).to_f64(), 2.5);
+    /// assert_eq!(x.quantize::<U6>(Rounding::HalfEven).to_f64(), 2.625);
+    /// ```
+    pub const fn quantize<F2: BitWidth>(self, mode: Rounding) -> Fixed<I, F2> {
+        if F2::BITS >= F::BITS {
+            Fixed::from_raw(self.val << (F2::BITS - F::BITS))
+        } else {
+            Fixed::from_raw(round_shr_u128(self.val, F::BITS - F2::BITS, mode))
+        }
+    }
+    /// Change the number of integer bits to `I2`, clamping the
+    /// value to the largest representable value if it does not fit.
+    /// ```
+    /// # use rhdl::bits::{Fixed, fixed, consts::*};
+    /// let x: Fixed<U4, U4> = fixed(9.5);
+    /// assert_eq!(x.saturate::<U3>().to_f64(), 7.9375);
+    /// assert_eq!(x.saturate::<U5>().to_f64(), 9.5);
+    /// ```
+    pub const fn saturate<I2: BitWidth>(self) -> Fixed<I2, F> {
+        if self.val > Fixed::<I2, F>::mask() {
+            Fixed::<I2, F>::MAX
+        } else {
+            Fixed::from_raw(self.val)
+        }
+    }
+    /// Build a (dynamic, stack allocated) vector containing
+    /// the bits that make up this value.
+    pub fn to_bools(self) -> Vec<bool> {
+        (0..Self::width())
+            .map(|i| self.val & (1 << i) != 0)
+            .collect()
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::fmt::Debug for Fixed<I, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}_uq{}.{}", self.to_f64(), I::BITS, F::BITS)
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::fmt::Display for Fixed<I, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_f64())
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::cmp::PartialOrd for Fixed<I, F> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::cmp::Ord for Fixed<I, F> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.val.cmp(&other.val)
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::ops::Add for Fixed<I, F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::from_raw(self.val.wrapping_add(rhs.val))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::ops::AddAssign for Fixed<I, F> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::ops::Sub for Fixed<I, F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::from_raw(self.val.wrapping_sub(rhs.val))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::ops::SubAssign for Fixed<I, F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<I1, I2, F> XAdd<Fixed<I2, F>> for Fixed<I1, F>
+where
+    I1: Max<I2> + BitWidth,
+    I2: BitWidth,
+    F: BitWidth,
+    Maximum<I1, I2>: Add<U1>,
+    op!(max(I1, I2) + U1): BitWidth,
+{
+    type Output = Fixed<op!(max(I1, I2) + U1), F>;
+    fn xadd(self, rhs: Fixed<I2, F>) -> Self::Output {
+        Fixed::from_raw(self.val.wrapping_add(rhs.val))
+    }
+}
+
+impl<I1, I2, F> XSub<Fixed<I2, F>> for Fixed<I1, F>
+where
+    I1: Max<I2> + BitWidth,
+    I2: BitWidth,
+    F: BitWidth,
+    Maximum<I1, I2>: Add<U1>,
+    op!(max(I1, I2) + U1): BitWidth,
+{
+    type Output = SignedFixed<op!(max(I1, I2) + U1), F>;
+    fn xsub(self, rhs: Fixed<I2, F>) -> Self::Output {
+        SignedFixed::from_raw((self.val as i128).wrapping_sub(rhs.val as i128))
+    }
+}
+
+impl<I1, I2, F1, F2> XMul<Fixed<I2, F2>> for Fixed<I1, F1>
+where
+    I1: Add<I2> + BitWidth,
+    I2: BitWidth,
+    F1: Add<F2> + BitWidth,
+    F2: BitWidth,
+    op!(I1 + I2): BitWidth,
+    op!(F1 + F2): BitWidth,
+{
+    type Output = Fixed<op!(I1 + I2), op!(F1 + F2)>;
+    fn xmul(self, rhs: Fixed<I2, F2>) -> Self::Output {
+        Fixed::from_raw(self.val.wrapping_mul(rhs.val))
+    }
+}
+
+impl<I, F> XNeg for Fixed<I, F>
+where
+    I: Add<U1> + BitWidth,
+    F: BitWidth,
+    op!(I + U1): BitWidth,
+{
+    type Output = SignedFixed<op!(I + U1), F>;
+    fn xneg(self) -> Self::Output {
+        SignedFixed::from_raw((self.val as i128).wrapping_neg())
+    }
+}
+
+impl<I, F> XSgn for Fixed<I, F>
+where
+    I: Add<U1> + BitWidth,
+    F: BitWidth,
+    op!(I + U1): BitWidth,
+{
+    type Output = SignedFixed<op!(I + U1), F>;
+    fn xsgn(self) -> Self::Output {
+        SignedFixed::from_raw(self.val as i128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rhdl_bits::alias::*;
+
+    #[test]
+    fn test_fixed_conversions() {
+        let x: Fixed<U4, U4> = fixed(3.75);
+        assert_eq!(x.raw(), 0x3C);
+        assert_eq!(x.as_bits(), b8(0x3C));
+        assert_eq!(x.to_f64(), 3.75);
+        assert_eq!(Fixed::<U4, U4>::MAX.to_f64(), 15.9375);
+        assert_eq!(Fixed::<U4, U4>::EPSILON.to_f64(), 0.0625);
+        assert_eq!(format!("{x}"), "3.75");
+        assert_eq!(format!("{x:?}"), "3.75_uq4.4");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fixed_out_of_range() {
+        let _: Fixed<U4, U4> = fixed(16.0);
+    }
+
+    #[test]
+    fn test_fixed_wrapping_arithmetic() {
+        let x: Fixed<U4, U4> = fixed(15.5);
+        let y: Fixed<U4, U4> = fixed(1.0);
+        assert_eq!((x + y).to_f64(), 0.5);
+        assert_eq!((y - x).to_f64(), 1.5);
+        assert!(y < x);
+    }
+
+    #[test]
+    fn test_fixed_exact_arithmetic() {
+        for a in 0..=255 {
+            for b in 0..=63 {
+                let x = Fixed::<U4, U4>::from_raw(a);
+                let y = Fixed::<U2, U4>::from_raw(b);
+                assert_eq!(x.xadd(y).to_f64(), x.to_f64() + y.to_f64());
+                assert_eq!(x.xsub(y).to_f64(), x.to_f64() - y.to_f64());
+                let z = Fixed::<U2, U2>::from_raw(b & 0xF);
+                assert_eq!(x.xmul(z).to_f64(), x.to_f64() * z.to_f64());
+            }
+            let x = Fixed::<U4, U4>::from_raw(a);
+            assert_eq!(x.xneg().to_f64(), -x.to_f64());
+            assert_eq!(x.xsgn().to_f64(), x.to_f64());
+            assert_eq!(x.xext::<U2>().to_f64(), x.to_f64());
+            assert_eq!(x.xshl::<U2>().to_f64(), x.to_f64());
+            assert_eq!(x.xshr::<U2>().to_f64(), (x.to_f64() * 4.0).floor() / 4.0);
+        }
+    }
+
+    #[test]
+    fn test_fixed_rounding() {
+        for a in 0..=255 {
+            let x = Fixed::<U4, U4>::from_raw(a);
+            let v = x.to_f64() * 2.0;
+            let check = |mode: Rounding, expected: f64| {
+                let expected = expected / 2.0 % 16.0;
+                assert_eq!(x.quantize::<U1>(mode).to_f64(), expected, "{x:?} {mode:?}");
+            };
+            check(Rounding::Truncate, v.floor());
+            check(Rounding::TowardZero, v.trunc());
+            check(Rounding::HalfUp, (v + 0.5).floor());
+            check(Rounding::HalfEven, v.round_ties_even());
+        }
+    }
+
+    #[test]
+    fn test_fixed_saturation() {
+        for a in 0..=255 {
+            let x = Fixed::<U4, U4>::from_raw(a);
+            assert_eq!(x.saturate::<U2>().to_f64(), x.to_f64().min(3.9375));
+            assert_eq!(x.saturate::<U6>().to_f64(), x.to_f64());
+        }
+    }
+}
@@ -84,13 +84,17 @@
 //! let bits: b8 = 0b1101_1010_u128.into(); // Short form (not the same as u8)
 //! ```
 //!
-//! Note that the [Bits] type only supports up to 128 bit values.  Larger bit vectors
-//! can easily be constructed using data structures (arrays, structs, enums, tuples).
-//! But arithmetic on them is not supported by default.  You will need to provide your
-//! own arithmetic implementations for these types.  This is a limitation of the
-//! [Bits] type, but it is a limitation that is not likely to be a problem in practice.
-//! Practical hardware limitations can mean that performing arithmetic on very long
-//! bit vectors is likely to be very slow.
+//! Note that the [Bits] type only supports up to 128 bit values.  For wider bit
+//! vectors (like a 256 bit data path), use the [WideBits] and [SignedWideBits] types,
+//! which take their width as a const generic.  They support the same operators, but
+//! are quite a bit slower to simulate, so stick with [Bits] where you can.  Use
+//! `wide` and `narrow` to move values between the two.
+//! ```
+//! # use rhdl::bits::{WideBits, alias::*};
+//! let x: WideBits<256> = b64(0xDEAD_BEEF).wide();
+//! let y = (x << 200_u128) >> 200_u128;
+//! assert_eq!(y.narrow::<rhdl::bits::consts::U64>(), b64(0xDEAD_BEEF));
+//! ```
 //!
 //! # Constructing [SignedBits]
 //! The [SignedBits] type can be constructed in the same way as the [Bits] type.  The
@@ -382,17 +386,93 @@
 //! assert_eq!(x.as_unsigned(), 0b1111_1111);
 //! ```
 //!
+//! ## Division and Remainder
+//!
+//! Division and remainder are supported for both [Bits] and [SignedBits], and
+//! follow the truncating semantics of the built in Rust integers.  Dividing the
+//! most negative value by `-1` wraps, just like `wrapping_div`.  Dividing by zero
+//! panics.
+//! ```
+//! # use rhdl::bits::alias::*;
+//! let x: b8 = 123.into();
+//! assert_eq!(x / 10, 12);
+//! assert_eq!(x % 10, 3);
+//! let y: s8 = (-7).into();
+//! assert_eq!(y / s8(2), -3);
+//! assert_eq!(y % s8(2), -1);
+//! ```
+//!
+//! In a kernel, the divisor must be a constant.  The division is then lowered to
+//! a multiply and shift sequence.  Division by a value computed at run time
+//! needs a divider core, which takes several clock cycles.
+//!
+//! ## Saturating and Overflowing Arithmetic
+//!
+//! When wrapping is not what you want, both [Bits] and [SignedBits] provide
+//! `saturating_add`, `saturating_sub` and `saturating_mul`, which clamp the result
+//! to the representable range, and `overflowing_add`, `overflowing_sub` and
+//! `overflowing_mul`, which return the wrapped result along with a flag that
+//! indicates if the operation overflowed.  These mirror the methods of the same
+//! name on the built in Rust integers, and can be used in kernels.
+//! ```
+//! # use rhdl::bits::alias::*;
+//! let x: b8 = 200.into();
+//! assert_eq!(x.saturating_add(b8(100)), 255);
+//! assert_eq!(x.overflowing_add(b8(100)), (b8(44), true));
+//! let y: s8 = (-100).into();
+//! assert_eq!(y.saturating_sub(s8(100)), -128);
+//! ```
+//!
+//! ## Bit Manipulation
+//!
+//! [Bits] also provides `count_ones`, `leading_zeros`, `trailing_zeros`,
+//! `reverse_bits`, `swap_bytes`, `rotate_left` and `rotate_right`, which behave like
+//! their counterparts on the built in Rust integers.  The counts are returned as
+//! a [Bits] of the same width as the argument, and the rotation amount is itself
+//! a [Bits] value, so that all of these can be used in kernels.
+//! ```
+//! # use rhdl::bits::alias::*;
+//! let x: b8 = 0b0010_1100.into();
+//! assert_eq!(x.count_ones(), 3);
+//! assert_eq!(x.leading_zeros(), 2);
+//! assert_eq!(x.rotate_right(b3(2)), 0b0000_1011);
+//! ```
+//!
+//! # Fixed point values
+//!
+//! The [Fixed] and [SignedFixed] types carry a binary point along with the bits.
+//! A `Fixed<I, F>` has `I` integer bits and `F` fractional bits, and is
+//! represented in hardware as a `Bits<I + F>`.  The width extending operations
+//! (`xadd`, `xsub`, `xmul`) keep track of where the binary point ends up, and
+//! [quantize](Fixed::quantize) and [saturate](Fixed::saturate) bring the result
+//! back down to the width you want.
+//! ```
+//! # use rhdl::bits::{Fixed, SignedFixed, fixed, consts::*, Rounding, xsub::XSub, xmul::XMul};
+//! let a: Fixed<U4, U4> = fixed(3.5);
+//! let b: Fixed<U4, U4> = fixed(5.25);
+//! let c: SignedFixed<U5, U4> = a.xsub(b);
+//! let d: SignedFixed<U10, U8> = c.xmul(c);
+//! let e: SignedFixed<U4, U2> = d.quantize::<U2>(Rounding::HalfUp).saturate::<U4>();
+//! assert_eq!(e.to_f64(), 3.0);
+//! ```
+//!
 #[doc(hidden)]
 pub mod add;
 #[doc(hidden)]
 pub mod and;
 #[doc(hidden)]
+pub mod bit_manip;
+#[doc(hidden)]
 pub mod bits_impl;
 #[doc(hidden)]
 pub mod bitwidth;
 #[doc(hidden)]
+pub mod div;
+#[doc(hidden)]
 pub mod dyn_bits;
 #[doc(hidden)]
+pub mod fixed_impl;
+#[doc(hidden)]
 pub mod mul;
 #[doc(hidden)]
 pub mod neg;
@@ -401,6 +481,10 @@ pub mod not;
 #[doc(hidden)]
 pub mod or;
 #[doc(hidden)]
+pub mod rem;
+#[doc(hidden)]
+pub mod saturating;
+#[doc(hidden)]
 pub mod shl;
 #[doc(hidden)]
 pub mod shr;
@@ -409,8 +493,14 @@ pub mod signed_bits_impl;
 #[doc(hidden)]
 pub mod signed_dyn_bits;
 #[doc(hidden)]
+pub mod signed_fixed_impl;
+#[doc(hidden)]
 pub mod sub;
 #[doc(hidden)]
+pub mod signed_wide_bits_impl;
+#[doc(hidden)]
+pub mod wide_bits_impl;
+#[doc(hidden)]
 pub mod xadd;
 #[doc(hidden)]
 pub mod xmul;
@@ -464,13 +554,24 @@ pub mod alias {
             pub use super::signed_bits_impl::s~N;
         )*
     });
+    pub use super::signed_wide_bits_impl::{s256, s512, s1024};
+    pub use super::wide_bits_impl::{b256, b512, b1024};
 }
 
 pub use bits_impl::Bits;
 pub use bits_impl::bits;
 pub use bitwidth::BitWidth;
+pub use fixed_impl::Fixed;
+pub use fixed_impl::Rounding;
+pub use fixed_impl::fixed;
 pub use signed_bits_impl::SignedBits;
 pub use signed_bits_impl::signed;
+pub use signed_fixed_impl::SignedFixed;
+pub use signed_fixed_impl::signed_fixed;
+pub use signed_wide_bits_impl::SignedWideBits;
+pub use signed_wide_bits_impl::wide_signed;
+pub use wide_bits_impl::WideBits;
+pub use wide_bits_impl::wide_bits;
 
 #[cfg(test)]
 mod test {
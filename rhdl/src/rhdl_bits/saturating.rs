@@ -0,0 +1,168 @@
+use super::{BitWidth, Bits, SignedBits, bits_impl::bits_masked, signed_bits_impl::signed_wrapped};
+
+impl<N: BitWidth> Bits<N> {
+    /// Add two [Bits] values, and return the wrapped result along with
+    /// a flag that is set if the addition overflowed.
+    pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let (sum, carry) = self.val.overflowing_add(rhs.val);
+        (bits_masked(sum), carry || sum > Self::MAX.val)
+    }
+    /// Subtract two [Bits] values, and return the wrapped result along with
+    /// a flag that is set if the subtraction underflowed.
+    pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        (
+            bits_masked(self.val.wrapping_sub(rhs.val)),
+            self.val < rhs.val,
+        )
+    }
+    /// Multiply two [Bits] values, and return the wrapped result along with
+    /// a flag that is set if the product overflowed.
+    pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+        let (product, carry) = self.val.overflowing_mul(rhs.val);
+        (bits_masked(product), carry || product > Self::MAX.val)
+    }
+    /// Add two [Bits] values, clamping the result to [Bits::MAX] on overflow.
+    /// ```
+    /// # use rhdl::bits::alias::*;
+    /// assert_eq!(b8(200).saturating_add(b8(100)), b8(255));
+    /// ```
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        match self.overflowing_add(rhs) {
+            (_, true) => Self::MAX,
+            (sum, false) => sum,
+        }
+    }
+    /// Subtract two [Bits] values, clamping the result to zero on underflow.
+    /// ```
+    /// # use rhdl::bits::alias::*;
+    /// assert_eq!(b8(100).saturating_sub(b8(200)), b8(0));
+    /// ```
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        match self.overflowing_sub(rhs) {
+            (_, true) => Self::ZERO,
+            (difference, false) => difference,
+        }
+    }
+    /// Multiply two [Bits] values, clamping the result to [Bits::MAX] on overflow.
+    pub fn saturating_mul(self, rhs: Self) -> Self {
+        match self.overflowing_mul(rhs) {
+            (_, true) => Self::MAX,
+            (product, false) => product,
+        }
+    }
+}
+
+impl<N: BitWidth> SignedBits<N> {
+    fn out_of_range(val: i128) -> bool {
+        val > Self::max_value() || val < Self::min_value()
+    }
+    /// Add two [SignedBits] values, and return the wrapped result along with
+    /// a flag that is set if the addition overflowed.
+    pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let (sum, carry) = self.val.overflowing_add(rhs.val);
+        (signed_wrapped(sum), carry || Self::out_of_range(sum))
+    }
+    /// Subtract two [SignedBits] values, and return the wrapped result along with
+    /// a flag that is set if the subtraction overflowed.
+    pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        let (difference, carry) = self.val.overflowing_sub(rhs.val);
+        (
+            signed_wrapped(difference),
+            carry || Self::out_of_range(difference),
+        )
+    }
+    /// Multiply two [SignedBits] values, and return the wrapped result along with
+    /// a flag that is set if the product overflowed.
+    pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+        let (product, carry) = self.val.overflowing_mul(rhs.val);
+        (
+            signed_wrapped(product),
+            carry || Self::out_of_range(product),
+        )
+    }
+    /// Add two [SignedBits] values, clamping the result to [SignedBits::MAX]
+    /// or [SignedBits::MIN] on overflow.
+    /// ```
+    /// # use rhdl::bits::alias::*;
+    /// assert_eq!(s8(100).saturating_add(s8(100)), s8(127));
+    /// assert_eq!(s8(-100).saturating_add(s8(-100)), s8(-128));
+    /// ```
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        match self.overflowing_add(rhs) {
+            (_, true) if rhs.val > 0 => Self::MAX,
+            (_, true) => Self::MIN,
+            (sum, false) => sum,
+        }
+    }
+    /// Subtract two [SignedBits] values, clamping the result to [SignedBits::MAX]
+    /// or [SignedBits::MIN] on overflow.
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        match self.overflowing_sub(rhs) {
+            (_, true) if rhs.val < 0 => Self::MAX,
+            (_, true) => Self::MIN,
+            (difference, false) => difference,
+        }
+    }
+    /// Multiply two [SignedBits] values, clamping the result to [SignedBits::MAX]
+    /// or [SignedBits::MIN] on overflow.
+    pub fn saturating_mul(self, rhs: Self) -> Self {
+        match self.overflowing_mul(rhs) {
+            (_, true) if self.is_negative() == rhs.is_negative() => Self::MAX,
+            (_, true) => Self::MIN,
+            (product, false) => product,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rhdl_bits::alias::*;
+    use crate::rhdl_bits::bitwidth::*;
+    use crate::rhdl_bits::{Bits, SignedBits};
+
+    #[test]
+    fn test_unsigned_matches_u8() {
+        for i in 0..=u8::MAX {
+            for j in 0..=u8::MAX {
+                let (a, b) = (b8(i as u128), b8(j as u128));
+                let (sum, flag) = i.overflowing_add(j);
+                assert_eq!(a.overflowing_add(b), (b8(sum as u128), flag));
+                let (difference, flag) = i.overflowing_sub(j);
+                assert_eq!(a.overflowing_sub(b), (b8(difference as u128), flag));
+                let (product, flag) = i.overflowing_mul(j);
+                assert_eq!(a.overflowing_mul(b), (b8(product as u128), flag));
+                assert_eq!(a.saturating_add(b), i.saturating_add(j) as u128);
+                assert_eq!(a.saturating_sub(b), i.saturating_sub(j) as u128);
+                assert_eq!(a.saturating_mul(b), i.saturating_mul(j) as u128);
+            }
+        }
+    }
+
+    #[test]
+    fn test_signed_matches_i8() {
+        for i in i8::MIN..=i8::MAX {
+            for j in i8::MIN..=i8::MAX {
+                let (a, b) = (s8(i as i128), s8(j as i128));
+                let (sum, flag) = i.overflowing_add(j);
+                assert_eq!(a.overflowing_add(b), (s8(sum as i128), flag));
+                let (difference, flag) = i.overflowing_sub(j);
+                assert_eq!(a.overflowing_sub(b), (s8(difference as i128), flag));
+                let (product, flag) = i.overflowing_mul(j);
+                assert_eq!(a.overflowing_mul(b), (s8(product as i128), flag));
+                assert_eq!(a.saturating_add(b), i.saturating_add(j) as i128);
+                assert_eq!(a.saturating_sub(b), i.saturating_sub(j) as i128);
+                assert_eq!(a.saturating_mul(b), i.saturating_mul(j) as i128);
+            }
+        }
+    }
+
+    #[test]
+    fn test_full_width() {
+        let a = Bits::<U128>::MAX;
+        assert_eq!(a.overflowing_add(a), (a - 1, true));
+        assert_eq!(a.saturating_mul(a), a);
+        let b = SignedBits::<U128>::MIN;
+        assert_eq!(b.overflowing_sub(SignedBits::<U128>::MAX), (1.into(), true));
+        assert_eq!(b.saturating_mul(b), SignedBits::<U128>::MAX);
+    }
+}
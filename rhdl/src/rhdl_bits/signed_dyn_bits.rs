@@ -2,6 +2,14 @@ use crate::rhdl_bits::signed_bits_impl::signed_wrapped;
 
 use super::{dyn_bits::DynBits, BitWidth, SignedBits};
 
+/// A signed bit vector whose width is tracked at run time, rather
+/// than in the type.  Like [SignedBits], it is backed by an `i128`,
+/// so it is limited to 128 bits (unlike
+/// [SignedWideBits](super::SignedWideBits)).  The width growing
+/// operations ([SignedDynBits::xext] and [SignedDynBits::xshl]) panic
+/// if the result would be wider than that.  To work with wider values,
+/// move to [SignedWideBits](super::SignedWideBits) with
+/// `as_signed_bits` and then `wide`.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SignedDynBits {
     pub val: i128,
@@ -49,7 +57,10 @@ impl SignedDynBits {
         x & 1 == 1
     }
     pub const fn xext<M: BitWidth>(self) -> SignedDynBits {
-        assert!(self.bits + M::BITS <= 128);
+        assert!(
+            self.bits + M::BITS <= 128,
+            "SignedDynBits is limited to 128 bits - use SignedWideBits instead"
+        );
         SignedDynBits {
             val: self.val,
             bits: M::BITS + self.bits,
@@ -63,7 +74,10 @@ impl SignedDynBits {
         }
     }
     pub const fn xshl<M: BitWidth>(self) -> SignedDynBits {
-        assert!(self.bits + M::BITS <= 128);
+        assert!(
+            self.bits + M::BITS <= 128,
+            "SignedDynBits is limited to 128 bits - use SignedWideBits instead"
+        );
         SignedDynBits {
             val: self.val << M::BITS,
             bits: self.bits + M::BITS,
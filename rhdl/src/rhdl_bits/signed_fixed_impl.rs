@@ -0,0 +1,406 @@
+use std::ops::{Add, Sub};
+
+use super::{
+    BitWidth, SignedBits, fixed_impl::Fixed, fixed_impl::Rounding, signed, xadd::XAdd, xmul::XMul,
+    xneg::XNeg, xsub::XSub,
+};
+use crate::rhdl_typenum::prelude::*;
+
+/// The [SignedFixed] type is a signed (2's complement) fixed point
+/// value with `I` integer bits (including the sign bit) and `F`
+/// fractional bits.  It is stored as a `I + F` bit signed value `raw`,
+/// and represents the real number `raw / 2^F`.  So a
+/// `SignedFixed<U2, U6>` covers the range `[-2, 2)` in steps of `1/64`.
+///
+/// See [Fixed] for the operations that are available.  As with
+/// [SignedBits], comparisons are signed, and right shifts sign extend.
+/// ```
+/// # use rhdl::bits::{SignedFixed, signed_fixed, consts::*, Rounding, xmul::XMul};
+/// let a: SignedFixed<U2, U6> = signed_fixed(-1.5);
+/// let b: SignedFixed<U4, U4> = signed_fixed(2.25);
+/// let c = a.xmul(b);
+/// assert_eq!(c.to_f64(), -3.375);
+/// let d: SignedFixed<U3, U2> = c.quantize::<U2>(Rounding::HalfUp).saturate::<U3>();
+/// assert_eq!(d.to_f64(), -3.25);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct SignedFixed<I, F> {
+    pub(crate) marker: std::marker::PhantomData<(I, F)>,
+    pub(crate) val: i128,
+}
+
+/// Construct a [SignedFixed] value from a floating point number.  The
+/// value is rounded to the nearest representable value.  Panics
+/// if the value is out of range.
+/// ```
+/// # use rhdl::bits::{SignedFixed, signed_fixed, consts::*};
+/// let x: SignedFixed<U4, U2> = signed_fixed(-3.25);
+/// assert_eq!(x.raw(), -13);
+/// ```
+pub fn signed_fixed<I: BitWidth, F: BitWidth>(val: f64) -> SignedFixed<I, F> {
+    SignedFixed::from_f64(val)
+}
+
+// Drop `shift` bits from the bottom of `val`, rounding as requested.
+pub(crate) const fn round_shr_i128(val: i128, shift: usize, mode: Rounding) -> i128 {
+    if shift == 0 {
+        return val;
+    }
+    let quotient = val >> shift;
+    let remainder = val & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    let round_up = match mode {
+        Rounding::Truncate => false,
+        Rounding::TowardZero => val < 0 && remainder != 0,
+        Rounding::HalfUp => remainder >= half,
+        Rounding::HalfEven => remainder > half || (remainder == half && quotient & 1 == 1),
+    };
+    quotient + round_up as i128
+}
+
+impl<I: BitWidth, F: BitWidth> SignedFixed<I, F> {
+    /// The number of integer bits (including the sign bit).
+    pub const INTEGER_BITS: usize = I::BITS;
+    /// The number of fractional bits.
+    pub const FRACTION_BITS: usize = F::BITS;
+    pub const ZERO: Self = Self::from_raw(0);
+    /// The largest value that can be represented (`2^(I-1) - 2^-F`).
+    pub const MAX: Self = Self::from_raw(Self::max_raw());
+    /// The most negative value that can be represented (`-2^(I-1)`).
+    pub const MIN: Self = Self::from_raw(Self::min_raw());
+    /// The smallest positive value that can be represented (`2^-F`).
+    pub const EPSILON: Self = Self::from_raw(1);
+    const fn width() -> usize {
+        assert!(
+            I::BITS + F::BITS <= 128,
+            "SignedFixed is limited to 128 bits in total"
+        );
+        I::BITS + F::BITS
+    }
+    const fn max_raw() -> i128 {
+        i128::MAX >> (128 - Self::width())
+    }
+    const fn min_raw() -> i128 {
+        i128::MIN >> (128 - Self::width())
+    }
+    pub(crate) const fn from_raw(val: i128) -> Self {
+        // Sign extend from the top bit of the value
+        let shift = 128 - Self::width();
+        Self {
+            marker: std::marker::PhantomData,
+            val: (val << shift) >> shift,
+        }
+    }
+    /// Construct a [SignedFixed] value from the raw bits, which are
+    /// interpreted as a value of `bits / 2^F`.
+    /// ```
+    /// # use rhdl::bits::{SignedFixed, consts::*, alias::*};
+    /// let x = SignedFixed::<U4, U4>::from_signed_bits(s8(-0x18));
+    /// assert_eq!(x.to_f64(), -1.5);
+    /// ```
+    pub const fn from_signed_bits(val: SignedBits<op!(I + F)>) -> Self
+    where
+        I: Add<F>,
+        op!(I + F): BitWidth,
+    {
+        Self::from_raw(val.val)
+    }
+    /// Return the raw bits that make up this value.
+    pub const fn as_signed_bits(self) -> SignedBits<op!(I + F)>
+    where
+        I: Add<F>,
+        op!(I + F): BitWidth,
+    {
+        signed(self.val)
+    }
+    /// Extract the raw `i128` behind the [SignedFixed] value.
+    pub const fn raw(self) -> i128 {
+        self.val
+    }
+    /// Test if the value is negative.
+    pub const fn is_negative(self) -> bool {
+        self.val < 0
+    }
+    /// Convert a floating point value to the nearest [SignedFixed] value.
+    /// Panics if the value is out of range.
+    pub fn from_f64(val: f64) -> Self {
+        let scaled = (val * (F::BITS as f64).exp2()).round();
+        assert!(
+            scaled >= Self::min_raw() as f64 && scaled <= Self::max_raw() as f64,
+            "value {val} is out of range for SignedFixed<{}, {}>",
+            I::BITS,
+            F::BITS
+        );
+        Self::from_raw(scaled as i128)
+    }
+    /// Convert the value to a floating point number.  This is exact
+    /// as long as the value fits in the mantissa of an `f64`.
+    pub fn to_f64(self) -> f64 {
+        self.val as f64 / (F::BITS as f64).exp2()
+    }
+    /// Reinterpret the bits of the value as an unsigned [Fixed] value.
+    pub const fn as_unsigned(self) -> Fixed<I, F> {
+        Fixed::from_raw(self.val as u128)
+    }
+    /// Add `M` integer bits to the value (with sign extension).  This is exact.
+    pub const fn xext<M>(self) -> SignedFixed<op!(I + M), F>
+    where
+        I: Add<M>,
+        op!(I + M): BitWidth,
+    {
+        SignedFixed::from_raw(self.val)
+    }
+    /// Add `M` fractional bits to the value.  This is exact.
+    pub const fn xshl<M>(self) -> SignedFixed<I, op!(F + M)>
+    where
+        F: Add<M>,
+        M: BitWidth,
+        op!(F + M): BitWidth,
+    {
+        SignedFixed::from_raw(self.val << M::BITS)
+    }
+    /// Drop `M` fractional bits from the value.  This truncates the
+    /// value (towards negative infinity).  See
+    /// [quantize](SignedFixed::quantize) for other rounding modes.
+    pub const fn xshr<M>(self) -> SignedFixed<I, op!(F - M)>
+    where
+        F: Sub<M>,
+        M: BitWidth,
+        op!(F - M): BitWidth,
+    {
+        SignedFixed::from_raw(self.val >> M::BITS)
+    }
+    /// Change the number of fractional bits to `F2`.  If bits
+    /// are dropped, the value is rounded using the given mode.
+    /// The integer part wraps if rounding overflows it, so
+    /// use [xext](SignedFixed::xext) first if that matters.
+    /// ```
+    /// # use rhdl::bits::{SignedFixed, signed_fixed, consts::*, Rounding};
+    /// let x: SignedFixed<U4, U4> = signed_fixed(-2.625);
+    /// assert_eq!(x.quantize::<U2>(Rounding::Truncate).to_f64(), -2.75);
+    /// assert_eq!(x.quantize::<U2>(Rounding::TowardZero).to_f64(), -2.5);
+    /// assert_eq!(x.quantize::<U2>(Rounding::HalfUp).to_f64(), -2.5);
+    /// assert_eq!(x.quantize::<U2>(Rounding::HalfEven).to_f64(), -2.5);
+    /// ```
+    pub const fn quantize<F2: BitWidth>(self, mode: Rounding) -> SignedFixed<I, F2> {
+        if F2::BITS >= F::BITS {
+            SignedFixed::from_raw(self.val << (F2::BITS - F::BITS))
+        } else {
+            SignedFixed::from_raw(round_shr_i128(self.val, F::BITS - F2::BITS, mode))
+        }
+    }
+    /// Change the number of integer bits to `I2`, clamping the
+    /// value to the representable range if it does not fit.
+    /// ```
+    /// # use rhdl::bits::{SignedFixed, signed_fixed, consts::*};
+    /// let x: SignedFixed<U5, U4> = signed_fixed(-9.5);
+    /// assert_eq!(x.saturate::<U4>().to_f64(), -8.0);
+    /// assert_eq!(x.saturate::<U6>().to_f64(), -9.5);
+    /// ```
+    pub const fn saturate<I2: BitWidth>(self) -> SignedFixed<I2, F> {
+        if self.val > SignedFixed::<I2, F>::max_raw() {
+            SignedFixed::<I2, F>::MAX
+        } else if self.val < SignedFixed::<I2, F>::min_raw() {
+            SignedFixed::<I2, F>::MIN
+        } else {
+            SignedFixed::from_raw(self.val)
+        }
+    }
+    /// Build a (dynamic, stack allocated) vector containing
+    /// the bits that make up this value.
+    pub fn to_bools(self) -> Vec<bool> {
+        (0..Self::width())
+            .map(|i| self.val & (1 << i) != 0)
+            .collect()
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::fmt::Debug for SignedFixed<I, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}_sq{}.{}", self.to_f64(), I::BITS, F::BITS)
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::fmt::Display for SignedFixed<I, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_f64())
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::cmp::PartialOrd for SignedFixed<I, F> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::cmp::Ord for SignedFixed<I, F> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.val.cmp(&other.val)
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::ops::Add for SignedFixed<I, F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::from_raw(self.val.wrapping_add(rhs.val))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::ops::AddAssign for SignedFixed<I, F> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::ops::Sub for SignedFixed<I, F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::from_raw(self.val.wrapping_sub(rhs.val))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::ops::SubAssign for SignedFixed<I, F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::ops::Neg for SignedFixed<I, F> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::from_raw(self.val.wrapping_neg())
+    }
+}
+
+impl<I1, I2, F> XAdd<SignedFixed<I2, F>> for SignedFixed<I1, F>
+where
+    I1: Max<I2> + BitWidth,
+    I2: BitWidth,
+    F: BitWidth,
+    Maximum<I1, I2>: Add<U1>,
+    op!(max(I1, I2) + U1): BitWidth,
+{
+    type Output = SignedFixed<op!(max(I1, I2) + U1), F>;
+    fn xadd(self, rhs: SignedFixed<I2, F>) -> Self::Output {
+        SignedFixed::from_raw(self.val.wrapping_add(rhs.val))
+    }
+}
+
+impl<I1, I2, F> XSub<SignedFixed<I2, F>> for SignedFixed<I1, F>
+where
+    I1: Max<I2> + BitWidth,
+    I2: BitWidth,
+    F: BitWidth,
+    Maximum<I1, I2>: Add<U1>,
+    op!(max(I1, I2) + U1): BitWidth,
+{
+    type Output = SignedFixed<op!(max(I1, I2) + U1), F>;
+    fn xsub(self, rhs: SignedFixed<I2, F>) -> Self::Output {
+        SignedFixed::from_raw(self.val.wrapping_sub(rhs.val))
+    }
+}
+
+impl<I1, I2, F1, F2> XMul<SignedFixed<I2, F2>> for SignedFixed<I1, F1>
+where
+    I1: Add<I2> + BitWidth,
+    I2: BitWidth,
+    F1: Add<F2> + BitWidth,
+    F2: BitWidth,
+    op!(I1 + I2): BitWidth,
+    op!(F1 + F2): BitWidth,
+{
+    type Output = SignedFixed<op!(I1 + I2), op!(F1 + F2)>;
+    fn xmul(self, rhs: SignedFixed<I2, F2>) -> Self::Output {
+        SignedFixed::from_raw(self.val.wrapping_mul(rhs.val))
+    }
+}
+
+impl<I, F> XNeg for SignedFixed<I, F>
+where
+    I: Add<U1> + BitWidth,
+    F: BitWidth,
+    op!(I + U1): BitWidth,
+{
+    type Output = SignedFixed<op!(I + U1), F>;
+    fn xneg(self) -> Self::Output {
+        SignedFixed::from_raw(self.val.wrapping_neg())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rhdl_bits::alias::*;
+
+    #[test]
+    fn test_signed_fixed_conversions() {
+        let x: SignedFixed<U4, U4> = signed_fixed(-3.75);
+        assert_eq!(x.raw(), -0x3C);
+        assert_eq!(x.as_signed_bits(), s8(-0x3C));
+        assert_eq!(x.as_unsigned().raw(), 0xC4);
+        assert_eq!(x.to_f64(), -3.75);
+        assert_eq!(SignedFixed::<U4, U4>::MAX.to_f64(), 7.9375);
+        assert_eq!(SignedFixed::<U4, U4>::MIN.to_f64(), -8.0);
+        assert_eq!(format!("{x}"), "-3.75");
+        assert_eq!(format!("{x:?}"), "-3.75_sq4.4");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_signed_fixed_out_of_range() {
+        let _: SignedFixed<U4, U4> = signed_fixed(8.0);
+    }
+
+    #[test]
+    fn test_signed_fixed_wrapping_arithmetic() {
+        let x: SignedFixed<U4, U4> = signed_fixed(7.5);
+        let y: SignedFixed<U4, U4> = signed_fixed(1.0);
+        assert_eq!((x + y).to_f64(), -7.5);
+        assert_eq!((-x - y - y).to_f64(), 6.5);
+        assert!(-x < y);
+    }
+
+    #[test]
+    fn test_signed_fixed_exact_arithmetic() {
+        for a in -128..=127 {
+            let x = SignedFixed::<U4, U4>::from_raw(a);
+            for b in -32..=31 {
+                let y = SignedFixed::<U2, U4>::from_raw(b);
+                assert_eq!(x.xadd(y).to_f64(), x.to_f64() + y.to_f64());
+                assert_eq!(x.xsub(y).to_f64(), x.to_f64() - y.to_f64());
+                let z = SignedFixed::<U2, U2>::from_raw(b >> 2);
+                assert_eq!(x.xmul(z).to_f64(), x.to_f64() * z.to_f64());
+            }
+            assert_eq!(x.xneg().to_f64(), -x.to_f64());
+            assert_eq!(x.xext::<U2>().to_f64(), x.to_f64());
+            assert_eq!(x.xshl::<U2>().to_f64(), x.to_f64());
+            assert_eq!(x.xshr::<U2>().to_f64(), (x.to_f64() * 4.0).floor() / 4.0);
+        }
+    }
+
+    #[test]
+    fn test_signed_fixed_rounding() {
+        for a in -128..=127 {
+            let x = SignedFixed::<U4, U4>::from_raw(a);
+            let v = x.to_f64() * 2.0;
+            let check = |mode: Rounding, expected: f64| {
+                // Wrap the expected value into the range [-8, 8)
+                let expected = (expected / 2.0 + 8.0).rem_euclid(16.0) - 8.0;
+                assert_eq!(x.quantize::<U1>(mode).to_f64(), expected, "{x:?} {mode:?}");
+            };
+            check(Rounding::Truncate, v.floor());
+            check(Rounding::TowardZero, v.trunc());
+            check(Rounding::HalfUp, (v + 0.5).floor());
+            check(Rounding::HalfEven, v.round_ties_even());
+        }
+    }
+
+    #[test]
+    fn test_signed_fixed_saturation() {
+        for a in -128..=127 {
+            let x = SignedFixed::<U4, U4>::from_raw(a);
+            assert_eq!(x.saturate::<U2>().to_f64(), x.to_f64().clamp(-2.0, 1.9375));
+            assert_eq!(x.saturate::<U6>().to_f64(), x.to_f64());
+        }
+    }
+}
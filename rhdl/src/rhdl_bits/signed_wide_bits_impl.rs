@@ -0,0 +1,454 @@
+#![allow(non_camel_case_types)]
+use std::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Mul, MulAssign,
+    Neg, Not, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+};
+
+use num_bigint::{BigInt, Sign};
+
+use super::{
+    BitWidth, Bits, SignedBits,
+    signed_bits_impl::signed_wrapped,
+    wide_bits_impl::{
+        WideBits, bits_add, bits_from_u128, bits_mul, bits_not, bits_resize, bits_shl, bits_shr,
+        bits_sub, bits_to_biguint, bits_to_u128, bits_zip,
+    },
+};
+
+/// The [SignedWideBits] type is the signed counterpart of
+/// [WideBits], for signed values that are wider than the
+/// 128 bit limit of [SignedBits].  The width is given as a
+/// const generic, so a 256 bit signed value is a
+/// `SignedWideBits<256>`.
+///
+/// As with [SignedBits], arithmetic is 2's complement wrapping
+/// arithmetic, comparisons are signed and right shifts sign
+/// extend the value.
+/// ```
+/// # use rhdl::bits::{SignedWideBits, wide_signed};
+/// let x: SignedWideBits<256> = wide_signed(-5);
+/// let y = x * x;
+/// assert_eq!(y, 25);
+/// assert!(x < y);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SignedWideBits<const N: usize> {
+    pub(crate) bits: [bool; N],
+}
+
+/// Helper function for creating a [SignedWideBits] value from
+/// a constant.  The value must fit in the width of the result.
+/// ```
+/// # use rhdl::bits::{SignedWideBits, wide_signed};
+/// const VALUE: SignedWideBits<256> = wide_signed(-42);
+/// assert_eq!(VALUE, -42);
+/// ```
+pub const fn wide_signed<const N: usize>(value: i128) -> SignedWideBits<N> {
+    assert!(N >= 128 || (value >> (N - 1) == 0 || value >> (N - 1) == -1));
+    SignedWideBits {
+        bits: bits_from_u128(value as u128, value < 0),
+    }
+}
+
+pub struct wide_signed<const N: usize> {
+    _private: (),
+}
+
+macro_rules! wide_signed_alias {
+    ($($name: ident => $width: expr),*) => {
+        $(
+            pub type $name = SignedWideBits<$width>;
+            pub const fn $name(value: i128) -> $name {
+                wide_signed::<$width>(value)
+            }
+        )*
+    };
+}
+
+wide_signed_alias!(s256 => 256, s512 => 512, s1024 => 1024);
+
+impl<const N: usize> SignedWideBits<N> {
+    pub const ZERO: Self = Self { bits: [false; N] };
+    /// The largest positive value that fits in the width.
+    pub const MAX: Self = {
+        let mut bits = [true; N];
+        bits[N - 1] = false;
+        Self { bits }
+    };
+    /// The smallest negative value that fits in the width.
+    pub const MIN: Self = {
+        let mut bits = [false; N];
+        bits[N - 1] = true;
+        Self { bits }
+    };
+    /// Test if the value is negative.
+    pub const fn is_negative(&self) -> bool {
+        self.bits[N - 1]
+    }
+    /// Test if the value is positive or zero.
+    pub const fn is_non_negative(&self) -> bool {
+        !self.is_negative()
+    }
+    /// Reinterpret the [SignedWideBits] value as an unsigned
+    /// [WideBits] value.
+    pub const fn as_unsigned(self) -> WideBits<N> {
+        WideBits { bits: self.bits }
+    }
+    /// Build a (dynamic, heap allocated) vector containing
+    /// the bits that make up this value, in LSB first order.
+    pub fn to_bools(self) -> Vec<bool> {
+        self.bits.to_vec()
+    }
+    /// Convert the value into an arbitrary precision signed integer.
+    pub fn to_bigint(self) -> BigInt {
+        let magnitude = bits_to_biguint(&self.bits);
+        if self.is_negative() {
+            BigInt::from_biguint(Sign::Plus, magnitude) - (BigInt::from(1) << N)
+        } else {
+            BigInt::from_biguint(Sign::Plus, magnitude)
+        }
+    }
+    /// Convert an arbitrary precision signed integer into a
+    /// [SignedWideBits] value, keeping only the lowest `N` bits
+    /// of its 2's complement representation.
+    pub fn from_bigint(value: &BigInt) -> Self {
+        let modulus = BigInt::from(1) << N;
+        let (_, magnitude) = (((value % &modulus) + &modulus) % &modulus).into_parts();
+        Self {
+            bits: std::array::from_fn(|ndx| magnitude.bit(ndx as u64)),
+        }
+    }
+    /// Sign extend or truncate the value to a new width.
+    /// ```
+    /// # use rhdl::bits::{SignedWideBits, wide_signed};
+    /// let x: SignedWideBits<256> = wide_signed(-7);
+    /// let y: SignedWideBits<512> = x.resize();
+    /// assert_eq!(y, -7);
+    /// ```
+    pub fn resize<const M: usize>(self) -> SignedWideBits<M> {
+        SignedWideBits {
+            bits: bits_resize(&self.bits, self.is_negative()),
+        }
+    }
+    /// Truncate (or sign extend) the value into a [SignedBits] value.
+    pub fn narrow<M: BitWidth>(self) -> SignedBits<M> {
+        let fill = self.is_negative();
+        let bits: [bool; 128] = bits_resize(&self.bits, fill);
+        signed_wrapped(bits_to_u128(&bits) as i128)
+    }
+    pub fn any(self) -> bool {
+        self.bits.iter().any(|b| *b)
+    }
+    pub fn all(self) -> bool {
+        self.bits.iter().all(|b| *b)
+    }
+    pub fn xor(self) -> bool {
+        self.bits.iter().fold(false, |acc, b| acc ^ b)
+    }
+}
+
+/// The default value for a [SignedWideBits] value is 0.
+impl<const N: usize> Default for SignedWideBits<N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+/// Provide conversion from an `i128` to a [SignedWideBits] value.
+/// Any `i128` fits when `N >= 128`.  For narrower widths, this will
+/// panic if the value does not fit (it is never truncated).
+impl<const N: usize> From<i128> for SignedWideBits<N> {
+    fn from(value: i128) -> Self {
+        wide_signed(value)
+    }
+}
+
+impl<const N: usize> std::cmp::PartialOrd for SignedWideBits<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> std::cmp::Ord for SignedWideBits<N> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // A negative value is smaller than any non-negative one.  With
+        // matching signs, the 2's complement bits compare as unsigned.
+        other
+            .is_negative()
+            .cmp(&self.is_negative())
+            .then_with(|| self.as_unsigned().cmp(&other.as_unsigned()))
+    }
+}
+
+impl<const N: usize> PartialEq<i128> for SignedWideBits<N> {
+    fn eq(&self, other: &i128) -> bool {
+        *self == wide_signed::<N>(*other)
+    }
+}
+
+impl<const N: usize> PartialOrd<i128> for SignedWideBits<N> {
+    fn partial_cmp(&self, other: &i128) -> Option<std::cmp::Ordering> {
+        self.partial_cmp(&wide_signed::<N>(*other))
+    }
+}
+
+impl<const N: usize> std::fmt::Debug for SignedWideBits<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}_s{}", self.to_bigint(), N)
+    }
+}
+
+impl<const N: usize> std::fmt::Display for SignedWideBits<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let val = self.to_bigint();
+        if self.is_negative() {
+            write!(f, "-{}'sd{}", N, -val)
+        } else {
+            write!(f, "{}'sd{}", N, val)
+        }
+    }
+}
+
+impl<const N: usize> std::fmt::LowerHex for SignedWideBits<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let val = self.to_bigint();
+        if self.is_negative() {
+            write!(f, "-{}'sh{:x}", N, -val)
+        } else {
+            write!(f, "{}'sh{:x}", N, val)
+        }
+    }
+}
+
+impl<const N: usize> std::fmt::UpperHex for SignedWideBits<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let val = self.to_bigint();
+        if self.is_negative() {
+            write!(f, "-{}'sH{:X}", N, -val)
+        } else {
+            write!(f, "{}'sH{:X}", N, val)
+        }
+    }
+}
+
+impl<const N: usize> std::fmt::Binary for SignedWideBits<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let val = self.to_bigint();
+        if self.is_negative() {
+            write!(f, "-{}'sb{:b}", N, -val)
+        } else {
+            write!(f, "{}'sb{:b}", N, val)
+        }
+    }
+}
+
+macro_rules! impl_signed_wide_binop {
+    ($trait: ident, $op: ident, $assign_trait: ident, $assign_op: ident, $func: expr) => {
+        impl<const N: usize> $trait for SignedWideBits<N> {
+            type Output = Self;
+            fn $op(self, rhs: Self) -> Self::Output {
+                SignedWideBits {
+                    bits: $func(&self.bits, &rhs.bits),
+                }
+            }
+        }
+        impl<const N: usize> $trait<i128> for SignedWideBits<N> {
+            type Output = Self;
+            fn $op(self, rhs: i128) -> Self::Output {
+                self.$op(wide_signed::<N>(rhs))
+            }
+        }
+        impl<const N: usize> $trait<SignedWideBits<N>> for i128 {
+            type Output = SignedWideBits<N>;
+            fn $op(self, rhs: SignedWideBits<N>) -> Self::Output {
+                wide_signed::<N>(self).$op(rhs)
+            }
+        }
+        impl<const N: usize> $assign_trait for SignedWideBits<N> {
+            fn $assign_op(&mut self, rhs: Self) {
+                *self = self.$op(rhs);
+            }
+        }
+        impl<const N: usize> $assign_trait<i128> for SignedWideBits<N> {
+            fn $assign_op(&mut self, rhs: i128) {
+                *self = self.$op(rhs);
+            }
+        }
+    };
+}
+
+// The low N bits of a 2's complement sum, difference or product
+// do not depend on the signedness of the arguments.
+impl_signed_wide_binop!(Add, add, AddAssign, add_assign, |a, b| bits_add(
+    a, b, false
+));
+impl_signed_wide_binop!(Sub, sub, SubAssign, sub_assign, bits_sub);
+impl_signed_wide_binop!(Mul, mul, MulAssign, mul_assign, bits_mul);
+impl_signed_wide_binop!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| {
+    bits_zip(a, b, |x, y| x & y)
+});
+impl_signed_wide_binop!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| {
+    bits_zip(a, b, |x, y| x | y)
+});
+impl_signed_wide_binop!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| {
+    bits_zip(a, b, |x, y| x ^ y)
+});
+
+impl<const N: usize> Not for SignedWideBits<N> {
+    type Output = Self;
+    fn not(self) -> Self::Output {
+        SignedWideBits {
+            bits: bits_not(&self.bits),
+        }
+    }
+}
+
+impl<const N: usize> Neg for SignedWideBits<N> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::ZERO - self
+    }
+}
+
+fn shift_amount(shift: u128) -> usize {
+    usize::try_from(shift).unwrap_or(usize::MAX)
+}
+
+impl<const N: usize> Shl<u128> for SignedWideBits<N> {
+    type Output = Self;
+    fn shl(self, rhs: u128) -> Self::Output {
+        SignedWideBits {
+            bits: bits_shl(&self.bits, shift_amount(rhs)),
+        }
+    }
+}
+
+impl<const N: usize, M: BitWidth> Shl<Bits<M>> for SignedWideBits<N> {
+    type Output = Self;
+    fn shl(self, rhs: Bits<M>) -> Self::Output {
+        self << rhs.raw()
+    }
+}
+
+impl<const N: usize> Shr<u128> for SignedWideBits<N> {
+    type Output = Self;
+    fn shr(self, rhs: u128) -> Self::Output {
+        SignedWideBits {
+            bits: bits_shr(&self.bits, shift_amount(rhs), self.is_negative()),
+        }
+    }
+}
+
+impl<const N: usize, M: BitWidth> Shr<Bits<M>> for SignedWideBits<N> {
+    type Output = Self;
+    fn shr(self, rhs: Bits<M>) -> Self::Output {
+        self >> rhs.raw()
+    }
+}
+
+impl<const N: usize> ShlAssign<u128> for SignedWideBits<N> {
+    fn shl_assign(&mut self, rhs: u128) {
+        *self = *self << rhs;
+    }
+}
+
+impl<const N: usize, M: BitWidth> ShlAssign<Bits<M>> for SignedWideBits<N> {
+    fn shl_assign(&mut self, rhs: Bits<M>) {
+        *self = *self << rhs;
+    }
+}
+
+impl<const N: usize> ShrAssign<u128> for SignedWideBits<N> {
+    fn shr_assign(&mut self, rhs: u128) {
+        *self = *self >> rhs;
+    }
+}
+
+impl<const N: usize, M: BitWidth> ShrAssign<Bits<M>> for SignedWideBits<N> {
+    fn shr_assign(&mut self, rhs: Bits<M>) {
+        *self = *self >> rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rhdl_bits::alias::*;
+    use crate::rhdl_bits::bitwidth::*;
+
+    #[test]
+    #[should_panic]
+    fn test_narrow_wide_signed_out_of_range() {
+        let _: SignedWideBits<8> = 128_i128.into();
+    }
+
+    #[test]
+    fn test_narrow_wide_signed_in_range() {
+        let x: SignedWideBits<8> = (-128_i128).into();
+        assert_eq!(x, -128);
+    }
+
+    #[test]
+    fn test_bigint_round_trip() {
+        for val in [-1_i128, 0, 1, i128::MIN, i128::MAX, -12345] {
+            let x: SignedWideBits<256> = wide_signed(val);
+            assert_eq!(x.to_bigint(), BigInt::from(val));
+            assert_eq!(SignedWideBits::<256>::from_bigint(&x.to_bigint()), x);
+        }
+        assert_eq!(
+            SignedWideBits::<256>::MIN.to_bigint(),
+            -(BigInt::from(1) << 255_usize)
+        );
+        assert_eq!(
+            SignedWideBits::<256>::MAX.to_bigint(),
+            (BigInt::from(1) << 255_usize) - 1
+        );
+    }
+
+    #[test]
+    fn test_cmp_is_signed() {
+        let a: SignedWideBits<256> = wide_signed(-1);
+        let b: SignedWideBits<256> = wide_signed(1);
+        assert!(a < b);
+        assert!(SignedWideBits::<256>::MIN < a);
+        assert!(SignedWideBits::<256>::MAX > b);
+        assert!(a.as_unsigned() > b.as_unsigned());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a: SignedWideBits<200> = wide_signed(i128::MIN);
+        let b = a + a;
+        assert_eq!(b.to_bigint(), BigInt::from(i128::MIN) * 2);
+        assert_eq!((-b).to_bigint(), BigInt::from(i128::MIN) * -2);
+        assert_eq!(b - b, 0);
+        assert_eq!(wide_signed::<200>(-3) * wide_signed::<200>(7), -21);
+        assert_eq!(SignedWideBits::<200>::MAX + 1, SignedWideBits::<200>::MIN);
+    }
+
+    #[test]
+    fn test_shr_sign_extends() {
+        let a: SignedWideBits<256> = wide_signed(-256);
+        assert_eq!(a >> 4_u128, -16);
+        assert_eq!(a >> 300_u128, -1);
+        assert_eq!(a << b8(1), -512);
+    }
+
+    #[test]
+    fn test_narrow_and_wide() {
+        let a = s16(-1234);
+        let w: SignedWideBits<512> = a.wide();
+        assert_eq!(w, -1234);
+        assert_eq!(w.narrow::<U16>(), a);
+        assert_eq!(w.narrow::<U128>().raw(), -1234);
+        let r: SignedWideBits<130> = w.resize();
+        assert_eq!(r, -1234);
+    }
+
+    #[test]
+    fn test_format() {
+        let a: SignedWideBits<256> = wide_signed(-42);
+        assert_eq!(format!("{a}"), "-256'sd42");
+        assert_eq!(format!("{a:?}"), "-42_s256");
+    }
+}
@@ -0,0 +1,520 @@
+#![allow(non_camel_case_types)]
+use std::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Mul, MulAssign,
+    Not, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+};
+
+use num_bigint::BigUint;
+
+use super::{
+    BitWidth, Bits, SignedBits, bits_impl::bits_masked, signed_wide_bits_impl::SignedWideBits,
+};
+
+/// The [WideBits] type is a fixed-size bit vector for widths
+/// that do not fit into the 128 bit limit of the [Bits] type.
+/// Think 256 bit AXI data paths, 512 bit hash states or wide
+/// CRC registers.  Unlike [Bits], the width is given as a
+/// const generic, so a 256 bit value is a `WideBits<256>`.
+///
+/// The value is stored one bit per element, so it is a good
+/// deal slower than [Bits] in simulation.  You should keep to
+/// [Bits] for anything that fits in 128 bits, and use
+/// [Bits::wide] and [WideBits::narrow] to move between the two.
+///
+/// As with [Bits], all arithmetic is 2's complement wrapping
+/// arithmetic, and comparisons are unsigned.
+/// ```
+/// # use rhdl::bits::{WideBits, wide_bits};
+/// let x: WideBits<256> = wide_bits(u128::MAX);
+/// let y = x + x;
+/// assert_eq!(y.narrow::<rhdl::bits::consts::U128>(), u128::MAX - 1);
+/// assert!(y > x);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WideBits<const N: usize> {
+    pub(crate) bits: [bool; N],
+}
+
+/// Helper function for creating a [WideBits] value from a
+/// constant.  The value must fit in the width of the result.
+/// ```
+/// # use rhdl::bits::{WideBits, wide_bits};
+/// const VALUE: WideBits<256> = wide_bits(0xDEAD_BEEF);
+/// assert_eq!(VALUE, 0xDEAD_BEEF);
+/// ```
+pub const fn wide_bits<const N: usize>(value: u128) -> WideBits<N> {
+    assert!(N >= 128 || value >> N == 0);
+    WideBits {
+        bits: bits_from_u128(value, false),
+    }
+}
+
+pub struct wide_bits<const N: usize> {
+    _private: (),
+}
+
+macro_rules! wide_alias {
+    ($($name: ident => $width: expr),*) => {
+        $(
+            pub type $name = WideBits<$width>;
+            pub const fn $name(value: u128) -> $name {
+                wide_bits::<$width>(value)
+            }
+        )*
+    };
+}
+
+wide_alias!(b256 => 256, b512 => 512, b1024 => 1024);
+
+// Fill an array of bits with a 128 bit value, extending it
+// with `fill` past the 128th bit.
+pub(crate) const fn bits_from_u128<const N: usize>(value: u128, fill: bool) -> [bool; N] {
+    let mut bits = [fill; N];
+    let mut ndx = 0;
+    while ndx < N && ndx < 128 {
+        bits[ndx] = (value >> ndx) & 1 == 1;
+        ndx += 1;
+    }
+    bits
+}
+
+pub(crate) fn bits_add<const N: usize>(a: &[bool; N], b: &[bool; N], carry: bool) -> [bool; N] {
+    let mut carry = carry;
+    std::array::from_fn(|ndx| {
+        let (a, b) = (a[ndx], b[ndx]);
+        let sum = a ^ b ^ carry;
+        carry = (a & b) | (a & carry) | (b & carry);
+        sum
+    })
+}
+
+pub(crate) fn bits_not<const N: usize>(a: &[bool; N]) -> [bool; N] {
+    a.map(|x| !x)
+}
+
+pub(crate) fn bits_sub<const N: usize>(a: &[bool; N], b: &[bool; N]) -> [bool; N] {
+    bits_add(a, &bits_not(b), true)
+}
+
+pub(crate) fn bits_mul<const N: usize>(a: &[bool; N], b: &[bool; N]) -> [bool; N] {
+    (0..N).filter(|&ndx| b[ndx]).fold([false; N], |acc, ndx| {
+        bits_add(&acc, &bits_shl(a, ndx), false)
+    })
+}
+
+pub(crate) fn bits_zip<const N: usize>(
+    a: &[bool; N],
+    b: &[bool; N],
+    f: impl Fn(bool, bool) -> bool,
+) -> [bool; N] {
+    std::array::from_fn(|ndx| f(a[ndx], b[ndx]))
+}
+
+pub(crate) fn bits_shl<const N: usize>(a: &[bool; N], shift: usize) -> [bool; N] {
+    std::array::from_fn(|ndx| ndx >= shift && a[ndx - shift])
+}
+
+// Shift right, with `fill` inserted at the MSB.
+pub(crate) fn bits_shr<const N: usize>(a: &[bool; N], shift: usize, fill: bool) -> [bool; N] {
+    std::array::from_fn(|ndx| match ndx.checked_add(shift) {
+        Some(src) if src < N => a[src],
+        _ => fill,
+    })
+}
+
+// Copy the bits into a new width, with `fill` used for any new bits.
+pub(crate) fn bits_resize<const N: usize, const M: usize>(a: &[bool; N], fill: bool) -> [bool; M] {
+    std::array::from_fn(|ndx| if ndx < N { a[ndx] } else { fill })
+}
+
+pub(crate) fn bits_to_u128(a: &[bool]) -> u128 {
+    a.iter()
+        .take(128)
+        .rev()
+        .fold(0, |acc, b| (acc << 1) | (*b as u128))
+}
+
+pub(crate) fn bits_to_biguint(a: &[bool]) -> BigUint {
+    let digits = a.iter().map(|b| *b as u8).collect::<Vec<_>>();
+    BigUint::from_radix_le(&digits, 2).unwrap_or_default()
+}
+
+// Shift amounts are clamped, since anything past the width
+// of the value shifts out all of the bits anyway.
+fn shift_amount(shift: u128) -> usize {
+    usize::try_from(shift).unwrap_or(usize::MAX)
+}
+
+impl<const N: usize> WideBits<N> {
+    /// A [WideBits] value with all bits set to 1.
+    pub const MASK: Self = Self { bits: [true; N] };
+    pub const MAX: Self = Self::MASK;
+    pub const ZERO: Self = Self { bits: [false; N] };
+    /// Return a [WideBits] value with all bits set to 1.
+    pub const fn mask() -> Self {
+        Self::MASK
+    }
+    /// Build a [WideBits] value from a slice of bools, in LSB
+    /// first order.  The slice must have exactly `N` elements.
+    pub fn from_bools(bits: &[bool]) -> Self {
+        assert_eq!(bits.len(), N);
+        Self {
+            bits: std::array::from_fn(|ndx| bits[ndx]),
+        }
+    }
+    /// Build a (dynamic, heap allocated) vector containing
+    /// the bits that make up this value, in LSB first order.
+    pub fn to_bools(self) -> Vec<bool> {
+        self.bits.to_vec()
+    }
+    /// Convert the value into an arbitrary precision unsigned integer.
+    pub fn to_biguint(self) -> BigUint {
+        bits_to_biguint(&self.bits)
+    }
+    /// Convert an arbitrary precision unsigned integer into a
+    /// [WideBits] value, keeping only the lowest `N` bits.
+    pub fn from_biguint(value: &BigUint) -> Self {
+        Self {
+            bits: std::array::from_fn(|ndx| value.bit(ndx as u64)),
+        }
+    }
+    /// Zero extend or truncate the value to a new width.
+    /// ```
+    /// # use rhdl::bits::{WideBits, wide_bits};
+    /// let x: WideBits<256> = wide_bits(0xDEAD_BEEF);
+    /// let y: WideBits<512> = x.resize();
+    /// assert_eq!(y, 0xDEAD_BEEF);
+    /// ```
+    pub fn resize<const M: usize>(self) -> WideBits<M> {
+        WideBits {
+            bits: bits_resize(&self.bits, false),
+        }
+    }
+    /// Truncate (or zero extend) the value into a [Bits] value.
+    pub fn narrow<M: BitWidth>(self) -> Bits<M> {
+        bits_masked(bits_to_u128(&self.bits))
+    }
+    /// Reinterpret the [WideBits] value as a [SignedWideBits] value.
+    pub const fn as_signed(self) -> SignedWideBits<N> {
+        SignedWideBits { bits: self.bits }
+    }
+    pub fn any(self) -> bool {
+        self.bits.iter().any(|b| *b)
+    }
+    pub fn all(self) -> bool {
+        self.bits.iter().all(|b| *b)
+    }
+    pub fn xor(self) -> bool {
+        self.bits.iter().fold(false, |acc, b| acc ^ b)
+    }
+}
+
+impl<N: BitWidth> Bits<N> {
+    /// Zero extend (or truncate) the [Bits] value into a [WideBits] value.
+    /// ```
+    /// # use rhdl::bits::{WideBits, alias::b8};
+    /// let x: WideBits<256> = b8(0xAB).wide();
+    /// assert_eq!(x, 0xAB);
+    /// ```
+    pub fn wide<const M: usize>(self) -> WideBits<M> {
+        WideBits {
+            bits: bits_from_u128(self.raw(), false),
+        }
+    }
+}
+
+impl<N: BitWidth> SignedBits<N> {
+    /// Sign extend (or truncate) the [SignedBits] value into a
+    /// [SignedWideBits] value.
+    /// ```
+    /// # use rhdl::bits::{SignedWideBits, alias::s8};
+    /// let x: SignedWideBits<256> = s8(-3).wide();
+    /// assert_eq!(x, -3);
+    /// ```
+    pub fn wide<const M: usize>(self) -> SignedWideBits<M> {
+        SignedWideBits {
+            bits: bits_from_u128(self.raw() as u128, self.is_negative()),
+        }
+    }
+}
+
+/// The default value for a [WideBits] value is 0.
+impl<const N: usize> Default for WideBits<N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+/// Provide conversion from a `u128` to a [WideBits] value.
+/// Any `u128` fits when `N >= 128`.  For narrower widths, this will
+/// panic if the value does not fit (it is never truncated).
+impl<const N: usize> From<u128> for WideBits<N> {
+    fn from(value: u128) -> Self {
+        wide_bits(value)
+    }
+}
+
+impl<const N: usize> std::cmp::PartialOrd for WideBits<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> std::cmp::Ord for WideBits<N> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Comparing from the MSB down is an unsigned comparison
+        self.bits.iter().rev().cmp(other.bits.iter().rev())
+    }
+}
+
+impl<const N: usize> PartialEq<u128> for WideBits<N> {
+    fn eq(&self, other: &u128) -> bool {
+        *self == wide_bits::<N>(*other)
+    }
+}
+
+impl<const N: usize> PartialOrd<u128> for WideBits<N> {
+    fn partial_cmp(&self, other: &u128) -> Option<std::cmp::Ordering> {
+        self.partial_cmp(&wide_bits::<N>(*other))
+    }
+}
+
+impl<const N: usize> std::fmt::Debug for WideBits<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}_b{}", self.to_biguint(), N)
+    }
+}
+
+impl<const N: usize> std::fmt::Display for WideBits<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}'d{}", N, self.to_biguint())
+    }
+}
+
+impl<const N: usize> std::fmt::LowerHex for WideBits<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}'h{:x}", N, self.to_biguint())
+    }
+}
+
+impl<const N: usize> std::fmt::UpperHex for WideBits<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}'H{:X}", N, self.to_biguint())
+    }
+}
+
+impl<const N: usize> std::fmt::Binary for WideBits<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}'b{:b}", N, self.to_biguint())
+    }
+}
+
+macro_rules! impl_wide_binop {
+    ($trait: ident, $op: ident, $assign_trait: ident, $assign_op: ident, $func: expr) => {
+        impl<const N: usize> $trait for WideBits<N> {
+            type Output = Self;
+            fn $op(self, rhs: Self) -> Self::Output {
+                WideBits {
+                    bits: $func(&self.bits, &rhs.bits),
+                }
+            }
+        }
+        impl<const N: usize> $trait<u128> for WideBits<N> {
+            type Output = Self;
+            fn $op(self, rhs: u128) -> Self::Output {
+                self.$op(wide_bits::<N>(rhs))
+            }
+        }
+        impl<const N: usize> $trait<WideBits<N>> for u128 {
+            type Output = WideBits<N>;
+            fn $op(self, rhs: WideBits<N>) -> Self::Output {
+                wide_bits::<N>(self).$op(rhs)
+            }
+        }
+        impl<const N: usize> $assign_trait for WideBits<N> {
+            fn $assign_op(&mut self, rhs: Self) {
+                *self = self.$op(rhs);
+            }
+        }
+        impl<const N: usize> $assign_trait<u128> for WideBits<N> {
+            fn $assign_op(&mut self, rhs: u128) {
+                *self = self.$op(rhs);
+            }
+        }
+    };
+}
+
+impl_wide_binop!(Add, add, AddAssign, add_assign, |a, b| bits_add(
+    a, b, false
+));
+impl_wide_binop!(Sub, sub, SubAssign, sub_assign, bits_sub);
+impl_wide_binop!(Mul, mul, MulAssign, mul_assign, bits_mul);
+impl_wide_binop!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| {
+    bits_zip(a, b, |x, y| x & y)
+});
+impl_wide_binop!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| {
+    bits_zip(a, b, |x, y| x | y)
+});
+impl_wide_binop!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| {
+    bits_zip(a, b, |x, y| x ^ y)
+});
+
+impl<const N: usize> Not for WideBits<N> {
+    type Output = Self;
+    fn not(self) -> Self::Output {
+        WideBits {
+            bits: bits_not(&self.bits),
+        }
+    }
+}
+
+impl<const N: usize> Shl<u128> for WideBits<N> {
+    type Output = Self;
+    fn shl(self, rhs: u128) -> Self::Output {
+        WideBits {
+            bits: bits_shl(&self.bits, shift_amount(rhs)),
+        }
+    }
+}
+
+impl<const N: usize, M: BitWidth> Shl<Bits<M>> for WideBits<N> {
+    type Output = Self;
+    fn shl(self, rhs: Bits<M>) -> Self::Output {
+        self << rhs.raw()
+    }
+}
+
+impl<const N: usize> Shr<u128> for WideBits<N> {
+    type Output = Self;
+    fn shr(self, rhs: u128) -> Self::Output {
+        WideBits {
+            bits: bits_shr(&self.bits, shift_amount(rhs), false),
+        }
+    }
+}
+
+impl<const N: usize, M: BitWidth> Shr<Bits<M>> for WideBits<N> {
+    type Output = Self;
+    fn shr(self, rhs: Bits<M>) -> Self::Output {
+        self >> rhs.raw()
+    }
+}
+
+impl<const N: usize> ShlAssign<u128> for WideBits<N> {
+    fn shl_assign(&mut self, rhs: u128) {
+        *self = *self << rhs;
+    }
+}
+
+impl<const N: usize, M: BitWidth> ShlAssign<Bits<M>> for WideBits<N> {
+    fn shl_assign(&mut self, rhs: Bits<M>) {
+        *self = *self << rhs;
+    }
+}
+
+impl<const N: usize> ShrAssign<u128> for WideBits<N> {
+    fn shr_assign(&mut self, rhs: u128) {
+        *self = *self >> rhs;
+    }
+}
+
+impl<const N: usize, M: BitWidth> ShrAssign<Bits<M>> for WideBits<N> {
+    fn shr_assign(&mut self, rhs: Bits<M>) {
+        *self = *self >> rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rhdl_bits::alias::*;
+    use crate::rhdl_bits::bitwidth::*;
+
+    fn big(x: &str) -> BigUint {
+        BigUint::parse_bytes(x.as_bytes(), 16).unwrap()
+    }
+
+    #[test]
+    fn test_add_carries_past_128_bits() {
+        let a: WideBits<256> = wide_bits(u128::MAX);
+        let b = a + 1;
+        assert_eq!(b.to_biguint(), BigUint::from(1_u8) << 128);
+        assert_eq!(b.narrow::<U128>(), 0);
+        assert_eq!(WideBits::<256>::MAX + 1, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_narrow_wide_bits_out_of_range() {
+        let _: WideBits<8> = 256_u128.into();
+    }
+
+    #[test]
+    fn test_narrow_wide_bits_in_range() {
+        let x: WideBits<8> = 255_u128.into();
+        assert!(x.all());
+    }
+
+    #[test]
+    fn test_sub_wraps() {
+        let a: WideBits<256> = wide_bits(0);
+        let b = a - 1;
+        assert_eq!(b, WideBits::<256>::MAX);
+        assert!(b.all());
+    }
+
+    #[test]
+    fn test_mul_matches_biguint() {
+        let a = big("DEADBEEFCAFEBABE0123456789ABCDEF0011223344556677");
+        let b = big("FEEDFACE8BADF00D");
+        let x = WideBits::<256>::from_biguint(&a);
+        let y = WideBits::<256>::from_biguint(&b);
+        assert_eq!((x * y).to_biguint(), (a * b) % (BigUint::from(1_u8) << 256));
+    }
+
+    #[test]
+    fn test_shifts() {
+        let a: WideBits<256> = wide_bits(0xF0);
+        assert_eq!((a << 200_u128) >> 200_u128, 0xF0);
+        assert_eq!(a << 256_u128, 0);
+        assert_eq!(a << b8(4), 0xF00);
+        assert_eq!(a >> b8(4), 0xF);
+    }
+
+    #[test]
+    fn test_cmp_is_unsigned() {
+        let a: WideBits<256> = wide_bits(1) << 255_u128;
+        let b: WideBits<256> = wide_bits(u128::MAX);
+        assert!(a > b);
+        assert!(b < a);
+        assert_eq!(a.cmp(&a), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_format() {
+        let a: WideBits<256> = wide_bits(0xDA) << 128_u128;
+        assert_eq!(format!("{a:x}"), "256'hda00000000000000000000000000000000");
+        assert_eq!(format!("{a:?}"), format!("{}_b256", a.to_biguint()));
+    }
+
+    #[test]
+    fn test_narrow_and_wide() {
+        let a = b64(0xDEAD_BEEF_CAFE_BABE);
+        let w: WideBits<512> = a.wide();
+        assert_eq!(w.narrow::<U64>(), a);
+        assert_eq!(w.narrow::<U16>(), 0xBABE);
+        let r: WideBits<200> = w.resize();
+        assert_eq!(r, 0xDEAD_BEEF_CAFE_BABE);
+    }
+
+    #[test]
+    fn test_logic_ops() {
+        let a: WideBits<300> = !WideBits::ZERO;
+        let b: WideBits<300> = wide_bits(0xFF);
+        assert_eq!(a & b, 0xFF);
+        assert_eq!((a ^ b) | b, a);
+        assert!(!(a ^ a).any());
+        assert!(!b.xor());
+        assert!(wide_bits::<300>(0x7).xor());
+    }
+}
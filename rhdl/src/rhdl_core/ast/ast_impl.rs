@@ -1,10 +1,12 @@
+use crate::rhdl_bits::Rounding;
 use crate::rhdl_core::{
     DigitalSignature, Kind, kernel::KernelFnKind, rhif::spec::Member, types::typed_bits::TypedBits,
 };
+use serde::{Deserialize, Serialize};
 
 // Modeled after rustc's AST
 
-#[derive(Clone, Copy, PartialEq, Hash, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Hash, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct NodeId(u32);
 
 impl NodeId {
@@ -78,6 +80,7 @@ pub enum PatKind {
     Struct(PatStruct),
     Type(PatType),
     Wild,
+    Rest,
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -154,6 +157,7 @@ pub enum ExprKind {
     Paren(ExprParen),
     Tuple(ExprTuple),
     ForLoop(ExprForLoop),
+    While(ExprWhile),
     Assign(ExprAssign),
     Group(ExprGroup),
     Field(ExprField),
@@ -250,6 +254,12 @@ pub struct ExprForLoop {
     pub body: Box<Block>,
 }
 
+#[derive(Debug, Clone, Hash)]
+pub struct ExprWhile {
+    pub cond: Box<Expr>,
+    pub body: Box<Block>,
+}
+
 #[derive(Debug, Clone, Hash)]
 pub struct ExprAssign {
     pub lhs: Box<Expr>,
@@ -336,6 +346,16 @@ pub struct ExprMethodCall {
     pub args: Vec<Box<Expr>>,
     pub method: &'static str,
     pub turbo: Option<usize>,
+    pub fixed: Option<FixedPoint>,
+}
+
+// The binary point of the receiver of a fixed point method (`quantize`
+// or `saturate`), and the rounding mode, which must be a constant.
+#[derive(Debug, Clone, Copy, Hash)]
+pub struct FixedPoint {
+    pub integer_bits: usize,
+    pub fraction_bits: usize,
+    pub rounding: Rounding,
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -349,6 +369,8 @@ pub enum BinOp {
     Add,
     Sub,
     Mul,
+    Div,
+    Rem,
     And,
     Or,
     BitXor,
@@ -365,6 +387,8 @@ pub enum BinOp {
     AddAssign,
     SubAssign,
     MulAssign,
+    DivAssign,
+    RemAssign,
     BitXorAssign,
     BitAndAssign,
     BitOrAssign,
@@ -379,6 +403,8 @@ impl BinOp {
             BinOp::AddAssign
                 | BinOp::SubAssign
                 | BinOp::MulAssign
+                | BinOp::DivAssign
+                | BinOp::RemAssign
                 | BinOp::BitXorAssign
                 | BinOp::BitAndAssign
                 | BinOp::BitOrAssign
@@ -398,6 +424,7 @@ pub enum UnOp {
 pub struct Arm {
     pub id: NodeId,
     pub kind: ArmKind,
+    pub guard: Option<Box<Expr>>,
     pub body: Box<Expr>,
 }
 
@@ -406,6 +433,7 @@ pub enum ArmKind {
     Wild,
     Constant(ArmConstant),
     Enum(ArmEnum),
+    Binding(ArmBinding),
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -413,6 +441,13 @@ pub struct ArmConstant {
     pub value: ExprLit,
 }
 
+// An irrefutable pattern (like `(a, b)` or `[first, .., last]`)
+// that destructures the value being matched.
+#[derive(Debug, Clone, Hash)]
+pub struct ArmBinding {
+    pub pat: Box<Pat>,
+}
+
 #[derive(Debug, Clone, Hash)]
 pub struct ArmEnum {
     pub pat: Box<Pat>,
@@ -423,10 +458,19 @@ pub struct ArmEnum {
 pub enum ExprLit {
     TypedBits(ExprTypedBits),
     Int(String),
+    IntConst(ExprIntConst),
     Bool(bool),
     Empty,
 }
 
+/// An integer constant (like a `u32` const item) that is not [Digital](crate::rhdl_core::Digital),
+/// and so enters the kernel untyped.  The code is kept for display.
+#[derive(Clone, Hash)]
+pub struct ExprIntConst {
+    pub value: String,
+    pub code: String,
+}
+
 #[derive(Clone, Hash)]
 pub struct ExprTypedBits {
     pub path: Box<Path>,
@@ -446,7 +490,7 @@ pub struct FieldPat {
     pub pat: Box<Pat>,
 }
 
-#[derive(Clone, Copy, Hash, PartialEq, PartialOrd, Ord, Eq)]
+#[derive(Clone, Copy, Hash, PartialEq, PartialOrd, Ord, Eq, Serialize, Deserialize)]
 pub struct FunctionId(u64);
 
 impl From<u64> for FunctionId {
@@ -467,9 +511,41 @@ impl std::fmt::LowerHex for FunctionId {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Hash, Serialize, Deserialize)]
 pub enum KernelFlags {
     AllowWeakPartial,
+    Allow(Lint),
+    Deny(Lint),
+}
+
+/// The lints that the compiler checks kernels for.  Lints are reported
+/// as warnings, unless allowed (`#[rhdl(allow(lossy_resize))]`) or
+/// denied (`#[rhdl(deny(lossy_resize))]`) on the kernel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Lint {
+    /// A field of the `D` type of a circuit that is never written
+    UnwrittenD,
+    /// A field of the `Q` type of a circuit that is never read
+    UnreadQ,
+    /// A resize that drops high bits of its argument
+    LossyResize,
+    /// A match arm that can never be reached
+    UnreachableArm,
+    /// A circuit output that is constant
+    ConstantOutput,
+}
+
+impl Lint {
+    /// The name used for the lint in `#[rhdl(allow(..))]`
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnwrittenD => "unwritten_d",
+            Lint::UnreadQ => "unread_q",
+            Lint::LossyResize => "lossy_resize",
+            Lint::UnreachableArm => "unreachable_arm",
+            Lint::ConstantOutput => "constant_output",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -485,7 +561,7 @@ pub struct KernelFn {
     pub flags: Vec<KernelFlags>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Hash, Serialize, Deserialize)]
 pub enum WrapOp {
     Ok,
     Err,
@@ -1,8 +1,10 @@
 use std::cell::Cell;
 use std::hash::{Hash, Hasher};
 
+use crate::rhdl_bits::Rounding;
 pub use crate::rhdl_core::ast::ast_impl::BinOp;
 pub use crate::rhdl_core::ast::ast_impl::UnOp;
+use crate::rhdl_core::ast::kernel_const::ConstValue;
 use crate::rhdl_core::kernel::KernelFnKind;
 use crate::rhdl_core::rhif::spec::Member;
 use crate::rhdl_core::types::typed_bits::TypedBits;
@@ -155,9 +157,28 @@ impl ASTBuilder {
         })
     }
 
+    pub fn arm_kind_binding(&self, pat: Box<Pat>) -> ArmKind {
+        ArmKind::Binding(ArmBinding { pat })
+    }
+
     pub fn arm(&self, kind: ArmKind, body: Box<Expr>) -> Box<Arm> {
         let id = self.id();
-        Box::new(Arm { id, kind, body })
+        Box::new(Arm {
+            id,
+            kind,
+            guard: None,
+            body,
+        })
+    }
+
+    pub fn guarded_arm(&self, kind: ArmKind, guard: Box<Expr>, body: Box<Expr>) -> Box<Arm> {
+        let id = self.id();
+        Box::new(Arm {
+            id,
+            kind,
+            guard: Some(guard),
+            body,
+        })
     }
 
     pub fn field_expr(&self, expr: Box<Expr>, member: Member) -> Box<Expr> {
@@ -233,6 +254,14 @@ impl ASTBuilder {
         })
     }
 
+    pub fn while_expr(&self, cond: Box<Expr>, body: Box<Block>) -> Box<Expr> {
+        let id = self.id();
+        Box::new(Expr {
+            id,
+            kind: ExprKind::While(ExprWhile { cond, body }),
+        })
+    }
+
     pub fn call_expr(
         &self,
         path: Box<Path>,
@@ -283,6 +312,32 @@ impl ASTBuilder {
                 args,
                 method,
                 turbo,
+                fixed: None,
+            }),
+        })
+    }
+    pub fn fixed_point_expr(
+        &self,
+        receiver: Box<Expr>,
+        args: Vec<Box<Expr>>,
+        method: &'static str,
+        turbo: Option<usize>,
+        (integer_bits, fraction_bits): (usize, usize),
+        rounding: Rounding,
+    ) -> Box<Expr> {
+        let id = self.id();
+        Box::new(Expr {
+            id,
+            kind: ExprKind::MethodCall(ExprMethodCall {
+                receiver,
+                args,
+                method,
+                turbo,
+                fixed: Some(FixedPoint {
+                    integer_bits,
+                    fraction_bits,
+                    rounding,
+                }),
             }),
         })
     }
@@ -306,6 +361,14 @@ impl ASTBuilder {
         })
     }
 
+    pub fn rest_pat(&self) -> Box<Pat> {
+        let id = self.id();
+        Box::new(Pat {
+            id,
+            kind: PatKind::Rest,
+        })
+    }
+
     pub fn wild_discriminant(&self) -> TypedBits {
         TypedBits {
             bits: vec![],
@@ -512,6 +575,21 @@ impl ASTBuilder {
         })
     }
 
+    pub fn const_expr(&self, path: Box<Path>, value: ConstValue, code: &str) -> Box<Expr> {
+        let lit = match value {
+            ConstValue::Int(value) => ExprLit::IntConst(ExprIntConst {
+                value,
+                code: code.to_string(),
+            }),
+            ConstValue::Digital(value) => ExprLit::TypedBits(ExprTypedBits {
+                path,
+                value,
+                code: code.to_string(),
+            }),
+        };
+        self.lit_expr(lit)
+    }
+
     pub fn expr_bits_with_length(&self, arg: Box<Expr>, len: usize) -> Box<Expr> {
         let id = self.id();
         Box::new(Expr {
@@ -0,0 +1,87 @@
+//! Compile time constants in kernels.
+//!
+//! Constants (like `N::BITS`, a `const` item, or a `const` block that
+//! calls a `const fn`) are evaluated when the kernel is built, and enter the
+//! kernel as literals.  Constants that are [Digital] (which includes
+//! `usize` and most of the primitive integers) enter the kernel with
+//! their type intact.  The remaining primitive integers (`u32`, `i16`
+//! and `isize`) are not [Digital], and become untyped integer literals,
+//! so that they take their width from the surrounding code just like `5`
+//! does.
+//!
+//! The choice between the two is made with autoref specialization,
+//! since the `#[kernel]` macro cannot see the type of the constant.
+use crate::rhdl_core::{Digital, TypedBits};
+
+/// The value of a constant expression in a kernel.
+pub enum ConstValue {
+    Int(String),
+    Digital(TypedBits),
+}
+
+/// Wraps a constant value so that [DigitalConst] and [IntegerConst] can
+/// decide how it enters the kernel.
+pub struct KernelConst<T>(pub T);
+
+pub trait DigitalConst {
+    fn const_value(&self) -> ConstValue;
+}
+
+pub trait IntegerConst {
+    fn const_value(&self) -> ConstValue;
+}
+
+impl<T: Digital> DigitalConst for KernelConst<T> {
+    fn const_value(&self) -> ConstValue {
+        ConstValue::Digital(self.0.typed_bits())
+    }
+}
+
+macro_rules! impl_integer_const {
+    ($($t:ty),*) => {
+        $(
+            impl IntegerConst for &KernelConst<$t> {
+                fn const_value(&self) -> ConstValue {
+                    ConstValue::Int(self.0.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_const!(u32, i16, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rhdl_bits::alias::*;
+
+    #[test]
+    fn test_integers_are_untyped() {
+        let ConstValue::Int(x) = (&KernelConst(12_u32)).const_value() else {
+            panic!("Expected an integer constant");
+        };
+        assert_eq!(x, "12");
+        let ConstValue::Int(x) = (&KernelConst(-3_isize)).const_value() else {
+            panic!("Expected an integer constant");
+        };
+        assert_eq!(x, "-3");
+    }
+
+    #[test]
+    fn test_digital_values_are_typed() {
+        let ConstValue::Digital(x) = KernelConst(b4(3)).const_value() else {
+            panic!("Expected a typed constant");
+        };
+        assert_eq!(x, b4(3).typed_bits());
+        let ConstValue::Digital(x) = KernelConst(true).const_value() else {
+            panic!("Expected a typed constant");
+        };
+        assert_eq!(x, true.typed_bits());
+        // The primitive integers that are Digital keep their type
+        let ConstValue::Digital(x) = KernelConst(12_usize).const_value() else {
+            panic!("Expected a typed constant");
+        };
+        assert_eq!(x, 12_usize.typed_bits());
+    }
+}
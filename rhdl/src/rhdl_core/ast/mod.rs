@@ -1,6 +1,7 @@
 pub mod ast_impl;
 pub mod builder;
+pub mod kernel_const;
 pub mod source;
 pub mod visit;
 pub mod visit_mut;
-pub use ast_impl::KernelFlags;
+pub use ast_impl::{KernelFlags, Lint};
@@ -34,8 +34,17 @@ impl<'a> SpannedSourceBuilder<'a> {
             ));
         }
         for (syn_input, ast_input) in fn_item.sig.inputs.iter().zip(kernel.inputs.iter()) {
-            let syn::FnArg::Typed(syn_input) = syn_input else {
-                return Err(syn::Error::new(syn_input.span(), "Mismatched input kinds"));
+            let syn_input = match syn_input {
+                syn::FnArg::Typed(syn_input) => syn_input,
+                syn::FnArg::Receiver(receiver) => {
+                    let ast::PatKind::Type(ast_type) = &ast_input.kind else {
+                        return Err(syn::Error::new(receiver.span(), "Mismatched input kinds"));
+                    };
+                    let span = receiver.span().byte_range();
+                    self.span_map.insert(ast_input.id, span.clone());
+                    self.span_map.insert(ast_type.pat.id, span);
+                    continue;
+                }
             };
             self.span_map
                 .insert(ast_input.id, syn_input.span().byte_range());
@@ -121,6 +130,7 @@ impl<'a> SpannedSourceBuilder<'a> {
         match (&syn_pat, &ast_pat.kind) {
             (syn::Pat::Ident(_), ast::PatKind::Ident(_)) => Ok(()),
             (syn::Pat::Wild(_), ast::PatKind::Wild) => Ok(()),
+            (syn::Pat::Rest(_), ast::PatKind::Rest) => Ok(()),
             (syn::Pat::Lit(_), ast::PatKind::Lit(_)) => Ok(()),
             (syn::Pat::Or(syn), ast::PatKind::Or(ast)) => {
                 self.pat_list(&syn.cases, &ast.segments, syn.span())
@@ -184,8 +194,17 @@ impl<'a> SpannedSourceBuilder<'a> {
     }
     fn arm(&mut self, syn: &syn::Arm, ast: &ast::Arm) -> syn::Result<()> {
         self.span_map.insert(ast.id, syn.span().byte_range());
-        if let (syn, ast::ArmKind::Enum(ast)) = (&syn.pat, &ast.kind) {
-            self.pattern(syn, &ast.pat)?
+        match &ast.kind {
+            ast::ArmKind::Enum(arm_pat) => self.pattern(&syn.pat, &arm_pat.pat)?,
+            ast::ArmKind::Binding(arm_pat) => self.pattern(&syn.pat, &arm_pat.pat)?,
+            _ => {}
+        }
+        match (&syn.guard, &ast.guard) {
+            (Some((_, syn_guard)), Some(ast_guard)) => self.expr(syn_guard, ast_guard)?,
+            (None, None) => {}
+            _ => {
+                return Err(syn::Error::new(syn.span(), "Mismatched match arm guards"));
+            }
         }
         self.expr(&syn.body, &ast.body)
     }
@@ -193,8 +212,10 @@ impl<'a> SpannedSourceBuilder<'a> {
         let syn::Expr::Let(syn_let) = syn.cond.as_ref() else {
             return Err(syn::Error::new(syn.span(), "Mismatched if-let condition"));
         };
-        if let ast::ArmKind::Enum(arm_pat) = &ast.kind {
-            self.pattern(&syn_let.pat, &arm_pat.pat)?;
+        match &ast.kind {
+            ast::ArmKind::Enum(arm_pat) => self.pattern(&syn_let.pat, &arm_pat.pat)?,
+            ast::ArmKind::Binding(arm_pat) => self.pattern(&syn_let.pat, &arm_pat.pat)?,
+            _ => {}
         }
         self.expr(&syn_let.expr, &ast.test)?;
         self.block(&syn.then_branch, &ast.then_block)?;
@@ -240,6 +261,10 @@ impl<'a> SpannedSourceBuilder<'a> {
                 self.expr(&syn.expr, &ast.expr)?;
                 self.block(&syn.body, &ast.body)
             }
+            (syn::Expr::While(syn), ast::ExprKind::While(ast)) => {
+                self.expr(&syn.cond, &ast.cond)?;
+                self.block(&syn.body, &ast.body)
+            }
             (syn::Expr::Group(syn), ast::ExprKind::Group(ast)) => self.expr(&syn.expr, &ast.expr),
             (syn::Expr::If(syn), ast::ExprKind::If(ast)) => {
                 self.expr(&syn.cond, &ast.cond)?;
@@ -284,6 +309,13 @@ impl<'a> SpannedSourceBuilder<'a> {
                 self.expr(&syn.receiver, &ast.receiver)?;
                 self.expr_list(&syn.args, &ast.args, syn.span())
             }
+            (syn::Expr::MethodCall(syn), ast::ExprKind::Call(ast)) => {
+                let Some((receiver, args)) = ast.args.split_first() else {
+                    return Err(syn::Error::new(syn.span(), "Mismatched method call"));
+                };
+                self.expr(&syn.receiver, receiver)?;
+                self.expr_list(&syn.args, args, syn.span())
+            }
             (syn::Expr::Paren(syn), ast::ExprKind::Paren(ast)) => self.expr(&syn.expr, &ast.expr),
             (syn::Expr::Path(_), ast::ExprKind::Path(_)) => Ok(()),
             (syn::Expr::Range(syn), ast::ExprKind::Range(ast)) => {
@@ -1,6 +1,7 @@
 use crate::rhdl_core::ast::ast_impl::{FunctionId, NodeId};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Hash, PartialOrd, Eq, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash, PartialOrd, Eq, Ord, Serialize, Deserialize)]
 pub struct SourceLocation {
     pub func: FunctionId,
     pub node: NodeId,
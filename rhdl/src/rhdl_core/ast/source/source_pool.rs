@@ -1,11 +1,12 @@
 use miette::{MietteError, SourceCode, SourceSpan, SpanContents};
 
 use crate::rhdl_core::ast::ast_impl::FunctionId;
+use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, hash::Hash, ops::Range};
 
 use super::spanned_source::SpannedSource;
 
-#[derive(Clone, Debug, Default, Hash)]
+#[derive(Clone, Debug, Default, Hash, Serialize, Deserialize)]
 pub struct SourcePool {
     pub source: BTreeMap<FunctionId, SpannedSource>,
     pub ranges: BTreeMap<FunctionId, Range<usize>>,
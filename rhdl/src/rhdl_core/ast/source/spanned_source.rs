@@ -7,8 +7,9 @@ use std::{
 };
 
 use super::source_pool::SourcePool;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SpannedSource {
     pub source: String,
     pub name: String,
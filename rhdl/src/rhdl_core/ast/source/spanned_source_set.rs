@@ -5,8 +5,9 @@ use crate::rhdl_core::ast::ast_impl::FunctionId;
 use super::{
     source_location::SourceLocation, source_pool::SourcePool, spanned_source::SpannedSource,
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Default, Hash)]
+#[derive(Clone, Debug, Default, Hash, Serialize, Deserialize)]
 pub struct SpannedSourceSet {
     pub sources: BTreeMap<FunctionId, SpannedSource>,
 }
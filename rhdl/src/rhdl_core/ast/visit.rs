@@ -114,6 +114,9 @@ pub fn visit_match_arm<V>(visitor: &mut V, arm: &Arm) -> Result<()>
 where
     V: Visitor + ?Sized,
 {
+    if let Some(guard) = &arm.guard {
+        visitor.visit_expr(guard)?;
+    }
     visitor.visit_expr(&arm.body)?;
     Ok(())
 }
@@ -163,6 +166,10 @@ where
             visitor.visit_expr(&expr.expr)?;
             visitor.visit_block(&expr.body)?;
         }
+        ExprKind::While(expr) => {
+            visitor.visit_expr(&expr.cond)?;
+            visitor.visit_block(&expr.body)?;
+        }
         ExprKind::Assign(expr) => {
             visitor.visit_expr(&expr.lhs)?;
             visitor.visit_expr(&expr.rhs)?;
@@ -114,8 +114,13 @@ pub fn visit_mut_match_arm<V>(visitor: &mut V, arm: &mut Arm) -> Result<()>
 where
     V: VisitorMut + ?Sized,
 {
-    if let ArmKind::Enum(enum_arm) = &mut arm.kind {
-        visitor.visit_mut_pat(&mut enum_arm.pat)?;
+    match &mut arm.kind {
+        ArmKind::Enum(enum_arm) => visitor.visit_mut_pat(&mut enum_arm.pat)?,
+        ArmKind::Binding(binding_arm) => visitor.visit_mut_pat(&mut binding_arm.pat)?,
+        _ => {}
+    }
+    if let Some(guard) = &mut arm.guard {
+        visitor.visit_mut_expr(guard)?;
     }
     visitor.visit_mut_expr(&mut arm.body)?;
     Ok(())
@@ -166,6 +171,10 @@ where
             visitor.visit_mut_expr(&mut expr.expr)?;
             visitor.visit_mut_block(&mut expr.body)?;
         }
+        ExprKind::While(expr) => {
+            visitor.visit_mut_expr(&mut expr.cond)?;
+            visitor.visit_mut_block(&mut expr.body)?;
+        }
         ExprKind::Assign(expr) => {
             visitor.visit_mut_expr(&mut expr.lhs)?;
             visitor.visit_mut_expr(&mut expr.rhs)?;
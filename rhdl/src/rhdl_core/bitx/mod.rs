@@ -1,6 +1,8 @@
 pub mod dyn_bit_manip;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BitX {
     Zero,
     One,
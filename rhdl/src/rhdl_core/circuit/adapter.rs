@@ -2,6 +2,7 @@ use crate::rhdl_core::{
     Circuit, CircuitDQ, CircuitDescriptor, CircuitIO, ClockReset, Digital, DigitalFn, Domain, Kind,
     RHDLError, Signal, Synchronous, Timed,
     bitx::BitX,
+    compiler::NtlPipeline,
     digital_fn::NoKernel2,
     hdl::ast::{
         Direction, Module, component_instance, concatenate, connection, id, index, index_bit,
@@ -115,9 +116,19 @@ impl<C: Synchronous, D: Domain> Circuit for Adapter<C, D> {
     }
 
     fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError> {
+        self.descriptor_with(name, &NtlPipeline::default())
+    }
+
+    fn descriptor_with(
+        &self,
+        name: &str,
+        pipeline: &NtlPipeline,
+    ) -> Result<CircuitDescriptor, RHDLError> {
         // We build a custom flow graph to connect the input to the circuit and the circuit to the output.
         let mut builder = ntl::Builder::new(name);
-        let child_descriptor = self.circuit.descriptor(&format!("{name}_inner"))?;
+        let child_descriptor = self
+            .circuit
+            .descriptor_with(&format!("{name}_inner"), pipeline)?;
         // This includes the clock and reset signals
         // It should be [clock, reset, inputs...]
         let input_reg: Kind = <Self::I as Timed>::static_kind();
@@ -138,9 +149,10 @@ impl<C: Synchronous, D: Domain> Circuit for Adapter<C, D> {
             output_kind: <<Self as CircuitIO>::O as Timed>::static_kind(),
             d_kind: <<Self as CircuitDQ>::D as Timed>::static_kind(),
             q_kind: <<Self as CircuitDQ>::Q as Timed>::static_kind(),
-            ntl: builder.build(ntl::builder::BuilderMode::Asynchronous)?,
+            ntl: builder.build_with(ntl::builder::BuilderMode::Asynchronous, pipeline)?,
             rtl: None,
             children: Default::default(),
+            ..Default::default()
         })
     }
 
@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 
 use crate::rhdl_core::{
     Circuit, CircuitDQ, CircuitDescriptor, CircuitIO, Digital, HDLDescriptor, Kind, RHDLError,
+    compiler::NtlPipeline,
     digital_fn::NoKernel2,
     hdl::ast::{Direction, Module, Statement, component_instance, connection, index},
     ntl, trace_pop_path, trace_push_path,
@@ -47,6 +48,14 @@ impl<T: Circuit, const N: usize> Circuit for [T; N] {
     // assumes that the children of the current circuit are named with field names
     // as part of a struct.
     fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError> {
+        self.descriptor_with(name, &NtlPipeline::default())
+    }
+
+    fn descriptor_with(
+        &self,
+        name: &str,
+        pipeline: &NtlPipeline,
+    ) -> Result<CircuitDescriptor, RHDLError> {
         let mut builder = ntl::Builder::new(name);
         let input_kind: Kind = Self::I::static_kind();
         let output_kind: Kind = Self::O::static_kind();
@@ -58,7 +67,7 @@ impl<T: Circuit, const N: usize> Circuit for [T; N] {
             let (output_bit_range, _) = bit_range(Self::O::static_kind(), &child_path)?;
             let (input_bit_range, _) = bit_range(Self::I::static_kind(), &child_path)?;
             let child_name = format!("{name}_{i}");
-            let child_desc = self[i].descriptor(&child_name)?;
+            let child_desc = self[i].descriptor_with(&child_name, pipeline)?;
             let offset = builder.import(&child_desc.ntl);
             // Wire up the child circuit inputs and outputs
             for (&t, c) in ti[input_bit_range].iter().zip(&child_desc.ntl.inputs[0]) {
@@ -75,9 +84,10 @@ impl<T: Circuit, const N: usize> Circuit for [T; N] {
             output_kind: Self::O::static_kind(),
             d_kind: Kind::Empty,
             q_kind: Kind::Empty,
-            ntl: builder.build(ntl::builder::BuilderMode::Asynchronous)?,
+            ntl: builder.build_with(ntl::builder::BuilderMode::Asynchronous, pipeline)?,
             rtl: None,
             children,
+            ..Default::default()
         })
     }
 
@@ -3,6 +3,7 @@ use std::collections::BTreeMap;
 use crate::rhdl_core::{
     CircuitDescriptor, ClockReset, Digital, HDLDescriptor, Kind, RHDLError, Synchronous,
     SynchronousDQ, SynchronousIO,
+    compiler::NtlPipeline,
     digital_fn::NoKernel3,
     hdl::ast::{Direction, Module, Statement, component_instance, connection, id, index},
     ntl, trace_pop_path, trace_push_path,
@@ -54,10 +55,15 @@ impl<T: Synchronous, const N: usize> Synchronous for [T; N] {
     // This requires a custom implementation because the default implementation
     // assumes that the children of the current circuit are named with field names
     // as part of a struct.
-    fn descriptor(
+    fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError> {
+        self.descriptor_with(name, &NtlPipeline::default())
+    }
+
+    fn descriptor_with(
         &self,
         name: &str,
-    ) -> Result<crate::rhdl_core::CircuitDescriptor, crate::rhdl_core::RHDLError> {
+        pipeline: &NtlPipeline,
+    ) -> Result<CircuitDescriptor, RHDLError> {
         let mut builder = ntl::Builder::new(name);
         let cr_kind: Kind = ClockReset::static_kind();
         let input_kind: Kind = Self::I::static_kind();
@@ -71,7 +77,7 @@ impl<T: Synchronous, const N: usize> Synchronous for [T; N] {
             let (output_bit_range, _) = bit_range(Self::O::static_kind(), &child_path)?;
             let (input_bit_range, _) = bit_range(Self::I::static_kind(), &child_path)?;
             let child_name = format!("{name}_{i}");
-            let child_desc = self[i].descriptor(&child_name)?;
+            let child_desc = self[i].descriptor_with(&child_name, pipeline)?;
             let offset = builder.import(&child_desc.ntl);
             for (&t, c) in tcr.iter().zip(&child_desc.ntl.inputs[0]) {
                 builder.copy_from_to(t, offset(c.into()));
@@ -90,9 +96,10 @@ impl<T: Synchronous, const N: usize> Synchronous for [T; N] {
             output_kind: Self::O::static_kind(),
             d_kind: Kind::Empty,
             q_kind: Kind::Empty,
-            ntl: builder.build(ntl::builder::BuilderMode::Synchronous)?,
+            ntl: builder.build_with(ntl::builder::BuilderMode::Synchronous, pipeline)?,
             rtl: None,
             children,
+            ..Default::default()
         })
     }
 
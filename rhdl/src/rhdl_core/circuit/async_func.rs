@@ -1,11 +1,11 @@
 use crate::rhdl_core::{
-    compile_design,
+    compiler::{cache::CompiledDesign, driver::compile_design_artifacts, lints::RHDLLint},
     digital_fn::{DigitalFn1, NoKernel2},
     hdl::{
         ast::{continuous_assignment, function_call, id, Direction, Module},
         builder::generate_verilog,
     },
-    ntl::from_rtl::build_ntl_from_rtl,
+    ntl,
     rtl::Object,
     Circuit, CircuitDQ, CircuitDescriptor, CircuitIO, CompilationMode, DigitalFn, HDLDescriptor,
     Kind, RHDLError, Timed,
@@ -16,6 +16,8 @@ use super::hdl_backend::maybe_port_wire;
 #[derive(Clone)]
 pub struct AsyncFunc<I: Timed, O: Timed> {
     module: Object,
+    netlist: ntl::Object,
+    lints: Vec<RHDLLint>,
     update: fn(I) -> O,
 }
 
@@ -36,9 +38,19 @@ impl<I: Timed, O: Timed> AsyncFunc<I, O> {
         T: DigitalFn,
         T: DigitalFn1<A0 = I, O = O>,
     {
-        let module = compile_design::<T>(CompilationMode::Asynchronous)?;
+        let CompiledDesign {
+            rtl: module,
+            ntl: netlist,
+            lints,
+            ..
+        } = compile_design_artifacts::<T>(CompilationMode::Asynchronous)?;
         let update = T::func();
-        Ok(Self { module, update })
+        Ok(Self {
+            module,
+            netlist,
+            lints,
+            update,
+        })
     }
 }
 
@@ -58,8 +70,10 @@ impl<I: Timed, O: Timed> Circuit for AsyncFunc<I, O> {
             output_kind: <Self::O as Timed>::static_kind(),
             d_kind: Kind::Empty,
             q_kind: Kind::Empty,
-            ntl: build_ntl_from_rtl(&self.module),
+            ntl: self.netlist.clone(),
             rtl: Some(self.module.clone()),
+            lints: self.lints.clone(),
+            synchronizer: None,
             children: Default::default(),
         })
     }
@@ -0,0 +1,142 @@
+//! Clock domain crossing report for a circuit hierarchy.
+//!
+//! The kernels of a design are checked by the compiler to never mix
+//! signals from different clock domains.  So the only places in a design
+//! where a signal can cross from one domain to another are the cores that
+//! are not written as kernels (synchronizers, dual clock memories, etc.),
+//! and the cores that are built from them.  The report walks the
+//! descriptor of a circuit, and lists each such crossing along with the
+//! synchronizer that protects it:
+//! ```ignore
+//! let report = cdc::cdc_report(&uut.descriptor("top")?);
+//! eprintln!("{report}");
+//! assert_eq!(report.unprotected().count(), 0);
+//! ```
+//! A core declares itself a synchronizer by setting
+//! [CircuitIO::SYNCHRONIZER](super::circuit_impl::CircuitIO::SYNCHRONIZER),
+//! which is carried into its descriptor.
+use crate::rhdl_core::{CircuitDescriptor, Color, Kind};
+
+/// A place in the design where signals cross between clock domains.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Crossing {
+    /// The path to the circuit in the hierarchy, as a list of field names
+    /// separated by dots
+    pub path: String,
+    /// The domains of the inputs to the circuit
+    pub inputs: Vec<Color>,
+    /// The domains of the outputs of the circuit
+    pub outputs: Vec<Color>,
+    /// The synchronizer that protects the crossing, if there is one
+    pub synchronizer: Option<&'static str>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CdcReport {
+    pub crossings: Vec<Crossing>,
+}
+
+impl CdcReport {
+    /// The crossings that are not protected by a synchronizer.
+    pub fn unprotected(&self) -> impl Iterator<Item = &Crossing> {
+        self.crossings
+            .iter()
+            .filter(|crossing| crossing.synchronizer.is_none())
+    }
+}
+
+/// Build the clock domain crossing report for the given circuit,
+/// recognizing the cores that declare themselves synchronizers.
+pub fn cdc_report(descriptor: &CircuitDescriptor) -> CdcReport {
+    let mut report = CdcReport::default();
+    find_crossings(&descriptor.unique_name, descriptor, &mut report);
+    report
+}
+
+fn find_crossings(path: &str, descriptor: &CircuitDescriptor, report: &mut CdcReport) {
+    let inputs = domains(&descriptor.input_kind);
+    let outputs = domains(&descriptor.output_kind);
+    let mut all = inputs.iter().chain(&outputs).copied().collect::<Vec<_>>();
+    sort_domains(&mut all);
+    if all.len() < 2 {
+        return;
+    }
+    // A circuit with a kernel cannot cross domains itself, and neither can
+    // a circuit that is only a wrapper around its children.  So the crossings
+    // are in the synchronizers, in the cores without kernels or children,
+    // and further down the hierarchy.
+    let leaf = descriptor.rtl.is_none() && descriptor.children.is_empty();
+    if descriptor.synchronizer.is_some() || leaf {
+        report.crossings.push(Crossing {
+            path: path.into(),
+            inputs,
+            outputs,
+            synchronizer: descriptor.synchronizer,
+        });
+        return;
+    }
+    for (name, child) in &descriptor.children {
+        find_crossings(&format!("{path}.{name}"), child, report);
+    }
+}
+
+// The clock domains of the signals in a kind, sorted by name
+fn domains(kind: &Kind) -> Vec<Color> {
+    fn collect(kind: &Kind, colors: &mut Vec<Color>) {
+        match kind {
+            Kind::Signal(_, color) => colors.push(*color),
+            Kind::Array(array) => collect(&array.base, colors),
+            Kind::Tuple(tuple) => tuple.elements.iter().for_each(|k| collect(k, colors)),
+            Kind::Struct(s) => s.fields.iter().for_each(|f| collect(&f.kind, colors)),
+            Kind::Enum(e) => e.variants.iter().for_each(|v| collect(&v.kind, colors)),
+            Kind::Bits(_) | Kind::Signed(_) | Kind::Empty => {}
+        }
+    }
+    let mut colors = vec![];
+    collect(kind, &mut colors);
+    sort_domains(&mut colors);
+    colors
+}
+
+fn sort_domains(colors: &mut Vec<Color>) {
+    colors.sort_by_key(|color| (color.name(), color.path()));
+    colors.dedup();
+}
+
+fn domain_list(colors: &[Color]) -> String {
+    colors
+        .iter()
+        .map(|color| color.name())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl std::fmt::Display for CdcReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rows = vec![["circuit", "inputs", "outputs", "synchronizer"].map(String::from)];
+        rows.extend(self.crossings.iter().map(|crossing| {
+            [
+                crossing.path.clone(),
+                domain_list(&crossing.inputs),
+                domain_list(&crossing.outputs),
+                crossing.synchronizer.unwrap_or("UNPROTECTED").into(),
+            ]
+        }));
+        let mut width = [0; 4];
+        for row in &rows {
+            for (width, cell) in width.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        for row in rows {
+            let line = row
+                .iter()
+                .zip(width)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
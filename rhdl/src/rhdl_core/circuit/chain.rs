@@ -1,7 +1,8 @@
 use crate::rhdl_core::ntl;
 use crate::rhdl_core::{
-    CircuitDescriptor, ClockReset, Digital, HDLDescriptor, Kind, Synchronous, SynchronousDQ,
-    SynchronousIO,
+    CircuitDescriptor, ClockReset, Digital, HDLDescriptor, Kind, RHDLError, Synchronous,
+    SynchronousDQ, SynchronousIO,
+    compiler::NtlPipeline,
     digital_fn::NoKernel3,
     hdl::ast::{
         Declaration, Direction, HDLKind, Module, component_instance, connection, id, unsigned_width,
@@ -71,14 +72,19 @@ where
         )
     }
 
-    fn descriptor(
+    fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError> {
+        self.descriptor_with(name, &NtlPipeline::default())
+    }
+
+    fn descriptor_with(
         &self,
         name: &str,
-    ) -> Result<crate::rhdl_core::CircuitDescriptor, crate::rhdl_core::RHDLError> {
+        pipeline: &NtlPipeline,
+    ) -> Result<CircuitDescriptor, RHDLError> {
         let a_name = format!("{name}_a");
         let b_name = format!("{name}_b");
-        let desc_a = self.a.descriptor(&a_name)?;
-        let desc_b = self.b.descriptor(&b_name)?;
+        let desc_a = self.a.descriptor_with(&a_name, pipeline)?;
+        let desc_b = self.b.descriptor_with(&b_name, pipeline)?;
         let mut builder = ntl::Builder::new(name);
         let input_kind: Kind = <A as SynchronousIO>::I::static_kind();
         let output_kind: Kind = <B as SynchronousIO>::O::static_kind();
@@ -117,9 +123,10 @@ where
             output_kind: desc_b.output_kind,
             q_kind: Kind::Empty,
             d_kind: Kind::Empty,
-            ntl: builder.build(ntl::builder::BuilderMode::Synchronous)?,
+            ntl: builder.build_with(ntl::builder::BuilderMode::Synchronous, pipeline)?,
             rtl: None,
             children: BTreeMap::from_iter(vec![(a_name, desc_a), (b_name, desc_b)]),
+            ..Default::default()
         };
         Ok(desc)
     }
@@ -1,11 +1,13 @@
 use super::circuit_impl::Circuit;
 use crate::prelude::ClockReset;
 use crate::rhdl_core::Kind;
-use crate::rhdl_core::ntl::from_rtl::build_ntl_from_rtl;
+use crate::rhdl_core::compiler::{
+    NtlPipeline, cache::CompiledDesign, driver::compile_circuit_artifacts, lints::RHDLLint,
+};
 use crate::rhdl_core::rtl::Object;
 use crate::rhdl_core::types::digital::Digital;
 use crate::rhdl_core::types::path::{Path, bit_range};
-use crate::rhdl_core::{CompilationMode, RHDLError, Synchronous, compile_design};
+use crate::rhdl_core::{CompilationMode, RHDLError, Synchronous};
 use std::collections::BTreeMap;
 
 // A few notes on the circuit descriptor struct
@@ -24,9 +26,37 @@ pub struct CircuitDescriptor {
     pub q_kind: Kind,
     pub rtl: Option<Object>,
     pub ntl: crate::core::ntl::object::Object,
+    /// The lint warnings found in the kernel of this circuit (but not
+    /// those of its children).
+    pub lints: Vec<RHDLLint>,
+    /// The name of the core, if it is a synchronizer that safely moves
+    /// signals between clock domains (see
+    /// [CircuitIO::SYNCHRONIZER](super::circuit_impl::CircuitIO::SYNCHRONIZER)).
+    pub synchronizer: Option<&'static str>,
     pub children: BTreeMap<String, CircuitDescriptor>,
 }
 
+// An empty descriptor.  Circuits that build their descriptors by hand
+// should fill in the fields they know about, and take the rest from
+// here (with `..Default::default()`), so that they keep building when
+// fields are added.
+impl Default for CircuitDescriptor {
+    fn default() -> Self {
+        Self {
+            unique_name: String::new(),
+            input_kind: Kind::Empty,
+            output_kind: Kind::Empty,
+            d_kind: Kind::Empty,
+            q_kind: Kind::Empty,
+            rtl: None,
+            ntl: Default::default(),
+            lints: vec![],
+            synchronizer: None,
+            children: Default::default(),
+        }
+    }
+}
+
 // Create a flow graph of an arbitrary circuit.  The model is
 //
 //          +-----------------+
@@ -48,12 +78,24 @@ pub struct CircuitDescriptor {
 pub fn build_descriptor<C: Circuit>(
     name: &str,
     children: BTreeMap<String, CircuitDescriptor>,
+) -> Result<CircuitDescriptor, RHDLError> {
+    build_descriptor_with::<C>(name, children, &NtlPipeline::default())
+}
+
+// As above, but the netlist is optimized with the given pipeline.
+pub fn build_descriptor_with<C: Circuit>(
+    name: &str,
+    children: BTreeMap<String, CircuitDescriptor>,
+    pipeline: &NtlPipeline,
 ) -> Result<CircuitDescriptor, RHDLError> {
     use crate::core::ntl;
-    let module = compile_design::<C::Kernel>(CompilationMode::Asynchronous)?;
-    // Build the netlist
-    // First construct the netlist for the update function
-    let update_netlist = build_ntl_from_rtl(&module);
+    // Compile the update function, along with its netlist
+    let CompiledDesign {
+        rtl: module,
+        ntl: update_netlist,
+        lints,
+        ..
+    } = compile_circuit_artifacts::<C::Kernel>(CompilationMode::Asynchronous)?;
     // Create a manual builder for the top level netlist
     let mut builder = ntl::builder::Builder::new(name);
     let output_kind: Kind = C::O::static_kind();
@@ -114,8 +156,10 @@ pub fn build_descriptor<C: Circuit>(
         output_kind: C::O::static_kind(),
         d_kind: C::D::static_kind(),
         q_kind: C::Q::static_kind(),
-        ntl: builder.build(ntl::builder::BuilderMode::Asynchronous)?,
+        ntl: builder.build_with(ntl::builder::BuilderMode::Asynchronous, pipeline)?,
         rtl: Some(module),
+        lints,
+        synchronizer: C::SYNCHRONIZER,
         children,
     })
 }
@@ -145,12 +189,24 @@ pub fn build_descriptor<C: Circuit>(
 pub fn build_synchronous_descriptor<C: Synchronous>(
     name: &str,
     children: BTreeMap<String, CircuitDescriptor>,
+) -> Result<CircuitDescriptor, RHDLError> {
+    build_synchronous_descriptor_with::<C>(name, children, &NtlPipeline::default())
+}
+
+// As above, but the netlist is optimized with the given pipeline.
+pub fn build_synchronous_descriptor_with<C: Synchronous>(
+    name: &str,
+    children: BTreeMap<String, CircuitDescriptor>,
+    pipeline: &NtlPipeline,
 ) -> Result<CircuitDescriptor, RHDLError> {
     use crate::core::ntl;
-    let module = compile_design::<C::Kernel>(CompilationMode::Synchronous)?;
-    // Build the netlist
-    // First construct the netlist for the update function
-    let update_netlist = build_ntl_from_rtl(&module);
+    // Compile the update function, along with its netlist
+    let CompiledDesign {
+        rtl: module,
+        ntl: update_netlist,
+        lints,
+        ..
+    } = compile_circuit_artifacts::<C::Kernel>(CompilationMode::Synchronous)?;
     // Create a manual builder for the top level netlist
     let mut builder = ntl::builder::Builder::new(name);
     // This is the kind of output of the update kernel - it must be equal to
@@ -230,6 +286,8 @@ pub fn build_synchronous_descriptor<C: Synchronous>(
         q_kind: C::Q::static_kind(),
         children,
         rtl: Some(module),
-        ntl: builder.build(ntl::builder::BuilderMode::Synchronous)?,
+        lints,
+        synchronizer: None,
+        ntl: builder.build_with(ntl::builder::BuilderMode::Synchronous, pipeline)?,
     })
 }
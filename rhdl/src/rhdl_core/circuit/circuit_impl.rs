@@ -1,8 +1,8 @@
 use crate::{
     prelude::Module,
     rhdl_core::{
-        circuit::yosys::run_yosys_synth, digital_fn::DigitalFn2, error::RHDLError,
-        ntl::hdl::generate_hdl, DigitalFn, Timed,
+        circuit::yosys::run_yosys_synth, compiler::NtlPipeline, digital_fn::DigitalFn2,
+        error::RHDLError, ntl::hdl::generate_hdl, DigitalFn, Timed,
     },
 };
 
@@ -12,6 +12,10 @@ pub trait CircuitIO: 'static + Sized + Clone + CircuitDQ {
     type I: Timed;
     type O: Timed;
     type Kernel: DigitalFn + DigitalFn2<A0 = Self::I, A1 = Self::Q, O = (Self::O, Self::D)>;
+    /// Cores that safely move signals between clock domains set this to
+    /// the name used for them in the clock domain crossing report (see
+    /// [cdc](super::cdc)).
+    const SYNCHRONIZER: Option<&'static str> = None;
 }
 
 pub trait CircuitDQ: 'static + Sized + Clone {
@@ -37,6 +41,22 @@ pub trait Circuit: 'static + Sized + Clone + CircuitIO {
     // auto derived
     fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError>;
 
+    // Like descriptor, but the netlist is optimized with the given pipeline.
+    // The derived circuits pass the pipeline down to their children, and
+    // use it as each level of the design is assembled.  Circuits that
+    // build their descriptors by hand have it run on the finished netlist.
+    fn descriptor_with(
+        &self,
+        name: &str,
+        pipeline: &NtlPipeline,
+    ) -> Result<CircuitDescriptor, RHDLError> {
+        let mut descriptor = self.descriptor(name)?;
+        if !pipeline.is_default() {
+            descriptor.ntl = pipeline.run(descriptor.ntl)?;
+        }
+        Ok(descriptor)
+    }
+
     // auto derived
     fn hdl(&self, name: &str) -> Result<HDLDescriptor, RHDLError>;
 
@@ -48,4 +68,11 @@ pub trait Circuit: 'static + Sized + Clone + CircuitIO {
         let descriptor = self.descriptor(name)?;
         generate_hdl(name, &descriptor.ntl)
     }
+
+    // Like netlist_hdl, but the netlist is built with the given pipeline
+    // so that user supplied passes are applied to the whole design.
+    fn netlist_hdl_with(&self, name: &str, pipeline: &NtlPipeline) -> Result<Module, RHDLError> {
+        let descriptor = self.descriptor_with(name, pipeline)?;
+        generate_hdl(name, &descriptor.ntl)
+    }
 }
@@ -1,13 +1,14 @@
 use crate::{
-    prelude::{trace, trace_pop_path, trace_push_path},
+    prelude::{trace, trace_pop_path, trace_push_path, BitWidth},
     rhdl_core::{
-        compile_design,
+        circuit::pipelined::Pipelined,
+        compiler::{cache::CompiledDesign, driver::compile_design_artifacts, lints::RHDLLint},
         digital_fn::{DigitalFn2, NoKernel3},
         hdl::{
             ast::{continuous_assignment, function_call, id, Direction, Module},
             builder::generate_verilog,
         },
-        ntl::from_rtl::build_ntl_from_rtl,
+        ntl,
         rtl::Object,
         CircuitDescriptor, ClockReset, CompilationMode, Digital, DigitalFn, HDLDescriptor, Kind,
         RHDLError, Synchronous, SynchronousDQ, SynchronousIO,
@@ -19,6 +20,8 @@ use super::hdl_backend::maybe_port_wire;
 #[derive(Clone)]
 pub struct Func<I: Digital, O: Digital> {
     module: Object,
+    netlist: ntl::Object,
+    lints: Vec<RHDLLint>,
     update: fn(ClockReset, I) -> O,
 }
 
@@ -39,9 +42,23 @@ impl<I: Digital, O: Digital> Func<I, O> {
         T: DigitalFn,
         T: DigitalFn2<A0 = ClockReset, A1 = I, O = O>,
     {
-        let module = compile_design::<T>(CompilationMode::Synchronous)?;
+        let CompiledDesign {
+            rtl: module,
+            ntl: netlist,
+            lints,
+            ..
+        } = compile_design_artifacts::<T>(CompilationMode::Synchronous)?;
         let update = T::func();
-        Ok(Self { module, update })
+        Ok(Self {
+            module,
+            netlist,
+            lints,
+            update,
+        })
+    }
+    /// Pipeline this function into `N` stages.
+    pub fn pipelined<N: BitWidth>(self) -> Pipelined<I, O, N> {
+        Pipelined::new(self.netlist, self.lints, self.update)
     }
 }
 
@@ -67,8 +84,10 @@ impl<I: Digital, O: Digital> Synchronous for Func<I, O> {
             d_kind: Kind::Empty,
             q_kind: Kind::Empty,
             rtl: Some(self.module.clone()),
+            lints: self.lints.clone(),
+            synchronizer: None,
             children: Default::default(),
-            ntl: build_ntl_from_rtl(&self.module),
+            ntl: self.netlist.clone(),
         })
     }
 
@@ -1,7 +1,8 @@
 use crate::rhdl_core::hdl::ast::Module;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
-#[derive(Clone, Hash)]
+#[derive(Clone, Hash, Serialize, Deserialize)]
 pub struct HDLDescriptor {
     pub name: String,
     pub body: Module,
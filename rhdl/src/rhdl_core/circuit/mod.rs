@@ -2,6 +2,7 @@ pub mod adapter;
 pub mod array_circuit;
 pub mod array_synchronous;
 pub mod async_func;
+pub mod cdc;
 pub mod chain;
 pub mod circuit_descriptor;
 pub mod circuit_impl;
@@ -11,5 +12,8 @@ pub mod func;
 pub mod hdl_backend;
 pub mod hdl_descriptor;
 pub mod phantom;
+pub mod pipelined;
+pub mod resources;
 pub mod synchronous;
+pub mod timing;
 pub mod yosys;
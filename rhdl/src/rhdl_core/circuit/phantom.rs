@@ -39,6 +39,7 @@ impl<T: Digital + 'static> Synchronous for std::marker::PhantomData<T> {
             children: Default::default(),
             rtl: None,
             ntl: ntl.build(ntl::builder::BuilderMode::Synchronous)?,
+            ..Default::default()
         })
     }
 
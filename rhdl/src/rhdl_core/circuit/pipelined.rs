@@ -0,0 +1,179 @@
+//! A combinational kernel, pipelined into a number of stages.
+//!
+//! Datapaths are easiest to write as combinational math in a single
+//! kernel.  A [Pipelined] circuit takes such a kernel (or a
+//! [Func](super::func::Func)) and inserts `N` banks of registers into its
+//! netlist, so that the stages between them are balanced in logic depth.  The output of the circuit
+//! is the output of the kernel, `N` clocks later:
+//! ```ignore
+//! let mac = Pipelined::<(b8, b8, b16), b16, U3>::try_new::<mac>()?;
+//! assert_eq!(Pipelined::<(b8, b8, b16), b16, U3>::LATENCY, 3);
+//! ```
+//! The pipeline registers have no reset, so the outputs are not defined
+//! until `N` clocks after the first input.  Nor does a reset clear them:
+//! the values in flight when the reset is asserted still come out of the
+//! pipeline, `N` clocks after they went in.
+use std::marker::PhantomData;
+
+use crate::{
+    prelude::{BitWidth, trace, trace_pop_path, trace_push_path},
+    rhdl_core::{
+        CircuitDescriptor, ClockReset, CompilationMode, Digital, DigitalFn, HDLDescriptor, Kind,
+        RHDLError, Synchronous, SynchronousDQ, SynchronousIO,
+        circuit::timing::LogicLevels,
+        compiler::{cache::CompiledDesign, driver::compile_design_artifacts, lints::RHDLLint},
+        digital_fn::{DigitalFn2, NoKernel3},
+        hdl::ast::{Direction, Module, component_instance, connection, id},
+        ntl::{self, hdl::generate_hdl, retime::retime},
+    },
+};
+
+use super::hdl_backend::maybe_port_wire;
+
+#[derive(Clone)]
+pub struct Pipelined<I: Digital, O: Digital, N: BitWidth> {
+    netlist: ntl::Object,
+    lints: Vec<RHDLLint>,
+    update: fn(ClockReset, I) -> O,
+    marker: PhantomData<N>,
+}
+
+impl<I: Digital, O: Digital, N: BitWidth> SynchronousIO for Pipelined<I, O, N> {
+    type I = I;
+    type O = O;
+    type Kernel = NoKernel3<ClockReset, I, (), (O, ())>;
+}
+
+impl<I: Digital, O: Digital, N: BitWidth> SynchronousDQ for Pipelined<I, O, N> {
+    type D = ();
+    type Q = ();
+}
+
+impl<I: Digital, O: Digital, N: BitWidth> Pipelined<I, O, N> {
+    /// The number of clocks between an input and the corresponding output.
+    pub const LATENCY: usize = N::BITS;
+
+    pub fn try_new<T>() -> Result<Self, RHDLError>
+    where
+        T: DigitalFn,
+        T: DigitalFn2<A0 = ClockReset, A1 = I, O = O>,
+    {
+        let CompiledDesign {
+            ntl: netlist,
+            lints,
+            ..
+        } = compile_design_artifacts::<T>(CompilationMode::Synchronous)?;
+        Ok(Self::new(netlist, lints, T::func()))
+    }
+
+    pub(crate) fn new(
+        netlist: ntl::Object,
+        lints: Vec<RHDLLint>,
+        update: fn(ClockReset, I) -> O,
+    ) -> Self {
+        Self {
+            netlist,
+            lints,
+            update,
+            marker: PhantomData,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+#[doc(hidden)]
+pub struct S<O: Digital> {
+    cr: ClockReset,
+    next: O,
+    stages: Vec<O>,
+}
+
+impl<I: Digital, O: Digital, N: BitWidth> Synchronous for Pipelined<I, O, N> {
+    type S = S<O>;
+
+    fn init(&self) -> Self::S {
+        S {
+            cr: ClockReset::dont_care(),
+            next: O::dont_care(),
+            stages: vec![O::dont_care(); N::BITS],
+        }
+    }
+
+    fn sim(&self, clock_reset: ClockReset, input: Self::I, state: &mut Self::S) -> Self::O {
+        trace_push_path("pipelined");
+        trace("input", &input);
+        let value = (self.update)(clock_reset, input);
+        let clock = clock_reset.clock;
+        if !clock.raw() {
+            state.next = value;
+        }
+        if clock.raw() && !state.cr.clock.raw() && !state.stages.is_empty() {
+            state.stages.rotate_right(1);
+            state.stages[0] = state.next;
+        }
+        state.cr = clock_reset;
+        let output = state.stages.last().copied().unwrap_or(value);
+        trace("output", &output);
+        trace_pop_path();
+        output
+    }
+
+    fn description(&self) -> String {
+        format!("Combinational function pipelined into {} stages", N::BITS)
+    }
+
+    fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError> {
+        Ok(CircuitDescriptor {
+            unique_name: name.to_string(),
+            input_kind: Self::I::static_kind(),
+            output_kind: Self::O::static_kind(),
+            d_kind: Kind::Empty,
+            q_kind: Kind::Empty,
+            rtl: None,
+            lints: self.lints.clone(),
+            children: Default::default(),
+            ntl: retime(&self.netlist, name, N::BITS, &LogicLevels)?,
+            ..Default::default()
+        })
+    }
+
+    fn hdl(&self, name: &str) -> Result<HDLDescriptor, RHDLError> {
+        let descriptor = self.descriptor(name)?;
+        let module_name = &descriptor.unique_name;
+        let inner_name = format!("{module_name}_inner");
+        let mut module = Module {
+            name: module_name.clone(),
+            description: self.description(),
+            ..Default::default()
+        };
+        module.ports = [
+            maybe_port_wire(Direction::Input, 2, "clock_reset"),
+            maybe_port_wire(Direction::Input, Self::I::bits(), "i"),
+            maybe_port_wire(Direction::Output, Self::O::bits(), "o"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        // The netlist module takes its arguments as (clock_reset, i)
+        let connections = [
+            Some(connection("arg_0", id("clock_reset"))),
+            (Self::I::bits() != 0).then(|| connection("arg_1", id("i"))),
+            Some(connection("out", id("o"))),
+        ];
+        module.statements.push(component_instance(
+            &inner_name,
+            "inner",
+            connections.into_iter().flatten().collect(),
+        ));
+        let inner = HDLDescriptor {
+            name: inner_name.clone(),
+            body: generate_hdl(&inner_name, &descriptor.ntl)?,
+            children: Default::default(),
+        };
+        Ok(HDLDescriptor {
+            name: module_name.into(),
+            body: module,
+            children: [(inner_name, inner)].into(),
+        })
+    }
+}
@@ -0,0 +1,311 @@
+//! Estimates of the resources used by a circuit.
+//!
+//! The estimates are made from the netlist of the circuit, without
+//! running a synthesis tool, so they are only approximate.  They are
+//! meant for catching area regressions in unit tests:
+//! ```ignore
+//! let report = resources::resource_report(&uut.descriptor("top")?);
+//! assert!(report.usage.lut6 < 40);
+//! ```
+//! The netlist of a circuit includes the netlists of its children, so
+//! the usage of a circuit includes the usage of its children.  And since
+//! the netlist of a circuit is optimized as a whole, the usage of a
+//! circuit can be less than the sum of the usage of its children.
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::rhdl_core::{
+    CircuitDescriptor,
+    ntl::{
+        Object,
+        object::BlackBoxMode,
+        spec::{BlackBox, OpCode, UnaryOp, Vector, VectorOp, Wire},
+        visit::visit_wires,
+    },
+};
+
+/// The resources used by a netlist.  The operators are counted by width
+/// (a map from the number of bits to the number of operators).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ResourceUsage {
+    /// The number of register bits, which are the outputs of the synchronous
+    /// black boxes (such as DFFs and registered memories).
+    pub flip_flops: usize,
+    /// Adders, subtractors and negations
+    pub adders: BTreeMap<usize, usize>,
+    pub multipliers: BTreeMap<usize, usize>,
+    /// Equality and ordering comparisons
+    pub comparators: BTreeMap<usize, usize>,
+    /// The number of single bit 2:1 multiplexers
+    pub muxes: usize,
+    /// The estimated number of 4 input lookup tables
+    pub lut4: usize,
+    /// The estimated number of 6 input lookup tables
+    pub lut6: usize,
+}
+
+/// The resource usage of a circuit, and of each of its children.
+#[derive(Clone, Debug)]
+pub struct ResourceReport {
+    pub name: String,
+    pub usage: ResourceUsage,
+    pub children: BTreeMap<String, ResourceReport>,
+}
+
+pub fn resource_report(descriptor: &CircuitDescriptor) -> ResourceReport {
+    ResourceReport {
+        name: descriptor.unique_name.clone(),
+        usage: estimate_resources(&descriptor.ntl),
+        children: descriptor
+            .children
+            .iter()
+            .map(|(name, child)| (name.clone(), resource_report(child)))
+            .collect(),
+    }
+}
+
+pub fn estimate_resources(ntl: &Object) -> ResourceUsage {
+    let mut usage = ResourceUsage {
+        lut4: estimate_luts(ntl, 4),
+        lut6: estimate_luts(ntl, 6),
+        ..Default::default()
+    };
+    for lop in &ntl.ops {
+        match &lop.op {
+            OpCode::BlackBox(BlackBox { lhs, code, .. })
+                if ntl.black_boxes[code.raw()].mode == BlackBoxMode::Synchronous =>
+            {
+                usage.flip_flops += lhs.len();
+            }
+            OpCode::Vector(Vector { op, lhs, arg1, .. }) => {
+                let (class, width) = match op {
+                    VectorOp::Add | VectorOp::Sub => (&mut usage.adders, lhs.len()),
+                    VectorOp::Mul => (&mut usage.multipliers, lhs.len()),
+                    VectorOp::Eq
+                    | VectorOp::Ne
+                    | VectorOp::Lt
+                    | VectorOp::Le
+                    | VectorOp::Gt
+                    | VectorOp::Ge => (&mut usage.comparators, arg1.len()),
+                    VectorOp::Shl | VectorOp::Shr => continue,
+                };
+                *class.entry(width).or_default() += 1;
+            }
+            OpCode::Unary(unary) if unary.op == UnaryOp::Neg => {
+                *usage.adders.entry(unary.lhs.len()).or_default() += 1;
+            }
+            OpCode::Select(_) => usage.muxes += 1,
+            OpCode::Case(case) => usage.muxes += case.entries.len().saturating_sub(1),
+            _ => {}
+        }
+    }
+    usage
+}
+
+// The number of k input LUTs needed to reduce n signals to one.
+fn lut_tree(mut n: usize, k: usize) -> usize {
+    let mut count = 0;
+    while n > 1 {
+        n = n.div_ceil(k);
+        count += n;
+    }
+    count.max(1)
+}
+
+// The single bit logic functions in the netlist, as the output and the
+// inputs of each.  These can be packed together into LUTs.
+fn logic_function(op: &OpCode) -> Option<(Wire, Vec<Wire>)> {
+    match op {
+        OpCode::Binary(binary) => Some((binary.lhs, vec![binary.arg1, binary.arg2])),
+        OpCode::Not(not) => Some((not.lhs, vec![not.arg])),
+        OpCode::Select(select) => Some((
+            select.lhs,
+            vec![select.selector, select.true_case, select.false_case],
+        )),
+        OpCode::Case(case) => Some((
+            case.lhs,
+            case.discriminant
+                .iter()
+                .chain(case.entries.iter().map(|(_, wire)| wire))
+                .copied()
+                .collect(),
+        )),
+        OpCode::Unary(unary) if unary.lhs.len() == 1 => Some((unary.lhs[0], unary.arg.clone())),
+        _ => None,
+    }
+}
+
+// The number of select bits of the largest mux that fits in a k input
+// LUT (a 2:1 mux for k = 4, a 4:1 mux for k = 6).
+fn mux_select_bits(k: usize) -> usize {
+    (1..).take_while(|s| (1 << s) + s <= k).last().unwrap_or(1)
+}
+
+// The LUTs used by the operators that are not packed with the logic
+// around them.  Arithmetic and ordering comparisons use one LUT per bit
+// to drive the carry chain, whatever the size of the LUT.  A multiplier
+// is an array of such adders, with two partial products per LUT (the
+// second one is formed in the carry logic).  The other operators are
+// built from trees of LUTs, so they need fewer of the larger LUTs.
+fn operator_luts(op: &OpCode, k: usize) -> usize {
+    match op {
+        OpCode::Vector(Vector {
+            op,
+            lhs,
+            arg1,
+            arg2,
+            ..
+        }) => match op {
+            VectorOp::Add
+            | VectorOp::Sub
+            | VectorOp::Lt
+            | VectorOp::Le
+            | VectorOp::Gt
+            | VectorOp::Ge => arg1.len().max(lhs.len()),
+            VectorOp::Mul => (arg1.len() * arg2.len()).div_ceil(2),
+            VectorOp::Eq | VectorOp::Ne => lut_tree(2 * arg1.len(), k),
+            // A barrel shifter, with one LUT per bit for each level of muxes
+            VectorOp::Shl | VectorOp::Shr => lhs.len() * arg2.len().div_ceil(mux_select_bits(k)),
+        },
+        OpCode::Unary(unary) if unary.op == UnaryOp::Neg => unary.lhs.len(),
+        // Each output bit is a function of the address.  Tables that are
+        // too big for one LUT are split across LUTs, and then muxed.
+        OpCode::Rom(rom) => {
+            let address = rom.address.len();
+            let per_bit = if address <= k {
+                1
+            } else {
+                (1_usize << (address - k).min(32)) * 2 - 1
+            };
+            rom.lhs.len() * per_bit
+        }
+        _ => 0,
+    }
+}
+
+// Estimate the number of k input LUTs used by the netlist.  The single
+// bit logic functions are packed greedily into LUTs: a function absorbs
+// the functions that drive it (and nothing else) as long as the result
+// still has at most k inputs.  Each function that is not absorbed takes
+// one LUT.
+fn estimate_luts(ntl: &Object, k: usize) -> usize {
+    let mut alias: HashMap<Wire, Wire> = HashMap::default();
+    for lop in &ntl.ops {
+        if let OpCode::Assign(assign) = &lop.op {
+            let rhs = alias.get(&assign.rhs).copied().unwrap_or(assign.rhs);
+            alias.insert(assign.lhs, rhs);
+        }
+    }
+    let resolve = |wire: Wire| alias.get(&wire).copied().unwrap_or(wire);
+    let mut fanout: HashMap<Wire, usize> = HashMap::default();
+    for lop in &ntl.ops {
+        if !matches!(lop.op, OpCode::Assign(_)) {
+            visit_wires(&lop.op, |sense, wire| {
+                if sense.is_read() {
+                    *fanout.entry(resolve(*wire)).or_default() += 1;
+                }
+            });
+        }
+    }
+    for wire in &ntl.outputs {
+        *fanout.entry(resolve(*wire)).or_default() += 1;
+    }
+    let mut support: HashMap<Wire, BTreeSet<Wire>> = HashMap::default();
+    let mut absorbed: HashSet<Wire> = HashSet::default();
+    let mut luts = 0;
+    for lop in &ntl.ops {
+        let Some((lhs, inputs)) = logic_function(&lop.op) else {
+            luts += operator_luts(&lop.op, k);
+            continue;
+        };
+        let inputs = inputs
+            .into_iter()
+            .filter(|wire| wire.is_reg())
+            .map(resolve)
+            .collect::<BTreeSet<_>>();
+        if inputs.len() > k {
+            luts += lut_tree(inputs.len(), k);
+            continue;
+        }
+        let mut candidates = inputs
+            .iter()
+            .filter(|wire| fanout.get(wire) == Some(&1))
+            .filter_map(|wire| support.get(wire).map(|leaves| (leaves.len(), *wire)))
+            .collect::<Vec<_>>();
+        candidates.sort();
+        let mut leaves = inputs;
+        for (_, wire) in candidates {
+            let mut trial = leaves.clone();
+            trial.remove(&wire);
+            trial.extend(support[&wire].iter().copied());
+            if trial.len() <= k {
+                leaves = trial;
+                absorbed.insert(wire);
+            }
+        }
+        support.insert(lhs, leaves);
+    }
+    luts + support
+        .keys()
+        .filter(|wire| !absorbed.contains(wire))
+        .count()
+}
+
+fn widths(counts: &BTreeMap<usize, usize>) -> String {
+    if counts.is_empty() {
+        return "-".into();
+    }
+    counts
+        .iter()
+        .map(|(width, count)| format!("{count}x{width}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl ResourceReport {
+    fn rows(&self, depth: usize, name: &str, rows: &mut Vec<[String; 8]>) {
+        let usage = &self.usage;
+        rows.push([
+            format!("{:indent$}{name}", "", indent = depth * 2),
+            usage.flip_flops.to_string(),
+            usage.lut4.to_string(),
+            usage.lut6.to_string(),
+            usage.muxes.to_string(),
+            widths(&usage.adders),
+            widths(&usage.multipliers),
+            widths(&usage.comparators),
+        ]);
+        for (name, child) in &self.children {
+            child.rows(depth + 1, name, rows);
+        }
+    }
+}
+
+// A table with one row per circuit, with the children indented below
+// their parents.  The operators are listed as count x width.
+impl std::fmt::Display for ResourceReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rows =
+            vec![["circuit", "ff", "lut4", "lut6", "mux", "add", "mul", "cmp"].map(String::from)];
+        self.rows(0, &self.name, &mut rows);
+        let mut width = [0; 8];
+        for row in &rows {
+            for (width, cell) in width.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        for row in rows {
+            let line = row
+                .iter()
+                .zip(width)
+                .enumerate()
+                .map(|(ndx, (cell, width))| match ndx {
+                    0 => format!("{cell:<width$}"),
+                    _ => format!("{cell:>width$}"),
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
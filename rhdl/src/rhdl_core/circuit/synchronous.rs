@@ -1,8 +1,9 @@
 use crate::{
     prelude::Module,
     rhdl_core::{
-        circuit::yosys::run_yosys_synth, digital_fn::DigitalFn3, error::RHDLError,
-        ntl::hdl::generate_hdl, CircuitDescriptor, ClockReset, Digital, DigitalFn, HDLDescriptor,
+        circuit::yosys::run_yosys_synth, compiler::NtlPipeline, digital_fn::DigitalFn3,
+        error::RHDLError, ntl::hdl::generate_hdl, CircuitDescriptor, ClockReset, Digital,
+        DigitalFn, HDLDescriptor,
     },
 };
 
@@ -31,6 +32,22 @@ pub trait Synchronous: 'static + Sized + Clone + SynchronousIO {
 
     fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError>;
 
+    // Like descriptor, but the netlist is optimized with the given pipeline.
+    // The derived circuits pass the pipeline down to their children, and
+    // use it as each level of the design is assembled.  Circuits that
+    // build their descriptors by hand have it run on the finished netlist.
+    fn descriptor_with(
+        &self,
+        name: &str,
+        pipeline: &NtlPipeline,
+    ) -> Result<CircuitDescriptor, RHDLError> {
+        let mut descriptor = self.descriptor(name)?;
+        if !pipeline.is_default() {
+            descriptor.ntl = pipeline.run(descriptor.ntl)?;
+        }
+        Ok(descriptor)
+    }
+
     fn hdl(&self, name: &str) -> Result<HDLDescriptor, RHDLError>;
 
     fn yosys_check(&self) -> Result<(), RHDLError> {
@@ -41,4 +58,11 @@ pub trait Synchronous: 'static + Sized + Clone + SynchronousIO {
         let descriptor = self.descriptor(name)?;
         generate_hdl(name, &descriptor.ntl)
     }
+
+    // Like netlist_hdl, but the netlist is built with the given pipeline
+    // so that user supplied passes are applied to the whole design.
+    fn netlist_hdl_with(&self, name: &str, pipeline: &NtlPipeline) -> Result<Module, RHDLError> {
+        let descriptor = self.descriptor_with(name, pipeline)?;
+        generate_hdl(name, &descriptor.ntl)
+    }
 }
@@ -0,0 +1,254 @@
+//! Static timing analysis of a circuit netlist.
+//!
+//! The analysis finds the deepest path of logic between two registers
+//! (or between the inputs and outputs of the circuit and a register).
+//! The delay of each op in the netlist comes from a [DelayModel].  The
+//! default model ([LogicLevels]) counts the levels of logic.
+//! The critical path is reported as a [miette::Diagnostic], so
+//! ```ignore
+//! let path = timing::critical_path(&uut, "top")?;
+//! eprintln!("{:?}", miette::Report::new(path));
+//! ```
+//! shows the kernel source lines that the path runs through.
+use miette::{Diagnostic, SourceSpan};
+use petgraph::{Direction, algo::toposort, graph::NodeIndex};
+use thiserror::Error;
+
+use crate::{
+    prelude::{Circuit, RHDLError, Synchronous},
+    rhdl_core::{
+        SourcePool,
+        error::rhdl_error,
+        ntl::{
+            Object,
+            error::NetLoopError,
+            graph::{GraphMode, NetGraph, WriteSource, make_net_graph},
+            spec::{OpCode, UnaryOp, VectorOp, Wire},
+        },
+    },
+};
+
+/// The delay model used to time the netlist.  The delay of each
+/// op is in arbitrary units (levels, picoseconds, etc.).
+pub trait DelayModel {
+    fn delay(&self, op: &OpCode) -> usize;
+}
+
+impl<F: Fn(&OpCode) -> usize> DelayModel for F {
+    fn delay(&self, op: &OpCode) -> usize {
+        self(op)
+    }
+}
+
+/// The default delay model, which counts the levels of logic.
+/// Each single bit op counts as one level.  The word ops count
+/// the levels they take once built from gates: one per bit of the
+/// carry chain for arithmetic and ordering comparisons, one per bit
+/// of the shift amount for shifts, and a tree for equality and the
+/// reductions.  Wiring, comments and black boxes are free.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogicLevels;
+
+// The depth of a tree of 2 input gates with n leaves
+fn tree_depth(n: usize) -> usize {
+    n.next_power_of_two().trailing_zeros() as usize
+}
+
+impl DelayModel for LogicLevels {
+    fn delay(&self, op: &OpCode) -> usize {
+        match op {
+            OpCode::Noop | OpCode::Comment(_) | OpCode::Assign(_) | OpCode::BlackBox(_) => 0,
+            OpCode::Vector(vector) => match vector.op {
+                VectorOp::Add
+                | VectorOp::Sub
+                | VectorOp::Lt
+                | VectorOp::Le
+                | VectorOp::Gt
+                | VectorOp::Ge => vector.arg1.len(),
+                VectorOp::Mul => vector.arg1.len() + vector.arg2.len(),
+                VectorOp::Eq | VectorOp::Ne => 1 + tree_depth(vector.arg1.len()),
+                VectorOp::Shl | VectorOp::Shr => vector.arg2.len(),
+            }
+            .max(1),
+            OpCode::Unary(unary) => match unary.op {
+                UnaryOp::Neg => unary.arg.len(),
+                UnaryOp::All | UnaryOp::Any | UnaryOp::Xor => tree_depth(unary.arg.len()),
+            }
+            .max(1),
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("RHDL Critical Path")]
+pub struct CriticalPath {
+    /// The total delay along the path
+    pub depth: usize,
+    pub src: SourcePool,
+    /// The source spans along the path, with the delay accumulated
+    /// at each of them.
+    pub elements: Vec<(usize, SourceSpan)>,
+}
+
+impl Diagnostic for CriticalPath {
+    fn severity(&self) -> Option<miette::Severity> {
+        Some(miette::Severity::Advice)
+    }
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.src)
+    }
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        Some(Box::new(format!(
+            "This is the longest path through the logic of the design, with a depth of {}",
+            self.depth
+        )))
+    }
+    fn labels<'a>(
+        &'a self,
+    ) -> Option<Box<dyn std::iter::Iterator<Item = miette::LabeledSpan> + 'a>> {
+        Some(Box::new(self.elements.iter().map(|(depth, span)| {
+            miette::LabeledSpan::new_primary_with_span(Some(format!("depth {depth}")), *span)
+        })))
+    }
+}
+
+/// Find the critical path of a synchronous circuit, counting levels of logic.
+/// The netlist is built for the circuit with the given name.
+pub fn critical_path<T: Synchronous>(uut: &T, name: &str) -> miette::Result<CriticalPath> {
+    critical_path_with(uut, name, &LogicLevels)
+}
+
+/// Find the critical path of a synchronous circuit with the given delay model.
+pub fn critical_path_with<T: Synchronous>(
+    uut: &T,
+    name: &str,
+    model: &impl DelayModel,
+) -> miette::Result<CriticalPath> {
+    let descriptor = uut.descriptor(name)?;
+    Ok(logic_depth(&descriptor.ntl, GraphMode::Synchronous, model)?)
+}
+
+/// Find the critical path of an asynchronous circuit, counting levels of logic.
+pub fn asynchronous_critical_path<T: Circuit>(uut: &T, name: &str) -> miette::Result<CriticalPath> {
+    asynchronous_critical_path_with(uut, name, &LogicLevels)
+}
+
+/// Find the critical path of an asynchronous circuit with the given delay model.
+pub fn asynchronous_critical_path_with<T: Circuit>(
+    uut: &T,
+    name: &str,
+    model: &impl DelayModel,
+) -> miette::Result<CriticalPath> {
+    let descriptor = uut.descriptor(name)?;
+    Ok(logic_depth(
+        &descriptor.ntl,
+        GraphMode::Asynchronous,
+        model,
+    )?)
+}
+
+/// Compute the critical path of a netlist.  Paths start at the inputs and
+/// at the outputs of black boxes, and end at the outputs and at the inputs
+/// of black boxes.
+pub fn logic_depth(
+    ntl: &Object,
+    mode: GraphMode,
+    model: &impl DelayModel,
+) -> Result<CriticalPath, RHDLError> {
+    let Arrivals { dep, arrival, from } = arrival_times(ntl, mode, model)?;
+    let code = &ntl.code;
+    // The end points are the nodes that drive the outputs and the black boxes.
+    let end_points = ntl
+        .outputs
+        .iter()
+        .chain(ntl.ops.iter().flat_map(|lop| match &lop.op {
+            OpCode::BlackBox(black_box) => black_box.arg.iter().flatten().collect(),
+            _ => vec![],
+        }))
+        .copied()
+        .flat_map(Wire::reg)
+        .filter_map(|reg| match dep.reg_map.get(&reg) {
+            Some(WriteSource::Input) => Some(dep.input_node),
+            Some(WriteSource::OpCode(ndx)) => Some(dep.op_nodes[*ndx]),
+            _ => None,
+        });
+    let Some(end) = end_points.max_by_key(|node| arrival[node.index()]) else {
+        return Ok(CriticalPath {
+            depth: 0,
+            src: code.source(),
+            elements: vec![],
+        });
+    };
+    let mut path = vec![end];
+    while let Some(pred) = from[path.last().unwrap().index()] {
+        path.push(pred);
+    }
+    let mut elements: Vec<(usize, SourceSpan)> = vec![];
+    for node in path.into_iter().rev() {
+        let WriteSource::OpCode(ndx) = dep.graph[node] else {
+            continue;
+        };
+        let Some(loc) = ntl.ops[ndx].loc else {
+            continue;
+        };
+        let span = SourceSpan::from(code.span(loc));
+        let depth = arrival[node.index()];
+        // Consecutive ops from the same source line are reported once
+        match elements.last_mut() {
+            Some(last) if last.1 == span => last.0 = depth,
+            _ => elements.push((depth, span)),
+        }
+    }
+    Ok(CriticalPath {
+        depth: arrival[end.index()],
+        src: code.source(),
+        elements,
+    })
+}
+
+// The arrival time at the output of each node of the net
+// graph, along with the node on the longest path into it.
+pub(crate) struct Arrivals {
+    pub(crate) dep: NetGraph,
+    pub(crate) arrival: Vec<usize>,
+    pub(crate) from: Vec<Option<NodeIndex>>,
+}
+
+pub(crate) fn arrival_times(
+    ntl: &Object,
+    mode: GraphMode,
+    model: &impl DelayModel,
+) -> Result<Arrivals, RHDLError> {
+    let dep = make_net_graph(ntl, mode);
+    let code = &ntl.code;
+    let order = toposort(&dep.graph, None).map_err(|cycle| {
+        let elements = match dep.graph[cycle.node_id()] {
+            WriteSource::OpCode(ndx) => ntl.ops[ndx]
+                .loc
+                .map(|loc| (None, SourceSpan::from(code.span(loc))))
+                .into_iter()
+                .collect(),
+            _ => vec![],
+        };
+        rhdl_error(NetLoopError {
+            src: code.source(),
+            elements,
+        })
+    })?;
+    let mut arrival = vec![0; dep.graph.node_count()];
+    let mut from: Vec<Option<NodeIndex>> = vec![None; dep.graph.node_count()];
+    for node in order {
+        let delay = match dep.graph[node] {
+            WriteSource::OpCode(ndx) => model.delay(&ntl.ops[ndx].op),
+            _ => 0,
+        };
+        let latest = dep
+            .graph
+            .neighbors_directed(node, Direction::Incoming)
+            .max_by_key(|pred| arrival[pred.index()]);
+        arrival[node.index()] = delay + latest.map(|pred| arrival[pred.index()]).unwrap_or(0);
+        from[node.index()] = latest;
+    }
+    Ok(Arrivals { dep, arrival, from })
+}
@@ -10,6 +10,7 @@
 //!  - You can select out items from the [SlotVec] with a functional
 //!    and unused items will be removed, and the keys reassigned.
 //!
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::hash::Hash;
 use std::ops::{Index, IndexMut};
@@ -20,7 +21,7 @@ pub trait SlotKey: Copy + Clone + PartialEq + Eq + Ord + PartialOrd + Hash {
     fn index(self) -> usize;
 }
 
-#[derive(Clone, Hash)]
+#[derive(Clone, Hash, Serialize, Deserialize)]
 pub struct SlotVec<T, I> {
     id: u64,
     vals: Vec<T>,
@@ -1,11 +1,12 @@
 use super::slot_vec::*;
+use serde::{Deserialize, Serialize};
 use std::hash::Hash;
 
 pub trait SymbolKind: Copy + Ord + Hash + Default {
     const NAME: &'static str;
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct LiteralId<K> {
     id: u64,
     index: usize,
@@ -38,7 +39,7 @@ impl<K: SymbolKind> std::fmt::Debug for LiteralId<K> {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct RegisterId<K>
 where
     K: Hash,
@@ -76,7 +77,7 @@ impl<K: SymbolKind> std::fmt::Debug for RegisterId<K> {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum Symbol<K: SymbolKind> {
     Literal(LiteralId<K>),
     Register(RegisterId<K>),
@@ -145,7 +146,7 @@ impl<K: SymbolKind> Symbol<K> {
     }
 }
 
-#[derive(Clone, Hash)]
+#[derive(Clone, Hash, Serialize, Deserialize)]
 pub struct SymbolTable<L, R, M, K: SymbolKind> {
     lit: SlotVec<(L, M), LiteralId<K>>,
     reg: SlotVec<(R, M), RegisterId<K>>,
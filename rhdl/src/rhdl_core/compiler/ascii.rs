@@ -104,6 +104,13 @@ impl AsciiRenderer {
                 self.render_pat(&enum_arm.pat)?;
                 self.push(&format!("#{:?}", &enum_arm.discriminant));
             }
+            ArmKind::Binding(binding_arm) => {
+                self.render_pat(&binding_arm.pat)?;
+            }
+        }
+        if let Some(guard) = &arm.guard {
+            self.push(" if ");
+            self.render_expr(guard)?;
         }
         self.push(" => ");
         self.render_expr(&arm.body)?;
@@ -254,6 +261,13 @@ impl AsciiRenderer {
                 self.render_block(&for_loop.body)?;
                 self.indent -= 1;
             }
+            ExprKind::While(while_loop) => {
+                self.push("while_loop");
+                self.indent += 1;
+                self.render_expr(&while_loop.cond)?;
+                self.render_block(&while_loop.body)?;
+                self.indent -= 1;
+            }
             ExprKind::Range(range) => {
                 self.push("range");
                 self.indent += 1;
@@ -0,0 +1,156 @@
+//! On-disk cache for compiled kernels
+//!
+//! Compiling a kernel through stage1 and stage2 and building its netlist
+//! is the bulk of the time spent constructing descriptors for large designs.
+//! The output is a pure function of the kernel AST (including the ASTs of
+//! any kernels it calls), the compilation mode, whether the kernel is the
+//! update function of a circuit, and the compiler itself, so it can be
+//! stored between runs.  The lint warnings found while compiling the kernel
+//! are stored with it.
+//!
+//! The cache is opt-in.  Set the `RHDL_CACHE_DIR` environment variable to a
+//! directory, or call [set_cache_dir] before compiling anything.  Each entry
+//! is a single file named by a SHA-256 of the key.  Unreadable or stale
+//! entries are treated as a miss and recompiled.  The key includes a
+//! fingerprint of the compiler sources (computed by the build script), so
+//! entries written by a different build of the compiler are never used.
+use std::{
+    collections::HashSet,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{LazyLock, RwLock},
+};
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::rhdl_core::{
+    RHDLError,
+    ast::{
+        ast_impl::{Expr, ExprCall, ExprKind, FunctionId, KernelFn},
+        visit::{self, Visitor},
+    },
+    kernel::{Kernel, KernelFnKind},
+    ntl, rhif, rtl,
+};
+
+use super::{CompilationMode, lints::RHDLLint, stage1::KernelRole};
+
+/// Bump this when the serialized form of the compiler output changes.
+const CACHE_FORMAT_VERSION: u32 = 2;
+
+const CACHE_DIR_ENV: &str = "RHDL_CACHE_DIR";
+
+static CACHE_DIR: LazyLock<RwLock<Option<PathBuf>>> =
+    LazyLock::new(|| RwLock::new(std::env::var_os(CACHE_DIR_ENV).map(PathBuf::from)));
+
+/// The artifacts produced by compiling a kernel.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CompiledDesign {
+    pub rhif: rhif::Object,
+    pub rtl: rtl::Object,
+    /// The netlist, after optimization
+    pub ntl: ntl::Object,
+    pub lints: Vec<RHDLLint>,
+}
+
+/// Set (or clear) the directory used to cache compiled kernels.  This
+/// overrides the `RHDL_CACHE_DIR` environment variable.
+pub fn set_cache_dir(dir: Option<PathBuf>) {
+    *CACHE_DIR.write().unwrap() = dir;
+}
+
+/// The directory used to cache compiled kernels, if caching is enabled.
+pub fn cache_dir() -> Option<PathBuf> {
+    CACHE_DIR.read().unwrap().clone()
+}
+
+// Hashes the AST of a kernel, and of every kernel it calls.  The
+// Debug form of a call only names the callee, so we need to walk
+// into the called kernels to catch changes to them.
+struct Fingerprint {
+    hasher: Sha256,
+    seen: HashSet<FunctionId>,
+}
+
+impl Visitor for Fingerprint {
+    fn visit_kernel_fn(&mut self, node: &KernelFn) -> Result<(), RHDLError> {
+        if !self.seen.insert(node.fn_id) {
+            return Ok(());
+        }
+        self.hasher.update(format!("{node:?}"));
+        visit::visit_kernel_fn(self, node)
+    }
+    fn visit_expr(&mut self, node: &Expr) -> Result<(), RHDLError> {
+        if let ExprKind::Call(ExprCall {
+            code: Some(KernelFnKind::Kernel(kernel)),
+            ..
+        }) = &node.kind
+        {
+            self.visit_kernel_fn(kernel.inner())?;
+        }
+        visit::visit_expr(self, node)
+    }
+}
+
+pub(crate) fn cache_key(
+    kernel: &Kernel,
+    type_name: &str,
+    mode: CompilationMode,
+    role: KernelRole,
+) -> Result<String, RHDLError> {
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION"));
+    hasher.update(env!("RHDL_COMPILER_FINGERPRINT"));
+    hasher.update(CACHE_FORMAT_VERSION.to_le_bytes());
+    hasher.update(type_name);
+    hasher.update(format!("{mode:?}"));
+    hasher.update(format!("{role:?}"));
+    let mut fingerprint = Fingerprint {
+        hasher,
+        seen: Default::default(),
+    };
+    fingerprint.visit_kernel_fn(kernel.inner())?;
+    Ok(format!("{:x}", fingerprint.hasher.finalize()))
+}
+
+fn entry_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{key}.ron"))
+}
+
+pub(crate) fn load(key: &str) -> Option<CompiledDesign> {
+    let path = entry_path(&cache_dir()?, key);
+    let text = std::fs::read_to_string(&path).ok()?;
+    match ron::de::from_str(&text) {
+        Ok(design) => {
+            debug!("Loaded compiled kernel from {}", path.display());
+            Some(design)
+        }
+        Err(err) => {
+            warn!("Ignoring unreadable cache entry {}: {err}", path.display());
+            None
+        }
+    }
+}
+
+// Failing to write the cache is not fatal - we just recompile next time.
+pub(crate) fn store(key: &str, design: &CompiledDesign) {
+    let Some(dir) = cache_dir() else {
+        return;
+    };
+    if let Err(err) = try_store(&dir, key, design) {
+        warn!("Unable to write cache entry in {}: {err}", dir.display());
+    }
+}
+
+fn try_store(dir: &Path, key: &str, design: &CompiledDesign) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let text = ron::ser::to_string(design)?;
+    // Write to a temporary file and rename it, so that concurrent test
+    // binaries never see a partial entry.
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(text.as_bytes())?;
+    file.persist(entry_path(dir, key))?;
+    Ok(())
+}
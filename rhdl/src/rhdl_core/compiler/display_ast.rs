@@ -52,6 +52,9 @@ impl PrettyPrinter {
             PatKind::Wild => {
                 self.push("_");
             }
+            PatKind::Rest => {
+                self.push("..");
+            }
             PatKind::Lit(lit) => {
                 self.push(&format!("{:?}", lit.lit));
             }
@@ -222,6 +225,12 @@ impl PrettyPrinter {
                 self.push(" ");
                 self.print_block(&expr.body)?;
             }
+            ExprKind::While(expr) => {
+                self.push("while ");
+                self.print_expr(&expr.cond)?;
+                self.push(" ");
+                self.print_block(&expr.body)?;
+            }
             ExprKind::Group(expr) => {
                 self.print_expr(&expr.expr)?;
             }
@@ -246,6 +255,9 @@ impl PrettyPrinter {
                         self.print_pattern(&enum_arm.pat)?;
                         self.push(&format!("#{:?}", enum_arm.discriminant));
                     }
+                    ArmKind::Binding(binding_arm) => {
+                        self.print_pattern(&binding_arm.pat)?;
+                    }
                 }
                 self.push(" = ");
                 self.print_expr(&expr.test)?;
@@ -284,6 +296,13 @@ impl PrettyPrinter {
                             self.print_pattern(&enum_arm.pat)?;
                             self.push(&format!("#{:?}", enum_arm.discriminant));
                         }
+                        ArmKind::Binding(binding_arm) => {
+                            self.print_pattern(&binding_arm.pat)?;
+                        }
+                    }
+                    if let Some(guard) = &arm.guard {
+                        self.push(" if ");
+                        self.print_expr(guard)?;
                     }
                     self.push(" => ");
                     self.print_expr(&arm.body)?;
@@ -388,6 +407,7 @@ impl std::fmt::Debug for ExprLit {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             ExprLit::Int(int) => write!(f, "{int}"),
+            ExprLit::IntConst(int) => write!(f, "{}", int.code),
             ExprLit::Bool(bool) => write!(f, "{bool}"),
             ExprLit::TypedBits(ty) => write!(f, "{}", ty.code),
             ExprLit::Empty => write!(f, "()"),
@@ -412,6 +432,8 @@ impl Display for BinOp {
             BinOp::Add => write!(f, "+"),
             BinOp::Sub => write!(f, "-"),
             BinOp::Mul => write!(f, "*"),
+            BinOp::Div => write!(f, "/"),
+            BinOp::Rem => write!(f, "%"),
             BinOp::And => write!(f, "&&"),
             BinOp::Or => write!(f, "||"),
             BinOp::BitXor => write!(f, "^"),
@@ -428,6 +450,8 @@ impl Display for BinOp {
             BinOp::AddAssign => write!(f, "+="),
             BinOp::SubAssign => write!(f, "-="),
             BinOp::MulAssign => write!(f, "*="),
+            BinOp::DivAssign => write!(f, "/="),
+            BinOp::RemAssign => write!(f, "%="),
             BinOp::BitXorAssign => write!(f, "^="),
             BinOp::BitAndAssign => write!(f, "&="),
             BinOp::BitOrAssign => write!(f, "|="),
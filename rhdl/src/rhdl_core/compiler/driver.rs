@@ -1,17 +1,28 @@
 use std::any::type_name;
 
-use crate::rhdl_core::{DigitalFn, KernelFnKind, RHDLError};
+use crate::rhdl_core::{
+    DigitalFn, KernelFnKind, RHDLError, kernel::Kernel, ntl::from_rtl::build_ntl_from_rtl,
+};
 
-use super::stage1::CompilationMode;
+use super::{
+    cache::{self, CompiledDesign},
+    lints::{collect_lints, replay_lints},
+    stage1::{CompilationMode, KernelRole},
+    stage3::optimize_ntl,
+};
 use anyhow::anyhow;
 
-pub fn compile_design_stage1<K: DigitalFn>(
-    mode: CompilationMode,
-) -> Result<crate::rhdl_core::rhif::Object, RHDLError> {
+fn kernel_for<K: DigitalFn>() -> Result<Kernel, RHDLError> {
     let Some(KernelFnKind::Kernel(kernel)) = K::kernel_fn() else {
         return Err(anyhow!("Missing kernel function provided for {}", type_name::<K>()).into());
     };
-    super::stage1::compile(kernel, mode)
+    Ok(kernel)
+}
+
+pub fn compile_design_stage1<K: DigitalFn>(
+    mode: CompilationMode,
+) -> Result<crate::rhdl_core::rhif::Object, RHDLError> {
+    super::stage1::compile(kernel_for::<K>()?, mode, KernelRole::Function)
 }
 
 pub fn compile_design_stage2(
@@ -20,9 +31,55 @@ pub fn compile_design_stage2(
     super::stage2::compile(object)
 }
 
+/// Compile a kernel to RHIF, RTL and an optimized netlist.  If a cache
+/// directory is configured (see [cache::set_cache_dir]), the result is
+/// loaded from and stored to the cache.
+pub fn compile_design_artifacts<K: DigitalFn>(
+    mode: CompilationMode,
+) -> Result<CompiledDesign, RHDLError> {
+    compile_artifacts::<K>(mode, KernelRole::Function)
+}
+
+/// Compile the kernel of a circuit, as [compile_design_artifacts], and
+/// check it for the circuit lints (see [super::lints]).
+pub fn compile_circuit_artifacts<K: DigitalFn>(
+    mode: CompilationMode,
+) -> Result<CompiledDesign, RHDLError> {
+    compile_artifacts::<K>(mode, KernelRole::Circuit)
+}
+
+fn compile_artifacts<K: DigitalFn>(
+    mode: CompilationMode,
+    role: KernelRole,
+) -> Result<CompiledDesign, RHDLError> {
+    let kernel = kernel_for::<K>()?;
+    let key = match cache::cache_dir() {
+        Some(_) => Some(cache::cache_key(&kernel, type_name::<K>(), mode, role)?),
+        None => None,
+    };
+    if let Some(design) = key.as_deref().and_then(cache::load) {
+        replay_lints(&design.lints);
+        return Ok(design);
+    }
+    let guard = collect_lints();
+    let rhif = super::stage1::compile(kernel, mode, role)?;
+    let lints = guard.take();
+    let rtl = compile_design_stage2(&rhif)?;
+    let ntl = optimize_ntl(build_ntl_from_rtl(&rtl)?)?;
+    let design = CompiledDesign {
+        rhif,
+        rtl,
+        ntl,
+        lints,
+    };
+    if let Some(key) = key {
+        cache::store(&key, &design);
+    }
+    Ok(design)
+}
+
 pub fn compile_design<K: DigitalFn>(
     mode: CompilationMode,
 ) -> Result<crate::rhdl_core::rtl::Object, RHDLError> {
-    let rhif = compile_design_stage1::<K>(mode)?;
-    compile_design_stage2(&rhif)
+    Ok(compile_design_artifacts::<K>(mode)?.rtl)
 }
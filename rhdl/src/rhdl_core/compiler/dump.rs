@@ -0,0 +1,145 @@
+//! Per-pass dumps of the intermediate representations
+//!
+//! When a dump directory is set (with the `RHDL_DUMP_DIR` environment
+//! variable, or by calling [set_dump_dir]), each compiler stage writes the
+//! text form of its IR after every pass.  The layout is
+//!
+//! ```text
+//!   <dir>/<object name>/<stage>/000_Input.txt
+//!   <dir>/<object name>/<stage>/001_<Pass>.txt
+//!   ...
+//!   <dir>/<object name>/<stage>/summary.txt
+//! ```
+//!
+//! where stage is one of `rhif`, `rtl` or `ntl`.  The summary has one line
+//! per pass with the number of instructions left after it ran, so that two
+//! runs can be compared with `diff`.  The stage directory is cleared when
+//! the stage starts, so the dump always reflects the last compilation of
+//! that object.  If a pass fails, the last file written shows its input.
+//!
+//! Kernels served from the compilation cache are not compiled, and so are
+//! not dumped.
+use std::{
+    fmt::Debug,
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+    sync::{LazyLock, RwLock},
+};
+
+use log::warn;
+
+use crate::rhdl_core::{ntl, rhif, rtl};
+
+const DUMP_DIR_ENV: &str = "RHDL_DUMP_DIR";
+
+static DUMP_DIR: LazyLock<RwLock<Option<PathBuf>>> =
+    LazyLock::new(|| RwLock::new(std::env::var_os(DUMP_DIR_ENV).map(PathBuf::from)));
+
+/// Set (or clear) the directory that per-pass IR dumps are written to.
+/// This overrides the `RHDL_DUMP_DIR` environment variable.
+pub fn set_dump_dir(dir: Option<PathBuf>) {
+    *DUMP_DIR.write().unwrap() = dir;
+}
+
+/// The directory that per-pass IR dumps are written to, if enabled.
+pub fn dump_dir() -> Option<PathBuf> {
+    DUMP_DIR.read().unwrap().clone()
+}
+
+pub(crate) trait Dump: Debug {
+    fn dump_name(&self) -> &str;
+    fn op_count(&self) -> usize;
+}
+
+impl Dump for rhif::Object {
+    fn dump_name(&self) -> &str {
+        &self.name
+    }
+    fn op_count(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|lop| !matches!(lop.op, rhif::spec::OpCode::Noop))
+            .count()
+    }
+}
+
+impl Dump for rtl::Object {
+    fn dump_name(&self) -> &str {
+        &self.name
+    }
+    fn op_count(&self) -> usize {
+        self.ops.len()
+    }
+}
+
+impl Dump for ntl::Object {
+    fn dump_name(&self) -> &str {
+        &self.name
+    }
+    fn op_count(&self) -> usize {
+        self.ops.len()
+    }
+}
+
+fn file_safe(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+// The name of a pass, as used in the dump, is the name of its type.
+pub(crate) fn pass_name<P: ?Sized>() -> &'static str {
+    let name = std::any::type_name::<P>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Records the IR after each pass of a single compiler stage.
+pub(crate) struct PassDump {
+    dir: Option<PathBuf>,
+    count: usize,
+}
+
+impl PassDump {
+    pub(crate) fn new<T: Dump>(stage: &str, obj: &T) -> Self {
+        let dir = dump_dir().map(|root| root.join(file_safe(obj.dump_name())).join(stage));
+        let mut dump = Self { dir, count: 0 };
+        if let Some(dir) = &dump.dir {
+            let _ = std::fs::remove_dir_all(dir);
+            if let Err(err) = std::fs::create_dir_all(dir) {
+                warn!(
+                    "Unable to create IR dump directory {}: {err}",
+                    dir.display()
+                );
+                dump.dir = None;
+            }
+        }
+        dump.record("Input", obj);
+        dump
+    }
+    pub(crate) fn record<T: Dump>(&mut self, pass: &str, obj: &T) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let file = dir.join(format!("{:03}_{}.txt", self.count, file_safe(pass)));
+        let line = format!("{:03} {pass:<48} {:>8}\n", self.count, obj.op_count());
+        let result = std::fs::write(&file, format!("{obj:?}")).and_then(|_| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(dir.join("summary.txt"))?
+                .write_all(line.as_bytes())
+        });
+        if let Err(err) = result {
+            warn!("Unable to write IR dump {}: {err}", file.display());
+            self.dir = None;
+        }
+        self.count += 1;
+    }
+}
@@ -0,0 +1,522 @@
+//! Lints for kernels and circuits.
+//!
+//! Lints flag code that compiles, but probably does not do what was
+//! intended.  They are reported as warnings (through the `log` crate),
+//! unless the kernel allows or denies them with an attribute:
+//! ```ignore
+//! #[kernel]
+//! #[rhdl(allow(lossy_resize), deny(unread_q))]
+//! pub fn kernel(cr: ClockReset, i: I, q: Q) -> (O, D) { .. }
+//! ```
+//! A denied lint is an error.  The kernel lints ([Lint::LossyResize] and
+//! [Lint::UnreachableArm]) are checked whenever a kernel is compiled.  The
+//! circuit lints ([Lint::UnwrittenD], [Lint::UnreadQ] and
+//! [Lint::ConstantOutput]) are also checked when the kernel is compiled
+//! as the update function of a circuit.  The warnings for a circuit are
+//! kept in its [CircuitDescriptor](crate::rhdl_core::CircuitDescriptor).
+//! Kernels loaded from the cache (see [super::cache]) are not compiled,
+//! so the warnings are stored with them, and reported again.
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
+
+use log::warn;
+use miette::{Diagnostic, SourceSpan};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::rhdl_core::{
+    Kind, RHDLError, SourcePool,
+    ast::{
+        KernelFlags, Lint,
+        ast_impl::{Expr, ExprKind, NodeId},
+        source::source_location::SourceLocation,
+        visit::{self, Visitor},
+    },
+    error::rhdl_error,
+    kernel::Kernel,
+    rhif::{
+        Object,
+        spec::{Case, CaseArgument, Cast, Index, OpCode, Select, Slot, Splice, Struct},
+        visit::visit_slots,
+    },
+    types::path::{Path, PathElement, bit_range},
+};
+
+#[derive(Clone, Debug, Error, Serialize, Deserialize)]
+pub enum LintCause {
+    #[error("Field `{field}` of the D type is never written")]
+    UnwrittenD { field: String },
+    #[error("Field `{field}` of the Q type is never read")]
+    UnreadQ { field: String },
+    #[error("This resize from {from} bits to {to} bits drops the high bits of its argument")]
+    LossyResize { from: usize, to: usize },
+    #[error("The wildcard arm can never be reached, since the other arms cover all {count} values")]
+    WildcardArmIsUnreachable { count: usize },
+    #[error("The arm for {value} can never be reached, since an earlier arm matches it")]
+    RepeatedArmIsUnreachable { value: String },
+    #[error("The output of this circuit is a constant")]
+    ConstantOutput,
+}
+
+#[derive(Clone, Debug, Error, Serialize, Deserialize)]
+#[error("RHDL Lint `{}`", .lint.name())]
+pub struct RHDLLint {
+    pub lint: Lint,
+    pub cause: LintCause,
+    pub src: SourcePool,
+    pub err_span: SourceSpan,
+    pub denied: bool,
+}
+
+impl Diagnostic for RHDLLint {
+    fn severity(&self) -> Option<miette::Severity> {
+        if self.denied {
+            Some(miette::Severity::Error)
+        } else {
+            Some(miette::Severity::Warning)
+        }
+    }
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.src)
+    }
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        let name = self.lint.name();
+        Some(Box::new(if self.denied {
+            format!("This lint is denied by `#[rhdl(deny({name}))]` on the kernel")
+        } else {
+            format!("If this is intended, add `#[rhdl(allow({name}))]` to the kernel")
+        }))
+    }
+    fn labels<'a>(&'a self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + 'a>> {
+        Some(Box::new(std::iter::once(
+            miette::LabeledSpan::new_primary_with_span(Some(self.cause.to_string()), self.err_span),
+        )))
+    }
+}
+
+thread_local! {
+    static LINTS: RefCell<Option<Vec<RHDLLint>>> = const { RefCell::new(None) };
+}
+
+pub struct LintGuard {
+    outer: Option<Vec<RHDLLint>>,
+}
+
+impl LintGuard {
+    pub fn take(self) -> Vec<RHDLLint> {
+        LINTS.with(|lints| lints.borrow().clone().unwrap_or_default())
+    }
+}
+
+// Guards can be nested, so the lints collected by an inner guard are
+// also handed to the outer one.
+impl Drop for LintGuard {
+    fn drop(&mut self) {
+        let inner = LINTS.take().unwrap_or_default();
+        let outer = self.outer.take().map(|mut outer| {
+            outer.extend(inner);
+            outer
+        });
+        LINTS.replace(outer);
+    }
+}
+
+/// Collect the lints that are warned about on this thread (in addition
+/// to logging them), until the guard is dropped.
+#[must_use]
+pub fn collect_lints() -> LintGuard {
+    LintGuard {
+        outer: LINTS.replace(Some(vec![])),
+    }
+}
+
+fn warn_lint(lint: RHDLLint) {
+    warn!("{:?}", miette::Report::new(lint.clone()));
+    LINTS.with(|lints| {
+        if let Some(lints) = lints.borrow_mut().as_mut() {
+            lints.push(lint);
+        }
+    });
+}
+
+/// Report the warnings of a kernel loaded from the cache again.
+pub(crate) fn replay_lints(lints: &[RHDLLint]) {
+    lints.iter().cloned().for_each(warn_lint);
+}
+
+enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+// The last flag that mentions the lint wins.
+fn level(flags: &[KernelFlags], lint: Lint) -> Level {
+    flags.iter().fold(Level::Warn, |level, flag| match flag {
+        KernelFlags::Allow(x) if *x == lint => Level::Allow,
+        KernelFlags::Deny(x) if *x == lint => Level::Deny,
+        _ => level,
+    })
+}
+
+struct Reporter<'a> {
+    obj: &'a Object,
+    seen: HashSet<(Lint, usize, usize)>,
+}
+
+impl<'a> Reporter<'a> {
+    fn new(obj: &'a Object) -> Self {
+        Self {
+            obj,
+            seen: HashSet::default(),
+        }
+    }
+    // A match with several bindings produces several case ops, so the
+    // same lint can be found more than once at a given location.
+    fn report(
+        &mut self,
+        lint: Lint,
+        cause: LintCause,
+        span: std::ops::Range<usize>,
+    ) -> Result<(), RHDLError> {
+        if !self.seen.insert((lint, span.start, span.end)) {
+            return Ok(());
+        }
+        let denied = match level(&self.obj.flags, lint) {
+            Level::Allow => return Ok(()),
+            Level::Warn => false,
+            Level::Deny => true,
+        };
+        let lint = RHDLLint {
+            lint,
+            cause,
+            src: self.obj.symbols.source(),
+            err_span: span.into(),
+            denied,
+        };
+        if denied {
+            return Err(rhdl_error(lint));
+        }
+        warn_lint(lint);
+        Ok(())
+    }
+    fn span(&self, loc: SourceLocation) -> std::ops::Range<usize> {
+        self.obj.symbols.span(loc)
+    }
+}
+
+/// The places in the source of a kernel that the kernel lints apply to.
+/// These are found from the AST, since the RHIF does not distinguish
+/// a `resize` from the other casts, or a `match` from other case ops.
+#[derive(Default)]
+pub(crate) struct LintSites {
+    resizes: HashSet<NodeId>,
+    matches: HashSet<NodeId>,
+}
+
+impl LintSites {
+    pub(crate) fn new(kernel: &Kernel) -> Result<Self, RHDLError> {
+        let mut sites = Self::default();
+        sites.visit_kernel_fn(kernel.inner())?;
+        Ok(sites)
+    }
+}
+
+impl Visitor for LintSites {
+    fn visit_expr(&mut self, node: &Expr) -> Result<(), RHDLError> {
+        match &node.kind {
+            ExprKind::MethodCall(call) if call.method == "resize" => {
+                self.resizes.insert(node.id);
+            }
+            ExprKind::Match(_) => {
+                self.matches.insert(node.id);
+            }
+            _ => {}
+        }
+        visit::visit_expr(self, node)
+    }
+}
+
+/// Check a compiled kernel for [Lint::LossyResize] and [Lint::UnreachableArm].
+pub(crate) fn check_kernel(obj: &Object, sites: &LintSites) -> Result<(), RHDLError> {
+    let mut reporter = Reporter::new(obj);
+    for lop in obj.ops.iter().filter(|lop| lop.loc.func == obj.fn_id) {
+        match &lop.op {
+            OpCode::Resize(cast) if sites.resizes.contains(&lop.loc.node) => {
+                if let Some(cause) = lossy_resize(obj, cast) {
+                    reporter.report(Lint::LossyResize, cause, reporter.span(lop.loc))?;
+                }
+            }
+            OpCode::Case(case) if sites.matches.contains(&lop.loc.node) => {
+                if let Some(cause) = unreachable_arm(obj, case) {
+                    reporter.report(Lint::UnreachableArm, cause, reporter.span(lop.loc))?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn lossy_resize(obj: &Object, cast: &Cast) -> Option<LintCause> {
+    let from = obj.kind(cast.arg).bits();
+    let to = obj.kind(cast.lhs).bits();
+    if to >= from {
+        return None;
+    }
+    // A literal that fits in the new width is not truncated
+    if let Slot::Literal(lit) = cast.arg {
+        let value = &obj.symtab[lit];
+        let round_trip = value.resize(to).and_then(|x| x.resize(from));
+        if round_trip.is_ok_and(|x| x.bits == value.bits) {
+            return None;
+        }
+    }
+    Some(LintCause::LossyResize { from, to })
+}
+
+// Only matches on integers can cover all of the values of the
+// discriminant - the Rust compiler checks the matches on enums.
+// A match on a constant is allowed, since it is common in generic code.
+fn unreachable_arm(obj: &Object, case: &Case) -> Option<LintCause> {
+    if case.discriminant.is_lit() {
+        return None;
+    }
+    let bits = match obj.kind(case.discriminant) {
+        Kind::Bits(n) | Kind::Signed(n) if n <= 16 => n,
+        _ => return None,
+    };
+    let mut covered = HashSet::new();
+    for (argument, _) in &case.table {
+        match argument {
+            CaseArgument::Slot(Slot::Literal(lit)) => {
+                let value = &obj.symtab[*lit];
+                if !covered.insert(value.bits.clone()) {
+                    return Some(LintCause::RepeatedArmIsUnreachable {
+                        value: format!("{value:?}"),
+                    });
+                }
+            }
+            CaseArgument::Wild if covered.len() == 1 << bits => {
+                return Some(LintCause::WildcardArmIsUnreachable {
+                    count: covered.len(),
+                });
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// The names of the fields of a struct or tuple, or None if the kind
+// does not have fields.
+fn field_names(kind: Kind) -> Option<Vec<String>> {
+    match kind {
+        Kind::Struct(strukt) => Some(strukt.fields.iter().map(|f| f.name.to_string()).collect()),
+        Kind::Tuple(tuple) => Some((0..tuple.elements.len()).map(|x| x.to_string()).collect()),
+        _ => None,
+    }
+}
+
+// The field of a struct or tuple that a path starts with.
+fn field_of(kind: Kind, element: Option<&PathElement>) -> Option<usize> {
+    let names = field_names(kind)?;
+    match element? {
+        PathElement::Field(name) => names.iter().position(|x| x == name.as_str()),
+        PathElement::TupleIndex(ndx) => Some(*ndx).filter(|ndx| *ndx < names.len()),
+        _ => None,
+    }
+}
+
+fn field_path(kind: Kind, ndx: usize) -> Path {
+    match kind {
+        Kind::Struct(strukt) => Path::default().field(&strukt.fields[ndx].name),
+        _ => Path::default().tuple_index(ndx),
+    }
+}
+
+// The fields of a struct or tuple that are not empty.
+fn fields_with_bits(kind: Kind) -> Vec<usize> {
+    let names = field_names(kind).unwrap_or_default();
+    (0..names.len())
+        .filter(|ndx| {
+            bit_range(kind, &field_path(kind, *ndx)).is_ok_and(|(_, kind)| !kind.is_empty())
+        })
+        .collect()
+}
+
+fn definitions(obj: &Object) -> HashMap<Slot, &OpCode> {
+    obj.ops
+        .iter()
+        .filter_map(|lop| lop.op.lhs().map(|lhs| (lhs, &lop.op)))
+        .collect()
+}
+
+/// Check the kernel of a circuit for [Lint::UnwrittenD] and [Lint::UnreadQ].
+/// The kernel takes `q` as its last argument, and returns `(o, d)`.  This
+/// runs before the kernel is optimized, since constant propagation folds
+/// the writes of constants into the initial value of `d`.
+pub(crate) fn check_circuit_state(obj: &Object) -> Result<(), RHDLError> {
+    let mut reporter = Reporter::new(obj);
+    let defs = definitions(obj);
+    let mut d_slots = vec![];
+    if !find_d_slots(&defs, obj.return_slot, &mut d_slots, &mut HashSet::new()) {
+        d_slots.clear();
+    }
+    if let Some(d) = d_slots.first().copied() {
+        let kind = obj.kind(d);
+        let mut written = vec![false; field_names(kind).unwrap_or_default().len()];
+        let mut visited = HashSet::new();
+        for slot in &d_slots {
+            mark_written(obj, &defs, *slot, &mut written, &mut visited);
+        }
+        for ndx in fields_with_bits(kind) {
+            if !written[ndx] {
+                let cause = LintCause::UnwrittenD {
+                    field: field_names(kind).unwrap_or_default()[ndx].clone(),
+                };
+                reporter.report(Lint::UnwrittenD, cause, obj.slot_span(d))?;
+            }
+        }
+    }
+    if let Some(q) = obj.arguments.last().copied().map(Slot::Register) {
+        let kind = obj.kind(q);
+        let mut read = vec![false; field_names(kind).unwrap_or_default().len()];
+        // The argument is copied into the binding for `q`, which may
+        // be copied again.
+        let mut aliases = HashSet::from([q]);
+        for lop in &obj.ops {
+            match &lop.op {
+                OpCode::Assign(assign) if aliases.contains(&assign.rhs) => {
+                    aliases.insert(assign.lhs);
+                }
+                OpCode::Index(Index { arg, path, .. }) if aliases.contains(arg) => {
+                    match field_of(kind, path.iter().next()) {
+                        Some(ndx) => read[ndx] = true,
+                        None => read.fill(true),
+                    }
+                }
+                op => visit_slots(op, |sense, slot| {
+                    if sense.is_read() && aliases.contains(slot) {
+                        read.fill(true);
+                    }
+                }),
+            }
+        }
+        if aliases.contains(&obj.return_slot) {
+            read.fill(true);
+        }
+        for ndx in fields_with_bits(kind) {
+            if !read[ndx] {
+                let cause = LintCause::UnreadQ {
+                    field: field_names(kind).unwrap_or_default()[ndx].clone(),
+                };
+                reporter.report(Lint::UnreadQ, cause, obj.slot_span(q))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check the (optimized) kernel of a circuit for [Lint::ConstantOutput].
+pub(crate) fn check_circuit_output(obj: &Object) -> Result<(), RHDLError> {
+    let o = match definitions(obj).get(&obj.return_slot) {
+        Some(OpCode::Tuple(tuple)) if tuple.fields.len() == 2 => tuple.fields[0],
+        _ => obj.return_slot,
+    };
+    if o.is_lit() && !obj.kind(o).is_empty() && !obj.kind(obj.return_slot).is_empty() {
+        Reporter::new(obj).report(
+            Lint::ConstantOutput,
+            LintCause::ConstantOutput,
+            obj.slot_span(o),
+        )?;
+    }
+    Ok(())
+}
+
+// Find the values that are returned as `d` by the kernel.  Returns false
+// if some of them cannot be found (e.g. the return value comes from a
+// function call).  Literals are skipped, since the return value starts
+// out as a literal that is only returned if the kernel does not return.
+fn find_d_slots(
+    defs: &HashMap<Slot, &OpCode>,
+    slot: Slot,
+    d_slots: &mut Vec<Slot>,
+    visited: &mut HashSet<Slot>,
+) -> bool {
+    if !visited.insert(slot) || slot.is_lit() {
+        return true;
+    }
+    match defs.get(&slot) {
+        Some(OpCode::Tuple(tuple)) if tuple.fields.len() == 2 => {
+            d_slots.push(tuple.fields[1]);
+            true
+        }
+        Some(OpCode::Assign(assign)) => find_d_slots(defs, assign.rhs, d_slots, visited),
+        Some(OpCode::Select(select)) => {
+            find_d_slots(defs, select.true_value, d_slots, visited)
+                && find_d_slots(defs, select.false_value, d_slots, visited)
+        }
+        Some(OpCode::Case(case)) => case
+            .table
+            .iter()
+            .all(|(_, value)| find_d_slots(defs, *value, d_slots, visited)),
+        _ => false,
+    }
+}
+
+// Mark the fields of `slot` that are written.  Values that are not
+// built up field by field (e.g. the result of a function call) count
+// as writing every field, and literals (the initial value) as writing
+// none of them.
+fn mark_written(
+    obj: &Object,
+    defs: &HashMap<Slot, &OpCode>,
+    slot: Slot,
+    written: &mut [bool],
+    visited: &mut HashSet<Slot>,
+) {
+    if !visited.insert(slot) || slot.is_lit() {
+        return;
+    }
+    let kind = obj.kind(slot);
+    match defs.get(&slot) {
+        Some(OpCode::Splice(Splice { orig, path, .. })) => {
+            match field_of(kind, path.iter().next()) {
+                Some(ndx) => written[ndx] = true,
+                None => written.fill(true),
+            }
+            mark_written(obj, defs, *orig, written, visited);
+        }
+        Some(OpCode::Struct(Struct { fields, rest, .. })) => {
+            for field in fields {
+                match field_of(kind, Path::default().member(&field.member).iter().next()) {
+                    Some(ndx) => written[ndx] = true,
+                    None => written.fill(true),
+                }
+            }
+            if let Some(rest) = rest {
+                mark_written(obj, defs, *rest, written, visited);
+            }
+        }
+        Some(OpCode::Select(Select {
+            true_value,
+            false_value,
+            ..
+        })) => {
+            mark_written(obj, defs, *true_value, written, visited);
+            mark_written(obj, defs, *false_value, written, visited);
+        }
+        Some(OpCode::Case(Case { table, .. })) => {
+            for (_, value) in table {
+                mark_written(obj, defs, *value, written, visited);
+            }
+        }
+        Some(OpCode::Assign(assign)) => {
+            mark_written(obj, defs, assign.rhs, written, visited);
+        }
+        _ => written.fill(true),
+    }
+}
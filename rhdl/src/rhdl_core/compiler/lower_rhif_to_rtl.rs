@@ -3,12 +3,14 @@ use std::collections::BTreeMap;
 use fnv::FnvHashMap;
 use log::debug;
 
+use crate::rhdl_bits::Rounding;
 use crate::rhdl_bits::alias::b8;
 
 use crate::rhdl_core::Digital;
 use crate::rhdl_core::TypedBits;
 use crate::rhdl_core::ast::ast_impl::WrapOp;
 use crate::rhdl_core::ast::source::source_location::SourceLocation;
+use crate::rhdl_core::bitx::BitX;
 use crate::rhdl_core::common::symtab::SymbolTable;
 use crate::rhdl_core::error::rhdl_error;
 use crate::rhdl_core::rhif::object::SourceDetails;
@@ -506,6 +508,34 @@ impl<'a> RTLCompiler<'a> {
         );
         Ok(())
     }
+    fn index_bits(
+        &mut self,
+        arg: Operand,
+        bit_range: std::ops::Range<usize>,
+        loc: SourceLocation,
+    ) -> Operand {
+        let kind = if bit_range.len() == 1 {
+            Kind::make_bool()
+        } else {
+            Kind::Bits(bit_range.len())
+        };
+        let lhs = self.reg(kind, loc);
+        self.lop(
+            tl::OpCode::Index(tl::Index {
+                lhs,
+                arg,
+                bit_range,
+                path: Path::default(),
+            }),
+            loc,
+        );
+        lhs
+    }
+    fn unary_flag(&mut self, op: tl::AluUnary, arg1: Operand, loc: SourceLocation) -> Operand {
+        let lhs = self.reg(Kind::make_bool(), loc);
+        self.lop(tl::OpCode::Unary(tl::Unary { op, lhs, arg1 }), loc);
+        lhs
+    }
     fn make_binary(&mut self, binary: &hf::Binary, loc: SourceLocation) -> Result<()> {
         let hf::Binary {
             lhs,
@@ -535,6 +565,8 @@ impl<'a> RTLCompiler<'a> {
             AluBinary::Add => rtl_binop(tl::AluBinary::Add),
             AluBinary::Sub => rtl_binop(tl::AluBinary::Sub),
             AluBinary::Mul => rtl_binop(tl::AluBinary::Mul),
+            AluBinary::Div => rtl_binop(tl::AluBinary::Div),
+            AluBinary::Rem => rtl_binop(tl::AluBinary::Rem),
             AluBinary::BitAnd => rtl_binop(tl::AluBinary::BitAnd),
             AluBinary::BitOr => rtl_binop(tl::AluBinary::BitOr),
             AluBinary::BitXor => rtl_binop(tl::AluBinary::BitXor),
@@ -549,6 +581,20 @@ impl<'a> RTLCompiler<'a> {
             AluBinary::XAdd => self.make_xadd_or_xmul(lhs, arg1, arg2, loc, tl::AluBinary::Add),
             AluBinary::XSub => self.make_xsub(lhs, arg1, arg2, loc),
             AluBinary::XMul => self.make_xadd_or_xmul(lhs, arg1, arg2, loc, tl::AluBinary::Mul),
+            AluBinary::SaturatingAdd => rtl_binop(tl::AluBinary::SaturatingAdd),
+            AluBinary::SaturatingSub => rtl_binop(tl::AluBinary::SaturatingSub),
+            AluBinary::SaturatingMul => rtl_binop(tl::AluBinary::SaturatingMul),
+            AluBinary::OverflowingAdd => rtl_binop(tl::AluBinary::OverflowingAdd),
+            AluBinary::OverflowingSub => rtl_binop(tl::AluBinary::OverflowingSub),
+            AluBinary::OverflowingMul => rtl_binop(tl::AluBinary::OverflowingMul),
+            AluBinary::RotateLeft => {
+                self.make_rotate(lhs, arg1, arg2, true, loc);
+                Ok(())
+            }
+            AluBinary::RotateRight => {
+                self.make_rotate(lhs, arg1, arg2, false, loc);
+                Ok(())
+            }
         }
     }
     fn make_case_argument(
@@ -599,6 +645,28 @@ impl<'a> RTLCompiler<'a> {
         );
         Ok(())
     }
+    fn make_rom(&mut self, rom: &hf::Rom, loc: SourceLocation) -> Result<()> {
+        let hf::Rom {
+            lhs,
+            address,
+            table,
+        } = rom;
+        if self.object.kind(*lhs).is_empty() {
+            return Ok(());
+        }
+        let lhs = self.operand(*lhs);
+        let address = self.operand(*address);
+        let table = table.iter().map(BitString::from).collect();
+        self.lop(
+            tl::OpCode::Rom(tl::Rom {
+                lhs,
+                address,
+                table,
+            }),
+            loc,
+        );
+        Ok(())
+    }
     fn make_dynamic_splice(&mut self, splice: &hf::Splice, loc: SourceLocation) -> Result<()> {
         let hf::Splice {
             lhs,
@@ -1050,6 +1118,383 @@ impl<'a> RTLCompiler<'a> {
             loc,
         );
     }
+    fn binary_flag(
+        &mut self,
+        op: tl::AluBinary,
+        arg1: Operand,
+        arg2: Operand,
+        loc: SourceLocation,
+    ) -> Operand {
+        let lhs = self.reg(Kind::make_bool(), loc);
+        self.lop(
+            tl::OpCode::Binary(tl::Binary {
+                lhs,
+                op,
+                arg1,
+                arg2,
+            }),
+            loc,
+        );
+        lhs
+    }
+    // Drops `from - to` fractional bits with a right shift, and then adds one
+    // if the rounding mode calls for it.  The decision only needs the first
+    // dropped bit (the guard), whether any of the remaining dropped bits are
+    // set (the sticky flag), the sign and the LSB of the shifted value.
+    fn make_quantize(
+        &mut self,
+        lhs: Operand,
+        arg: Operand,
+        from: usize,
+        to: usize,
+        rounding: Rounding,
+        loc: SourceLocation,
+    ) {
+        if to >= from {
+            return self.make_xshl(lhs, arg, to - from, loc);
+        }
+        let signed = self.operand_is_signed(arg);
+        if matches!(rounding, Rounding::Truncate)
+            || (matches!(rounding, Rounding::TowardZero) && !signed)
+        {
+            return self.make_xshr(lhs, arg, from - to, loc);
+        }
+        let shift = from - to;
+        let arg_len = self.operand_bit_width(arg);
+        let len = arg_len - shift;
+        let kind = if signed {
+            Kind::Signed(len)
+        } else {
+            Kind::Bits(len)
+        };
+        let quotient = self.reg(kind, loc);
+        self.make_xshr(quotient, arg, shift, loc);
+        let guard = self.index_bits(arg, shift - 1..shift, loc);
+        let sticky = (shift > 1).then(|| {
+            let dropped = self.index_bits(arg, 0..shift - 1, loc);
+            self.unary_flag(tl::AluUnary::Any, dropped, loc)
+        });
+        let round_up = match rounding {
+            Rounding::HalfUp => guard,
+            Rounding::HalfEven => {
+                let lsb = self.index_bits(arg, shift..shift + 1, loc);
+                let odd_or_above = match sticky {
+                    Some(sticky) => self.binary_flag(tl::AluBinary::BitOr, lsb, sticky, loc),
+                    None => lsb,
+                };
+                self.binary_flag(tl::AluBinary::BitAnd, guard, odd_or_above, loc)
+            }
+            _ => {
+                let sign = self.index_bits(arg, arg_len - 1..arg_len, loc);
+                let inexact = match sticky {
+                    Some(sticky) => self.binary_flag(tl::AluBinary::BitOr, guard, sticky, loc),
+                    None => guard,
+                };
+                self.binary_flag(tl::AluBinary::BitAnd, sign, inexact, loc)
+            }
+        };
+        // Widen the flag to the width (and signedness) of the result
+        let increment = self.reg(Kind::Bits(len), loc);
+        self.lop(
+            tl::OpCode::Cast(tl::Cast {
+                lhs: increment,
+                arg: round_up,
+                len,
+                kind: CastKind::Resize,
+            }),
+            loc,
+        );
+        let increment = if signed {
+            let signed_increment = self.reg(kind, loc);
+            self.lop(
+                tl::OpCode::Cast(tl::Cast {
+                    lhs: signed_increment,
+                    arg: increment,
+                    len,
+                    kind: CastKind::Signed,
+                }),
+                loc,
+            );
+            signed_increment
+        } else {
+            increment
+        };
+        self.lop(
+            tl::OpCode::Binary(tl::Binary {
+                lhs,
+                op: tl::AluBinary::Add,
+                arg1: quotient,
+                arg2: increment,
+            }),
+            loc,
+        );
+    }
+    // Drops `from - to` integer bits.  The value fits if the dropped bits
+    // are all zero (unsigned), or if they all match the sign bit of the
+    // result (signed).  Otherwise, the result is clamped to the largest
+    // (or for negative values, the smallest) value of the new width.
+    fn make_saturate(
+        &mut self,
+        lhs: Operand,
+        arg: Operand,
+        from: usize,
+        to: usize,
+        loc: SourceLocation,
+    ) {
+        let arg_len = self.operand_bit_width(arg);
+        let len = arg_len + to - from;
+        let truncated = if to >= from {
+            lhs
+        } else {
+            let kind = if self.operand_is_signed(arg) {
+                Kind::Signed(len)
+            } else {
+                Kind::Bits(len)
+            };
+            self.reg(kind, loc)
+        };
+        self.lop(
+            tl::OpCode::Cast(tl::Cast {
+                lhs: truncated,
+                arg,
+                len,
+                kind: CastKind::Resize,
+            }),
+            loc,
+        );
+        if to >= from {
+            return;
+        }
+        let (fits, limit) = if self.operand_is_signed(arg) {
+            let top = self.index_bits(arg, len - 1..arg_len, loc);
+            let all_ones = self.unary_flag(tl::AluUnary::All, top, loc);
+            let any_ones = self.unary_flag(tl::AluUnary::Any, top, loc);
+            let all_zeros = self.unary_flag(tl::AluUnary::Not, any_ones, loc);
+            let fits = self.binary_flag(tl::AluBinary::BitOr, all_ones, all_zeros, loc);
+            let sign = self.index_bits(arg, arg_len - 1..arg_len, loc);
+            let extreme = |msb: BitX, rest: BitX| TypedBits {
+                bits: std::iter::repeat_n(rest, len - 1)
+                    .chain(std::iter::once(msb))
+                    .collect(),
+                kind: Kind::Signed(len),
+            };
+            let min = self.lit(extreme(BitX::One, BitX::Zero), loc);
+            let max = self.lit(extreme(BitX::Zero, BitX::One), loc);
+            let limit = self.reg(Kind::Signed(len), loc);
+            self.lop(
+                tl::OpCode::Select(tl::Select {
+                    lhs: limit,
+                    cond: sign,
+                    true_value: min,
+                    false_value: max,
+                }),
+                loc,
+            );
+            (fits, limit)
+        } else {
+            let top = self.index_bits(arg, len..arg_len, loc);
+            let overflow = self.unary_flag(tl::AluUnary::Any, top, loc);
+            let fits = self.unary_flag(tl::AluUnary::Not, overflow, loc);
+            let max = self.lit(
+                TypedBits {
+                    bits: vec![BitX::One; len],
+                    kind: Kind::Bits(len),
+                },
+                loc,
+            );
+            (fits, max)
+        };
+        self.lop(
+            tl::OpCode::Select(tl::Select {
+                lhs,
+                cond: fits,
+                true_value: truncated,
+                false_value: limit,
+            }),
+            loc,
+        );
+    }
+    // Wires the bits of `arg` into `lhs`, so that bit `i` of `lhs` is bit
+    // `order[i]` of `arg`.  Runs of consecutive bits are taken as a single slice.
+    fn make_permutation(
+        &mut self,
+        lhs: Operand,
+        arg: Operand,
+        order: &[usize],
+        loc: SourceLocation,
+    ) {
+        let mut args = vec![];
+        let mut start = 0;
+        while start < order.len() {
+            let mut end = start + 1;
+            while end < order.len() && order[end] == order[end - 1] + 1 {
+                end += 1;
+            }
+            args.push(self.index_bits(arg, order[start]..order[end - 1] + 1, loc));
+            start = end;
+        }
+        self.lop(tl::OpCode::Concat(tl::Concat { lhs, args }), loc);
+    }
+    // A barrel rotator.  Stage `k` rotates by 2^k (modulo the width) if bit `k`
+    // of the amount is set, so the depth is logarithmic in the range of the amount.
+    fn make_rotate(
+        &mut self,
+        lhs: Operand,
+        arg: Operand,
+        amount: Operand,
+        left: bool,
+        loc: SourceLocation,
+    ) {
+        let len = self.operand_bit_width(arg);
+        let mut current = arg;
+        let mut step = 1 % len;
+        for k in 0..self.operand_bit_width(amount) {
+            if step != 0 {
+                let shift = if left { len - step } else { step };
+                let order = (0..len).map(|i| (i + shift) % len).collect::<Vec<_>>();
+                let rotated = self.reg(Kind::Bits(len), loc);
+                self.make_permutation(rotated, current, &order, loc);
+                let cond = self.index_bits(amount, k..k + 1, loc);
+                let next = self.reg(Kind::Bits(len), loc);
+                self.lop(
+                    tl::OpCode::Select(tl::Select {
+                        lhs: next,
+                        cond,
+                        true_value: rotated,
+                        false_value: current,
+                    }),
+                    loc,
+                );
+                current = next;
+            }
+            step = (2 * step) % len;
+        }
+        self.lop(tl::OpCode::Assign(tl::Assign { lhs, rhs: current }), loc);
+    }
+    // An adder tree, where each adder is only as wide as the number of
+    // bits it is counting requires.
+    fn make_count_ones(&mut self, lhs: Operand, arg: Operand, loc: SourceLocation) {
+        let len = self.operand_bit_width(arg);
+        let width = |count: usize| (usize::BITS - count.leading_zeros()) as usize;
+        let mut level = (0..len)
+            .map(|i| (self.index_bits(arg, i..i + 1, loc), 1))
+            .collect::<Vec<_>>();
+        while level.len() > 1 {
+            let mut next = vec![];
+            for pair in level.chunks(2) {
+                let &[(a, a_count), (b, b_count)] = pair else {
+                    next.push(pair[0]);
+                    continue;
+                };
+                let count = a_count + b_count;
+                let sum_kind = Kind::Bits(width(count));
+                let a_ext = self.reg(sum_kind, loc);
+                let b_ext = self.reg(sum_kind, loc);
+                for (lhs, arg) in [(a_ext, a), (b_ext, b)] {
+                    self.lop(
+                        tl::OpCode::Cast(tl::Cast {
+                            lhs,
+                            arg,
+                            len: sum_kind.bits(),
+                            kind: CastKind::Resize,
+                        }),
+                        loc,
+                    );
+                }
+                let sum = self.reg(sum_kind, loc);
+                self.lop(
+                    tl::OpCode::Binary(tl::Binary {
+                        lhs: sum,
+                        op: tl::AluBinary::Add,
+                        arg1: a_ext,
+                        arg2: b_ext,
+                    }),
+                    loc,
+                );
+                next.push((sum, count));
+            }
+            level = next;
+        }
+        self.lop(
+            tl::OpCode::Cast(tl::Cast {
+                lhs,
+                arg: level[0].0,
+                len,
+                kind: CastKind::Resize,
+            }),
+            loc,
+        );
+    }
+    // A leading zero counter tree.  The bits are taken in scan order (MSB
+    // first for leading zeros, LSB first for trailing zeros), and padded with
+    // ones up to a power of two.  Each node of the tree covers 2^k bits and
+    // holds a flag that is set if all of them are zero, and a k bit count of
+    // the leading zeros.  Two nodes are merged by taking the count from the
+    // first if it is not all zeros, and from the second (plus 2^k) otherwise.
+    fn make_count_zeros(&mut self, lhs: Operand, arg: Operand, leading: bool, loc: SourceLocation) {
+        let len = self.operand_bit_width(arg);
+        let mut level = (0..(len + 1).next_power_of_two())
+            .map(|j| {
+                let zero = if j >= len {
+                    self.lit(false.typed_bits(), loc)
+                } else {
+                    let ndx = if leading { len - 1 - j } else { j };
+                    let bit = self.index_bits(arg, ndx..ndx + 1, loc);
+                    self.unary_flag(tl::AluUnary::Not, bit, loc)
+                };
+                (zero, None)
+            })
+            .collect::<Vec<(Operand, Option<Operand>)>>();
+        let mut width = 0;
+        while level.len() > 1 {
+            let mut next = vec![];
+            for pair in level.chunks(2) {
+                let (first_zero, first_count) = pair[0];
+                let (second_zero, second_count) = pair[1];
+                let zero = self.reg(Kind::make_bool(), loc);
+                self.lop(
+                    tl::OpCode::Binary(tl::Binary {
+                        lhs: zero,
+                        op: tl::AluBinary::BitAnd,
+                        arg1: first_zero,
+                        arg2: second_zero,
+                    }),
+                    loc,
+                );
+                let count = self.reg(Kind::Bits(width + 1), loc);
+                let args = match (first_count, second_count) {
+                    (Some(first_count), Some(second_count)) => {
+                        let low = self.reg(Kind::Bits(width), loc);
+                        self.lop(
+                            tl::OpCode::Select(tl::Select {
+                                lhs: low,
+                                cond: first_zero,
+                                true_value: second_count,
+                                false_value: first_count,
+                            }),
+                            loc,
+                        );
+                        vec![low, first_zero]
+                    }
+                    _ => vec![first_zero],
+                };
+                self.lop(tl::OpCode::Concat(tl::Concat { lhs: count, args }), loc);
+                next.push((zero, Some(count)));
+            }
+            level = next;
+            width += 1;
+        }
+        let count = level[0].1.expect("count tree has at least two leaves");
+        self.lop(
+            tl::OpCode::Cast(tl::Cast {
+                lhs,
+                arg: count,
+                len,
+                kind: CastKind::Resize,
+            }),
+            loc,
+        );
+    }
     fn make_unary(&mut self, unary: &hf::Unary, loc: SourceLocation) -> Result<()> {
         let hf::Unary { lhs, op, arg1 } = *unary;
         if self.object.kind(lhs).is_empty() {
@@ -1083,6 +1528,27 @@ impl<'a> RTLCompiler<'a> {
             hf::AluUnary::XShr(cnt) => self.make_xshr(lhs, arg1, cnt, loc),
             hf::AluUnary::XNeg => self.make_xneg(lhs, arg1, loc),
             hf::AluUnary::XSgn => self.make_xsgn(lhs, arg1, loc),
+            hf::AluUnary::Quantize(from, to, rounding) => {
+                self.make_quantize(lhs, arg1, from, to, rounding, loc)
+            }
+            hf::AluUnary::Saturate(from, to) => self.make_saturate(lhs, arg1, from, to, loc),
+            hf::AluUnary::CountOnes => self.make_count_ones(lhs, arg1, loc),
+            hf::AluUnary::LeadingZeros => self.make_count_zeros(lhs, arg1, true, loc),
+            hf::AluUnary::TrailingZeros => self.make_count_zeros(lhs, arg1, false, loc),
+            hf::AluUnary::ReverseBits => {
+                let order = (0..self.operand_bit_width(arg1)).rev().collect::<Vec<_>>();
+                self.make_permutation(lhs, arg1, &order, loc);
+            }
+            hf::AluUnary::SwapBytes => {
+                let len = self.operand_bit_width(arg1);
+                if !len.is_multiple_of(8) {
+                    return Err(self.raise_ice(ICE::SwapBytesRequiresWholeBytes { len }, loc));
+                }
+                let order = (0..len)
+                    .map(|i| len - 8 * (i / 8 + 1) + i % 8)
+                    .collect::<Vec<_>>();
+                self.make_permutation(lhs, arg1, &order, loc);
+            }
         };
         Ok(())
     }
@@ -1108,6 +1574,9 @@ impl<'a> RTLCompiler<'a> {
                 hf::OpCode::Case(case) => {
                     self.make_case(case, loc)?;
                 }
+                hf::OpCode::Rom(rom) => {
+                    self.make_rom(rom, loc)?;
+                }
                 hf::OpCode::Comment(comment) => {
                     self.lop(tl::OpCode::Comment(comment.clone()), loc);
                 }
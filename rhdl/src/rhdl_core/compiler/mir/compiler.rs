@@ -20,9 +20,10 @@ use crate::rhdl_core::TypedBits;
 use crate::rhdl_core::ast::ast_impl;
 use crate::rhdl_core::ast::ast_impl::{
     Arm, ArmKind, BitsKind, Block, ExprArray, ExprAssign, ExprBinary, ExprBits, ExprBlock,
-    ExprCall, ExprCast, ExprField, ExprForLoop, ExprIf, ExprIfLet, ExprIndex, ExprMatch,
-    ExprMethodCall, ExprPath, ExprRepeat, ExprRet, ExprStruct, ExprTry, ExprTuple, ExprTypedBits,
-    ExprUnary, FieldValue, Local, NodeId, Pat, PatKind, Stmt, StmtKind, WrapOp,
+    ExprCall, ExprCast, ExprField, ExprForLoop, ExprIf, ExprIfLet, ExprIndex, ExprIntConst,
+    ExprMatch, ExprMethodCall, ExprPath, ExprRepeat, ExprRet, ExprStruct, ExprTry, ExprTuple,
+    ExprTypedBits, ExprUnary, ExprWhile, FieldValue, Local, NodeId, Pat, PatKind, Stmt, StmtKind,
+    WrapOp,
 };
 use crate::rhdl_core::ast::source::builder::build_spanned_source_for_kernel;
 use crate::rhdl_core::ast::source::spanned_source::SpannedSource;
@@ -34,7 +35,7 @@ use crate::rhdl_core::common::symtab::SymbolTable;
 use crate::rhdl_core::compiler::ascii;
 use crate::rhdl_core::compiler::display_ast::pretty_print_statement;
 use crate::rhdl_core::compiler::stage1::CompilationMode;
-use crate::rhdl_core::compiler::stage1::compile;
+use crate::rhdl_core::compiler::stage1::{KernelRole, compile};
 use crate::rhdl_core::error::RHDLError;
 use crate::rhdl_core::kernel::Kernel;
 use crate::rhdl_core::rhif;
@@ -81,6 +82,8 @@ fn binop_to_alu(op: BinOp) -> AluBinary {
         BinOp::Add | BinOp::AddAssign => AluBinary::Add,
         BinOp::Sub | BinOp::SubAssign => AluBinary::Sub,
         BinOp::Mul | BinOp::MulAssign => AluBinary::Mul,
+        BinOp::Div | BinOp::DivAssign => AluBinary::Div,
+        BinOp::Rem | BinOp::RemAssign => AluBinary::Rem,
         BinOp::BitXor | BinOp::BitXorAssign => AluBinary::BitXor,
         BinOp::And | BinOp::BitAnd | BinOp::BitAndAssign => AluBinary::BitAnd,
         BinOp::Or | BinOp::BitOr | BinOp::BitOrAssign => AluBinary::BitOr,
@@ -147,6 +150,57 @@ impl std::fmt::Debug for Scope {
 
 const EARLY_RETURN_FLAG_NAME: &str = "__$early_return_flag";
 
+// The maximum number of times a while loop is allowed to be unrolled.
+const MAX_WHILE_LOOP_ITERATIONS: usize = 4096;
+
+// Counts the number of places in which a given local variable
+// is written to (either via assignment or a self-assign binop), and
+// notes if the variable is shadowed by a new binding.
+struct LoopVariableWrites {
+    name: &'static str,
+    count: usize,
+    shadowed: bool,
+}
+
+impl Visitor for LoopVariableWrites {
+    fn visit_pat(&mut self, node: &Pat) -> Result<()> {
+        if let PatKind::Ident(ident) = &node.kind
+            && ident.name == self.name
+        {
+            self.shadowed = true;
+        }
+        crate::rhdl_core::ast::visit::visit_pat(self, node)
+    }
+    fn visit_expr(&mut self, node: &Expr) -> Result<()> {
+        let target = match &node.kind {
+            ExprKind::Assign(assign) => Some(&assign.lhs),
+            ExprKind::Binary(bin) if bin.op.is_self_assign() => Some(&bin.lhs),
+            _ => None,
+        };
+        if let Some(ExprKind::Path(path)) = target.map(|x| &x.kind)
+            && path_as_ident(&path.path) == Some(self.name)
+        {
+            self.count += 1;
+        }
+        crate::rhdl_core::ast::visit::visit_expr(self, node)
+    }
+}
+
+// Wrap a value to the range of the given (bits or signed) kind.
+fn wrap_to_kind(value: i128, kind: &Kind) -> i128 {
+    let bits = kind.bits();
+    if bits >= 128 {
+        return value;
+    }
+    let modulus = 1_i128 << bits;
+    let value = value.rem_euclid(modulus);
+    if kind.is_signed() && value >= modulus / 2 {
+        value - modulus
+    } else {
+        value
+    }
+}
+
 type Result<T> = std::result::Result<T, RHDLError>;
 
 pub struct MirContext<'a> {
@@ -418,16 +472,22 @@ impl<'a> MirContext<'a> {
                 Ok(())
             }
             PatKind::Slice(slice) => {
+                // Elements that follow a rest pattern (as in `[a, .., b]`) are
+                // indexed from the end of the array.
+                let rest = slice
+                    .elems
+                    .iter()
+                    .position(|pat| matches!(pat.kind, PatKind::Rest));
                 for (ndx, pat) in slice.elems.iter().enumerate() {
+                    let path = match rest {
+                        Some(rest) if ndx == rest => continue,
+                        Some(rest) if ndx > rest => {
+                            Path::default().index_from_end(slice.elems.len() - ndx)
+                        }
+                        _ => Path::default().index(ndx),
+                    };
                     let element_rhs = self.reg(pat.id);
-                    self.op(
-                        op_index(
-                            element_rhs,
-                            rhs,
-                            crate::rhdl_core::types::path::Path::default().index(ndx),
-                        ),
-                        pat.id,
-                    );
+                    self.op(op_index(element_rhs, rhs, path), pat.id);
                     self.initialize_local(pat, element_rhs)?;
                 }
                 Ok(())
@@ -437,7 +497,7 @@ impl<'a> MirContext<'a> {
                 // initialize_local needs to be called after bind_pattern.
                 self.initialize_local(&type_pat.pat, rhs)
             }
-            PatKind::Wild | PatKind::Lit(_) | PatKind::Path(_) => Ok(()),
+            PatKind::Wild | PatKind::Rest | PatKind::Lit(_) | PatKind::Path(_) => Ok(()),
             _ => Err(self
                 .raise_ice(
                     ICE::UnsupportedPatternInInitializeLocal {
@@ -450,7 +510,7 @@ impl<'a> MirContext<'a> {
     }
     fn stash(&mut self, kernel: &Kernel) -> Result<FuncId> {
         let ndx = self.stash.len().into();
-        let object = compile(kernel.clone(), self.mode)?;
+        let object = compile(kernel.clone(), self.mode, KernelRole::Callee)?;
         self.stash.insert(ndx, Box::new(object));
         Ok(ndx)
     }
@@ -488,9 +548,14 @@ impl<'a> MirContext<'a> {
     }
     fn coerce_literal_to_i32(&self, val: &ExprLit, loc: NodeId) -> Result<i32> {
         match val {
-            ExprLit::Int(i) => i.parse::<i32>().map_err(|err| err.into()),
+            ExprLit::Int(i) | ExprLit::IntConst(ExprIntConst { value: i, .. }) => {
+                i.parse::<i32>().map_err(|err| err.into())
+            }
             ExprLit::Bool(b) => Ok(if *b { 1 } else { 0 }),
-            ExprLit::TypedBits(tb) => tb.value.as_i64().map(|x| x as i32),
+            ExprLit::TypedBits(tb) => i32::try_from(tb.value.as_i64()?).map_err(|_| {
+                self.raise_syntax_error(Syntax::LoopConstantOutOfRange, loc)
+                    .into()
+            }),
             ExprLit::Empty => Err(self.raise_ice(ICE::CannotCoerceEmptyToInteger, loc).into()),
         }
     }
@@ -536,14 +601,30 @@ impl<'a> MirContext<'a> {
         self.op(op_array(lhs, elements), id);
         Ok(lhs)
     }
-    fn arm(&mut self, target: Slot, lhs: Slot, arm: &Arm) -> Result<CaseArgument> {
+    // The fallback arms are the arms that follow an arm with a guard, and are
+    // matched against the target if the guard is false.
+    fn arm(
+        &mut self,
+        target: Slot,
+        lhs: Slot,
+        arm: &Arm,
+        fallback: &[Box<Arm>],
+    ) -> Result<CaseArgument> {
         match &arm.kind {
             ArmKind::Wild => {
-                self.wrap_expr_in_block(lhs, &arm.body)?;
+                self.arm_body(target, lhs, arm, fallback)?;
+                Ok(CaseArgument::Wild)
+            }
+            ArmKind::Binding(arm_binding) => {
+                self.new_scope();
+                self.bind_pattern(&arm_binding.pat)?;
+                self.initialize_local(&arm_binding.pat, target)?;
+                self.arm_body(target, lhs, arm, fallback)?;
+                self.end_scope();
                 Ok(CaseArgument::Wild)
             }
             ArmKind::Constant(constant) => {
-                self.wrap_expr_in_block(lhs, &arm.body)?;
+                self.arm_body(target, lhs, arm, fallback)?;
                 let value = self.lit(arm.id, constant.value.clone());
                 let disc = self.reg(arm.id);
                 self.op(
@@ -581,13 +662,50 @@ impl<'a> MirContext<'a> {
                 let payload = self.reg(arm_enum.pat.id);
                 self.op(op_index(payload, target, path), arm_enum.pat.id);
                 self.initialize_local(&arm_enum.pat, payload)?;
-                let result = self.expr(&arm.body)?;
+                let result = self.guarded_expr(target, arm, fallback)?;
                 self.op(op_assign(lhs, result), arm_enum.pat.id);
                 self.end_scope();
                 Ok(CaseArgument::Slot(discriminant_slot))
             }
         }
     }
+    fn arm_body(
+        &mut self,
+        target: Slot,
+        lhs: Slot,
+        arm: &Arm,
+        fallback: &[Box<Arm>],
+    ) -> Result<()> {
+        let result = self.guarded_expr(target, arm, fallback)?;
+        if lhs != result {
+            self.op(op_assign(lhs, result), arm.body.id);
+        }
+        Ok(())
+    }
+    // An arm with a guard is lowered to
+    //    if guard { body } else { match target { fallback arms } }
+    // so that the guarded arms of a match form a priority chain of selects.
+    fn guarded_expr(&mut self, target: Slot, arm: &Arm, fallback: &[Box<Arm>]) -> Result<Slot> {
+        let Some(guard) = &arm.guard else {
+            return self.expr(&arm.body);
+        };
+        let op_result = self.reg(arm.id);
+        let then_result = self.reg(arm.body.id);
+        let else_result = self.reg(arm.id);
+        let cond = self.expr(guard)?;
+        self.branch(
+            arm.id,
+            cond,
+            (op_result, then_result, else_result),
+            |this, then_result| this.wrap_expr_in_block(then_result, &arm.body),
+            |this, else_result| {
+                let result = this.match_arms(arm.id, target, fallback)?;
+                this.op(op_assign(else_result, result), arm.id);
+                Ok(())
+            },
+        )?;
+        Ok(op_result)
+    }
     fn assign(&mut self, id: NodeId, assign: &ExprAssign) -> Result<Slot> {
         let rhs = self.expr(&assign.rhs)?;
         let (rebind, path) = self.expr_lhs(&assign.lhs)?;
@@ -620,6 +738,8 @@ impl<'a> MirContext<'a> {
             BinOp::AddAssign => AluBinary::Add,
             BinOp::SubAssign => AluBinary::Sub,
             BinOp::MulAssign => AluBinary::Mul,
+            BinOp::DivAssign => AluBinary::Div,
+            BinOp::RemAssign => AluBinary::Rem,
             BinOp::BitXorAssign => AluBinary::BitXor,
             BinOp::BitAndAssign => AluBinary::BitAnd,
             BinOp::BitOrAssign => AluBinary::BitOr,
@@ -672,6 +792,8 @@ impl<'a> MirContext<'a> {
             BinOp::AddAssign
                 | BinOp::SubAssign
                 | BinOp::MulAssign
+                | BinOp::DivAssign
+                | BinOp::RemAssign
                 | BinOp::BitXorAssign
                 | BinOp::BitAndAssign
                 | BinOp::ShlAssign
@@ -730,7 +852,7 @@ impl<'a> MirContext<'a> {
                 Ok(())
             }
             PatKind::Paren(paren) => self.type_pattern(&paren.pat, kind),
-            PatKind::Wild => Ok(()),
+            PatKind::Wild | PatKind::Rest => Ok(()),
             _ => Err(self
                 .raise_ice(
                     ICE::UnsupportedPatternInTypePattern {
@@ -776,7 +898,7 @@ impl<'a> MirContext<'a> {
                 Ok(())
             }
             PatKind::Paren(paren) => self.bind_pattern(&paren.pat),
-            PatKind::Wild => Ok(()),
+            PatKind::Wild | PatKind::Rest => Ok(()),
             _ => Err(self
                 .raise_ice(
                     ICE::UnsupportedPatternInBindPattern {
@@ -890,6 +1012,7 @@ impl<'a> MirContext<'a> {
             ExprKind::Match(_match) => self.match_expr(expr.id, _match),
             ExprKind::Ret(_return) => self.return_expr(expr.id, _return),
             ExprKind::ForLoop(for_loop) => self.for_loop(for_loop),
+            ExprKind::While(while_loop) => self.while_loop(expr.id, while_loop),
             ExprKind::Assign(assign) => self.assign(expr.id, assign),
             ExprKind::Range(_) => Err(self
                 .raise_syntax_error(Syntax::RangesInForLoopsOnly, expr.id)
@@ -1028,6 +1151,282 @@ impl<'a> MirContext<'a> {
         let empty = self.lit_empty(for_loop.body.id);
         Ok(empty)
     }
+    // Follow a slot back through the ops emitted so far to see if it holds
+    // a compile time constant.  We only look through plain copies and bit
+    // casts, which is enough to see through `let mut i = 0;` or
+    // `let mut i = b4(0);`.  Along the way, we pick up the kind of the
+    // value if it has been declared or fixed by a cast.
+    fn constant_value_of_slot(
+        &self,
+        slot: Slot,
+        id: NodeId,
+    ) -> Result<Option<(i64, Option<Kind>)>> {
+        let declared = self.ty.get(&slot).copied();
+        if let Some(lid) = slot.lit() {
+            let lit = &self.symtab[lid];
+            let kind = match lit {
+                ExprLit::TypedBits(tb) => Some(tb.value.kind),
+                ExprLit::Empty => return Ok(None),
+                _ => None,
+            };
+            let value = self.coerce_literal_to_i32(lit, id)?;
+            return Ok(Some((value.into(), declared.or(kind))));
+        }
+        let Some(lop) = self.ops.iter().rev().find(|lop| lop.op.lhs() == Some(slot)) else {
+            return Ok(None);
+        };
+        let (arg, kind) = match &lop.op {
+            OpCode::Assign(assign) => (assign.rhs, None),
+            OpCode::AsBits(cast) => (cast.arg, cast.len.map(Kind::make_bits)),
+            OpCode::AsSigned(cast) => (cast.arg, cast.len.map(Kind::make_signed)),
+            _ => return Ok(None),
+        };
+        Ok(self
+            .constant_value_of_slot(arg, id)?
+            .map(|(value, inner)| (value, declared.or(kind).or(inner))))
+    }
+    fn constant_value_of_expr(&self, expr: &Expr) -> Result<Option<i64>> {
+        match &expr.kind {
+            ExprKind::Lit(lit) => Ok(Some(self.coerce_literal_to_i32(lit, expr.id)?.into())),
+            ExprKind::Paren(paren) => self.constant_value_of_expr(&paren.expr),
+            ExprKind::Group(group) => self.constant_value_of_expr(&group.expr),
+            ExprKind::Unary(unary) if matches!(unary.op, UnOp::Neg) => {
+                Ok(self.constant_value_of_expr(&unary.expr)?.map(|x| -x))
+            }
+            ExprKind::Bits(bits) => self.constant_value_of_expr(&bits.arg),
+            ExprKind::Call(call)
+                if matches!(
+                    call.code,
+                    Some(KernelFnKind::BitConstructor(_) | KernelFnKind::SignedBitsConstructor(_))
+                ) && call.args.len() == 1 =>
+            {
+                self.constant_value_of_expr(&call.args[0])
+            }
+            ExprKind::Path(path) => {
+                let Some((slot, _)) =
+                    path_as_ident(&path.path).and_then(|name| self.lookup_name(name))
+                else {
+                    return Ok(None);
+                };
+                Ok(self
+                    .constant_value_of_slot(slot, expr.id)?
+                    .map(|(value, _)| value))
+            }
+            _ => Ok(None),
+        }
+    }
+    // Recognize the loop condition of a while loop.  It must be a comparison
+    // between a local variable and a constant (on either side).  The returned
+    // comparison is normalized so that the loop variable is on the left.
+    fn while_loop_condition(&self, cond: &Expr) -> Result<(&'static str, Slot, BinOp, i64)> {
+        let unbounded = || self.raise_syntax_error(Syntax::WhileLoopUnboundedCondition, cond.id);
+        let bin = match &cond.kind {
+            ExprKind::Binary(bin) => bin,
+            ExprKind::Paren(paren) => return self.while_loop_condition(&paren.expr),
+            ExprKind::Group(group) => return self.while_loop_condition(&group.expr),
+            _ => return Err(unbounded().into()),
+        };
+        let as_ident = |expr: &Expr| match &expr.kind {
+            ExprKind::Path(path) => path_as_ident(&path.path),
+            _ => None,
+        };
+        let (name, op, bound) = if let Some(name) = as_ident(&bin.lhs) {
+            (name, bin.op, self.constant_value_of_expr(&bin.rhs)?)
+        } else if let Some(name) = as_ident(&bin.rhs) {
+            let flipped = match bin.op {
+                BinOp::Lt => BinOp::Gt,
+                BinOp::Le => BinOp::Ge,
+                BinOp::Gt => BinOp::Lt,
+                BinOp::Ge => BinOp::Le,
+                op => op,
+            };
+            (name, flipped, self.constant_value_of_expr(&bin.lhs)?)
+        } else {
+            return Err(unbounded().into());
+        };
+        let Some(bound) = bound else {
+            return Err(unbounded().into());
+        };
+        if !matches!(
+            op,
+            BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge | BinOp::Ne
+        ) {
+            return Err(unbounded().into());
+        }
+        let Some((slot, _)) = self.lookup_name(name) else {
+            return Err(unbounded().into());
+        };
+        Ok((name, slot, op, bound))
+    }
+    // Recognize the update of the loop variable in the body of a while loop.
+    // It must be a single, unconditional statement at the top level of the
+    // body of the form `i += c`, `i -= c`, `i = i + c`, etc.  Any other writes
+    // to the loop variable (or bindings that shadow it) make the trip count
+    // unknowable.
+    fn while_loop_step(&self, name: &'static str, body: &Block) -> Result<(BinOp, i64)> {
+        let mut writes = LoopVariableWrites {
+            name,
+            count: 0,
+            shadowed: false,
+        };
+        writes.visit_block(body)?;
+        if writes.shadowed {
+            return Err(self
+                .raise_syntax_error(
+                    Syntax::WhileLoopVariableShadowed {
+                        name: name.to_owned(),
+                    },
+                    body.id,
+                )
+                .into());
+        }
+        if writes.count > 1 {
+            return Err(self
+                .raise_syntax_error(
+                    Syntax::WhileLoopMultipleUpdates {
+                        name: name.to_owned(),
+                    },
+                    body.id,
+                )
+                .into());
+        }
+        let is_loop_var = |expr: &Expr| matches!(&expr.kind, ExprKind::Path(path) if path_as_ident(&path.path) == Some(name));
+        for stmt in &body.stmts {
+            let (StmtKind::Semi(expr) | StmtKind::Expr(expr)) = &stmt.kind else {
+                continue;
+            };
+            let (op, step) = match &expr.kind {
+                ExprKind::Binary(bin) if bin.op.is_self_assign() && is_loop_var(&bin.lhs) => {
+                    (bin.op, &bin.rhs)
+                }
+                ExprKind::Assign(assign) if is_loop_var(&assign.lhs) => match &assign.rhs.kind {
+                    ExprKind::Binary(bin) if is_loop_var(&bin.lhs) => (bin.op, &bin.rhs),
+                    _ => break,
+                },
+                _ => continue,
+            };
+            if let Some(step) = self.constant_value_of_expr(step)? {
+                return Ok((op, step));
+            }
+            break;
+        }
+        Err(self
+            .raise_syntax_error(
+                Syntax::WhileLoopNoConstantStep {
+                    name: name.to_owned(),
+                },
+                body.id,
+            )
+            .into())
+    }
+    // A while loop is unrolled in the same way as a for loop.  To do that,
+    // we need to know how many times the loop runs, which we determine by
+    // simulating the loop variable from its (constant) initial value using
+    // the (constant) step until the condition fails.  If the kind of the
+    // loop variable is known, the simulation wraps the way the hardware
+    // (and Rust) would.  Otherwise, we insist that the variable stays between
+    // its initial value and the bound, where it is known to be representable.
+    fn while_loop(&mut self, id: NodeId, while_loop: &ExprWhile) -> Result<Slot> {
+        let (name, slot, cmp, bound) = self.while_loop_condition(&while_loop.cond)?;
+        let Some((init, kind)) = self.constant_value_of_slot(slot, while_loop.cond.id)? else {
+            return Err(self
+                .raise_syntax_error(
+                    Syntax::WhileLoopNonConstantInitialValue {
+                        name: name.to_owned(),
+                    },
+                    while_loop.cond.id,
+                )
+                .into());
+        };
+        let (step_op, step) = self.while_loop_step(name, &while_loop.body)?;
+        let shift = match step_op {
+            BinOp::Add
+            | BinOp::AddAssign
+            | BinOp::Sub
+            | BinOp::SubAssign
+            | BinOp::Mul
+            | BinOp::MulAssign => 0,
+            BinOp::Shl | BinOp::ShlAssign | BinOp::Shr | BinOp::ShrAssign if step >= 0 => {
+                step as u32
+            }
+            _ => {
+                return Err(self
+                    .raise_syntax_error(
+                        Syntax::WhileLoopNoConstantStep {
+                            name: name.to_owned(),
+                        },
+                        while_loop.body.id,
+                    )
+                    .into());
+            }
+        };
+        let kind = kind.filter(|kind| kind.is_unsigned() || kind.is_signed());
+        let (init, bound, step) = (i128::from(init), i128::from(bound), i128::from(step));
+        let (init, bound) = match &kind {
+            Some(kind) => (wrap_to_kind(init, kind), wrap_to_kind(bound, kind)),
+            None => (init, bound),
+        };
+        let range = init.min(bound)..=init.max(bound);
+        let unsigned_128 = kind
+            .as_ref()
+            .is_some_and(|kind| kind.is_unsigned() && kind.bits() >= 128);
+        let is_less = |a: i128, b: i128| {
+            if unsigned_128 {
+                (a as u128) < (b as u128)
+            } else {
+                a < b
+            }
+        };
+        let mut value = init;
+        let mut trip_count = 0;
+        while match cmp {
+            BinOp::Lt => is_less(value, bound),
+            BinOp::Le => !is_less(bound, value),
+            BinOp::Gt => is_less(bound, value),
+            BinOp::Ge => !is_less(value, bound),
+            _ => value != bound,
+        } {
+            trip_count += 1;
+            if trip_count > MAX_WHILE_LOOP_ITERATIONS {
+                return Err(self
+                    .raise_syntax_error(
+                        Syntax::WhileLoopTooManyIterations {
+                            limit: MAX_WHILE_LOOP_ITERATIONS,
+                        },
+                        id,
+                    )
+                    .into());
+            }
+            let (next, overflow) = match step_op {
+                BinOp::Add | BinOp::AddAssign => value.overflowing_add(step),
+                BinOp::Sub | BinOp::SubAssign => value.overflowing_sub(step),
+                BinOp::Mul | BinOp::MulAssign => value.overflowing_mul(step),
+                BinOp::Shl | BinOp::ShlAssign if shift < 127 => value.overflowing_mul(1 << shift),
+                BinOp::Shl | BinOp::ShlAssign => (0, value != 0),
+                _ => (value >> shift.min(127), false),
+            };
+            value = match &kind {
+                Some(kind) => wrap_to_kind(next, kind),
+                None if !overflow && range.contains(&next) => next,
+                None => {
+                    return Err(self
+                        .raise_syntax_error(
+                            Syntax::WhileLoopVariableOutOfRange {
+                                name: name.to_owned(),
+                            },
+                            id,
+                        )
+                        .into());
+                }
+            };
+        }
+        debug!("While loop on {name} unrolled {trip_count} times");
+        for _ in 0..trip_count {
+            let result = self.reg(while_loop.body.id);
+            self.block(result, &while_loop.body)?;
+        }
+        Ok(self.lit_empty(id))
+    }
     fn if_let_expr(&mut self, id: NodeId, if_let_expr: &ExprIfLet) -> Result<Slot> {
         // Try a rewrite of if let -> match
         // We have if let arm_kind = test { body} {else_branch}
@@ -1040,6 +1439,7 @@ impl<'a> MirContext<'a> {
         let active_arm = Arm {
             id,
             kind: if_let_expr.kind.clone(),
+            guard: None,
             body: Box::new(Expr {
                 id: if_let_expr.then_block.id,
                 kind: ExprKind::Block(ExprBlock {
@@ -1050,6 +1450,7 @@ impl<'a> MirContext<'a> {
         let else_arm = Arm {
             id,
             kind: ArmKind::Wild,
+            guard: None,
             body: if_let_expr
                 .else_branch
                 .clone()
@@ -1067,18 +1468,41 @@ impl<'a> MirContext<'a> {
         let else_id = if_expr.else_branch.as_ref().map(|x| x.id).unwrap_or(id);
         let else_result = self.reg(else_id);
         let cond = self.expr(&if_expr.cond)?;
+        self.branch(
+            id,
+            cond,
+            (op_result, then_result, else_result),
+            |this, then_result| this.block(then_result, &if_expr.then_branch),
+            |this, else_result| {
+                if let Some(expr) = if_expr.else_branch.as_ref() {
+                    this.wrap_expr_in_block(else_result, expr)
+                } else {
+                    let empty = this.lit_empty(id);
+                    this.op(op_assign(else_result, empty), id);
+                    Ok(())
+                }
+            },
+        )?;
+        Ok(op_result)
+    }
+    // Lower a two way branch on `cond` into a select of the results of the
+    // two branches.  Local variables rebound in either branch are merged
+    // with selects as well.
+    fn branch(
+        &mut self,
+        id: NodeId,
+        cond: Slot,
+        (op_result, then_result, else_result): (Slot, Slot, Slot),
+        then_branch: impl FnOnce(&mut Self, Slot) -> Result<()>,
+        else_branch: impl FnOnce(&mut Self, Slot) -> Result<()>,
+    ) -> Result<()> {
         let locals_prior_to_branch = self.locals();
         debug!("Locals prior to branch {:?}", locals_prior_to_branch);
-        self.block(then_result, &if_expr.then_branch)?;
+        then_branch(self, then_result)?;
         let locals_after_then_branch = self.locals().clone();
         debug!("Locals after then branch {:?}", locals_after_then_branch);
         self.set_locals(&locals_prior_to_branch, id)?;
-        if let Some(expr) = if_expr.else_branch.as_ref() {
-            self.wrap_expr_in_block(else_result, expr)?;
-        } else {
-            let empty = self.lit_empty(id);
-            self.op(op_assign(else_result, empty), id);
-        }
+        else_branch(self, else_result)?;
         let locals_after_else_branch = self.locals();
         self.set_locals(&locals_prior_to_branch, id)?;
         // Linearize the if statement.
@@ -1115,7 +1539,7 @@ impl<'a> MirContext<'a> {
             self.op(op_select(new_binding, cond, then_binding, else_binding), id);
         }
         self.op(op_select(op_result, cond, then_result, else_result), id);
-        Ok(op_result)
+        Ok(())
     }
     fn index(&mut self, id: NodeId, index: &ExprIndex) -> Result<Slot> {
         let lhs = self.reg(id);
@@ -1145,6 +1569,39 @@ impl<'a> MirContext<'a> {
     fn match_expr(&mut self, id: NodeId, match_expr: &ExprMatch) -> Result<Slot> {
         let lhs = self.reg(id);
         let target = self.expr(&match_expr.expr)?;
+        self.match_target(id, lhs, target, &match_expr.arms)?;
+        Ok(lhs)
+    }
+    fn match_arms(&mut self, id: NodeId, target: Slot, arms: &[Box<Arm>]) -> Result<Slot> {
+        let lhs = self.reg(id);
+        self.match_target(id, lhs, target, arms)?;
+        Ok(lhs)
+    }
+    fn match_target(
+        &mut self,
+        id: NodeId,
+        lhs: Slot,
+        target: Slot,
+        arms: &[Box<Arm>],
+    ) -> Result<()> {
+        // The arms that follow the first arm with a guard are matched
+        // in a nested match, both when the guard fails, and when the
+        // guarded arm does not match at all.
+        let guarded = arms.iter().position(|arm| arm.guard.is_some());
+        let (arms, fallback) = match guarded {
+            Some(ndx) => arms.split_at(ndx + 1),
+            None => (arms, &[][..]),
+        };
+        if guarded.is_some() && fallback.is_empty() {
+            return Err(self
+                .raise_syntax_error(Syntax::MatchGuardWithoutFallback, id)
+                .into());
+        }
+        let needs_fallback = !fallback.is_empty()
+            && !matches!(
+                arms.last().map(|arm| &arm.kind),
+                Some(ArmKind::Wild | ArmKind::Binding(_))
+            );
         let discriminant = self.reg(id);
         self.op(
             op_index(
@@ -1159,14 +1616,21 @@ impl<'a> MirContext<'a> {
         let mut arguments = vec![];
         let mut arm_locals = vec![];
         let mut arm_lhs = vec![];
-        for arm in &match_expr.arms {
+        for arm in arms {
             self.set_locals(&locals_prior_to_match, id)?;
             let lhs = self.reg(id);
-            let disc = self.arm(target, lhs, arm)?;
+            let disc = self.arm(target, lhs, arm, fallback)?;
             arm_lhs.push(lhs);
             arguments.push(disc);
             arm_locals.push(self.locals().clone());
         }
+        if needs_fallback {
+            self.set_locals(&locals_prior_to_match, id)?;
+            let lhs = self.match_arms(id, target, fallback)?;
+            arm_lhs.push(lhs);
+            arguments.push(CaseArgument::Wild);
+            arm_locals.push(self.locals().clone());
+        }
         self.set_locals(&locals_prior_to_match, id)?;
         let mut rebound_locals = BTreeSet::new();
         for branch_locals in &arm_locals {
@@ -1202,7 +1666,7 @@ impl<'a> MirContext<'a> {
         }
         let match_expr_table = arguments.iter().cloned().zip(arm_lhs).collect::<Vec<_>>();
         self.op(op_case(lhs, discriminant, match_expr_table), id);
-        Ok(lhs)
+        Ok(())
     }
     fn resize(&mut self, id: NodeId, cast: &ExprMethodCall) -> Result<Slot> {
         // First the inferred case...
@@ -1231,13 +1695,32 @@ impl<'a> MirContext<'a> {
         self.op(op_unary(op(len), lhs, arg), id);
         Ok(lhs)
     }
+    // The rounding mode argument of `quantize` is a constant, and is
+    // carried (along with the binary point of the receiver) by the call.
+    fn fixed_point(&mut self, id: NodeId, call: &ExprMethodCall) -> Result<Slot> {
+        let lhs = self.reg(id);
+        let arg = self.expr(&call.receiver)?;
+        let (Some(len), Some(fixed)) = (call.turbo, call.fixed) else {
+            return Err(self
+                .raise_syntax_error(Syntax::FixedPointWithoutLength, id)
+                .into());
+        };
+        let op = if call.method == "quantize" {
+            AluUnary::Quantize(fixed.fraction_bits, len, fixed.rounding)
+        } else {
+            AluUnary::Saturate(fixed.integer_bits, len)
+        };
+        self.op(op_unary(op, lhs, arg), id);
+        Ok(lhs)
+    }
     fn method_call(&mut self, id: NodeId, method_call: &ExprMethodCall) -> Result<Slot> {
         // Special case the `cast` method calls and the extended arithmetic ops
         match method_call.method {
-            "resize" => return self.resize(id, method_call),
+            "resize" | "wide" | "narrow" => return self.resize(id, method_call),
             "xext" => return self.xops(id, method_call, AluUnary::XExt),
             "xshl" => return self.xops(id, method_call, AluUnary::XShl),
             "xshr" => return self.xops(id, method_call, AluUnary::XShr),
+            "quantize" | "saturate" => return self.fixed_point(id, method_call),
             _ => {}
         }
         let lhs = self.reg(id);
@@ -1252,6 +1735,14 @@ impl<'a> MirContext<'a> {
             "xadd" => Some(AluBinary::XAdd),
             "xsub" => Some(AluBinary::XSub),
             "xmul" => Some(AluBinary::XMul),
+            "saturating_add" => Some(AluBinary::SaturatingAdd),
+            "saturating_sub" => Some(AluBinary::SaturatingSub),
+            "saturating_mul" => Some(AluBinary::SaturatingMul),
+            "overflowing_add" => Some(AluBinary::OverflowingAdd),
+            "overflowing_sub" => Some(AluBinary::OverflowingSub),
+            "overflowing_mul" => Some(AluBinary::OverflowingMul),
+            "rotate_left" => Some(AluBinary::RotateLeft),
+            "rotate_right" => Some(AluBinary::RotateRight),
             _ => None,
         } {
             let rhs = self.expr(&method_call.args[0])?;
@@ -1266,6 +1757,11 @@ impl<'a> MirContext<'a> {
             "as_signed" => AluUnary::Signed,
             "xneg" => AluUnary::XNeg,
             "xsgn" => AluUnary::XSgn,
+            "count_ones" => AluUnary::CountOnes,
+            "leading_zeros" => AluUnary::LeadingZeros,
+            "trailing_zeros" => AluUnary::TrailingZeros,
+            "reverse_bits" => AluUnary::ReverseBits,
+            "swap_bytes" => AluUnary::SwapBytes,
             // The `val` method is a special case used to strip the clocking context
             // from a signal.
             "val" => AluUnary::Val,
@@ -205,6 +205,8 @@ pub enum ICE {
     InvalidXopsKind { a: Kind, b: Kind },
     #[error("Result of an Xops (xadd, xsub, xmul) cannot be assigned to a literal")]
     XopsResultMustBeRegister,
+    #[error("Argument of swap_bytes must be a whole number of bytes, not {len} bits")]
+    SwapBytesRequiresWholeBytes { len: usize },
     #[error("Argument of pad operation must be either a Bits or SignedBits value, not {a:?}")]
     InvalidPadKind { a: Kind },
     #[error("Argument of cut operation must be either a Bits or SignedBits value, not {a:?}")]
@@ -251,6 +253,49 @@ pub enum Syntax {
     #[error("For loop with non-integer end value is not supported")]
     #[diagnostic(help("Use a literal integer range like 0..5 for the for loop range"))]
     ForLoopNonIntegerEndValue,
+    #[error("While loop condition cannot be bounded at compile time")]
+    #[diagnostic(help(
+        "Use a condition that compares a local variable against a constant, like `while i < 8`"
+    ))]
+    WhileLoopUnboundedCondition,
+    #[error("While loop variable `{name}` does not have a constant initial value")]
+    #[diagnostic(help(
+        "Initialize the loop variable with a literal or constant before the loop, like `let mut {name} = 0;`"
+    ))]
+    WhileLoopNonConstantInitialValue { name: String },
+    #[error("While loop variable `{name}` is not advanced by a constant step")]
+    #[diagnostic(help(
+        "Update the loop variable with a constant at the top level of the loop body, like `{name} += 1;`"
+    ))]
+    WhileLoopNoConstantStep { name: String },
+    #[error("While loop variable `{name}` is modified more than once in the loop body")]
+    #[diagnostic(help(
+        "The loop variable must be updated exactly once per iteration, by a constant step, and not inside of a branch"
+    ))]
+    WhileLoopMultipleUpdates { name: String },
+    #[error("While loop does not terminate within {limit} iterations")]
+    #[diagnostic(help(
+        "Check the loop condition and step.  Loops that run for more than {limit} iterations must be restructured."
+    ))]
+    WhileLoopTooManyIterations { limit: usize },
+    #[error("While loop variable `{name}` is shadowed in the loop body")]
+    #[diagnostic(help(
+        "Rename the variable bound in the loop body so that it does not hide the loop variable `{name}`"
+    ))]
+    WhileLoopVariableShadowed { name: String },
+    #[error("While loop variable `{name}` leaves the range of values it can be shown to hold")]
+    #[diagnostic(help(
+        "The width of `{name}` is not known here, so RHDL cannot tell if it wraps around.  Give it an explicit type, like `let mut {name} = b8(0);`"
+    ))]
+    WhileLoopVariableOutOfRange { name: String },
+    #[error("Loop bound or step does not fit in a 32 bit signed integer")]
+    #[diagnostic(help("Use a smaller constant for the loop bound or step"))]
+    LoopConstantOutOfRange,
+    #[error("Match guard on the last arm of a match")]
+    #[diagnostic(help(
+        "The arm with a guard must be followed by arms that cover the values for which the guard is false"
+    ))]
+    MatchGuardWithoutFallback,
     #[error("Unsupported method call")]
     #[diagnostic(help(
         "Only .all(), .any(), .xor(), .as_unsigned(), .as_signed(), .resize() and .pad() are supported in kernels"
@@ -279,6 +324,19 @@ pub enum Syntax {
         "Use a turbofish to indicate how many bits you want to prepend (msb), e.g., a.xext::<U4>() or how many bits to shift left or right, as a.xshr<U2>()"
     ))]
     XOpsWithoutLength,
+    #[error("RHDL cannot infer the number of bits in a quantize/saturate operation")]
+    #[diagnostic(help(
+        "Use a turbofish to indicate how many fractional bits you want to keep, e.g., a.quantize::<U4>(Rounding::HalfUp), or how many integer bits, as a.saturate::<U4>()"
+    ))]
+    FixedPointWithoutLength,
+    #[error("RHDL only supports division and remainder by a constant")]
+    #[diagnostic(help(
+        "Division by a constant is lowered to a multiply and a shift.  To divide by a value that is only known at run time, use a multi-cycle divider core instead of the `/` or `%` operator."
+    ))]
+    DivisionByNonConstant,
+    #[error("Division by zero")]
+    #[diagnostic(help("The divisor in this division or remainder is the constant zero"))]
+    DivisionByZero,
 }
 
 #[derive(Debug, Error, Diagnostic)]
@@ -11,7 +11,7 @@ use std::collections::BTreeMap;
 use crate::rhdl_core::{
     Digital, Kind, TypedBits,
     ast::{
-        ast_impl::{ExprLit, WrapOp},
+        ast_impl::{ExprIntConst, ExprLit, WrapOp},
         source::source_location::SourceLocation,
     },
     compiler::mir::{
@@ -142,7 +142,7 @@ impl<'a> MirTypeInference<'a> {
                 }
                 tb.value
             }
-            ExprLit::Int(x) => {
+            ExprLit::Int(x) | ExprLit::IntConst(ExprIntConst { value: x, .. }) => {
                 if kind.is_unsigned() {
                     let x_as_u128 = if let Some(x) = x.strip_prefix("0b") {
                         u128::from_str_radix(x, 2)?
@@ -213,7 +213,7 @@ impl<'a> MirTypeInference<'a> {
             let id = *id;
             let ty = match lit {
                 ExprLit::TypedBits(tb) => self.ctx.from_kind(id, tb.value.kind),
-                ExprLit::Int(_) => self.ctx.ty_integer(id),
+                ExprLit::Int(_) | ExprLit::IntConst(_) => self.ctx.ty_integer(id),
                 ExprLit::Bool(_) => self.ctx.ty_bool(id),
                 ExprLit::Empty => self.ctx.ty_empty(id),
             };
@@ -327,6 +327,21 @@ impl<'a> MirTypeInference<'a> {
                 let lhs_ty = self.ctx.ty_with_sign_and_len(loc, a1_sign, len);
                 self.unify(loc, op.lhs, lhs_ty)?;
             }
+            AluUnary::Quantize(from, to, _) | AluUnary::Saturate(from, to) => {
+                let Some(a1_len) = self.ctx.project_bit_length(a1) else {
+                    return Ok(());
+                };
+                let Ok(a1_len) = self.ctx.cast_ty_as_bit_length(a1_len) else {
+                    return Ok(());
+                };
+                let Some(a1_sign) = self.ctx.project_sign_flag(a1) else {
+                    return Ok(());
+                };
+                let computed_len = self.validate_bit_size((a1_len + to).saturating_sub(from), loc)?;
+                let len = self.ctx.ty_const_len(loc, computed_len);
+                let lhs_ty = self.ctx.ty_with_sign_and_len(loc, a1_sign, len);
+                self.unify(loc, op.lhs, lhs_ty)?;
+            }
             AluUnary::XNeg | AluUnary::XSgn => {
                 let Some(a1_len) = self.ctx.project_bit_length(a1) else {
                     return Ok(());
@@ -350,9 +365,33 @@ impl<'a> MirTypeInference<'a> {
             | AluBinary::BitOr
             | AluBinary::BitXor
             | AluBinary::Mul
-            | AluBinary::Sub => {
+            | AluBinary::Div
+            | AluBinary::Rem
+            | AluBinary::Sub
+            | AluBinary::SaturatingAdd
+            | AluBinary::SaturatingSub
+            | AluBinary::SaturatingMul => {
                 self.enforce_data_types_binary(loc, op.lhs, op.arg1, op.arg2)?;
             }
+            AluBinary::OverflowingAdd | AluBinary::OverflowingSub | AluBinary::OverflowingMul => {
+                // LHS is a tuple of the wrapped result and the overflow flag
+                let arg1_data = if self.ctx.is_signal(op.arg1) {
+                    self.ctx.project_signal_value(op.arg1)
+                } else {
+                    Some(op.arg1)
+                };
+                let arg2_data = if self.ctx.is_signal(op.arg2) {
+                    self.ctx.project_signal_value(op.arg2)
+                } else {
+                    Some(op.arg2)
+                };
+                if let (Some(arg1_data), Some(arg2_data)) = (arg1_data, arg2_data) {
+                    self.unify(loc, arg1_data, arg2_data)?;
+                    let flag = self.ctx.ty_bool(loc);
+                    let lhs_var = self.ctx.ty_tuple(loc, vec![arg1_data, flag]);
+                    self.unify(loc, op.lhs, lhs_var)?;
+                }
+            }
             AluBinary::XAdd => {
                 self.try_xadd_xmul(loc, op.lhs, op.arg1, op.arg2, |a, b| a.max(b) + 1)?;
             }
@@ -383,7 +422,7 @@ impl<'a> MirTypeInference<'a> {
                     self.unify(loc, arg1_data, arg2_data)?;
                 }
             }
-            AluBinary::Shl | AluBinary::Shr => {
+            AluBinary::Shl | AluBinary::Shr | AluBinary::RotateLeft | AluBinary::RotateRight => {
                 self.unify(loc, op.lhs, op.arg1)?;
                 if let Some(flag) = self.ctx.project_sign_flag(op.arg2) {
                     let unsigned_flag = self.ctx.ty_sign_flag(loc, SignFlag::Unsigned);
@@ -421,6 +460,11 @@ impl<'a> MirTypeInference<'a> {
                 PathElement::Index(ndx) => {
                     arg = self.ctx.ty_index(arg, *ndx)?;
                 }
+                PathElement::IndexFromEnd(_) => {
+                    // Only arrays can be indexed from the end, and all of their
+                    // elements have the same type.
+                    arg = self.ctx.ty_index(arg, 0)?;
+                }
                 PathElement::Field(member) => {
                     arg = self.ctx.ty_field(arg, member)?;
                 }
@@ -711,6 +755,13 @@ impl<'a> MirTypeInference<'a> {
                         self.unify(loc, lhs, val_ty)?;
                     }
                 }
+                OpCode::Rom(rom) => {
+                    let lhs = self.slot_ty(rom.lhs);
+                    for value in &rom.table {
+                        let val_ty = self.ctx.from_kind(loc, value.kind);
+                        self.unify(loc, lhs, val_ty)?;
+                    }
+                }
                 OpCode::Enum(enumerate) => {
                     let lhs = self.slot_ty(enumerate.lhs);
                     let Kind::Enum(enum_k) = &enumerate.template.kind else {
@@ -879,7 +930,12 @@ impl<'a> MirTypeInference<'a> {
                     let lhs = self.slot_ty(unary.lhs);
                     let arg1 = self.slot_ty(unary.arg1);
                     match unary.op {
-                        AluUnary::Not => {
+                        AluUnary::Not
+                        | AluUnary::CountOnes
+                        | AluUnary::LeadingZeros
+                        | AluUnary::TrailingZeros
+                        | AluUnary::ReverseBits
+                        | AluUnary::SwapBytes => {
                             self.unify(loc, lhs, arg1)?;
                         }
                         AluUnary::Neg => {
@@ -904,7 +960,9 @@ impl<'a> MirTypeInference<'a> {
                         | AluUnary::XShl(_)
                         | AluUnary::XShr(_)
                         | AluUnary::XNeg
-                        | AluUnary::XSgn => {
+                        | AluUnary::XSgn
+                        | AluUnary::Quantize(..)
+                        | AluUnary::Saturate(..) => {
                             self.type_ops.push(TypeOperation {
                                 loc: op.loc,
                                 kind: TypeOperationKind::UnaryOp(TypeUnaryOp {
@@ -1162,16 +1220,28 @@ pub fn infer(mir: Mir) -> Result<Object> {
         .cloned()
         .map(|lop| {
             let loc = lop.loc;
-            if let OpCode::Wrap(mut wrap) = lop.op {
-                let ty = final_type_map[&wrap.lhs];
-                let lhs_kind = infer.ctx.into_kind(ty)?;
-                wrap.kind = Some(lhs_kind);
-                Ok(LocatedOpCode {
-                    loc,
-                    op: OpCode::Wrap(wrap),
-                })
-            } else {
-                Ok(lop)
+            match lop.op {
+                OpCode::Wrap(mut wrap) => {
+                    let ty = final_type_map[&wrap.lhs];
+                    let lhs_kind = infer.ctx.into_kind(ty)?;
+                    wrap.kind = Some(lhs_kind);
+                    Ok(LocatedOpCode {
+                        loc,
+                        op: OpCode::Wrap(wrap),
+                    })
+                }
+                // Indices from the end of an array (from patterns like `[a, .., b]`)
+                // can be resolved now that the length of the array is known.
+                OpCode::Index(mut index) if index.path.any_from_end() => {
+                    let ty = final_type_map[&index.arg];
+                    let arg_kind = infer.ctx.into_kind(ty)?;
+                    index.path = index.path.resolve_indices_from_end(arg_kind)?;
+                    Ok(LocatedOpCode {
+                        loc,
+                        op: OpCode::Index(index),
+                    })
+                }
+                _ => Ok(lop),
             }
         })
         .collect::<anyhow::Result<Vec<_>>>()?;
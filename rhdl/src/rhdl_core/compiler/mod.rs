@@ -1,4 +1,7 @@
+pub mod cache;
 pub mod driver;
+pub mod dump;
+pub mod lints;
 pub use driver::compile_design;
 pub mod ascii;
 mod display_ast;
@@ -11,4 +14,5 @@ mod stage3;
 pub use stage1::CompilationMode;
 mod lower_rhif_to_rtl;
 mod ntl_passes;
-pub use stage3::optimize_ntl;
+pub use ntl_passes::lower_to_gates::LowerToGates;
+pub use stage3::{NtlPass, NtlPipeline, optimize_ntl};
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use crate::{
+    prelude::RHDLError,
+    rhdl_core::{
+        common::{sense::Sense, slot_vec::SlotKey, symtab::RegisterId},
+        compiler::ntl_passes::pass::Pass,
+        ntl::{
+            object::{LocatedOpCode, Object},
+            spec::{BinaryOp, OpCode, Vector, VectorOp, Wire, assign},
+            visit::visit_wires_mut,
+        },
+    },
+};
+
+#[derive(Default, Debug, Clone)]
+pub struct CommonSubexpressionElimination {}
+
+// The op codes only implement PartialEq, but none of them contain
+// anything (like a float) that is not equal to itself.
+#[derive(PartialEq, Hash)]
+struct Exact(OpCode);
+
+impl Eq for Exact {}
+
+fn normalize_vector(vector: &mut Vector) {
+    match vector.op {
+        VectorOp::Add | VectorOp::Mul | VectorOp::Eq | VectorOp::Ne
+            if vector.arg2 < vector.arg1 =>
+        {
+            std::mem::swap(&mut vector.arg1, &mut vector.arg2);
+        }
+        VectorOp::Gt => {
+            vector.op = VectorOp::Lt;
+            std::mem::swap(&mut vector.arg1, &mut vector.arg2);
+        }
+        VectorOp::Ge => {
+            vector.op = VectorOp::Le;
+            std::mem::swap(&mut vector.arg1, &mut vector.arg2);
+        }
+        _ => {}
+    }
+}
+
+// The key for an op is the op with its arguments in canonical order,
+// and every output replaced by a placeholder.  Black boxes hold the
+// registers of the design, and are never merged.  Literals do not need
+// to be canonicalized, since the RemoveExtraLiterals pass already has.
+fn cse_key(op: &OpCode) -> Option<(Vec<Wire>, Exact)> {
+    let mut op = op.clone();
+    match &mut op {
+        OpCode::Binary(binary) => match binary.op {
+            BinaryOp::Xor | BinaryOp::And | BinaryOp::Or => {
+                if binary.arg2 < binary.arg1 {
+                    std::mem::swap(&mut binary.arg1, &mut binary.arg2);
+                }
+            }
+        },
+        OpCode::Vector(vector) => normalize_vector(vector),
+        OpCode::Case(_)
+        | OpCode::Rom(_)
+        | OpCode::Select(_)
+        | OpCode::Not(_)
+        | OpCode::Unary(_) => {}
+        OpCode::Noop | OpCode::Assign(_) | OpCode::Comment(_) | OpCode::BlackBox(_) => {
+            return None;
+        }
+    }
+    let mut lhs = vec![];
+    visit_wires_mut(&mut op, |sense, wire| {
+        if sense == Sense::Write {
+            lhs.push(*wire);
+            *wire = Wire::Register(RegisterId::new(0, 0));
+        }
+    });
+    Some((lhs, Exact(op)))
+}
+
+impl Pass for CommonSubexpressionElimination {
+    fn run(mut input: Object) -> Result<Object, RHDLError> {
+        let mut seen: HashMap<Exact, Vec<Wire>> = HashMap::default();
+        let mut ops = Vec::with_capacity(input.ops.len());
+        for lop in input.ops {
+            let Some((lhs, key)) = cse_key(&lop.op) else {
+                ops.push(lop);
+                continue;
+            };
+            match seen.get(&key) {
+                // The duplicate becomes a copy of the first result, which
+                // is then removed by the RemoveExtraRegisters pass.
+                Some(rhs) => ops.extend(lhs.into_iter().zip(rhs).map(|(lhs, rhs)| LocatedOpCode {
+                    op: assign(lhs, *rhs),
+                    loc: lop.loc,
+                })),
+                None => {
+                    seen.insert(key, lhs);
+                    ops.push(lop);
+                }
+            }
+        }
+        input.ops = ops;
+        Ok(input)
+    }
+
+    fn description() -> &'static str {
+        "Replace repeated computations with a copy of the first result"
+    }
+}
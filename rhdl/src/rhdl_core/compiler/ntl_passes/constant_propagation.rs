@@ -1,5 +1,5 @@
 use crate::{
-    prelude::{BitString, RHDLError},
+    prelude::{BitString, BitX, RHDLError},
     rhdl_core::{
         TypedBits,
         ast::source::source_location::SourceLocation,
@@ -7,8 +7,8 @@ use crate::{
             Object,
             object::LocatedOpCode,
             spec::{
-                Binary, BinaryOp, Case, CaseEntry, Not, OpCode, Unary, UnaryOp, Vector, VectorOp,
-                Wire, assign,
+                Binary, BinaryOp, Case, CaseEntry, Not, OpCode, Rom, Unary, UnaryOp, Vector,
+                VectorOp, Wire, assign,
             },
         },
         rtl::{
@@ -156,6 +156,34 @@ fn compute_case(input: &Object, case: Case) -> OpCode {
     assign(case.lhs, case.entries[entry_ndx].1)
 }
 
+fn compute_rom(
+    input: &mut Object,
+    rom: Rom,
+    source: Option<SourceLocation>,
+    lop: &mut Vec<LocatedOpCode>,
+) {
+    let Some(address) = vec_op(input, false, &rom.address) else {
+        lop.push(LocatedOpCode {
+            op: OpCode::Rom(rom),
+            loc: source,
+        });
+        return;
+    };
+    let value = BitString::from(address)
+        .as_address()
+        .and_then(|address| rom.table.get(address))
+        .map(|value| value.bits().to_vec())
+        .unwrap_or_else(|| vec![BitX::X; rom.lhs.len()]);
+    for (&lhs, &rhs) in rom.lhs.iter().zip(&value) {
+        let details = input.symtab[lhs].clone();
+        let rhs = input.symtab.lit(rhs, details);
+        lop.push(LocatedOpCode {
+            op: assign(lhs, rhs),
+            loc: source,
+        })
+    }
+}
+
 impl Pass for ConstantPropagationPass {
     fn description() -> &'static str {
         "Constant propagation"
@@ -175,6 +203,7 @@ impl Pass for ConstantPropagationPass {
                     loc: lop.loc,
                     op: compute_case(&input, case),
                 }),
+                OpCode::Rom(rom) => compute_rom(&mut input, rom, lop.loc, &mut ops),
                 OpCode::Unary(unary) => compute_unary(&mut input, unary, lop.loc, &mut ops),
                 OpCode::Not(not) => ops.push(LocatedOpCode {
                     loc: lop.loc,
@@ -0,0 +1,372 @@
+use crate::{
+    prelude::{BitX, RHDLError},
+    rhdl_core::{
+        ast::source::source_location::SourceLocation,
+        ntl::{
+            Object,
+            object::{LocatedOpCode, WireDetails},
+            spec::{
+                self, Binary, BinaryOp, CaseEntry, Not, OpCode, Select, UnaryOp, VectorOp, Wire,
+                assign,
+            },
+        },
+    },
+};
+
+use crate::rhdl_core::compiler::NtlPass;
+
+/// Lower the word level ops (`Vector`, `Unary`, `Case` and `Rom`) to
+/// single bit `Binary`, `Not` and `Select` gates.  The result is a purely
+/// bit level netlist, as needed by the BLIF and AIGER writers.  The pass
+/// is not part of the default pipeline, since the word level ops make
+/// for much better Verilog.  Add it to a
+/// [NtlPipeline](crate::rhdl_core::compiler::NtlPipeline) with
+/// `before_fixpoint` so the built in optimizations clean up the gates.
+#[derive(Default, Debug, Clone)]
+pub struct LowerToGates {}
+
+// Builds the gates that replace a single op.  The new wires share the
+// details (and source location) of the op they replace.
+struct Gates<'a> {
+    object: &'a mut Object,
+    ops: Vec<LocatedOpCode>,
+    loc: Option<SourceLocation>,
+    details: WireDetails,
+}
+
+impl Gates<'_> {
+    fn wire(&mut self) -> Wire {
+        self.object.symtab.reg((), self.details.clone())
+    }
+    fn lit(&mut self, value: bool) -> Wire {
+        self.object.symtab.lit(value.into(), self.details.clone())
+    }
+    fn push(&mut self, op: OpCode) {
+        self.ops.push(LocatedOpCode { op, loc: self.loc });
+    }
+    fn assign(&mut self, lhs: Wire, rhs: Wire) {
+        self.push(assign(lhs, rhs));
+    }
+    fn binary(&mut self, op: BinaryOp, arg1: Wire, arg2: Wire) -> Wire {
+        // Fold the constants here, so that the gates do not pile up
+        match (op, self.object.bitx(arg1), self.object.bitx(arg2)) {
+            (BinaryOp::And, Some(BitX::Zero), _) | (BinaryOp::Or, Some(BitX::One), _) => {
+                return arg1;
+            }
+            (BinaryOp::And, _, Some(BitX::Zero)) | (BinaryOp::Or, _, Some(BitX::One)) => {
+                return arg2;
+            }
+            (BinaryOp::And, Some(BitX::One), _)
+            | (BinaryOp::Or | BinaryOp::Xor, Some(BitX::Zero), _) => return arg2,
+            (BinaryOp::And, _, Some(BitX::One))
+            | (BinaryOp::Or | BinaryOp::Xor, _, Some(BitX::Zero)) => return arg1,
+            _ => {}
+        }
+        let lhs = self.wire();
+        self.push(OpCode::Binary(Binary {
+            op,
+            lhs,
+            arg1,
+            arg2,
+        }));
+        lhs
+    }
+    fn and(&mut self, arg1: Wire, arg2: Wire) -> Wire {
+        self.binary(BinaryOp::And, arg1, arg2)
+    }
+    fn or(&mut self, arg1: Wire, arg2: Wire) -> Wire {
+        self.binary(BinaryOp::Or, arg1, arg2)
+    }
+    fn xor(&mut self, arg1: Wire, arg2: Wire) -> Wire {
+        self.binary(BinaryOp::Xor, arg1, arg2)
+    }
+    fn not(&mut self, arg: Wire) -> Wire {
+        match self.object.bitx(arg) {
+            Some(BitX::Zero) => return self.lit(true),
+            Some(BitX::One) => return self.lit(false),
+            _ => {}
+        }
+        let lhs = self.wire();
+        self.push(OpCode::Not(Not { lhs, arg }));
+        lhs
+    }
+    fn mux(&mut self, selector: Wire, true_case: Wire, false_case: Wire) -> Wire {
+        match self.object.bitx(selector) {
+            Some(BitX::One) => return true_case,
+            Some(BitX::Zero) => return false_case,
+            _ => {}
+        }
+        let lhs = self.wire();
+        self.push(OpCode::Select(Select {
+            lhs,
+            selector,
+            true_case,
+            false_case,
+        }));
+        lhs
+    }
+    fn reduce(&mut self, op: BinaryOp, args: &[Wire], empty: bool) -> Wire {
+        match args.split_first() {
+            Some((first, rest)) => rest
+                .iter()
+                .fold(*first, |acc, arg| self.binary(op, acc, *arg)),
+            None => self.lit(empty),
+        }
+    }
+    // Sign or zero extend (or truncate) the vector to the given width
+    fn extend(&mut self, arg: &[Wire], width: usize, signed: bool) -> Vec<Wire> {
+        let fill = match arg.last() {
+            Some(msb) if signed => *msb,
+            _ => self.lit(false),
+        };
+        (0..width)
+            .map(|ndx| arg.get(ndx).copied().unwrap_or(fill))
+            .collect()
+    }
+    // Ripple carry adder, returning the sum and the carry out
+    fn add(&mut self, arg1: &[Wire], arg2: &[Wire], mut carry: Wire) -> Vec<Wire> {
+        arg1.iter()
+            .zip(arg2)
+            .map(|(a, b)| {
+                let half = self.xor(*a, *b);
+                let sum = self.xor(half, carry);
+                let generate = self.and(*a, *b);
+                let propagate = self.and(half, carry);
+                carry = self.or(generate, propagate);
+                sum
+            })
+            .collect()
+    }
+    fn sub(&mut self, arg1: &[Wire], arg2: &[Wire]) -> Vec<Wire> {
+        let inverted = arg2.iter().map(|b| self.not(*b)).collect::<Vec<_>>();
+        let one = self.lit(true);
+        self.add(arg1, &inverted, one)
+    }
+    // The low bits of the product (which are the same for signed and
+    // unsigned arguments extended to the width of the product)
+    fn mul(&mut self, arg1: &[Wire], arg2: &[Wire]) -> Vec<Wire> {
+        let width = arg1.len();
+        let zero = self.lit(false);
+        let mut acc = vec![zero; width];
+        for (shift, b) in arg2.iter().enumerate() {
+            let partial = arg1[..width - shift]
+                .iter()
+                .map(|a| self.and(*a, *b))
+                .collect::<Vec<_>>();
+            let sum = self.add(&acc[shift..], &partial, zero);
+            acc[shift..].copy_from_slice(&sum);
+        }
+        acc
+    }
+    fn eq(&mut self, arg1: &[Wire], arg2: &[Wire]) -> Wire {
+        let diff = arg1
+            .iter()
+            .zip(arg2)
+            .map(|(a, b)| self.xor(*a, *b))
+            .collect::<Vec<_>>();
+        let any = self.reduce(BinaryOp::Or, &diff, false);
+        self.not(any)
+    }
+    // The arguments are extended by one bit, so the sign of the
+    // difference cannot overflow
+    fn lt(&mut self, arg1: &[Wire], arg2: &[Wire], signed: bool) -> Wire {
+        let width = arg1.len().max(arg2.len()) + 1;
+        let arg1 = self.extend(arg1, width, signed);
+        let arg2 = self.extend(arg2, width, signed);
+        self.sub(&arg1, &arg2)[width - 1]
+    }
+    // A barrel shifter, with `fill` shifted in from the end
+    fn shift(&mut self, arg: &[Wire], amount: &[Wire], left: bool, signed: bool) -> Vec<Wire> {
+        let width = arg.len();
+        let mut current = arg.to_vec();
+        for (bit, select) in amount.iter().enumerate() {
+            let fill = match current.last() {
+                Some(msb) if signed && !left => *msb,
+                _ => self.lit(false),
+            };
+            let distance = 1_usize.checked_shl(bit as u32).unwrap_or(usize::MAX);
+            let shifted = (0..width)
+                .map(|ndx| {
+                    let source = if left {
+                        ndx.checked_sub(distance)
+                    } else {
+                        ndx.checked_add(distance).filter(|src| *src < width)
+                    };
+                    source.map(|src| current[src]).unwrap_or(fill)
+                })
+                .collect::<Vec<_>>();
+            current = shifted
+                .into_iter()
+                .zip(current)
+                .map(|(shifted, current)| self.mux(*select, shifted, current))
+                .collect();
+        }
+        current
+    }
+    // A one hot match of the selector against each pattern
+    fn matches(&mut self, selector: &[Wire], pattern: &[BitX]) -> Wire {
+        let bits = selector
+            .iter()
+            .zip(pattern)
+            .filter_map(|(wire, bit)| match bit {
+                BitX::One => Some(*wire),
+                BitX::Zero => Some(self.not(*wire)),
+                BitX::X => None,
+            })
+            .collect::<Vec<_>>();
+        self.reduce(BinaryOp::And, &bits, true)
+    }
+    fn vector(&mut self, op: &spec::Vector) {
+        let width = op.lhs.len();
+        let result = match op.op {
+            VectorOp::Add | VectorOp::Sub | VectorOp::Mul => {
+                let arg1 = self.extend(&op.arg1, width, op.signed);
+                let arg2 = self.extend(&op.arg2, width, op.signed);
+                match op.op {
+                    VectorOp::Add => {
+                        let zero = self.lit(false);
+                        self.add(&arg1, &arg2, zero)
+                    }
+                    VectorOp::Sub => self.sub(&arg1, &arg2),
+                    _ => self.mul(&arg1, &arg2),
+                }
+            }
+            VectorOp::Eq | VectorOp::Ne => {
+                let common = op.arg1.len().max(op.arg2.len());
+                let arg1 = self.extend(&op.arg1, common, op.signed);
+                let arg2 = self.extend(&op.arg2, common, op.signed);
+                let eq = self.eq(&arg1, &arg2);
+                if op.op == VectorOp::Eq {
+                    vec![eq]
+                } else {
+                    vec![self.not(eq)]
+                }
+            }
+            VectorOp::Lt => vec![self.lt(&op.arg1, &op.arg2, op.signed)],
+            VectorOp::Gt => vec![self.lt(&op.arg2, &op.arg1, op.signed)],
+            VectorOp::Ge => {
+                let lt = self.lt(&op.arg1, &op.arg2, op.signed);
+                vec![self.not(lt)]
+            }
+            VectorOp::Le => {
+                let gt = self.lt(&op.arg2, &op.arg1, op.signed);
+                vec![self.not(gt)]
+            }
+            VectorOp::Shl | VectorOp::Shr => {
+                // Shift in a width that holds both the argument and the result
+                let common = width.max(op.arg1.len());
+                let arg = self.extend(&op.arg1, common, op.signed);
+                self.shift(&arg, &op.arg2, op.op == VectorOp::Shl, op.signed)
+            }
+        };
+        self.drive(&op.lhs, result);
+    }
+    fn unary(&mut self, op: &spec::Unary) {
+        let result = match op.op {
+            UnaryOp::All => vec![self.reduce(BinaryOp::And, &op.arg, true)],
+            UnaryOp::Any => vec![self.reduce(BinaryOp::Or, &op.arg, false)],
+            UnaryOp::Xor => vec![self.reduce(BinaryOp::Xor, &op.arg, false)],
+            UnaryOp::Neg => {
+                let width = op.lhs.len();
+                let zero = self.lit(false);
+                let arg = self.extend(&op.arg, width, false);
+                self.sub(&vec![zero; width], &arg)
+            }
+        };
+        self.drive(&op.lhs, result);
+    }
+    fn case(&mut self, op: &spec::Case) {
+        // The first matching entry wins, so each entry is masked by
+        // the ones that came before it
+        let mut terms = vec![];
+        let mut taken = self.lit(false);
+        for (entry, value) in &op.entries {
+            let hit = match entry {
+                CaseEntry::Literal(pattern) => self.matches(&op.discriminant, pattern.bits()),
+                CaseEntry::WildCard => self.lit(true),
+            };
+            let free = self.not(taken);
+            let first = self.and(hit, free);
+            terms.push(self.and(first, *value));
+            taken = self.or(taken, hit);
+        }
+        let result = self.reduce(BinaryOp::Or, &terms, false);
+        self.assign(op.lhs, result);
+    }
+    fn rom(&mut self, op: &spec::Rom) {
+        let hits = (0..op.table.len())
+            .map(|ndx| {
+                let pattern = (0..op.address.len())
+                    .map(|bit| (ndx.checked_shr(bit as u32).unwrap_or(0) & 1 == 1).into())
+                    .collect::<Vec<BitX>>();
+                self.matches(&op.address, &pattern)
+            })
+            .collect::<Vec<_>>();
+        let result = (0..op.lhs.len())
+            .map(|bit| {
+                let set = op
+                    .table
+                    .iter()
+                    .zip(&hits)
+                    .filter(|(value, _)| value.bits().get(bit) == Some(&BitX::One))
+                    .map(|(_, hit)| *hit)
+                    .collect::<Vec<_>>();
+                self.reduce(BinaryOp::Or, &set, false)
+            })
+            .collect();
+        self.drive(&op.lhs, result);
+    }
+    fn drive(&mut self, lhs: &[Wire], result: Vec<Wire>) {
+        let result = self.extend(&result, lhs.len(), false);
+        for (lhs, rhs) in lhs.iter().zip(result) {
+            self.assign(*lhs, rhs);
+        }
+    }
+}
+
+// Lower a single op that drives the given wires.  An op that drives no
+// wires has no effect, and is dropped.
+fn lower(
+    object: &mut Object,
+    loc: Option<SourceLocation>,
+    lhs: &[Wire],
+    build: impl FnOnce(&mut Gates),
+) -> Vec<LocatedOpCode> {
+    let Some(&first) = lhs.first() else {
+        return vec![];
+    };
+    let mut gates = Gates {
+        details: object.symtab[first].clone(),
+        object,
+        ops: vec![],
+        loc,
+    };
+    build(&mut gates);
+    gates.ops
+}
+
+impl NtlPass for LowerToGates {
+    fn description(&self) -> &str {
+        "Lower word level ops to single bit gates"
+    }
+    fn run(&self, mut input: Object) -> Result<Object, RHDLError> {
+        let ops = std::mem::take(&mut input.ops);
+        let mut lowered = Vec::with_capacity(ops.len());
+        for lop in ops {
+            let object = &mut input;
+            let loc = lop.loc;
+            let gates = match &lop.op {
+                OpCode::Vector(vector) => lower(object, loc, &vector.lhs, |g| g.vector(vector)),
+                OpCode::Unary(unary) => lower(object, loc, &unary.lhs, |g| g.unary(unary)),
+                OpCode::Case(case) => lower(object, loc, std::slice::from_ref(&case.lhs), |g| {
+                    g.case(case)
+                }),
+                OpCode::Rom(rom) => lower(object, loc, &rom.lhs, |g| g.rom(rom)),
+                _ => vec![lop],
+            };
+            lowered.extend(gates);
+        }
+        input.ops = lowered;
+        Ok(input)
+    }
+}
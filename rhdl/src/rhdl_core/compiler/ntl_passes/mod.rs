@@ -1,4 +1,5 @@
 pub mod check_for_undriven;
+pub mod common_subexpression_elimination;
 pub mod constant_propagation;
 pub mod constant_reg_elimination;
 pub mod dead_code_elimination;
@@ -6,6 +7,7 @@ pub mod lower_any_all;
 pub mod lower_bitwise_op_with_constant;
 pub mod lower_case;
 pub mod lower_selects;
+pub mod lower_to_gates;
 pub mod pass;
 pub mod remove_extra_literals;
 pub mod remove_extra_registers;
@@ -2,7 +2,7 @@ use log::debug;
 use std::collections::{BTreeMap, HashSet};
 
 use crate::rhdl_core::{
-    Color, Kind,
+    Kind,
     ast::source::source_location::SourceLocation,
     compiler::mir::{
         error::{ClockError, RHDLClockDomainViolation},
@@ -55,15 +55,7 @@ impl ClockDomainContext<'_> {
             .into_iter()
             .map(|ty| {
                 if let Ok(clock) = self.ctx.cast_ty_as_clock(ty) {
-                    match clock {
-                        Color::Red => "Red",
-                        Color::Orange => "Orange",
-                        Color::Yellow => "Yellow",
-                        Color::Green => "Green",
-                        Color::Blue => "Blue",
-                        Color::Indigo => "Indigo",
-                        Color::Violet => "Violet",
-                    }
+                    clock.name()
                 } else {
                     "Unknown"
                 }
@@ -267,7 +259,7 @@ impl ClockDomainContext<'_> {
         for element in path.iter() {
             debug!("Path project {} {:?}", self.ctx.desc(arg), element);
             match element {
-                PathElement::Index(ndx) => {
+                PathElement::Index(ndx) | PathElement::IndexFromEnd(ndx) => {
                     arg = self
                         .ctx
                         .ty_index(arg, *ndx)
@@ -298,6 +290,12 @@ impl ClockDomainContext<'_> {
                     if self.ctx.is_unresolved(slot_ty) || self.ctx.is_unresolved(arg_ty) {
                         return Ok(self.ctx.ty_var(id));
                     }
+                    // Indexing into a literal table yields an unclocked value.  The
+                    // clock domain of the result is set by the index (see the
+                    // handling of OpCode::Index).
+                    if matches!(arg.kind.as_ref(), TypeKind::Const(Const::Unclocked)) {
+                        continue;
+                    }
                     // First, index the argument type to get a base type
                     arg = self.ctx.ty_index(arg, 0)?;
                     let slot_domains = collect_clock_domains(slot_ty);
@@ -343,6 +341,30 @@ impl ClockDomainContext<'_> {
         self.slot_map[slot]
     }
     fn check(&mut self) -> Result<(), RHDLError> {
+        self.unify_ops()?;
+        debug!("*****Clock domain check complete*****");
+        let resolved_map = self
+            .slot_map
+            .clone()
+            .into_iter()
+            .map(|(k, v)| (k, self.ctx.apply(v)))
+            .collect::<Vec<_>>();
+        for ty in &resolved_map {
+            let desc = self.ctx.desc(ty.1);
+            debug!("Slot {:?} has type {:?}", ty.0, desc);
+            if self.ctx.is_unresolved(ty.1) {
+                return Err(self
+                    .raise_clock_domain_error(
+                        self.obj.symbols.fallback(self.obj.fn_id),
+                        &[ty.0],
+                        ClockError::UnresolvedClock,
+                    )
+                    .into());
+            }
+        }
+        Ok(())
+    }
+    fn unify_ops(&mut self) -> Result<(), RHDLError> {
         debug!("Code before clock check: {:?}", self.obj);
         self.import_literals();
         self.import_registers();
@@ -355,7 +377,7 @@ impl ClockDomainContext<'_> {
             }
             match op {
                 OpCode::Binary(binary) => {
-                    if !binary.op.is_comparison() {
+                    if !binary.op.is_comparison() && !binary.op.is_overflowing() {
                         self.unify_clocks(
                             &[binary.arg1, binary.arg2, binary.lhs],
                             loc,
@@ -430,6 +452,16 @@ impl ClockDomainContext<'_> {
                     )?;
                 }
                 OpCode::Index(index) => {
+                    if index.arg.is_lit() {
+                        for slot in index.path.dynamic_slots() {
+                            self.unify_projected_clocks(
+                                index.lhs,
+                                *slot,
+                                loc,
+                                ClockError::IndexClockMismatch,
+                            )?;
+                        }
+                    }
                     let rhs_project = self.ty_path_project(index.arg, &index.path, loc)?;
                     let ty_lhs = self.slot_type(&index.lhs);
                     if self.ctx.unify(rhs_project, ty_lhs).is_err() {
@@ -571,10 +603,28 @@ impl ClockDomainContext<'_> {
                         }
                     }
                 }
+                OpCode::Rom(rom) => {
+                    self.unify_projected_clocks(
+                        rom.address,
+                        rom.lhs,
+                        loc,
+                        ClockError::IndexClockMismatch,
+                    )?;
+                }
                 OpCode::Exec(exec) => {
+                    // The called kernel is checked in this context, with its own
+                    // registers, so that the clock domains of its arguments and
+                    // return value are tied to the ones at this call site.
                     let sub = &self.obj.externals[&exec.id];
-                    let ret_ty =
-                        self.import_kind_with_unknown_domains(loc, &sub.kind(sub.return_slot));
+                    let mut callee = ClockDomainContext {
+                        obj: sub,
+                        ctx: std::mem::take(&mut self.ctx),
+                        slot_map: BTreeMap::new(),
+                    };
+                    let result = callee.unify_ops();
+                    self.ctx = callee.ctx;
+                    result?;
+                    let ret_ty = callee.slot_map[&sub.return_slot];
                     let lhs_ty = self.slot_type(&exec.lhs);
                     if self.ctx.unify(ret_ty, lhs_ty).is_err() {
                         return Err(self
@@ -585,13 +635,8 @@ impl ClockDomainContext<'_> {
                             )
                             .into());
                     }
-                    for (kind, slot) in sub
-                        .arguments
-                        .iter()
-                        .map(|x| sub.kind(Slot::Register(*x)))
-                        .zip(exec.args.iter())
-                    {
-                        let arg_ty = self.import_kind_with_unknown_domains(loc, &kind);
+                    for (arg, slot) in sub.arguments.iter().zip(exec.args.iter()) {
+                        let arg_ty = callee.slot_map[&Slot::Register(*arg)];
                         let slot_ty = self.slot_type(slot);
                         if self.ctx.unify(arg_ty, slot_ty).is_err() {
                             return Err(self
@@ -617,26 +662,6 @@ impl ClockDomainContext<'_> {
                 OpCode::Comment(_) | OpCode::Noop => {}
             }
         }
-        debug!("*****Clock domain check complete*****");
-        let resolved_map = self
-            .slot_map
-            .clone()
-            .into_iter()
-            .map(|(k, v)| (k, self.ctx.apply(v)))
-            .collect::<Vec<_>>();
-        for ty in &resolved_map {
-            let desc = self.ctx.desc(ty.1);
-            debug!("Slot {:?} has type {:?}", ty.0, desc);
-            if self.ctx.is_unresolved(ty.1) {
-                return Err(self
-                    .raise_clock_domain_error(
-                        self.obj.symbols.fallback(self.obj.fn_id),
-                        &[ty.0],
-                        ClockError::UnresolvedClock,
-                    )
-                    .into());
-            }
-        }
         Ok(())
     }
 }
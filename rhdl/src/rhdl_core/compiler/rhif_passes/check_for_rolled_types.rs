@@ -57,9 +57,14 @@ impl Pass for CheckForRolledTypesPass {
                         AluBinary::Add
                         | AluBinary::Sub
                         | AluBinary::Mul
+                        | AluBinary::Div
+                        | AluBinary::Rem
                         | AluBinary::BitAnd
                         | AluBinary::BitOr
-                        | AluBinary::BitXor,
+                        | AluBinary::BitXor
+                        | AluBinary::SaturatingAdd
+                        | AluBinary::SaturatingSub
+                        | AluBinary::SaturatingMul,
                     lhs,
                     arg1,
                     arg2,
@@ -75,6 +80,22 @@ impl Pass for CheckForRolledTypesPass {
                         loc,
                     )?;
                 }
+                OpCode::Binary(Binary {
+                    op:
+                        AluBinary::OverflowingAdd
+                        | AluBinary::OverflowingSub
+                        | AluBinary::OverflowingMul,
+                    lhs: _,
+                    arg1,
+                    arg2,
+                }) => {
+                    check_register_like(
+                        &obj,
+                        &[(*arg1, slot_type(arg1)), (*arg2, slot_type(arg2))],
+                        Syntax::RollYourOwnBinary,
+                        loc,
+                    )?;
+                }
                 OpCode::Unary(Unary {
                     op: AluUnary::Val,
                     lhs: _,
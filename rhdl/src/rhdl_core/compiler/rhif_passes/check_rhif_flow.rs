@@ -7,8 +7,8 @@ use crate::rhdl_core::{
     rhif::{
         Object,
         spec::{
-            Array, Assign, Binary, Case, Cast, Enum, Exec, Index, OpCode, Repeat, Retime, Select,
-            Slot, Splice, Struct, Tuple, Unary, Wrap,
+            Array, Assign, Binary, Case, Cast, Enum, Exec, Index, OpCode, Repeat, Retime, Rom,
+            Select, Slot, Splice, Struct, Tuple, Unary, Wrap,
         },
     },
 };
@@ -190,6 +190,14 @@ fn check_flow<'a>(obj: &'a Object, mut init_set: InitSet<'a>) -> Result<InitSet<
                 }
                 init_set.write(lhs)?;
             }
+            OpCode::Rom(Rom {
+                lhs,
+                address,
+                table: _,
+            }) => {
+                init_set.read(address)?;
+                init_set.write(lhs)?;
+            }
             OpCode::Exec(Exec { lhs, id: _, args }) => {
                 init_set.read_all(args)?;
                 init_set.write(lhs)?;
@@ -10,7 +10,7 @@ use crate::rhdl_core::{
         self, Object,
         spec::{
             AluBinary, AluUnary, Array, Assign, Binary, Case, CaseArgument, Cast, Enum, Exec,
-            Index, OpCode, Repeat, Retime, Select, Slot, Splice, Struct, Tuple, Unary, Wrap,
+            Index, OpCode, Repeat, Retime, Rom, Select, Slot, Splice, Struct, Tuple, Unary, Wrap,
         },
     },
     types::path::{Path, PathElement, sub_kind},
@@ -143,7 +143,12 @@ fn check_type_correctness(obj: &Object) -> Result<(), RHDLError> {
                     | AluBinary::BitAnd
                     | AluBinary::BitOr
                     | AluBinary::Mul
-                    | AluBinary::BitXor,
+                    | AluBinary::Div
+                    | AluBinary::Rem
+                    | AluBinary::BitXor
+                    | AluBinary::SaturatingAdd
+                    | AluBinary::SaturatingSub
+                    | AluBinary::SaturatingMul,
                 lhs,
                 arg1,
                 arg2,
@@ -185,7 +190,19 @@ fn check_type_correctness(obj: &Object) -> Result<(), RHDLError> {
                 eq_kinds(slot_type(lhs), result_ty, loc)?;
             }
             OpCode::Binary(Binary {
-                op: AluBinary::Shl | AluBinary::Shr,
+                op:
+                    AluBinary::OverflowingAdd | AluBinary::OverflowingSub | AluBinary::OverflowingMul,
+                lhs,
+                arg1,
+                arg2,
+            }) => {
+                eq_kinds(slot_type(arg1), slot_type(arg2), loc)?;
+                let result_ty =
+                    Kind::make_tuple(vec![slot_type(arg1).signal_data(), Kind::make_bool()]);
+                eq_kinds(slot_type(lhs), result_ty, loc)?;
+            }
+            OpCode::Binary(Binary {
+                op: AluBinary::Shl | AluBinary::Shr | AluBinary::RotateLeft | AluBinary::RotateRight,
                 lhs,
                 arg1,
                 arg2,
@@ -220,7 +237,15 @@ fn check_type_correctness(obj: &Object) -> Result<(), RHDLError> {
             // them for their own types.  So we need to check that they are only
             // applied to base types.
             OpCode::Unary(Unary {
-                op: AluUnary::Not | AluUnary::Neg | AluUnary::Val,
+                op:
+                    AluUnary::Not
+                    | AluUnary::Neg
+                    | AluUnary::Val
+                    | AluUnary::CountOnes
+                    | AluUnary::LeadingZeros
+                    | AluUnary::TrailingZeros
+                    | AluUnary::ReverseBits
+                    | AluUnary::SwapBytes,
                 lhs,
                 arg1,
             }) => {
@@ -255,6 +280,17 @@ fn check_type_correctness(obj: &Object) -> Result<(), RHDLError> {
                     loc,
                 )?;
             }
+            OpCode::Unary(Unary {
+                op: AluUnary::Quantize(from, to, _) | AluUnary::Saturate(from, to),
+                lhs,
+                arg1,
+            }) => {
+                eq_kinds(
+                    slot_type(lhs),
+                    xops_kind(obj, loc, slot_type(arg1), *to as isize - *from as isize)?,
+                    loc,
+                )?;
+            }
             OpCode::Unary(Unary {
                 op: AluUnary::XNeg,
                 lhs,
@@ -444,6 +480,22 @@ fn check_type_correctness(obj: &Object) -> Result<(), RHDLError> {
                     }
                 }
             }
+            OpCode::Rom(Rom {
+                lhs,
+                address,
+                table,
+            }) => {
+                for value in table {
+                    eq_kinds(slot_type(lhs), value.kind, loc)?;
+                }
+                if !slot_type(address).signal_data().is_unsigned() {
+                    return Err(TypeCheckPass::raise_ice(
+                        obj,
+                        ICE::IndexValueMustBeUnsigned,
+                        obj.symtab[address].location,
+                    ));
+                }
+            }
             OpCode::Exec(Exec {
                 lhs,
                 id: func_id,
@@ -7,7 +7,7 @@ use crate::rhdl_core::{
         runtime_ops::{array, binary, tuple, unary},
         spec::{
             Array, Assign, Binary, Case, CaseArgument, Cast, Enum, Exec, Index, OpCode, Repeat,
-            Select, Slot, Splice, Struct, Tuple, Unary, Wrap,
+            Rom, Select, Slot, Splice, Struct, Tuple, Unary, Wrap,
         },
         vm::execute,
     },
@@ -280,6 +280,43 @@ fn propagate_index(
     }
 }
 
+fn propagate_rom(
+    loc: SourceLocation,
+    params: Rom,
+    obj: &mut Object,
+) -> Result<LocatedOpCode, RHDLError> {
+    let Rom {
+        lhs,
+        address,
+        table,
+    } = &params;
+    let rhs = if let Slot::Literal(address_lit) = address {
+        // Addresses past the end of the table are don't care, just like an
+        // unmatched case.
+        obj.symtab[address_lit]
+            .as_i64()
+            .ok()
+            .and_then(|address| usize::try_from(address).ok())
+            .and_then(|address| table.get(address))
+            .cloned()
+            .unwrap_or_else(|| TypedBits::dont_care_from_kind(obj.kind(*lhs)))
+    } else if !table.is_empty() && table.iter().all(|value| value == &table[0]) {
+        table[0].clone()
+    } else {
+        return Ok(LocatedOpCode {
+            op: OpCode::Rom(params),
+            loc,
+        });
+    };
+    Ok(LocatedOpCode {
+        op: OpCode::Assign(Assign {
+            lhs: *lhs,
+            rhs: assign_literal(loc, rhs, obj),
+        }),
+        loc,
+    })
+}
+
 fn propagate_splice(
     loc: SourceLocation,
     params: Splice,
@@ -524,6 +561,7 @@ impl Pass for ConstantPropagation {
                 OpCode::Struct(strukt) => propagate_struct(lop.loc, strukt, &mut input),
                 OpCode::Enum(enumerate) => propagate_enum(lop.loc, enumerate, &mut input),
                 OpCode::Case(case) => propagate_case(lop.loc, case, &mut input),
+                OpCode::Rom(rom) => propagate_rom(lop.loc, rom, &mut input),
                 OpCode::Exec(exec) => propagate_exec(lop.loc, exec, &mut input),
                 OpCode::Wrap(wrap) => propagate_wrap(lop.loc, wrap, &mut input),
                 OpCode::Assign(_) | OpCode::Noop | OpCode::Comment(_) | OpCode::Retime(_) => {
@@ -51,6 +51,9 @@ impl Pass for DeadCodeEliminationPass {
                 OpCode::Case(case) => {
                     mark_active(&case.lhs);
                 }
+                OpCode::Rom(rom) => {
+                    mark_active(&rom.lhs);
+                }
                 OpCode::Enum(enumerate) => {
                     mark_active(&enumerate.lhs);
                 }
@@ -0,0 +1,95 @@
+use crate::rhdl_core::{
+    Kind, TypedBits,
+    error::RHDLError,
+    rhif::{
+        Object,
+        object::LocatedOpCode,
+        spec::{Index, OpCode, Rom, Slot},
+    },
+    types::path::{Path, PathElement, sub_kind},
+};
+
+use super::pass::Pass;
+
+// A dynamic index into a literal (like a `const` table) is
+// turned into a ROM, instead of a case statement on the index
+// with one arm per element.  Only paths with a single dynamic
+// index are lowered.
+pub struct LowerLiteralIndexToRom {}
+
+fn rom_table(
+    value: &TypedBits,
+    path: &Path,
+    address: Slot,
+    obj: &Object,
+) -> Option<Vec<TypedBits>> {
+    let ndx = path
+        .iter()
+        .position(|element| matches!(element, PathElement::DynamicIndex(_)))?;
+    let prefix = path.iter().take(ndx).copied().collect::<Path>();
+    let Ok(Kind::Array(array)) = sub_kind(value.kind, &prefix) else {
+        return None;
+    };
+    let address_bits = obj.kind(address).bits();
+    let size = if address_bits < usize::BITS as usize {
+        array.size.min(1 << address_bits)
+    } else {
+        array.size
+    };
+    (0..size)
+        .map(|element| {
+            let path = path
+                .iter()
+                .enumerate()
+                .map(|(pos, x)| {
+                    if pos == ndx {
+                        PathElement::Index(element)
+                    } else {
+                        *x
+                    }
+                })
+                .collect::<Path>();
+            value.path(&path).ok()
+        })
+        .collect()
+}
+
+fn lower_index(index: Index, obj: &Object) -> OpCode {
+    let Index { lhs, arg, path } = &index;
+    let dynamic_slots = path.dynamic_slots().copied().collect::<Vec<_>>();
+    let (Slot::Literal(lit), [address @ Slot::Register(_)]) = (arg, dynamic_slots.as_slice())
+    else {
+        return OpCode::Index(index);
+    };
+    if obj.kind(*lhs).is_empty() {
+        return OpCode::Index(index);
+    }
+    let Some(table) = rom_table(&obj.symtab[lit], path, *address, obj) else {
+        return OpCode::Index(index);
+    };
+    OpCode::Rom(Rom {
+        lhs: *lhs,
+        address: *address,
+        table,
+    })
+}
+
+impl Pass for LowerLiteralIndexToRom {
+    fn description() -> &'static str {
+        "Lower dynamic indices into literals to ROMs"
+    }
+    fn run(mut input: Object) -> Result<Object, RHDLError> {
+        let ops = std::mem::take(&mut input.ops);
+        input.ops = ops
+            .into_iter()
+            .map(|lop| match lop.op {
+                OpCode::Index(index) => LocatedOpCode {
+                    op: lower_index(index, &input),
+                    loc: lop.loc,
+                },
+                _ => lop,
+            })
+            .collect();
+        Ok(input)
+    }
+}
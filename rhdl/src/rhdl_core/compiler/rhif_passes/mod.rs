@@ -8,6 +8,7 @@ pub(crate) mod lower_dynamic_indices_with_constant_arguments;
 pub(crate) mod lower_index_to_copy;
 pub(crate) mod lower_inferred_casts;
 pub(crate) mod lower_inferred_retimes;
+pub(crate) mod lower_literal_index_to_rom;
 pub(crate) mod partial_initialization_check;
 pub(crate) mod pass;
 pub(crate) mod pre_cast_literals;
@@ -264,6 +264,16 @@ fn check_for_partial_initialization(map: &mut CoverageMap) -> Result<(), RHDLErr
                 }
                 map.cover(case.lhs, lhs_cover);
             }
+            OpCode::Rom(rom) => {
+                map.ensure_covered(rom.address, loc)?;
+                let lhs_kind = obj.kind(rom.lhs);
+                let mut lhs_cover =
+                    std::iter::repeat_n(true, lhs_kind.bits()).collect::<Vec<bool>>();
+                for value in &rom.table {
+                    merge_cover(&mut lhs_cover, &typed_bit_cover(value), weak_mode);
+                }
+                map.cover(rom.lhs, lhs_cover);
+            }
             OpCode::Enum(inner) => {
                 inner
                     .fields
@@ -0,0 +1,33 @@
+use crate::rhdl_core::{
+    RHDLError,
+    compiler::mir::error::Syntax,
+    rtl::{
+        Object,
+        spec::{AluBinary, OpCode},
+    },
+};
+
+use super::pass::Pass;
+
+#[derive(Default, Debug, Clone)]
+pub struct CheckDivisorsAreConstant {}
+
+impl Pass for CheckDivisorsAreConstant {
+    fn run(input: Object) -> Result<Object, RHDLError> {
+        for op in &input.ops {
+            if let OpCode::Binary(binary) = &op.op
+                && matches!(binary.op, AluBinary::Div | AluBinary::Rem)
+            {
+                return Err(Self::raise_syntax_error(
+                    &input,
+                    Syntax::DivisionByNonConstant,
+                    op.loc,
+                ));
+            }
+        }
+        Ok(input)
+    }
+    fn description() -> &'static str {
+        "Check that all divisions are by a constant"
+    }
+}
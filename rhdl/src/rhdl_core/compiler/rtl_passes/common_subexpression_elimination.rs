@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use crate::rhdl_core::{
+    Kind, RHDLError, TypedBits,
+    common::{
+        sense::Sense,
+        slot_vec::SlotKey,
+        symtab::{LiteralId, RegisterId},
+    },
+    rtl::{
+        Object,
+        spec::{AluBinary, Assign, Binary, Index, OpCode, Operand, OperandKind, Splice},
+        visit::visit_operands_mut,
+    },
+    types::path::Path,
+};
+
+use super::pass::Pass;
+
+#[derive(Default, Debug, Clone)]
+pub struct CommonSubexpressionElimination {}
+
+// The op codes and literals only implement PartialEq, but none of
+// them contain anything (like a float) that is not equal to itself.
+#[derive(PartialEq, Hash)]
+struct Exact<T>(T);
+
+impl<T: PartialEq> Eq for Exact<T> {}
+
+type Key = (Kind, Exact<OpCode>);
+
+// Swap the arguments of commutative operators into a canonical order,
+// and rewrite > and >= as < and <= so that `a > b` matches `b < a`.
+fn normalize(binary: &mut Binary) {
+    match binary.op {
+        AluBinary::Add
+        | AluBinary::Mul
+        | AluBinary::BitXor
+        | AluBinary::BitAnd
+        | AluBinary::BitOr
+        | AluBinary::Eq
+        | AluBinary::Ne
+            if binary.arg2 < binary.arg1 =>
+        {
+            std::mem::swap(&mut binary.arg1, &mut binary.arg2);
+        }
+        AluBinary::Gt => {
+            binary.op = AluBinary::Lt;
+            std::mem::swap(&mut binary.arg1, &mut binary.arg2);
+        }
+        AluBinary::Ge => {
+            binary.op = AluBinary::Le;
+            std::mem::swap(&mut binary.arg1, &mut binary.arg2);
+        }
+        _ => {}
+    }
+}
+
+// The key for an op is the op with its output replaced by a placeholder,
+// each literal replaced by the first literal with the same value, and
+// the (purely descriptive) paths removed.  The kind of the output is
+// included so that ops producing different types are never merged.
+fn cse_key(
+    input: &Object,
+    literals: &HashMap<LiteralId<OperandKind>, LiteralId<OperandKind>>,
+    op: &OpCode,
+) -> Option<(Operand, Key)> {
+    let mut op = op.clone();
+    let lhs = match &mut op {
+        OpCode::Binary(binary) => binary.lhs,
+        OpCode::Index(Index { lhs, path, .. }) | OpCode::Splice(Splice { lhs, path, .. }) => {
+            *path = Path::default();
+            *lhs
+        }
+        OpCode::Unary(unary) => unary.lhs,
+        OpCode::Case(case) => case.lhs,
+        OpCode::Rom(rom) => rom.lhs,
+        OpCode::Cast(cast) => cast.lhs,
+        OpCode::Concat(concat) => concat.lhs,
+        OpCode::Select(select) => select.lhs,
+        OpCode::Noop | OpCode::Assign(_) | OpCode::Comment(_) => return None,
+    };
+    let kind = input.kind(lhs);
+    visit_operands_mut(&mut op, |sense, operand| match sense {
+        Sense::Write => *operand = Operand::Register(RegisterId::new(0, 0)),
+        Sense::Read => {
+            if let Operand::Literal(lid) = operand {
+                *lid = literals[lid];
+            }
+        }
+    });
+    if let OpCode::Binary(binary) = &mut op {
+        normalize(binary);
+    }
+    Some((lhs, (kind, Exact(op))))
+}
+
+impl Pass for CommonSubexpressionElimination {
+    fn run(mut input: Object) -> Result<Object, RHDLError> {
+        let mut first_literal: HashMap<Exact<TypedBits>, LiteralId<OperandKind>> =
+            HashMap::default();
+        let literals = input
+            .symtab
+            .iter_lit()
+            .map(|(lid, (value, _))| {
+                let first = *first_literal.entry(Exact(value.clone())).or_insert(lid);
+                (lid, first)
+            })
+            .collect::<HashMap<_, _>>();
+        let mut seen: HashMap<Key, Operand> = HashMap::default();
+        let mut replacements = vec![];
+        for (ndx, lop) in input.ops.iter().enumerate() {
+            let Some((lhs, key)) = cse_key(&input, &literals, &lop.op) else {
+                continue;
+            };
+            match seen.get(&key) {
+                Some(rhs) => replacements.push((ndx, lhs, *rhs)),
+                None => {
+                    seen.insert(key, lhs);
+                }
+            }
+        }
+        // The duplicates become copies of the first result, which are
+        // then removed by the RemoveExtraRegisters pass.
+        for (ndx, lhs, rhs) in replacements {
+            input.ops[ndx].op = OpCode::Assign(Assign { lhs, rhs });
+        }
+        Ok(input)
+    }
+    fn description() -> &'static str {
+        "Replace repeated computations with a copy of the first result"
+    }
+}
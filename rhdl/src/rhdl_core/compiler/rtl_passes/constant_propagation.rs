@@ -9,9 +9,10 @@ use crate::rhdl_core::{
         object::LocatedOpCode,
         spec::{
             Assign, Binary, Case, CaseArgument, Cast, CastKind, Concat, Index, OpCode, Operand,
-            Select, Splice, Unary,
+            Rom, Select, Splice, Unary,
         },
     },
+    types::bit_string::BitString,
 };
 
 use super::pass::Pass;
@@ -137,6 +138,35 @@ fn propagate_case(
     }
 }
 
+fn propagate_rom(
+    loc: SourceLocation,
+    rom: Rom,
+    obj: &mut Object,
+) -> Result<LocatedOpCode, RHDLError> {
+    let Rom {
+        lhs,
+        address,
+        table,
+    } = &rom;
+    let Operand::Literal(address) = address else {
+        return Ok(LocatedOpCode {
+            op: OpCode::Rom(rom),
+            loc,
+        });
+    };
+    let value = BitString::from(&obj.symtab[address])
+        .as_address()
+        .and_then(|address| table.get(address))
+        .cloned()
+        .unwrap_or_else(|| BitString::dont_care_from_kind(obj.kind(*lhs)));
+    let details = obj.symtab[lhs].clone();
+    let rhs = obj.symtab.lit(TypedBits::from(value), details);
+    Ok(LocatedOpCode {
+        op: OpCode::Assign(Assign { lhs: *lhs, rhs }),
+        loc,
+    })
+}
+
 fn propagate_cast(
     loc: SourceLocation,
     cast: crate::rhdl_core::rtl::spec::Cast,
@@ -289,6 +319,7 @@ impl Pass for ConstantPropagationPass {
                 OpCode::Unary(inner) => propagate_unary(lop.loc, inner, &mut input),
                 OpCode::Concat(inner) => propagate_concat(lop.loc, inner, &mut input),
                 OpCode::Case(inner) => propagate_case(lop.loc, inner, &mut input),
+                OpCode::Rom(inner) => propagate_rom(lop.loc, inner, &mut input),
                 OpCode::Cast(inner) => propagate_cast(lop.loc, inner, &mut input),
                 OpCode::Select(inner) => propagate_select(lop.loc, inner, &mut input),
                 OpCode::Splice(inner) => propagate_splice(lop.loc, inner, &mut input),
@@ -42,6 +42,9 @@ impl Pass for DeadCodeEliminationPass {
                 OpCode::Case(case) => {
                     mark_active(&case.lhs);
                 }
+                OpCode::Rom(rom) => {
+                    mark_active(&rom.lhs);
+                }
                 OpCode::Cast(cast) => {
                     mark_active(&cast.lhs);
                 }
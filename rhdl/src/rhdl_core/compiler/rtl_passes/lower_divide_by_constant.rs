@@ -0,0 +1,253 @@
+use std::ops::Range;
+
+use num_bigint::{BigInt, BigUint};
+
+use crate::{
+    prelude::{Kind, Path},
+    rhdl_core::{
+        RHDLError, TypedBits,
+        ast::source::source_location::SourceLocation,
+        bitx::dyn_bit_manip::{from_biguint, to_bigint, to_biguint},
+        compiler::mir::error::Syntax,
+        rhif::object::SourceDetails,
+        rtl::{
+            Object,
+            object::LocatedOpCode,
+            spec::{
+                AluBinary, AluUnary, Assign, Binary, Cast, CastKind, Index, OpCode, Operand,
+                Select, Unary,
+            },
+        },
+    },
+};
+
+use super::pass::Pass;
+
+// Returns the multiplier `m` and shift `s` such that `(x * m) >> s == x / d`
+// for every `len` bit unsigned `x`.  This is the round-up method of Granlund
+// and Montgomery, and requires that `d` not be a power of two.  The
+// multiplier fits in `len + 1` bits.
+fn magic_multiplier(d: &BigUint, len: usize) -> (BigUint, usize) {
+    let log2_d = (d - 1u32).bits() as usize;
+    let shift = len + log2_d;
+    let multiplier = (BigUint::from(1u32) << shift) / d + 1u32;
+    (multiplier, shift)
+}
+
+struct Lowering<'a> {
+    input: &'a mut Object,
+    details: SourceDetails,
+    loc: SourceLocation,
+}
+
+impl Lowering<'_> {
+    fn reg(&mut self, kind: Kind) -> Operand {
+        self.input.symtab.reg(kind, self.details.clone())
+    }
+    fn lit(&mut self, value: &BigUint, len: usize) -> Operand {
+        let bits = TypedBits {
+            bits: from_biguint(value, len),
+            kind: Kind::Bits(len),
+        };
+        self.input.symtab.lit(bits, self.details.clone())
+    }
+    fn push(&mut self, op: OpCode) {
+        self.input.ops.push(LocatedOpCode { op, loc: self.loc });
+    }
+    fn binary(&mut self, op: AluBinary, kind: Kind, arg1: Operand, arg2: Operand) -> Operand {
+        let lhs = self.reg(kind);
+        self.push(OpCode::Binary(Binary {
+            op,
+            lhs,
+            arg1,
+            arg2,
+        }));
+        lhs
+    }
+    fn unary(&mut self, op: AluUnary, kind: Kind, arg1: Operand) -> Operand {
+        let lhs = self.reg(kind);
+        self.push(OpCode::Unary(Unary { op, lhs, arg1 }));
+        lhs
+    }
+    fn resize(&mut self, arg: Operand, len: usize) -> Operand {
+        let lhs = self.reg(Kind::Bits(len));
+        self.push(OpCode::Cast(Cast {
+            lhs,
+            arg,
+            len,
+            kind: CastKind::Resize,
+        }));
+        lhs
+    }
+    fn index(&mut self, arg: Operand, bit_range: Range<usize>) -> Operand {
+        let kind = if bit_range.len() == 1 {
+            Kind::make_bool()
+        } else {
+            Kind::Bits(bit_range.len())
+        };
+        let lhs = self.reg(kind);
+        self.push(OpCode::Index(Index {
+            lhs,
+            arg,
+            bit_range,
+            path: Path::default(),
+        }));
+        lhs
+    }
+    fn select(
+        &mut self,
+        kind: Kind,
+        cond: Operand,
+        true_value: Operand,
+        false_value: Operand,
+    ) -> Operand {
+        let lhs = self.reg(kind);
+        self.push(OpCode::Select(Select {
+            lhs,
+            cond,
+            true_value,
+            false_value,
+        }));
+        lhs
+    }
+    // Divide the `len` bit unsigned value `x` by the constant `d`, and return
+    // either the quotient or the remainder.
+    fn unsigned_divide(&mut self, x: Operand, d: &BigUint, len: usize, remainder: bool) -> Operand {
+        if d.count_ones() == 1 {
+            let k = d.trailing_zeros().unwrap_or_default() as usize;
+            return match (remainder, k) {
+                (false, 0) => x,
+                (true, 0) => self.lit(&BigUint::ZERO, len),
+                (false, k) => {
+                    let quotient = self.index(x, k..len);
+                    self.resize(quotient, len)
+                }
+                (true, k) => {
+                    let remainder = self.index(x, 0..k);
+                    self.resize(remainder, len)
+                }
+            };
+        }
+        let (multiplier, shift) = magic_multiplier(d, len);
+        let width = 2 * len + 1;
+        let x_wide = self.resize(x, width);
+        let multiplier = self.lit(&multiplier, width);
+        let product = self.binary(AluBinary::Mul, Kind::Bits(width), x_wide, multiplier);
+        let quotient = self.index(product, shift..width);
+        let quotient = self.resize(quotient, len);
+        if !remainder {
+            return quotient;
+        }
+        let d = self.lit(d, len);
+        let multiple = self.binary(AluBinary::Mul, Kind::Bits(len), quotient, d);
+        self.binary(AluBinary::Sub, Kind::Bits(len), x, multiple)
+    }
+    // Divide the `len` bit signed value `x` by the constant `d`, truncating
+    // towards zero.  The division is done on the magnitudes, and the signs
+    // fixed up afterwards.
+    fn signed_divide(&mut self, x: Operand, d: &BigInt, len: usize, remainder: bool) -> Operand {
+        let kind = Kind::Signed(len);
+        let x_negative = self.index(x, len - 1..len);
+        let x_negated = self.unary(AluUnary::Neg, kind, x);
+        let x_abs = self.select(kind, x_negative, x_negated, x);
+        let x_abs = self.unary(AluUnary::Unsigned, Kind::Bits(len), x_abs);
+        let magnitude = self.unsigned_divide(x_abs, d.magnitude(), len, remainder);
+        let result = self.unary(AluUnary::Signed, kind, magnitude);
+        let result_negated = self.unary(AluUnary::Neg, kind, result);
+        // The remainder takes the sign of the dividend, while the quotient
+        // is negative when exactly one of the dividend and divisor is.
+        if !remainder && d.sign() == num_bigint::Sign::Minus {
+            self.select(kind, x_negative, result, result_negated)
+        } else {
+            self.select(kind, x_negative, result_negated, result)
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct LowerDivideByConstant {}
+
+impl LowerDivideByConstant {
+    fn constant_divisor(input: &Object, divisor: Operand) -> Option<BigInt> {
+        let Operand::Literal(lit) = divisor else {
+            return None;
+        };
+        let divisor = &input.symtab[&lit];
+        if divisor.kind.is_signed() {
+            to_bigint(&divisor.bits)
+        } else {
+            to_biguint(&divisor.bits).map(BigInt::from)
+        }
+    }
+}
+
+impl Pass for LowerDivideByConstant {
+    fn run(mut input: Object) -> Result<Object, RHDLError> {
+        let ops = std::mem::take(&mut input.ops);
+        for lop in ops {
+            if let OpCode::Binary(binary) = &lop.op
+                && matches!(binary.op, AluBinary::Div | AluBinary::Rem)
+                && let Some(divisor) = Self::constant_divisor(&input, binary.arg2)
+            {
+                if divisor == BigInt::ZERO {
+                    return Err(Self::raise_syntax_error(
+                        &input,
+                        Syntax::DivisionByZero,
+                        lop.loc,
+                    ));
+                }
+                let kind = input.kind(binary.lhs);
+                let remainder = binary.op == AluBinary::Rem;
+                let mut lowering = Lowering {
+                    details: input.symtab[binary.lhs].clone(),
+                    input: &mut input,
+                    loc: lop.loc,
+                };
+                let result = if kind.is_signed() {
+                    lowering.signed_divide(binary.arg1, &divisor, kind.bits(), remainder)
+                } else {
+                    lowering.unsigned_divide(
+                        binary.arg1,
+                        divisor.magnitude(),
+                        kind.bits(),
+                        remainder,
+                    )
+                };
+                lowering.push(OpCode::Assign(Assign {
+                    lhs: binary.lhs,
+                    rhs: result,
+                }));
+                continue;
+            }
+            input.ops.push(lop);
+        }
+        Ok(input)
+    }
+    fn description() -> &'static str {
+        "Lower division by a constant to a multiply and shift"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::magic_multiplier;
+
+    #[test]
+    fn test_magic_multiplier_is_exact() {
+        for len in 1..=10 {
+            for d in 3..(1u64 << len) {
+                if d.is_power_of_two() {
+                    continue;
+                }
+                let (multiplier, shift) = magic_multiplier(&BigUint::from(d), len);
+                assert!(multiplier.bits() as usize <= len + 1);
+                for x in 0..(1u64 << len) {
+                    let quotient: BigUint = (BigUint::from(x) * &multiplier) >> shift;
+                    assert_eq!(quotient, BigUint::from(x / d), "{x} / {d} with {len} bits");
+                }
+            }
+        }
+    }
+}
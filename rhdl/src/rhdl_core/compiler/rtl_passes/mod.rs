@@ -1,6 +1,9 @@
+pub(crate) mod check_divisors_are_constant;
 pub(crate) mod check_no_zero_resize;
+pub(crate) mod common_subexpression_elimination;
 pub(crate) mod constant_propagation;
 pub(crate) mod dead_code_elimination;
+pub(crate) mod lower_divide_by_constant;
 pub(crate) mod lower_empty_splice_to_copy;
 pub(crate) mod lower_index_all_to_copy;
 pub(crate) mod lower_multiply_to_shift;
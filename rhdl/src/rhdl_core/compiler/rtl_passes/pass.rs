@@ -1,7 +1,7 @@
 use crate::rhdl_core::{
     RHDLError,
     ast::source::source_location::SourceLocation,
-    compiler::mir::error::{ICE, RHDLCompileError},
+    compiler::mir::error::{ICE, RHDLCompileError, RHDLSyntaxError, Syntax},
     error::rhdl_error,
     rtl::Object,
 };
@@ -15,6 +15,14 @@ pub trait Pass {
             err_span: symbols.span(loc).into(),
         })
     }
+    fn raise_syntax_error(obj: &Object, cause: Syntax, loc: SourceLocation) -> RHDLError {
+        let symbols = &obj.symbols;
+        rhdl_error(RHDLSyntaxError {
+            cause,
+            src: symbols.source(),
+            err_span: symbols.span(loc).into(),
+        })
+    }
     fn run(input: Object) -> Result<Object, RHDLError>;
     fn description() -> &'static str;
 }
@@ -28,9 +28,14 @@ impl Pass for RemoveExtraRegistersPass {
             .collect();
         let inv_map: HashMap<EnaKey, RegisterId<_>> =
             reg_map.iter().map(|(&reg, &key)| (key, reg)).collect();
-        // Loop over the assignment op codes, and union the arguments in the table
+        // Loop over the assignment op codes, and union the arguments in the table.
+        // Registers that differ in signedness (e.g., a signed field of a struct)
+        // cannot be merged, since the copy is also a change of type.
         for lop in &input.ops {
             if let OpCode::Assign(Assign { lhs, rhs }) = &lop.op {
+                if input.kind(*lhs).is_signed() != input.kind(*rhs).is_signed() {
+                    continue;
+                }
                 if let (Some(lhs_reg), Some(rhs_reg)) = (lhs.reg(), rhs.reg()) {
                     let lhs_key = reg_map[&lhs_reg];
                     let rhs_key = reg_map[&rhs_reg];
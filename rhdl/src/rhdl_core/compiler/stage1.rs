@@ -2,6 +2,8 @@ use log::info;
 
 use crate::rhdl_core::{
     compiler::{
+        dump::{PassDump, pass_name},
+        lints::{LintSites, check_circuit_output, check_circuit_state, check_kernel},
         mir::{compiler::compile_mir, infer::infer},
         rhif_passes::{
             check_clock_domain::CheckClockDomain, check_for_rolled_types::CheckForRolledTypesPass,
@@ -11,6 +13,7 @@ use crate::rhdl_core::{
             lower_dynamic_indices_with_constant_arguments::LowerDynamicIndicesWithConstantArguments,
             lower_inferred_casts::LowerInferredCastsPass,
             lower_inferred_retimes::LowerInferredRetimesPass,
+            lower_literal_index_to_rom::LowerLiteralIndexToRom,
             partial_initialization_check::PartialInitializationCheck, pass::Pass,
             pre_cast_literals::PreCastLiterals,
             precast_integer_literals_in_binops::PrecastIntegerLiteralsInBinops,
@@ -31,9 +34,10 @@ use crate::rhdl_core::{
 
 type Result<T> = std::result::Result<T, RHDLError>;
 
-fn wrap_pass<P: Pass>(obj: Object) -> Result<Object> {
+fn wrap_pass<P: Pass>(obj: Object, dump: &mut PassDump) -> Result<Object> {
     info!("Running Stage 1 Compiler Pass {}", P::description());
     let obj = P::run(obj)?;
+    dump.record(pass_name::<P>(), &obj);
     info!("Pass complete - checking symbol table");
     let obj = SymbolTableIsComplete::run(obj)?;
     Ok(obj)
@@ -45,28 +49,44 @@ pub enum CompilationMode {
     Synchronous,
 }
 
-pub(crate) fn compile(kernel: Kernel, mode: CompilationMode) -> Result<Object> {
+// What a kernel is being compiled as.  The kernels of circuits are
+// checked for additional lints.  The clock domains of a kernel that is
+// called by another kernel are checked at each call site instead, since
+// they depend on the arguments it is called with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum KernelRole {
+    Function,
+    Circuit,
+    Callee,
+}
+
+pub(crate) fn compile(kernel: Kernel, mode: CompilationMode, role: KernelRole) -> Result<Object> {
+    let sites = LintSites::new(&kernel)?;
     let mir = compile_mir(kernel, mode)?;
     let mut obj = infer(mir)?;
+    let mut dump = PassDump::new("rhif", &obj);
     obj = SymbolTableIsComplete::run(obj)?;
-    obj = wrap_pass::<CheckForRolledTypesPass>(obj)?;
+    if role == KernelRole::Circuit {
+        check_circuit_state(&obj)?;
+    }
+    obj = wrap_pass::<CheckForRolledTypesPass>(obj, &mut dump)?;
     let mut hash = obj.hash_value();
     loop {
-        obj = wrap_pass::<RemoveUnneededMuxesPass>(obj)?;
-        obj = wrap_pass::<RemoveExtraRegistersPass>(obj)?;
-        obj = wrap_pass::<RemoveUnusedLiterals>(obj)?;
-        obj = wrap_pass::<RemoveUselessCastsPass>(obj)?;
-        obj = wrap_pass::<RemoveEmptyCasesPass>(obj)?;
-        obj = wrap_pass::<RemoveUnusedRegistersPass>(obj)?;
-        obj = wrap_pass::<PropagateLiteralsPass>(obj)?;
-        obj = wrap_pass::<DeadCodeEliminationPass>(obj)?;
+        obj = wrap_pass::<RemoveUnneededMuxesPass>(obj, &mut dump)?;
+        obj = wrap_pass::<RemoveExtraRegistersPass>(obj, &mut dump)?;
+        obj = wrap_pass::<RemoveUnusedLiterals>(obj, &mut dump)?;
+        obj = wrap_pass::<RemoveUselessCastsPass>(obj, &mut dump)?;
+        obj = wrap_pass::<RemoveEmptyCasesPass>(obj, &mut dump)?;
+        obj = wrap_pass::<RemoveUnusedRegistersPass>(obj, &mut dump)?;
+        obj = wrap_pass::<PropagateLiteralsPass>(obj, &mut dump)?;
+        obj = wrap_pass::<DeadCodeEliminationPass>(obj, &mut dump)?;
         let new_hash = obj.hash_value();
         if new_hash == hash {
             break;
         }
         hash = new_hash;
     }
-    if matches!(mode, CompilationMode::Asynchronous) {
+    if matches!(mode, CompilationMode::Asynchronous) && role != KernelRole::Callee {
         info!(
             "Running Stage 1 Compiler Pass {}",
             CheckClockDomain::description()
@@ -75,21 +95,22 @@ pub(crate) fn compile(kernel: Kernel, mode: CompilationMode) -> Result<Object> {
     }
     let mut hash = obj.hash_value();
     loop {
-        obj = wrap_pass::<PropagateLiteralsPass>(obj)?;
-        obj = wrap_pass::<RemoveUnneededMuxesPass>(obj)?;
-        obj = wrap_pass::<RemoveExtraRegistersPass>(obj)?;
-        obj = wrap_pass::<RemoveUnusedLiterals>(obj)?;
-        obj = wrap_pass::<PreCastLiterals>(obj)?;
-        obj = wrap_pass::<RemoveUselessCastsPass>(obj)?;
-        obj = wrap_pass::<RemoveEmptyCasesPass>(obj)?;
-        obj = wrap_pass::<RemoveUnusedRegistersPass>(obj)?;
-        obj = wrap_pass::<DeadCodeEliminationPass>(obj)?;
-        obj = wrap_pass::<PrecomputeDiscriminantPass>(obj)?;
-        obj = wrap_pass::<LowerInferredCastsPass>(obj)?;
-        obj = wrap_pass::<PrecastIntegerLiteralsInBinops>(obj)?;
-        obj = wrap_pass::<LowerInferredRetimesPass>(obj)?;
-        obj = wrap_pass::<LowerDynamicIndicesWithConstantArguments>(obj)?;
-        obj = wrap_pass::<ConstantPropagation>(obj)?;
+        obj = wrap_pass::<PropagateLiteralsPass>(obj, &mut dump)?;
+        obj = wrap_pass::<RemoveUnneededMuxesPass>(obj, &mut dump)?;
+        obj = wrap_pass::<RemoveExtraRegistersPass>(obj, &mut dump)?;
+        obj = wrap_pass::<RemoveUnusedLiterals>(obj, &mut dump)?;
+        obj = wrap_pass::<PreCastLiterals>(obj, &mut dump)?;
+        obj = wrap_pass::<RemoveUselessCastsPass>(obj, &mut dump)?;
+        obj = wrap_pass::<RemoveEmptyCasesPass>(obj, &mut dump)?;
+        obj = wrap_pass::<RemoveUnusedRegistersPass>(obj, &mut dump)?;
+        obj = wrap_pass::<DeadCodeEliminationPass>(obj, &mut dump)?;
+        obj = wrap_pass::<PrecomputeDiscriminantPass>(obj, &mut dump)?;
+        obj = wrap_pass::<LowerInferredCastsPass>(obj, &mut dump)?;
+        obj = wrap_pass::<PrecastIntegerLiteralsInBinops>(obj, &mut dump)?;
+        obj = wrap_pass::<LowerInferredRetimesPass>(obj, &mut dump)?;
+        obj = wrap_pass::<LowerDynamicIndicesWithConstantArguments>(obj, &mut dump)?;
+        obj = wrap_pass::<LowerLiteralIndexToRom>(obj, &mut dump)?;
+        obj = wrap_pass::<ConstantPropagation>(obj, &mut dump)?;
         let new_hash = obj.hash_value();
         if new_hash == hash {
             break;
@@ -111,5 +132,9 @@ pub(crate) fn compile(kernel: Kernel, mode: CompilationMode) -> Result<Object> {
         PartialInitializationCheck::description()
     );
     obj = PartialInitializationCheck::run(obj)?;
+    check_kernel(&obj, &sites)?;
+    if role == KernelRole::Circuit {
+        check_circuit_output(&obj)?;
+    }
     Ok(obj)
 }
@@ -1,10 +1,15 @@
 use crate::rhdl_core::{
     RHDLError,
     compiler::{
+        dump::{PassDump, pass_name},
         lower_rhif_to_rtl::compile_to_rtl,
         rtl_passes::{
-            check_no_zero_resize::CheckNoZeroResize, constant_propagation::ConstantPropagationPass,
+            check_divisors_are_constant::CheckDivisorsAreConstant,
+            check_no_zero_resize::CheckNoZeroResize,
+            common_subexpression_elimination::CommonSubexpressionElimination,
+            constant_propagation::ConstantPropagationPass,
             dead_code_elimination::DeadCodeEliminationPass,
+            lower_divide_by_constant::LowerDivideByConstant,
             lower_empty_splice_to_copy::LowerEmptySpliceToCopy,
             lower_index_all_to_copy::LowerIndexAllToCopy,
             lower_multiply_to_shift::LowerMultiplyToShift,
@@ -26,37 +31,43 @@ use log::{debug, info};
 
 type Result<T> = std::result::Result<T, RHDLError>;
 
-fn wrap_pass<P: Pass>(obj: rtl::Object) -> Result<rtl::Object> {
+fn wrap_pass<P: Pass>(obj: rtl::Object, dump: &mut PassDump) -> Result<rtl::Object> {
     info!("Running Stage 2 compiler Pass {}", P::description());
-    P::run(obj)
+    let obj = P::run(obj)?;
+    dump.record(pass_name::<P>(), &obj);
+    Ok(obj)
 }
 
 pub(crate) fn compile(object: &crate::rhdl_core::rhif::Object) -> Result<rtl::Object> {
     let mut rtl = compile_to_rtl(object)?;
+    let mut dump = PassDump::new("rtl", &rtl);
     let mut hash = rtl.hash_value();
     loop {
-        rtl = wrap_pass::<LowerSignalCasts>(rtl)?;
-        rtl = wrap_pass::<RemoveExtraRegistersPass>(rtl)?;
-        rtl = wrap_pass::<SymbolTableIsComplete>(rtl)?;
-        rtl = wrap_pass::<RemoveUnusedOperandsPass>(rtl)?;
-        rtl = wrap_pass::<StripEmptyArgsFromConcat>(rtl)?;
-        rtl = wrap_pass::<DeadCodeEliminationPass>(rtl)?;
-        rtl = wrap_pass::<LowerEmptySpliceToCopy>(rtl)?;
-        rtl = wrap_pass::<LowerSingleConcatToCopy>(rtl)?;
-        rtl = wrap_pass::<LowerIndexAllToCopy>(rtl)?;
-        rtl = wrap_pass::<RemoveEmptyFunctionArguments>(rtl)?;
-        rtl = wrap_pass::<LowerMultiplyToShift>(rtl)?;
-        rtl = wrap_pass::<LowerShiftByConstant>(rtl)?;
-        rtl = wrap_pass::<LowerShiftsByZeroToCopy>(rtl)?;
-        rtl = wrap_pass::<LowerNotEqualZeroToAny>(rtl)?;
-        rtl = wrap_pass::<ConstantPropagationPass>(rtl)?;
+        rtl = wrap_pass::<LowerSignalCasts>(rtl, &mut dump)?;
+        rtl = wrap_pass::<RemoveExtraRegistersPass>(rtl, &mut dump)?;
+        rtl = wrap_pass::<SymbolTableIsComplete>(rtl, &mut dump)?;
+        rtl = wrap_pass::<RemoveUnusedOperandsPass>(rtl, &mut dump)?;
+        rtl = wrap_pass::<StripEmptyArgsFromConcat>(rtl, &mut dump)?;
+        rtl = wrap_pass::<DeadCodeEliminationPass>(rtl, &mut dump)?;
+        rtl = wrap_pass::<LowerEmptySpliceToCopy>(rtl, &mut dump)?;
+        rtl = wrap_pass::<LowerSingleConcatToCopy>(rtl, &mut dump)?;
+        rtl = wrap_pass::<LowerIndexAllToCopy>(rtl, &mut dump)?;
+        rtl = wrap_pass::<RemoveEmptyFunctionArguments>(rtl, &mut dump)?;
+        rtl = wrap_pass::<LowerDivideByConstant>(rtl, &mut dump)?;
+        rtl = wrap_pass::<LowerMultiplyToShift>(rtl, &mut dump)?;
+        rtl = wrap_pass::<LowerShiftByConstant>(rtl, &mut dump)?;
+        rtl = wrap_pass::<LowerShiftsByZeroToCopy>(rtl, &mut dump)?;
+        rtl = wrap_pass::<LowerNotEqualZeroToAny>(rtl, &mut dump)?;
+        rtl = wrap_pass::<ConstantPropagationPass>(rtl, &mut dump)?;
+        rtl = wrap_pass::<CommonSubexpressionElimination>(rtl, &mut dump)?;
         let new_hash = rtl.hash_value();
         if new_hash == hash {
             break;
         }
         hash = new_hash;
     }
-    rtl = wrap_pass::<CheckNoZeroResize>(rtl)?;
+    rtl = wrap_pass::<CheckNoZeroResize>(rtl, &mut dump)?;
+    rtl = wrap_pass::<CheckDivisorsAreConstant>(rtl, &mut dump)?;
     debug!("{rtl:?}");
     Ok(rtl)
 }
@@ -1,50 +1,143 @@
+use std::rc::Rc;
+
 use log::info;
 
 use crate::{
     prelude::RHDLError,
     rhdl_core::{
-        compiler::ntl_passes::{
-            check_for_undriven::CheckForUndriven, constant_propagation::ConstantPropagationPass,
-            constant_reg_elimination::ConstantRegisterElimination,
-            dead_code_elimination::DeadCodeElimination, lower_any_all::LowerAnyAll,
-            lower_bitwise_op_with_constant::LowerBitwiseOpWithConstant, lower_case::LowerCase,
-            lower_selects::LowerSelects, pass::Pass,
-            remove_extra_literals::RemoveExtraLiteralsPass,
-            remove_extra_registers::RemoveExtraRegistersPass,
-            reorder_instructions::ReorderInstructions, single_write::SingleRegisterWrite,
-            symbol_table_is_complete::SymbolTableIsComplete,
+        compiler::{
+            dump::{PassDump, pass_name},
+            ntl_passes::{
+                check_for_undriven::CheckForUndriven,
+                common_subexpression_elimination::CommonSubexpressionElimination,
+                constant_propagation::ConstantPropagationPass,
+                constant_reg_elimination::ConstantRegisterElimination,
+                dead_code_elimination::DeadCodeElimination, lower_any_all::LowerAnyAll,
+                lower_bitwise_op_with_constant::LowerBitwiseOpWithConstant, lower_case::LowerCase,
+                lower_selects::LowerSelects, pass::Pass,
+                remove_extra_literals::RemoveExtraLiteralsPass,
+                remove_extra_registers::RemoveExtraRegistersPass,
+                reorder_instructions::ReorderInstructions, single_write::SingleRegisterWrite,
+                symbol_table_is_complete::SymbolTableIsComplete,
+            },
         },
         ntl::Object,
     },
 };
 
-fn wrap_pass<P: Pass>(obj: Object) -> Result<Object, RHDLError> {
+fn wrap_pass<P: Pass>(obj: Object, dump: &mut PassDump) -> Result<Object, RHDLError> {
     info!("Running Stage 3 compiler Pass {}", P::description());
-    P::run(obj)
+    let obj = P::run(obj)?;
+    dump.record(pass_name::<P>(), &obj);
+    Ok(obj)
+}
+
+/// A netlist pass supplied from outside the compiler, to be added to an
+/// [NtlPipeline].  Passes are objects, so they can carry their own
+/// configuration (a cell library, a LUT size, and so on).
+pub trait NtlPass {
+    fn run(&self, input: Object) -> Result<Object, RHDLError>;
+    fn description(&self) -> &str;
+    /// The name of the pass in the IR dumps.
+    fn name(&self) -> &'static str {
+        pass_name::<Self>()
+    }
 }
 
-pub fn optimize_ntl(mut input: Object) -> Result<Object, RHDLError> {
-    let mut hash = input.hash_value();
-    input = wrap_pass::<SymbolTableIsComplete>(input)?;
-    loop {
-        input = wrap_pass::<RemoveExtraLiteralsPass>(input)?;
-        input = wrap_pass::<ConstantRegisterElimination>(input)?;
-        input = wrap_pass::<LowerCase>(input)?;
-        input = wrap_pass::<LowerSelects>(input)?;
-        input = wrap_pass::<RemoveExtraRegistersPass>(input)?;
-        input = wrap_pass::<ConstantPropagationPass>(input)?;
-        input = wrap_pass::<LowerBitwiseOpWithConstant>(input)?;
-        input = wrap_pass::<LowerAnyAll>(input)?;
-        input = wrap_pass::<DeadCodeElimination>(input)?;
-        let new_hash = input.hash_value();
-        if new_hash == hash {
-            break;
+fn run_user_passes(
+    passes: &[Rc<dyn NtlPass>],
+    mut obj: Object,
+    dump: &mut PassDump,
+) -> Result<Object, RHDLError> {
+    for pass in passes {
+        info!("Running Stage 3 user Pass {}", pass.description());
+        obj = pass.run(obj)?;
+        dump.record(pass.name(), &obj);
+    }
+    Ok(obj)
+}
+
+/// The sequence of passes used to optimize a netlist.
+///
+/// The built in optimizations run in a loop until the netlist stops
+/// changing, and are followed by a set of checks and a final ordering
+/// of the instructions.  Additional passes can be added at three points:
+///
+/// - [NtlPipeline::before_fixpoint] passes run once, before the loop.
+/// - [NtlPipeline::in_fixpoint] passes run on every iteration, after the
+///   built in optimizations.  They must eventually stop changing the
+///   netlist, or the loop will never terminate.
+/// - [NtlPipeline::after_fixpoint] passes run once, after the loop and
+///   before the final checks, so their output is still checked for
+///   undriven wires and multiple writes.
+///
+/// Within each point, passes run in the order they were added.  The
+/// pipeline is used when the descriptor of a design is built (see
+/// [Synchronous::descriptor_with](crate::rhdl_core::Synchronous::descriptor_with)),
+/// so the passes run on the netlist of each level of the design as it is
+/// assembled, along with the built in optimizations.
+/// ```ignore
+/// let pipeline = NtlPipeline::default()
+///     .in_fixpoint(MyCarryChains { lut_size: 6 })
+///     .after_fixpoint(MyLint::default());
+/// let hdl = circuit.netlist_hdl_with("top", &pipeline)?;
+/// ```
+#[derive(Clone, Default)]
+pub struct NtlPipeline {
+    before: Vec<Rc<dyn NtlPass>>,
+    fixpoint: Vec<Rc<dyn NtlPass>>,
+    after: Vec<Rc<dyn NtlPass>>,
+}
+
+impl NtlPipeline {
+    pub fn before_fixpoint(mut self, pass: impl NtlPass + 'static) -> Self {
+        self.before.push(Rc::new(pass));
+        self
+    }
+    pub fn in_fixpoint(mut self, pass: impl NtlPass + 'static) -> Self {
+        self.fixpoint.push(Rc::new(pass));
+        self
+    }
+    pub fn after_fixpoint(mut self, pass: impl NtlPass + 'static) -> Self {
+        self.after.push(Rc::new(pass));
+        self
+    }
+    /// True if no passes have been added to the built in ones.
+    pub fn is_default(&self) -> bool {
+        self.before.is_empty() && self.fixpoint.is_empty() && self.after.is_empty()
+    }
+    pub fn run(&self, mut input: Object) -> Result<Object, RHDLError> {
+        let mut dump = PassDump::new("ntl", &input);
+        input = wrap_pass::<SymbolTableIsComplete>(input, &mut dump)?;
+        input = run_user_passes(&self.before, input, &mut dump)?;
+        let mut hash = input.hash_value();
+        loop {
+            input = wrap_pass::<RemoveExtraLiteralsPass>(input, &mut dump)?;
+            input = wrap_pass::<ConstantRegisterElimination>(input, &mut dump)?;
+            input = wrap_pass::<LowerCase>(input, &mut dump)?;
+            input = wrap_pass::<LowerSelects>(input, &mut dump)?;
+            input = wrap_pass::<RemoveExtraRegistersPass>(input, &mut dump)?;
+            input = wrap_pass::<ConstantPropagationPass>(input, &mut dump)?;
+            input = wrap_pass::<LowerBitwiseOpWithConstant>(input, &mut dump)?;
+            input = wrap_pass::<LowerAnyAll>(input, &mut dump)?;
+            input = wrap_pass::<CommonSubexpressionElimination>(input, &mut dump)?;
+            input = wrap_pass::<DeadCodeElimination>(input, &mut dump)?;
+            input = run_user_passes(&self.fixpoint, input, &mut dump)?;
+            let new_hash = input.hash_value();
+            if new_hash == hash {
+                break;
+            }
+            hash = new_hash;
         }
-        hash = new_hash;
+        input = run_user_passes(&self.after, input, &mut dump)?;
+        input = wrap_pass::<SymbolTableIsComplete>(input, &mut dump)?;
+        input = wrap_pass::<SingleRegisterWrite>(input, &mut dump)?;
+        input = wrap_pass::<ReorderInstructions>(input, &mut dump)?;
+        input = wrap_pass::<CheckForUndriven>(input, &mut dump)?;
+        Ok(input)
     }
-    input = wrap_pass::<SymbolTableIsComplete>(input)?;
-    input = wrap_pass::<SingleRegisterWrite>(input)?;
-    input = wrap_pass::<ReorderInstructions>(input)?;
-    input = wrap_pass::<CheckForUndriven>(input)?;
-    Ok(input)
+}
+
+pub fn optimize_ntl(input: Object) -> Result<Object, RHDLError> {
+    NtlPipeline::default().run(input)
 }
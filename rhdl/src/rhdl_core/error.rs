@@ -38,6 +38,9 @@ pub enum RHDLError {
     RHDLClockDomainViolation(
         #[from] Box<crate::rhdl_core::compiler::mir::error::RHDLClockDomainViolation>,
     ),
+    #[error("RHDL Lint")]
+    #[diagnostic(transparent)]
+    RHDLLint(#[from] Box<crate::rhdl_core::compiler::lints::RHDLLint>),
     #[error("RHDL Dynamic Type Error")]
     #[diagnostic(transparent)]
     RHDLDynamicTypeError(#[from] Box<crate::rhdl_core::types::error::DynamicTypeError>),
@@ -66,6 +69,8 @@ pub enum RHDLError {
     NoOutputsError,
     #[error("syn parsing error: {0}")]
     SynError(#[from] syn::Error),
+    #[error("VHDL export error: {0}")]
+    VhdlExportError(String),
     #[error("Top module export error: {0}")]
     ExportError(#[from] crate::rhdl_core::circuit::fixture::ExportError),
     #[error("This module is not synthesizable")]
@@ -1,15 +1,17 @@
 use crate::{
     prelude::Kind,
     rhdl_core::{
+        RHDLError,
         bitx::BitX,
         rtl::spec::{AluBinary, AluUnary},
         types::bit_string::BitString,
     },
 };
 
-use super::formatter;
+use super::{formatter, vhdl};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Hash, Default)]
+#[derive(Debug, Clone, Hash, Default, Serialize, Deserialize)]
 pub struct Module {
     pub name: String,
     pub description: String,
@@ -24,6 +26,11 @@ impl Module {
     pub fn as_verilog(&self) -> String {
         formatter::module(self)
     }
+    /// Translate the module (and its submodules) to VHDL-2008.  This
+    /// fails if the module contains raw Verilog statements.
+    pub fn as_vhdl(&self) -> Result<String, RHDLError> {
+        vhdl::module(self)
+    }
 }
 
 impl std::fmt::Display for Module {
@@ -34,7 +41,7 @@ impl std::fmt::Display for Module {
 
 // function {signed} [width-1:0] name(args);
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Function {
     pub name: String,
     pub width: SignedWidth,
@@ -48,9 +55,12 @@ impl Function {
     pub fn as_verilog(&self) -> String {
         formatter::function(self)
     }
+    pub fn as_vhdl(&self) -> Result<String, RHDLError> {
+        vhdl::function(self)
+    }
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Literals {
     pub name: String,
     pub value: BitString,
@@ -63,7 +73,7 @@ pub fn literal(name: &str, value: &BitString) -> Literals {
     }
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Serialize, Deserialize)]
 pub enum SignedWidth {
     Unsigned(usize),
     Signed(usize),
@@ -104,6 +114,16 @@ impl From<&Kind> for SignedWidth {
     }
 }
 
+impl From<&BitString> for SignedWidth {
+    fn from(bs: &BitString) -> Self {
+        if bs.is_signed() {
+            SignedWidth::Signed(bs.len())
+        } else {
+            SignedWidth::Unsigned(bs.len())
+        }
+    }
+}
+
 pub fn signed_width(width: usize) -> SignedWidth {
     SignedWidth::Signed(width)
 }
@@ -112,7 +132,7 @@ pub fn unsigned_width(width: usize) -> SignedWidth {
     SignedWidth::Unsigned(width)
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Port {
     pub name: String,
     pub direction: Direction,
@@ -129,20 +149,20 @@ pub fn port(name: &str, direction: Direction, kind: HDLKind, width: SignedWidth)
     }
 }
 
-#[derive(Debug, Clone, Hash, Copy, PartialEq)]
+#[derive(Debug, Clone, Hash, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Direction {
     Input,
     Output,
     Inout,
 }
 
-#[derive(Debug, Clone, Hash, Copy, PartialEq)]
+#[derive(Debug, Clone, Hash, Copy, PartialEq, Serialize, Deserialize)]
 pub enum HDLKind {
     Wire,
     Reg,
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Declaration {
     pub kind: HDLKind,
     pub name: String,
@@ -176,7 +196,7 @@ pub fn input_reg(name: &str, width: SignedWidth) -> Declaration {
     declaration(HDLKind::Reg, name, width, None)
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Assignment {
     pub target: String,
     pub source: Box<Expression>,
@@ -196,7 +216,7 @@ pub fn non_blocking_assignment(target: &str, source: Expression) -> Statement {
     })
 }
 
-#[derive(Debug, Clone, Hash, Default)]
+#[derive(Debug, Clone, Hash, Default, Serialize, Deserialize)]
 pub struct ComponentInstance {
     pub name: String,
     pub instance_name: String,
@@ -215,7 +235,7 @@ pub fn component_instance(
     })
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Connection {
     pub target: String,
     pub source: Box<Expression>,
@@ -228,7 +248,7 @@ pub fn connection(target: &str, source: Expression) -> Connection {
     }
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub enum Expression {
     FunctionCall(FunctionCall),
     Identifier(String),
@@ -261,7 +281,7 @@ pub fn concatenate(expressions: Vec<Expression>) -> Expression {
     Expression::Concat(expressions)
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Repeat {
     pub target: Box<Expression>,
     pub count: usize,
@@ -289,7 +309,7 @@ pub fn id(name: &str) -> Expression {
     Expression::Identifier(name.to_string())
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Index {
     pub target: String,
     pub range: std::ops::Range<usize>,
@@ -306,7 +326,7 @@ pub fn index_bit(target: &str, bit: usize) -> Expression {
     index(target, bit..(bit + 1))
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct DynamicIndex {
     pub argument: String,
     pub offset: Box<Expression>,
@@ -326,7 +346,7 @@ pub fn dynamic_index(argument: &str, offset: Expression, len: usize) -> Expressi
     })
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct MemoryIndex {
     pub target: String,
     pub address: Box<Expression>,
@@ -339,13 +359,13 @@ pub fn memory_index(target: &str, address: Expression) -> Expression {
     })
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct FunctionCall {
     pub name: String,
     pub arguments: Vec<Expression>,
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub enum Statement {
     ContinuousAssignment(Assignment),
     ComponentInstance(ComponentInstance),
@@ -369,7 +389,7 @@ pub fn comment(comment: &str) -> Statement {
     Statement::Comment(comment.to_string())
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Assert {
     pub left: Box<Expression>,
     pub right: Box<Expression>,
@@ -396,7 +416,7 @@ pub fn finish() -> Statement {
     Statement::Finish
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Display {
     pub format: String,
     pub args: Vec<Expression>,
@@ -413,7 +433,7 @@ pub fn delay(time: u64) -> Statement {
     Statement::Delay(time)
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct If {
     pub condition: Box<Expression>,
     pub true_expr: Vec<Statement>,
@@ -432,7 +452,7 @@ pub fn if_statement(
     })
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Always {
     pub sensitivity: Vec<Events>,
     pub block: Vec<Statement>,
@@ -442,7 +462,7 @@ pub fn always(sensitivity: Vec<Events>, block: Vec<Statement>) -> Statement {
     Statement::Always(Always { sensitivity, block })
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub enum Events {
     Posedge(String),
     Negedge(String),
@@ -457,7 +477,7 @@ pub fn continuous_assignment(target: &str, source: Expression) -> Statement {
     })
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Initial {
     pub block: Vec<Statement>,
 }
@@ -466,7 +486,7 @@ pub fn initial(block: Vec<Statement>) -> Statement {
     Statement::Initial(Initial { block })
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct DynamicSplice {
     pub lhs: String,
     pub arg: Box<Expression>,
@@ -497,7 +517,7 @@ pub fn dynamic_splice(
 
 */
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Unary {
     pub operator: AluUnary,
     pub operand: Box<Expression>,
@@ -529,14 +549,14 @@ pub fn unary(operator: AluUnary, operand: Expression) -> Expression {
     })
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Binary {
     pub operator: AluBinary,
     pub left: Box<Expression>,
     pub right: Box<Expression>,
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Splice {
     pub target: String,
     pub source: Box<Expression>,
@@ -558,7 +578,7 @@ pub fn splice(
     })
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Select {
     pub condition: Box<Expression>,
     pub true_expr: Box<Expression>,
@@ -573,13 +593,13 @@ pub fn select(condition: Expression, true_expr: Expression, false_expr: Expressi
     })
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub struct Case {
     pub discriminant: Box<Expression>,
     pub cases: Vec<(CaseItem, Statement)>,
 }
 
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub enum CaseItem {
     Literal(BitString),
     Wild,
@@ -591,3 +611,38 @@ pub fn case(discriminant: Expression, cases: Vec<(CaseItem, Statement)>) -> Stat
         cases,
     })
 }
+
+// A ROM is a case statement with one arm per table entry.  Addresses
+// past the end of the table are assigned don't care values.
+pub fn rom(
+    target: &str,
+    address: Expression,
+    address_width: usize,
+    table: &[BitString],
+) -> Statement {
+    let address_literal = |ndx: usize| {
+        BitString::unsigned(
+            (0..address_width)
+                .map(|bit| (bit < usize::BITS as usize && (ndx >> bit) & 1 == 1).into())
+                .collect(),
+        )
+    };
+    let mut cases = table
+        .iter()
+        .enumerate()
+        .map(|(ndx, value)| {
+            (
+                CaseItem::Literal(address_literal(ndx)),
+                assign(target, bit_string(value)),
+            )
+        })
+        .collect::<Vec<_>>();
+    let covers_all = address_width < usize::BITS as usize && table.len() >= 1 << address_width;
+    if let (false, Some(value)) = (covers_all, table.first()) {
+        cases.push((
+            CaseItem::Wild,
+            assign(target, bit_string(&value.dont_care())),
+        ));
+    }
+    case(address, cases)
+}
@@ -7,13 +7,14 @@ use crate::{
         compiler::mir::error::{ICE, RHDLCompileError},
         error::rhdl_error,
         hdl::ast::{
-            self, CaseItem, Function, HDLKind, assign, concatenate, constant, declaration, id,
-            index, index_bit, input_reg, literal, repeat, unary,
+            self, CaseItem, Expression, Function, HDLKind, assign, bit_string, concatenate,
+            constant, declaration, id, index, index_bit, input_reg, literal, repeat, select,
+            signed_width, unary, unsigned_width,
         },
         rtl::{
             self,
             object::LocatedOpCode,
-            spec::{AluUnary, CaseArgument, CastKind, Operand},
+            spec::{AluBinary, AluUnary, CaseArgument, CastKind, Operand},
         },
     },
 };
@@ -130,6 +131,10 @@ impl TranslationContext<'_> {
         Ok(())
     }
     fn translate_binary(&mut self, binary: &tl::Binary) -> Result<()> {
+        if let Some(op) = binary.op.unchecked() {
+            self.translate_checked_binary(binary, op);
+            return Ok(());
+        }
         self.func.block.push(ast::assign(
             &self.rtl.op_name(binary.lhs),
             ast::binary(
@@ -140,6 +145,85 @@ impl TranslationContext<'_> {
         ));
         Ok(())
     }
+    /// The saturating and overflowing operators.  The operation is computed at
+    /// full precision (N+1 bits for add and sub, 2N bits for mul) into a local
+    /// register, and the overflow flag is read off of the bits that do not fit in
+    /// the N bit result.
+    fn translate_checked_binary(&mut self, binary: &tl::Binary, op: AluBinary) {
+        let kind = self.rtl.kind(binary.arg1);
+        let len = kind.bits();
+        let signed = kind.is_signed();
+        let full_len = if op == AluBinary::Mul {
+            2 * len
+        } else {
+            len + 1
+        };
+        let lhs = self.rtl.op_name(binary.lhs);
+        let full = format!("{lhs}_full");
+        let width = if signed {
+            signed_width(full_len)
+        } else {
+            unsigned_width(full_len)
+        };
+        self.func
+            .registers
+            .push(declaration(HDLKind::Reg, &full, width, None));
+        let arg1 = extend(&self.rtl.op_name(binary.arg1), len, full_len, signed);
+        let arg2 = extend(&self.rtl.op_name(binary.arg2), len, full_len, signed);
+        self.func
+            .block
+            .push(assign(&full, ast::binary(op, arg1, arg2)));
+        let flag = match (signed, op) {
+            // Any of the high bits are set
+            (false, AluBinary::Mul) => unary(AluUnary::Any, index(&full, len..full_len)),
+            // The carry (or borrow) out of the top bit
+            (false, _) => index_bit(&full, len),
+            // The high bits are not a sign extension of the result
+            (true, AluBinary::Mul) => ast::binary(
+                AluBinary::BitAnd,
+                unary(AluUnary::Any, index(&full, len - 1..full_len)),
+                unary(
+                    AluUnary::Not,
+                    unary(AluUnary::All, index(&full, len - 1..full_len)),
+                ),
+            ),
+            // The sign bit and the carry into it disagree
+            (true, _) => ast::binary(
+                AluBinary::BitXor,
+                index_bit(&full, len),
+                index_bit(&full, len - 1),
+            ),
+        };
+        let value = index(&full, 0..len);
+        if binary.op.is_overflowing() {
+            self.func
+                .block
+                .push(assign(&lhs, concatenate(vec![flag, value])));
+            return;
+        }
+        // A word with the given top bit, and the given value for the rest
+        let word = |rest: BitX, top: BitX| {
+            let mut bits = vec![rest; len];
+            bits[len - 1] = top;
+            bit_string(&BitString::unsigned(bits))
+        };
+        let limit = if signed {
+            // Saturate in the direction of the sign of the full precision result
+            let sign = index_bit(&full, full_len - 1);
+            select(
+                sign,
+                word(BitX::Zero, BitX::One),
+                word(BitX::One, BitX::Zero),
+            )
+        } else if op == AluBinary::Sub {
+            word(BitX::Zero, BitX::Zero)
+        } else {
+            word(BitX::One, BitX::One)
+        };
+        self.func
+            .block
+            .push(assign(&lhs, select(flag, limit, value)));
+    }
     fn translate_case(&mut self, case: &tl::Case) -> Result<()> {
         let discriminant = id(&self.rtl.op_name(case.discriminant));
         let lhs = self.rtl.op_name(case.lhs);
@@ -161,6 +245,15 @@ impl TranslationContext<'_> {
         self.func.block.push(ast::case(discriminant, table));
         Ok(())
     }
+    fn translate_rom(&mut self, rom: &tl::Rom) -> Result<()> {
+        let address = id(&self.rtl.op_name(rom.address));
+        let address_width = self.rtl.kind(rom.address).bits();
+        let lhs = self.rtl.op_name(rom.lhs);
+        self.func
+            .block
+            .push(ast::rom(&lhs, address, address_width, &rom.table));
+        Ok(())
+    }
     fn translate_concat(&mut self, concat: &tl::Concat) -> Result<()> {
         let args = concat
             .args
@@ -222,6 +315,7 @@ impl TranslationContext<'_> {
             tl::OpCode::Assign(assign) => self.translate_assign(assign),
             tl::OpCode::Binary(binary) => self.translate_binary(binary),
             tl::OpCode::Case(case) => self.translate_case(case),
+            tl::OpCode::Rom(rom) => self.translate_rom(rom),
             tl::OpCode::Cast(cast) => self.translate_cast(cast, lop.loc),
             tl::OpCode::Comment(comment) => self.translate_comment(comment),
             tl::OpCode::Concat(concat) => self.translate_concat(concat),
@@ -286,6 +380,17 @@ impl TranslationContext<'_> {
     }
 }
 
+// Extend the argument to the given width, with sign extension if needed.
+fn extend(arg: &str, arg_len: usize, len: usize, signed: bool) -> Expression {
+    if signed {
+        let prefix = repeat(index_bit(arg, arg_len - 1), len - arg_len);
+        unary(AluUnary::Signed, concatenate(vec![prefix, id(arg)]))
+    } else {
+        let prefix = repeat(constant(BitX::Zero), len - arg_len);
+        concatenate(vec![prefix, id(arg)])
+    }
+}
+
 fn translate(object: &crate::rhdl_core::rtl::Object) -> Result<Function> {
     let context = TranslationContext {
         func: Function {
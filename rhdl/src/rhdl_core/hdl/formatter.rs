@@ -1,5 +1,5 @@
 use crate::rhdl_core::{
-    bitx::{bitx_string, BitX},
+    bitx::{BitX, bitx_string},
     hdl::ast::Events,
     rtl::spec::AluBinary,
     rtl::spec::AluUnary,
@@ -243,6 +243,8 @@ fn binop(ast: AluBinary) -> &'static str {
         AluBinary::Add => "+",
         AluBinary::Sub => "-",
         AluBinary::Mul => "*",
+        AluBinary::Div => "/",
+        AluBinary::Rem => "%",
         AluBinary::BitAnd => "&",
         AluBinary::BitOr => "|",
         AluBinary::BitXor => "^",
@@ -254,6 +256,13 @@ fn binop(ast: AluBinary) -> &'static str {
         AluBinary::Le => "<=",
         AluBinary::Gt => ">",
         AluBinary::Ge => ">=",
+        // These are written out at full precision by the builder
+        AluBinary::SaturatingAdd
+        | AluBinary::SaturatingSub
+        | AluBinary::SaturatingMul
+        | AluBinary::OverflowingAdd
+        | AluBinary::OverflowingSub
+        | AluBinary::OverflowingMul => unreachable!("checked operator {ast:?} in HDL"),
     }
 }
 
@@ -1,3 +1,5 @@
 pub mod ast;
 pub mod builder;
 pub mod formatter;
+pub mod systemverilog;
+pub mod vhdl;
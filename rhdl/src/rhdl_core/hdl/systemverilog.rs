@@ -0,0 +1,373 @@
+// SystemVerilog output with typed ports.
+//
+// The Verilog backend flattens every value into a bit vector.  This
+// backend writes the same flat design, and wraps it in a module whose
+// ports use packed types built from the `Kind` of each port.  The types
+// are collected in a package named after the design:
+//   - structs become `typedef struct packed`, and tuples become anonymous
+//     packed structs with fields `_0`, `_1`, ...
+//   - arrays become packed arrays of their element type
+//   - enums become a `typedef enum` of the discriminant, wrapped in a
+//     packed struct with a packed union of the variant payloads when any
+//     of the variants carry data
+// Packed types are laid out with the first member in the most significant
+// bits, so fields are declared in the reverse of their order in the
+// `Kind`.  The result has the same bit layout as the flat vector, and so
+// the wrapper connects the typed ports straight to the flat module.
+use std::collections::{HashMap, HashSet};
+
+use crate::rhdl_core::{
+    Circuit, CircuitIO, ClockReset, Digital, DiscriminantAlignment, Kind, Synchronous,
+    SynchronousIO,
+    error::RHDLError,
+    types::kind::{Enum, Struct},
+};
+
+use super::ast::{Direction, Module, Port, SignedWidth};
+
+// The keywords of IEEE 1800-2017, which must be escaped when used as names.
+const KEYWORDS: &str = "accept_on alias always always_comb always_ff always_latch and assert \
+    assign assume automatic before begin bind bins binsof bit break buf bufif0 bufif1 byte case \
+    casex casez cell chandle checker class clocking cmos config const constraint context continue \
+    cover covergroup coverpoint cross deassign default defparam design disable dist do edge else \
+    end endcase endchecker endclass endclocking endconfig endfunction endgenerate endgroup \
+    endinterface endmodule endpackage endprimitive endprogram endproperty endspecify endsequence \
+    endtable endtask enum event eventually expect export extends extern final first_match for \
+    force foreach forever fork forkjoin function generate genvar global highz0 highz1 if iff \
+    ifnone ignore_bins illegal_bins implements implies import incdir include initial inout input \
+    inside instance int integer interconnect interface intersect join join_any join_none large \
+    let liblist library local localparam logic longint macromodule matches medium modport module \
+    nand negedge nettype new nexttime nmos nor noshowcancelled not notif0 notif1 null or output \
+    package packed parameter pmos posedge primitive priority program property protected pull0 \
+    pull1 pulldown pullup pulsestyle_ondetect pulsestyle_onevent pure rand randc randcase \
+    randsequence rcmos real realtime ref reg reject_on release repeat restrict return rnmos \
+    rpmos rtran rtranif0 rtranif1 s_always s_eventually s_nexttime s_until s_until_with scalared \
+    sequence shortint shortreal showcancelled signed small soft solve specify specparam static \
+    string strong strong0 strong1 struct super supply0 supply1 sync_accept_on sync_reject_on \
+    table tagged task this throughout time timeprecision timeunit tran tranif0 tranif1 tri tri0 \
+    tri1 triand trior trireg type typedef union unique unique0 unsigned until until_with untyped \
+    use uwire var vectored virtual void wait wait_order wand weak weak0 weak1 while wildcard wire \
+    with within wor xnor xor";
+
+fn ident(name: &str) -> String {
+    if KEYWORDS.split_whitespace().any(|keyword| keyword == name) {
+        format!("\\{name} ")
+    } else {
+        name.into()
+    }
+}
+
+// The short name of a type, i.e., `Out` for `rhdl_fpga::fifo::synchronous::Out<u8>`
+fn short_name(name: &str) -> String {
+    let name = name.split('<').next().unwrap_or_default();
+    let name = name.trim_end_matches("::");
+    let name = name.rsplit("::").next().unwrap_or_default();
+    let name = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    if name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        name
+    } else {
+        format!("T{name}")
+    }
+}
+
+// A type declaration split into the type and its packed dimensions,
+// i.e., `Foo_t` and `[3:0]` for a member holding 4 `Foo`s.
+struct Decl {
+    ty: String,
+    dims: String,
+}
+
+impl Decl {
+    fn named(ty: &str) -> Self {
+        Decl {
+            ty: ty.into(),
+            dims: String::new(),
+        }
+    }
+    fn vector(signed: bool, width: usize) -> Self {
+        match (signed, width) {
+            (false, 1) => Decl::named("logic"),
+            (false, _) => Decl {
+                ty: "logic".into(),
+                dims: format!("[{}:0]", width - 1),
+            },
+            (true, _) => Decl {
+                ty: "logic signed".into(),
+                dims: format!("[{}:0]", width - 1),
+            },
+        }
+    }
+    fn ty(&self) -> String {
+        if self.dims.is_empty() {
+            self.ty.clone()
+        } else {
+            format!("{} {}", self.ty, self.dims)
+        }
+    }
+    fn member(&self, name: &str) -> String {
+        format!("{} {};", self.ty(), ident(name))
+    }
+}
+
+#[derive(Default)]
+struct Types {
+    names: HashMap<Kind, String>,
+    taken: HashSet<String>,
+    typedefs: Vec<String>,
+}
+
+impl Types {
+    // Pick a unique base name for a named type.  Different instances of a
+    // generic type (e.g., `Option<b4>` and `Option<b8>`) get a numeric suffix.
+    fn base_name(&mut self, name: &str) -> String {
+        let base = short_name(name);
+        let mut candidate = base.clone();
+        let mut suffix = 1;
+        while self.taken.contains(&candidate) {
+            candidate = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        self.taken.insert(candidate.clone());
+        candidate
+    }
+    // The declaration of a value of the given kind, or `None` if the kind
+    // holds no bits.
+    fn decl(&mut self, kind: &Kind) -> Option<Decl> {
+        if kind.bits() == 0 {
+            return None;
+        }
+        Some(match kind {
+            Kind::Bits(width) => Decl::vector(false, *width),
+            Kind::Signed(width) => Decl::vector(true, *width),
+            Kind::Signal(kind, _) => return self.decl(kind),
+            Kind::Array(array) => {
+                let base = self.decl(&array.base)?;
+                Decl {
+                    ty: base.ty,
+                    dims: format!("[{}:0]{}", array.size - 1, base.dims),
+                }
+            }
+            Kind::Tuple(tuple) => {
+                let members = tuple
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .filter_map(|(ndx, kind)| Some(self.decl(kind)?.member(&format!("_{ndx}"))))
+                    .collect::<Vec<_>>();
+                Decl::named(&packed_struct(&members))
+            }
+            Kind::Struct(s) => {
+                Decl::named(&self.named(kind, |types, name| types.structure(name, s)))
+            }
+            Kind::Enum(e) => {
+                Decl::named(&self.named(kind, |types, name| types.enumeration(name, e)))
+            }
+            Kind::Empty => return None,
+        })
+    }
+    // The name of the typedef for a kind, which is declared (after the
+    // types it depends on) the first time it is seen.
+    fn named(&mut self, kind: &Kind, declare: impl FnOnce(&mut Self, &str) -> String) -> String {
+        if let Some(name) = self.names.get(kind) {
+            return format!("{name}_t");
+        }
+        let name = self.base_name(&kind.get_name());
+        self.names.insert(*kind, name.clone());
+        let typedef = declare(self, &name);
+        self.typedefs.push(typedef);
+        format!("{name}_t")
+    }
+    fn structure(&mut self, name: &str, s: &Struct) -> String {
+        let members = s
+            .fields
+            .iter()
+            .rev()
+            .filter_map(|field| Some(self.decl(&field.kind)?.member(&field.name)))
+            .collect::<Vec<_>>();
+        format!("typedef {} {name}_t;", packed_struct(&members))
+    }
+    fn enumeration(&mut self, name: &str, e: &Enum) -> String {
+        let layout = e.discriminant_layout;
+        let mask = u64::MAX >> (64 - layout.width.clamp(1, 64));
+        let values = e
+            .variants
+            .iter()
+            .map(|variant| {
+                format!(
+                    "{name}_{} = {}'d{}",
+                    variant.name,
+                    layout.width,
+                    variant.discriminant as u64 & mask
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",\n");
+        let payload_width = e
+            .variants
+            .iter()
+            .map(|variant| variant.kind.bits())
+            .max()
+            .unwrap_or_default();
+        let tag_name = if payload_width == 0 {
+            format!("{name}_t")
+        } else {
+            format!("{name}_tag_t")
+        };
+        let tag = format!(
+            "typedef enum logic [{}:0] {{\n{values}\n}} {tag_name};",
+            layout.width.saturating_sub(1)
+        );
+        if payload_width == 0 {
+            return tag;
+        }
+        let payloads = e
+            .variants
+            .iter()
+            .filter_map(|variant| {
+                let decl = self.decl(&variant.kind)?;
+                let pad = payload_width - variant.kind.bits();
+                if pad == 0 {
+                    return Some(decl.member(&variant.name));
+                }
+                // The payload sits in the low bits, with zeros above it
+                let padded =
+                    packed_struct(&[Decl::vector(false, pad).member("pad"), decl.member("data")]);
+                Some(format!("{padded} {};", ident(&variant.name)))
+            })
+            .collect::<Vec<_>>();
+        let payload = format!("union packed {{\n{}\n}} payload;", payloads.join("\n"));
+        // An enum with a single variant has no discriminant
+        if layout.width == 0 {
+            return format!("typedef {} {name}_t;", packed_struct(&[payload]));
+        }
+        let tag_member = format!("{tag_name} tag;");
+        let members = match layout.alignment {
+            DiscriminantAlignment::Lsb => [payload, tag_member],
+            DiscriminantAlignment::Msb => [tag_member, payload],
+        };
+        format!("{tag}\ntypedef {} {name}_t;", packed_struct(&members))
+    }
+}
+
+fn packed_struct(members: &[String]) -> String {
+    format!("struct packed {{\n{}\n}}", members.join("\n"))
+}
+
+// Indent the lines of the package based on the braces
+fn reformat(txt: &str) -> String {
+    let mut indent: usize = 0;
+    let mut result = String::new();
+    for line in txt.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let opens = line.matches('{').count();
+        let closes = line.matches('}').count();
+        if line.starts_with('}') || line.starts_with("endpackage") {
+            indent = indent.saturating_sub(1);
+        }
+        result.push_str(&"    ".repeat(indent));
+        result.push_str(line);
+        result.push('\n');
+        if line.starts_with('}') {
+            indent += 1;
+        }
+        indent = (indent + opens).saturating_sub(closes);
+        if line.starts_with("package") {
+            indent += 1;
+        }
+    }
+    result
+}
+
+fn direction(dir: Direction) -> &'static str {
+    match dir {
+        Direction::Input => "input",
+        Direction::Output => "output",
+        Direction::Inout => "inout",
+    }
+}
+
+fn port(types: &mut Types, port: &Port, kinds: &[(&str, Kind)]) -> String {
+    let decl = kinds
+        .iter()
+        .find(|(name, _)| *name == port.name)
+        .and_then(|(_, kind)| types.decl(kind));
+    let decl = decl.unwrap_or_else(|| match port.width {
+        SignedWidth::Unsigned(width) => Decl::vector(false, width),
+        SignedWidth::Signed(width) => Decl::vector(true, width),
+    });
+    format!(
+        "{} {} {}",
+        direction(port.direction),
+        decl.ty(),
+        ident(&port.name)
+    )
+}
+
+/// Write the module as SystemVerilog.  The ports of the module are typed
+/// using the kinds given for them by name; ports without a kind are
+/// left as bit vectors.  The design is written as a package of types,
+/// the flat Verilog design (with the top module renamed to `<name>_flat`),
+/// and a module with the original name and typed ports that wraps it.
+pub fn module(module: &Module, kinds: &[(&str, Kind)]) -> String {
+    let name = &module.name;
+    let flat_name = format!("{name}_flat");
+    let package_name = format!("{name}_types");
+    let mut types = Types::default();
+    let ports = module
+        .ports
+        .iter()
+        .map(|p| port(&mut types, p, kinds))
+        .collect::<Vec<_>>()
+        .join(",\n    ");
+    let connections = module
+        .ports
+        .iter()
+        .map(|p| format!(".{0}({0})", p.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let package = reformat(&format!(
+        "package {package_name};\n{}\nendpackage",
+        types.typedefs.join("\n")
+    ));
+    let flat = Module {
+        name: flat_name.clone(),
+        ..module.clone()
+    };
+    format!(
+        "{package}\n{}// {}\nmodule {name}\n    import {package_name}::*;\n(\n    {ports}\n);\n    {flat_name} flat({connections});\nendmodule\n",
+        flat.as_verilog(),
+        module.description,
+    )
+}
+
+/// Write a synchronous circuit as SystemVerilog, with typed ports.
+pub fn synchronous<T: Synchronous>(uut: &T, name: &str) -> Result<String, RHDLError> {
+    let hdl = uut.hdl(name)?.as_module();
+    Ok(module(
+        &hdl,
+        &[
+            ("clock_reset", ClockReset::static_kind()),
+            ("i", <T as SynchronousIO>::I::static_kind()),
+            ("o", <T as SynchronousIO>::O::static_kind()),
+        ],
+    ))
+}
+
+/// Write an asynchronous circuit as SystemVerilog, with typed ports.
+pub fn asynchronous<T: Circuit>(uut: &T, name: &str) -> Result<String, RHDLError> {
+    let hdl = uut.hdl(name)?.as_module();
+    Ok(module(
+        &hdl,
+        &[
+            ("i", <T as CircuitIO>::I::static_kind()),
+            ("o", <T as CircuitIO>::O::static_kind()),
+        ],
+    ))
+}
@@ -0,0 +1,1489 @@
+// VHDL-2008 output for the HDL AST.
+//
+// The AST is built for Verilog, so the translation has to fill in a few
+// things that Verilog does implicitly.  All signals are `unsigned` or
+// `signed` vectors from `numeric_std` (single bits are vectors of length
+// one).  Verilog sizes and converts the operands of an expression to fit
+// the assignment, so the translation tracks the width and signedness of
+// each expression, and inserts the conversions.  The helper functions
+// needed for this (and for the Verilog operators without a VHDL
+// equivalent) are in the `rhdl_pkg` package, which is written at the top
+// of each design.
+//
+// Some Verilog idioms are mapped onto VHDL ones:
+//   - `initial` blocks that only assign constants become initial values
+//     for the signals (or ports, or memories) they assign
+//   - assignments to a concatenation of signals go through a temporary
+//   - a `function` assigns its result to a variable that is returned at
+//     the end of the function
+//
+// `Custom` statements hold raw Verilog, which cannot be translated, so a
+// design that contains one is rejected before any VHDL is written.
+use std::collections::{HashMap, HashSet};
+
+use crate::rhdl_core::{
+    RHDLError,
+    bitx::{BitX, bitx_string},
+    rtl::spec::{AluBinary, AluUnary},
+    types::bit_string::BitString,
+};
+
+use super::ast::{
+    Always, Assert, Binary, Case, CaseItem, ComponentInstance, Declaration, Direction, Display,
+    DynamicSplice, Events, Expression, Function, If, Module, Port, Select, SignedWidth, Splice,
+    Statement, Unary,
+};
+
+const PACKAGE: &str = r#"library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+package rhdl_pkg is
+    function rhdl_fit(v : unsigned; n : natural) return unsigned;
+    function rhdl_fit(v : signed; n : natural) return signed;
+    function rhdl_bit(b : boolean) return unsigned;
+    function rhdl_bit(b : std_ulogic) return unsigned;
+    function rhdl_true(v : unsigned) return boolean;
+    function rhdl_true(v : signed) return boolean;
+    function rhdl_mux(c : boolean; t, f : unsigned) return unsigned;
+    function rhdl_mux(c : boolean; t, f : signed) return signed;
+    function rhdl_repeat(v : unsigned; n : natural) return unsigned;
+    function rhdl_shl(v : unsigned; a : unsigned) return unsigned;
+    function rhdl_shl(v : signed; a : unsigned) return signed;
+    function rhdl_shr(v : unsigned; a : unsigned) return unsigned;
+    function rhdl_shr(v : signed; a : unsigned) return signed;
+    function rhdl_and(v : unsigned) return unsigned;
+    function rhdl_or(v : unsigned) return unsigned;
+    function rhdl_xor(v : unsigned) return unsigned;
+end package rhdl_pkg;
+
+package body rhdl_pkg is
+    -- Truncate or extend (with zeros) to n bits
+    function rhdl_fit(v : unsigned; n : natural) return unsigned is
+        alias x : unsigned(v'length - 1 downto 0) is v;
+    begin
+        if n <= x'length then
+            return x(n - 1 downto 0);
+        end if;
+        return resize(x, n);
+    end function rhdl_fit;
+
+    -- Truncate or sign extend to n bits
+    function rhdl_fit(v : signed; n : natural) return signed is
+        alias x : signed(v'length - 1 downto 0) is v;
+    begin
+        if n <= x'length then
+            return x(n - 1 downto 0);
+        end if;
+        return resize(x, n);
+    end function rhdl_fit;
+
+    function rhdl_bit(b : boolean) return unsigned is
+    begin
+        if b then
+            return "1";
+        end if;
+        return "0";
+    end function rhdl_bit;
+
+    function rhdl_bit(b : std_ulogic) return unsigned is
+        variable r : unsigned(0 downto 0);
+    begin
+        r(0) := b;
+        return r;
+    end function rhdl_bit;
+
+    function rhdl_true(v : unsigned) return boolean is
+    begin
+        return v /= 0;
+    end function rhdl_true;
+
+    function rhdl_true(v : signed) return boolean is
+    begin
+        return v /= 0;
+    end function rhdl_true;
+
+    function rhdl_mux(c : boolean; t, f : unsigned) return unsigned is
+    begin
+        if c then
+            return t;
+        end if;
+        return f;
+    end function rhdl_mux;
+
+    function rhdl_mux(c : boolean; t, f : signed) return signed is
+    begin
+        if c then
+            return t;
+        end if;
+        return f;
+    end function rhdl_mux;
+
+    function rhdl_repeat(v : unsigned; n : natural) return unsigned is
+        alias x : unsigned(v'length - 1 downto 0) is v;
+        variable r : unsigned(v'length * n - 1 downto 0);
+    begin
+        for k in 0 to n - 1 loop
+            r((k + 1) * x'length - 1 downto k * x'length) := x;
+        end loop;
+        return r;
+    end function rhdl_repeat;
+
+    -- The amount of a shift, clamped to n.  Verilog shifts by any amount
+    -- (shifting by n or more bits clears the vector, or fills it with the
+    -- sign bit), but to_integer overflows for amounts wider than 31 bits.
+    function rhdl_amount(a : unsigned; n : natural) return natural is
+    begin
+        if a > n then
+            return n;
+        end if;
+        return to_integer(a);
+    end function rhdl_amount;
+
+    function rhdl_shl(v : unsigned; a : unsigned) return unsigned is
+    begin
+        return shift_left(v, rhdl_amount(a, v'length));
+    end function rhdl_shl;
+
+    function rhdl_shl(v : signed; a : unsigned) return signed is
+    begin
+        return shift_left(v, rhdl_amount(a, v'length));
+    end function rhdl_shl;
+
+    function rhdl_shr(v : unsigned; a : unsigned) return unsigned is
+    begin
+        return shift_right(v, rhdl_amount(a, v'length));
+    end function rhdl_shr;
+
+    function rhdl_shr(v : signed; a : unsigned) return signed is
+    begin
+        return shift_right(v, rhdl_amount(a, v'length));
+    end function rhdl_shr;
+
+    function rhdl_and(v : unsigned) return unsigned is
+        variable r : std_ulogic := '1';
+    begin
+        for k in v'range loop
+            r := r and v(k);
+        end loop;
+        return rhdl_bit(r);
+    end function rhdl_and;
+
+    function rhdl_or(v : unsigned) return unsigned is
+        variable r : std_ulogic := '0';
+    begin
+        for k in v'range loop
+            r := r or v(k);
+        end loop;
+        return rhdl_bit(r);
+    end function rhdl_or;
+
+    function rhdl_xor(v : unsigned) return unsigned is
+        variable r : std_ulogic := '0';
+    begin
+        for k in v'range loop
+            r := r xor v(k);
+        end loop;
+        return rhdl_bit(r);
+    end function rhdl_xor;
+end package body rhdl_pkg;
+"#;
+
+const CONTEXT: &str = "library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+use work.rhdl_pkg.all;
+";
+
+const RESERVED: &[&str] = &[
+    "abs",
+    "access",
+    "after",
+    "alias",
+    "all",
+    "and",
+    "architecture",
+    "array",
+    "assert",
+    "assume",
+    "attribute",
+    "begin",
+    "block",
+    "body",
+    "buffer",
+    "bus",
+    "case",
+    "component",
+    "configuration",
+    "constant",
+    "context",
+    "cover",
+    "default",
+    "disconnect",
+    "downto",
+    "else",
+    "elsif",
+    "end",
+    "entity",
+    "exit",
+    "fairness",
+    "file",
+    "for",
+    "force",
+    "function",
+    "generate",
+    "generic",
+    "group",
+    "guarded",
+    "if",
+    "impure",
+    "in",
+    "inertial",
+    "inout",
+    "is",
+    "label",
+    "library",
+    "linkage",
+    "literal",
+    "loop",
+    "map",
+    "mod",
+    "nand",
+    "new",
+    "next",
+    "nor",
+    "not",
+    "null",
+    "of",
+    "on",
+    "open",
+    "or",
+    "others",
+    "out",
+    "package",
+    "parameter",
+    "port",
+    "postponed",
+    "procedure",
+    "process",
+    "property",
+    "protected",
+    "pure",
+    "range",
+    "record",
+    "register",
+    "reject",
+    "release",
+    "rem",
+    "report",
+    "restrict",
+    "return",
+    "rol",
+    "ror",
+    "select",
+    "sequence",
+    "severity",
+    "shared",
+    "signal",
+    "sla",
+    "sll",
+    "sra",
+    "srl",
+    "strong",
+    "subtype",
+    "then",
+    "to",
+    "transport",
+    "type",
+    "unaffected",
+    "units",
+    "until",
+    "use",
+    "variable",
+    "vmode",
+    "vprop",
+    "vunit",
+    "wait",
+    "when",
+    "while",
+    "with",
+    "xnor",
+    "xor",
+];
+
+// Names that are not legal VHDL identifiers are written as extended
+// identifiers.
+fn ident(name: &str) -> String {
+    let basic = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.ends_with('_')
+        && !name.contains("__")
+        && !RESERVED.contains(&name.to_ascii_lowercase().as_str());
+    if basic {
+        name.to_string()
+    } else {
+        format!("\\{}\\", name.replace('\\', "\\\\"))
+    }
+}
+
+fn vector_type(width: &SignedWidth) -> String {
+    let base = if width.is_signed() {
+        "signed"
+    } else {
+        "unsigned"
+    };
+    format!("{base}({} downto 0)", width.len() as isize - 1)
+}
+
+fn bits_literal(bits: &[BitX]) -> String {
+    format!("\"{}\"", bitx_string(bits).to_ascii_uppercase())
+}
+
+fn bit_string(bs: &BitString) -> Typed {
+    let base = if bs.is_signed() { "signed" } else { "unsigned" };
+    Typed {
+        text: format!("{base}'({})", bits_literal(bs.bits())),
+        ty: Some(bs.into()),
+    }
+}
+
+// The netlist HDL uses sized Verilog literals (like `1'b0`) as identifiers.
+fn verilog_literal(name: &str) -> Option<BitString> {
+    let (width, value) = name.split_once('\'')?;
+    let width = width.parse::<usize>().ok()?;
+    let (signed, value) = match value.strip_prefix('s') {
+        Some(value) => (true, value),
+        None => (false, value),
+    };
+    let bits = value
+        .strip_prefix('b')?
+        .chars()
+        .rev()
+        .map(|c| match c {
+            '0' => Some(BitX::Zero),
+            '1' => Some(BitX::One),
+            'x' | 'X' | 'z' | 'Z' => Some(BitX::X),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    (bits.len() == width).then(|| {
+        if signed {
+            BitString::signed(bits)
+        } else {
+            BitString::unsigned(bits)
+        }
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Class {
+    Signal,
+    Variable,
+    Constant,
+    Memory,
+}
+
+#[derive(Clone, Debug)]
+struct Symbol {
+    name: String,
+    width: SignedWidth,
+    class: Class,
+}
+
+// A translated expression, along with its type (if known)
+struct Typed {
+    text: String,
+    ty: Option<SignedWidth>,
+}
+
+impl Typed {
+    fn unsigned(self) -> Typed {
+        match self.ty {
+            Some(SignedWidth::Signed(len)) => Typed {
+                text: format!("unsigned({})", self.text),
+                ty: Some(SignedWidth::Unsigned(len)),
+            },
+            _ => self,
+        }
+    }
+    fn len(&self) -> Option<usize> {
+        self.ty.map(|ty| ty.len())
+    }
+}
+
+// Convert an expression to the given type, following the Verilog rules:
+// the expression is truncated or extended (based on its own signedness),
+// and then reinterpreted as the type of the target.
+fn fit(expr: Typed, target: SignedWidth) -> String {
+    let Some(ty) = expr.ty else {
+        return expr.text;
+    };
+    let mut text = expr.text;
+    if ty.len() != target.len() {
+        text = format!("rhdl_fit({text}, {})", target.len());
+    }
+    match (ty.is_signed(), target.is_signed()) {
+        (false, true) => format!("signed({text})"),
+        (true, false) => format!("unsigned({text})"),
+        _ => text,
+    }
+}
+
+fn indent(lines: Vec<String>) -> impl Iterator<Item = String> {
+    lines.into_iter().map(|line| format!("    {line}"))
+}
+
+// The target of an assignment
+enum Target {
+    Name(String),
+    Element(String, String),
+    Concat(Vec<String>),
+}
+
+fn parse_target(target: &str) -> Target {
+    let target = target.trim();
+    if let Some(parts) = target
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+    {
+        return Target::Concat(parts.split(',').map(|x| x.trim().to_string()).collect());
+    }
+    if let Some((name, rest)) = target.split_once('[')
+        && let Some(address) = rest.strip_suffix(']')
+    {
+        return Target::Element(name.trim().into(), address.trim().into());
+    }
+    Target::Name(target.into())
+}
+
+struct Context<'a> {
+    entities: &'a HashMap<String, &'a Module>,
+    functions: HashMap<String, &'a Function>,
+    symbols: HashMap<String, Symbol>,
+    temporaries: Vec<(String, SignedWidth)>,
+    counter: usize,
+    sequential: bool,
+}
+
+impl<'a> Context<'a> {
+    fn new(entities: &'a HashMap<String, &'a Module>) -> Self {
+        Self {
+            entities,
+            functions: Default::default(),
+            symbols: Default::default(),
+            temporaries: vec![],
+            counter: 0,
+            sequential: false,
+        }
+    }
+    fn declare(&mut self, name: &str, width: SignedWidth, class: Class) {
+        self.symbols.insert(
+            name.into(),
+            Symbol {
+                name: ident(name),
+                width,
+                class,
+            },
+        );
+    }
+    fn temporary(&mut self, width: SignedWidth) -> String {
+        let name = format!("rhdl_tmp_{}", self.counter);
+        self.counter += 1;
+        self.temporaries.push((name.clone(), width));
+        name
+    }
+    fn name(&self, name: &str) -> Typed {
+        if let Some(literal) = verilog_literal(name) {
+            return bit_string(&literal);
+        }
+        match self.symbols.get(name) {
+            Some(symbol) => Typed {
+                text: symbol.name.clone(),
+                ty: Some(symbol.width),
+            },
+            None => Typed {
+                text: ident(name),
+                ty: None,
+            },
+        }
+    }
+    fn assign_op(&self, name: &str) -> &'static str {
+        match self.symbols.get(name).map(|symbol| symbol.class) {
+            Some(Class::Variable) => ":=",
+            _ => "<=",
+        }
+    }
+    fn address(&self, address: &str) -> String {
+        match address.parse::<usize>() {
+            Ok(address) => address.to_string(),
+            Err(_) => format!("to_integer({})", self.name(address).text),
+        }
+    }
+    fn expression(&self, ast: &Expression) -> Typed {
+        match ast {
+            Expression::Binary(ast) => self.binary(ast),
+            Expression::Unary(ast) => self.unary(ast),
+            Expression::Literal(ast) => bit_string(ast),
+            Expression::Identifier(ast) => self.name(ast),
+            Expression::Select(ast) => self.select(ast),
+            Expression::Concat(ast) => self.concatenate(ast),
+            Expression::FunctionCall(ast) => {
+                let function = self.functions.get(&ast.name);
+                let args = ast
+                    .arguments
+                    .iter()
+                    .enumerate()
+                    .map(|(ndx, arg)| {
+                        let arg = self.expression(arg);
+                        match function.and_then(|f| f.arguments.get(ndx)) {
+                            Some(decl) => fit(arg, decl.width),
+                            None => arg.text,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let text = if args.is_empty() {
+                    ident(&ast.name)
+                } else {
+                    format!("{}({})", ident(&ast.name), args.join(", "))
+                };
+                Typed {
+                    text,
+                    ty: function.map(|f| f.width),
+                }
+            }
+            Expression::DynamicIndex(ast) => {
+                let target = self.name(&ast.argument);
+                let offset = self.expression(&ast.offset).text;
+                let slice = Typed {
+                    text: format!(
+                        "{}(to_integer({offset}) + {} downto to_integer({offset}))",
+                        target.text,
+                        ast.len as isize - 1
+                    ),
+                    ty: target.ty.map(|ty| match ty {
+                        SignedWidth::Signed(_) => SignedWidth::Signed(ast.len),
+                        SignedWidth::Unsigned(_) => SignedWidth::Unsigned(ast.len),
+                    }),
+                };
+                slice.unsigned()
+            }
+            Expression::Index(ast) => {
+                let target = self.name(&ast.target);
+                let len = ast.range.len();
+                let slice = Typed {
+                    text: format!(
+                        "{}({} downto {})",
+                        target.text,
+                        ast.range.end as isize - 1,
+                        ast.range.start
+                    ),
+                    ty: Some(match target.ty {
+                        Some(SignedWidth::Signed(_)) => SignedWidth::Signed(len),
+                        _ => SignedWidth::Unsigned(len),
+                    }),
+                };
+                // A part select is unsigned in Verilog
+                slice.unsigned()
+            }
+            Expression::Repeat(ast) => {
+                let target = self.expression(&ast.target).unsigned();
+                Typed {
+                    ty: target
+                        .len()
+                        .map(|len| SignedWidth::Unsigned(len * ast.count)),
+                    text: format!("rhdl_repeat({}, {})", target.text, ast.count),
+                }
+            }
+            Expression::Const(ast) => bit_string(&BitString::unsigned(vec![*ast])),
+            Expression::MemoryIndex(ast) => {
+                let address = self.expression(&ast.address).text;
+                let memory = self.name(&ast.target);
+                Typed {
+                    text: format!("{}(to_integer({address}))", memory.text),
+                    ty: memory.ty,
+                }
+            }
+        }
+    }
+    fn binary(&self, ast: &Binary) -> Typed {
+        let left = self.expression(&ast.left);
+        let right = self.expression(&ast.right);
+        // Verilog treats the operands as unsigned unless both are signed.
+        // The amount of a shift does not count, since it is always unsigned.
+        let shift = matches!(ast.operator, AluBinary::Shl | AluBinary::Shr);
+        let (left, right) = match (left.ty, right.ty) {
+            (Some(l), Some(r)) if !shift && l.is_signed() != r.is_signed() => {
+                (left.unsigned(), right.unsigned())
+            }
+            _ => (left, right),
+        };
+        let signed = left.ty.is_some_and(|ty| ty.is_signed());
+        let typed = |len: Option<usize>| {
+            len.map(|len| {
+                if signed {
+                    SignedWidth::Signed(len)
+                } else {
+                    SignedWidth::Unsigned(len)
+                }
+            })
+        };
+        let max = left.len().zip(right.len()).map(|(l, r)| l.max(r));
+        match ast.operator {
+            AluBinary::Add | AluBinary::Sub | AluBinary::Div | AluBinary::Rem => {
+                let op = match ast.operator {
+                    AluBinary::Add => "+",
+                    AluBinary::Sub => "-",
+                    AluBinary::Div => "/",
+                    _ => "rem",
+                };
+                Typed {
+                    text: format!("({} {op} {})", left.text, right.text),
+                    ty: typed(max),
+                }
+            }
+            AluBinary::Mul => Typed {
+                text: format!("({} * {})", left.text, right.text),
+                ty: typed(left.len().zip(right.len()).map(|(l, r)| l + r)),
+            },
+            AluBinary::BitAnd | AluBinary::BitOr | AluBinary::BitXor => {
+                let op = match ast.operator {
+                    AluBinary::BitAnd => "and",
+                    AluBinary::BitOr => "or",
+                    _ => "xor",
+                };
+                let ty = typed(max);
+                let (left, right) = match ty {
+                    Some(ty) => (fit(left, ty), fit(right, ty)),
+                    None => (left.text, right.text),
+                };
+                Typed {
+                    text: format!("({left} {op} {right})"),
+                    ty,
+                }
+            }
+            AluBinary::Shl | AluBinary::Shr => {
+                let func = if ast.operator == AluBinary::Shl {
+                    "rhdl_shl"
+                } else {
+                    "rhdl_shr"
+                };
+                Typed {
+                    text: format!("{func}({}, {})", left.text, right.unsigned().text),
+                    ty: left.ty,
+                }
+            }
+            AluBinary::Eq
+            | AluBinary::Ne
+            | AluBinary::Lt
+            | AluBinary::Le
+            | AluBinary::Gt
+            | AluBinary::Ge => {
+                let op = match ast.operator {
+                    AluBinary::Eq => "=",
+                    AluBinary::Ne => "/=",
+                    AluBinary::Lt => "<",
+                    AluBinary::Le => "<=",
+                    AluBinary::Gt => ">",
+                    _ => ">=",
+                };
+                Typed {
+                    text: format!("rhdl_bit({} {op} {})", left.text, right.text),
+                    ty: Some(SignedWidth::Unsigned(1)),
+                }
+            }
+            // These are written out at full precision by the builder
+            AluBinary::SaturatingAdd
+            | AluBinary::SaturatingSub
+            | AluBinary::SaturatingMul
+            | AluBinary::OverflowingAdd
+            | AluBinary::OverflowingSub
+            | AluBinary::OverflowingMul => {
+                unreachable!("checked operator {:?} in HDL", ast.operator)
+            }
+        }
+    }
+    fn unary(&self, ast: &Unary) -> Typed {
+        let arg = self.expression(&ast.operand);
+        let reduce = |func: &str, arg: Typed| Typed {
+            text: format!("{func}({})", arg.unsigned().text),
+            ty: Some(SignedWidth::Unsigned(1)),
+        };
+        match ast.operator {
+            AluUnary::Neg => match arg.ty {
+                Some(SignedWidth::Signed(_)) => Typed {
+                    text: format!("(-{})", arg.text),
+                    ty: arg.ty,
+                },
+                _ => Typed {
+                    text: format!("(0 - {})", arg.text),
+                    ty: arg.ty,
+                },
+            },
+            AluUnary::Not => Typed {
+                text: format!("(not {})", arg.text),
+                ty: arg.ty,
+            },
+            AluUnary::All => reduce("rhdl_and", arg),
+            AluUnary::Any => reduce("rhdl_or", arg),
+            AluUnary::Xor => reduce("rhdl_xor", arg),
+            AluUnary::Signed => match arg.ty {
+                Some(SignedWidth::Signed(_)) => arg,
+                _ => Typed {
+                    text: format!("signed({})", arg.text),
+                    ty: arg.len().map(SignedWidth::Signed),
+                },
+            },
+            AluUnary::Unsigned => arg.unsigned(),
+            AluUnary::Val => arg,
+        }
+    }
+    fn select(&self, ast: &Select) -> Typed {
+        let condition = self.condition(&ast.condition);
+        let true_expr = self.expression(&ast.true_expr);
+        let false_expr = self.expression(&ast.false_expr);
+        let ty = match (true_expr.ty, false_expr.ty) {
+            (Some(t), Some(f)) => {
+                let len = t.len().max(f.len());
+                Some(if t.is_signed() && f.is_signed() {
+                    SignedWidth::Signed(len)
+                } else {
+                    SignedWidth::Unsigned(len)
+                })
+            }
+            _ => None,
+        };
+        let (true_expr, false_expr) = match ty {
+            Some(ty) => (fit(true_expr, ty), fit(false_expr, ty)),
+            None => (true_expr.text, false_expr.text),
+        };
+        Typed {
+            text: format!("rhdl_mux({condition}, {true_expr}, {false_expr})"),
+            ty,
+        }
+    }
+    fn concatenate(&self, ast: &[Expression]) -> Typed {
+        let parts = ast
+            .iter()
+            .map(|x| self.expression(x).unsigned())
+            .collect::<Vec<_>>();
+        if parts.len() == 1 {
+            return parts.into_iter().next().unwrap();
+        }
+        let len = parts.iter().map(Typed::len).sum::<Option<usize>>();
+        Typed {
+            text: format!(
+                "({})",
+                parts
+                    .into_iter()
+                    .map(|x| x.text)
+                    .collect::<Vec<_>>()
+                    .join(" & ")
+            ),
+            ty: len.map(SignedWidth::Unsigned),
+        }
+    }
+    fn condition(&self, ast: &Expression) -> String {
+        format!("rhdl_true({})", self.expression(ast).text)
+    }
+    fn assignment(&mut self, target: &str, source: &Expression) -> Vec<String> {
+        let source = self.expression(source);
+        self.assign_to(parse_target(target), source)
+    }
+    fn assign_to(&mut self, target: Target, source: Typed) -> Vec<String> {
+        match target {
+            Target::Name(name) => {
+                let text = match self.symbols.get(&name) {
+                    Some(symbol) => fit(source, symbol.width),
+                    None => source.text,
+                };
+                vec![format!(
+                    "{} {} {text};",
+                    self.name(&name).text,
+                    self.assign_op(&name)
+                )]
+            }
+            Target::Element(name, address) => {
+                let text = match self.symbols.get(&name) {
+                    Some(symbol) => fit(source, symbol.width),
+                    None => source.text,
+                };
+                vec![format!(
+                    "{}({}) {} {text};",
+                    self.name(&name).text,
+                    self.address(&address),
+                    self.assign_op(&name)
+                )]
+            }
+            Target::Concat(mut parts) if parts.len() == 1 => {
+                self.assign_to(Target::Name(parts.remove(0)), source)
+            }
+            Target::Concat(parts) => {
+                let width = SignedWidth::Unsigned(self.widths(&parts).iter().sum());
+                let temp = self.temporary(width);
+                let op = if self.sequential { ":=" } else { "<=" };
+                let mut lines = vec![format!("{temp} {op} {};", fit(source, width))];
+                lines.extend(self.split(&temp, &parts));
+                lines
+            }
+        }
+    }
+    fn widths(&self, parts: &[String]) -> Vec<usize> {
+        parts
+            .iter()
+            .map(|part| {
+                self.symbols
+                    .get(part)
+                    .map(|symbol| symbol.width.len())
+                    .unwrap_or(1)
+            })
+            .collect()
+    }
+    // Assign the bits of a temporary to a list of signals, which are
+    // listed from the MSB to the LSB (as in a Verilog concatenation)
+    fn split(&mut self, temp: &str, parts: &[String]) -> Vec<String> {
+        let mut lines = vec![];
+        let mut offset = 0;
+        for (part, len) in parts.iter().zip(self.widths(parts)).rev() {
+            let slice = Typed {
+                text: format!("{temp}({} downto {offset})", offset + len - 1),
+                ty: Some(SignedWidth::Unsigned(len)),
+            };
+            lines.extend(self.assign_to(Target::Name(part.clone()), slice));
+            offset += len;
+        }
+        lines
+    }
+    fn statements(&mut self, ast: &[Statement]) -> Vec<String> {
+        ast.iter().flat_map(|x| self.statement(x)).collect()
+    }
+    fn statement(&mut self, ast: &Statement) -> Vec<String> {
+        match ast {
+            Statement::ContinuousAssignment(ast)
+            | Statement::Assignment(ast)
+            | Statement::NonblockingAssignment(ast) => self.assignment(&ast.target, &ast.source),
+            Statement::ComponentInstance(ast) => self.component_instance(ast),
+            Statement::DynamicSplice(ast) => self.dynamic_splice(ast),
+            Statement::Initial(ast) => self.process(None, None, &ast.block),
+            Statement::Splice(ast) => self.splice(ast),
+            Statement::Case(ast) => self.case(ast),
+            Statement::Always(ast) => self.always(ast),
+            Statement::If(ast) => self.if_statement(ast),
+            Statement::Delay(ast) => vec![format!("wait for {ast} ns;")],
+            Statement::Display(ast) => self.display(ast),
+            // Rejected by `check_statements` before translation
+            Statement::Custom(_) => vec![],
+            Statement::Finish => vec!["std.env.finish;".into()],
+            Statement::Assert(ast) => self.assert(ast),
+            Statement::Comment(ast) => comment(ast),
+        }
+    }
+    fn component_instance(&mut self, ast: &ComponentInstance) -> Vec<String> {
+        let ports = self
+            .entities
+            .get(&ast.name)
+            .map(|module| module.ports.as_slice())
+            .unwrap_or_default();
+        let mut lines = vec![];
+        let connections = ast
+            .connections
+            .iter()
+            .map(|connection| {
+                let port = ports.iter().find(|port| port.name == connection.target);
+                let source = self.expression(&connection.source);
+                let actual = match (port, &*connection.source) {
+                    // Outputs must be connected to a name
+                    (Some(port), Expression::Concat(parts))
+                        if port.direction == Direction::Output && parts.len() > 1 =>
+                    {
+                        let temp = self.temporary(SignedWidth::Unsigned(port.width.len()));
+                        let parts = parts
+                            .iter()
+                            .map(|part| match part {
+                                Expression::Identifier(name) => name.clone(),
+                                _ => self.expression(part).text,
+                            })
+                            .collect::<Vec<_>>();
+                        lines.extend(self.split(&temp, &parts));
+                        temp
+                    }
+                    (Some(port), _) if port.direction == Direction::Input => {
+                        fit(source, port.width)
+                    }
+                    _ => source.text,
+                };
+                format!("{} => {actual}", ident(&connection.target))
+            })
+            .collect::<Vec<_>>();
+        let entity = format!(
+            "{} : entity work.{}",
+            ident(&ast.instance_name),
+            ident(&ast.name)
+        );
+        let mut instance = if connections.is_empty() {
+            vec![format!("{entity};")]
+        } else {
+            let count = connections.len();
+            std::iter::once(format!("{entity} port map ("))
+                .chain(connections.into_iter().enumerate().map(|(ndx, x)| {
+                    if ndx + 1 < count {
+                        format!("    {x},")
+                    } else {
+                        format!("    {x}")
+                    }
+                }))
+                .chain(std::iter::once(");".to_string()))
+                .collect()
+        };
+        instance.extend(lines);
+        instance
+    }
+    fn dynamic_splice(&mut self, ast: &DynamicSplice) -> Vec<String> {
+        let mut lines = self.assignment(&ast.lhs, &ast.arg);
+        let offset = self.expression(&ast.offset).text;
+        let value = fit(self.expression(&ast.value), SignedWidth::Unsigned(ast.len));
+        lines.push(format!(
+            "{}(to_integer({offset}) + {} downto to_integer({offset})) {} {value};",
+            self.name(&ast.lhs).text,
+            ast.len as isize - 1,
+            self.assign_op(&ast.lhs)
+        ));
+        lines
+    }
+    fn splice(&mut self, ast: &Splice) -> Vec<String> {
+        let mut lines = self.assignment(&ast.target, &ast.source);
+        let value = fit(
+            self.expression(&ast.value),
+            SignedWidth::Unsigned(ast.replace_range.len()),
+        );
+        let value = match self.symbols.get(&ast.target) {
+            Some(symbol) if symbol.width.is_signed() => format!("signed({value})"),
+            _ => value,
+        };
+        lines.push(format!(
+            "{}({} downto {}) {} {value};",
+            self.name(&ast.target).text,
+            ast.replace_range.end as isize - 1,
+            ast.replace_range.start,
+            self.assign_op(&ast.target)
+        ));
+        lines
+    }
+    fn case(&mut self, ast: &Case) -> Vec<String> {
+        let mut lines = vec![format!(
+            "case {} is",
+            self.expression(&ast.discriminant).text
+        )];
+        for (item, statement) in &ast.cases {
+            let choice = match item {
+                CaseItem::Literal(literal) => bits_literal(literal.bits()),
+                CaseItem::Wild => "others".into(),
+            };
+            lines.push(format!("    when {choice} =>"));
+            lines.extend(indent(indent(self.statement(statement)).collect()));
+        }
+        if !ast
+            .cases
+            .iter()
+            .any(|(item, _)| matches!(item, CaseItem::Wild))
+        {
+            lines.push("    when others =>".into());
+            lines.push("        null;".into());
+        }
+        lines.push("end case;".into());
+        lines
+    }
+    fn if_statement(&mut self, ast: &If) -> Vec<String> {
+        let mut lines = vec![format!("if {} then", self.condition(&ast.condition))];
+        lines.extend(indent(self.statements(&ast.true_expr)));
+        if !ast.false_expr.is_empty() {
+            lines.push("else".into());
+            lines.extend(indent(self.statements(&ast.false_expr)));
+        }
+        lines.push("end if;".into());
+        lines
+    }
+    fn always(&mut self, ast: &Always) -> Vec<String> {
+        let mut sensitivity = vec![];
+        let mut edges = vec![];
+        for event in &ast.sensitivity {
+            match event {
+                Events::Posedge(signal) => {
+                    sensitivity.push(self.name(signal).text);
+                    edges.push(format!("rising_edge({}(0))", self.name(signal).text));
+                }
+                Events::Negedge(signal) => {
+                    sensitivity.push(self.name(signal).text);
+                    edges.push(format!("falling_edge({}(0))", self.name(signal).text));
+                }
+                Events::Change(signal) => sensitivity.push(self.name(signal).text),
+                Events::Star => sensitivity.push("all".into()),
+            }
+        }
+        let edge = (!edges.is_empty()).then(|| edges.join(" or "));
+        self.process(Some(sensitivity), edge, &ast.block)
+    }
+    // A process with the given sensitivity list, or one that runs once
+    // (for an initial block) if there is none.  The statements of a
+    // clocked process are only run on the given edge.
+    fn process(
+        &mut self,
+        sensitivity: Option<Vec<String>>,
+        edge: Option<String>,
+        block: &[Statement],
+    ) -> Vec<String> {
+        let saved = std::mem::take(&mut self.temporaries);
+        self.sequential = true;
+        let mut body = self.statements(block);
+        if let Some(edge) = edge {
+            body = std::iter::once(format!("if {edge} then"))
+                .chain(indent(body))
+                .chain(std::iter::once("end if;".to_string()))
+                .collect();
+        }
+        self.sequential = false;
+        let temporaries = std::mem::replace(&mut self.temporaries, saved);
+        let mut lines = match &sensitivity {
+            Some(sensitivity) => vec![format!("process ({}) is", sensitivity.join(", "))],
+            None => vec!["process is".into()],
+        };
+        lines.extend(variables(&temporaries));
+        lines.push("begin".into());
+        if sensitivity.is_none() {
+            body.push("wait;".into());
+        }
+        lines.extend(indent(body));
+        lines.push("end process;".into());
+        lines
+    }
+    fn display(&self, ast: &Display) -> Vec<String> {
+        let mut args = ast.args.iter();
+        let mut message = vec![];
+        let mut text = String::new();
+        let mut chars = ast.format.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                if c == '"' {
+                    text.push('"');
+                }
+                text.push(c);
+                continue;
+            }
+            let mut spec = String::new();
+            while let Some(d) = chars.next_if(|d| d.is_ascii_digit()) {
+                spec.push(d);
+            }
+            let Some(format) = chars.next() else {
+                break;
+            };
+            if format == '%' {
+                text.push('%');
+                continue;
+            }
+            message.push(format!("\"{}\"", std::mem::take(&mut text)));
+            if let Some(arg) = args.next() {
+                let arg = self.expression(arg).unsigned().text;
+                message.push(match format {
+                    'd' => format!("integer'image(to_integer({arg}))"),
+                    'b' => format!("to_string({arg})"),
+                    _ => format!("to_hstring({arg})"),
+                });
+            }
+        }
+        message.push(format!("\"{text}\""));
+        vec![format!("report {};", message.join(" & "))]
+    }
+    fn assert(&self, ast: &Assert) -> Vec<String> {
+        let left = self.expression(&ast.left).unsigned();
+        let right = self.expression(&ast.right).unsigned();
+        let right = match left.ty {
+            Some(ty) => fit(right, ty),
+            None => right.text,
+        };
+        let left = left.text;
+        vec![
+            format!("assert std_logic_vector({left}) = std_logic_vector({right})"),
+            format!(
+                "    report \"ASSERTION FAILED 0x\" & to_hstring({left}) & \" !== 0x\" & to_hstring({right}) & \" CASE {}\"",
+                ast.cause
+            ),
+            "    severity failure;".into(),
+        ]
+    }
+}
+
+fn variables(temporaries: &[(String, SignedWidth)]) -> impl Iterator<Item = String> + '_ {
+    temporaries
+        .iter()
+        .map(|(name, width)| format!("    variable {name} : {};", vector_type(width)))
+}
+
+fn comment(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| format!("-- {line}").trim_end().to_string())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .chain(text.is_empty().then(|| "--".to_string()))
+        .collect()
+}
+
+fn declaration_comment(decl: &Declaration) -> String {
+    decl.alias
+        .as_ref()
+        .map(|alias| format!(" -- {}", alias.replace('\n', " ")))
+        .unwrap_or_default()
+}
+
+fn function_text(context: &mut Context, ast: &Function) -> Vec<String> {
+    let mut scope = Context::new(context.entities);
+    scope.functions = context.functions.clone();
+    scope.counter = context.counter;
+    scope.sequential = true;
+    for arg in &ast.arguments {
+        scope.declare(&arg.name, arg.width, Class::Constant);
+    }
+    for reg in &ast.registers {
+        scope.declare(&reg.name, reg.width, Class::Variable);
+    }
+    for lit in &ast.literals {
+        scope.declare(&lit.name, (&lit.value).into(), Class::Constant);
+    }
+    scope.symbols.insert(
+        ast.name.clone(),
+        Symbol {
+            name: "rhdl_result".into(),
+            width: ast.width,
+            class: Class::Variable,
+        },
+    );
+    let body = scope.statements(&ast.block);
+    context.counter = scope.counter;
+    let name = ident(&ast.name);
+    let base = if ast.width.is_signed() {
+        "signed"
+    } else {
+        "unsigned"
+    };
+    let mut lines = if ast.arguments.is_empty() {
+        vec![format!("function {name} return {base} is")]
+    } else {
+        let count = ast.arguments.len();
+        std::iter::once(format!("function {name}("))
+            .chain(ast.arguments.iter().enumerate().map(|(ndx, arg)| {
+                let sep = if ndx + 1 < count { ";" } else { "" };
+                format!(
+                    "    {} : {}{sep}",
+                    ident(&arg.name),
+                    vector_type(&arg.width)
+                )
+            }))
+            .chain(std::iter::once(format!(") return {base} is")))
+            .collect()
+    };
+    lines.extend(ast.registers.iter().map(|reg| {
+        format!(
+            "    variable {} : {};{}",
+            ident(&reg.name),
+            vector_type(&reg.width),
+            declaration_comment(reg)
+        )
+    }));
+    lines.extend(ast.literals.iter().map(|lit| {
+        format!(
+            "    constant {} : {} := {};",
+            ident(&lit.name),
+            vector_type(&(&lit.value).into()),
+            bits_literal(lit.value.bits())
+        )
+    }));
+    lines.push(format!(
+        "    variable rhdl_result : {};",
+        vector_type(&ast.width)
+    ));
+    lines.extend(variables(&scope.temporaries));
+    lines.push("begin".into());
+    lines.extend(indent(body));
+    lines.push("    return rhdl_result;".into());
+    lines.push(format!("end function {name};"));
+    lines
+}
+
+// Initial blocks that only assign constants are turned into initial
+// values.  Returns the initial values of the signals, and of the
+// elements of the memories.
+#[derive(Default)]
+struct InitialValues {
+    signals: HashMap<String, BitString>,
+    elements: HashMap<String, Vec<(usize, BitString)>>,
+    folded: HashSet<usize>,
+}
+
+fn initial_values(ast: &Module) -> InitialValues {
+    let mut values = InitialValues::default();
+    for (ndx, statement) in ast.statements.iter().enumerate() {
+        let Statement::Initial(initial) = statement else {
+            continue;
+        };
+        let constants = initial
+            .block
+            .iter()
+            .map(|statement| match statement {
+                Statement::Assignment(assign) => match &*assign.source {
+                    Expression::Literal(value) => {
+                        Some((parse_target(&assign.target), value.clone()))
+                    }
+                    Expression::Const(value) => Some((
+                        parse_target(&assign.target),
+                        BitString::unsigned(vec![*value]),
+                    )),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some(constants) = constants else {
+            continue;
+        };
+        let constants = constants
+            .into_iter()
+            .map(|(target, value)| match target {
+                Target::Name(name) => Some((name, None, value)),
+                Target::Element(name, address) => {
+                    Some((name, Some(address.parse::<usize>().ok()?), value))
+                }
+                Target::Concat(_) => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some(constants) = constants else {
+            continue;
+        };
+        values.folded.insert(ndx);
+        for (name, address, value) in constants {
+            match address {
+                Some(address) => values
+                    .elements
+                    .entry(name)
+                    .or_default()
+                    .push((address, value)),
+                None => {
+                    values.signals.insert(name, value);
+                }
+            }
+        }
+    }
+    values
+}
+
+fn initial_value(value: Option<&BitString>, width: &SignedWidth) -> String {
+    value
+        .map(|value| format!(" := {}", fit(bit_string(value), *width)))
+        .unwrap_or_default()
+}
+
+fn port(ast: &Port, values: &InitialValues) -> String {
+    let direction = match ast.direction {
+        Direction::Input => "in",
+        Direction::Output => "out",
+        Direction::Inout => "inout",
+    };
+    format!(
+        "{} : {direction} {}{}",
+        ident(&ast.name),
+        vector_type(&ast.width),
+        initial_value(values.signals.get(&ast.name), &ast.width)
+    )
+}
+
+// Verilog memories are declared as `name[hi:lo]`
+fn memory_declaration(decl: &Declaration) -> Option<(String, usize, usize)> {
+    let (name, range) = decl.name.split_once('[')?;
+    let (hi, lo) = range.strip_suffix(']')?.split_once(':')?;
+    let hi = hi.trim().parse::<usize>().ok()?;
+    let lo = lo.trim().parse::<usize>().ok()?;
+    Some((name.trim().to_string(), hi.min(lo), hi.max(lo)))
+}
+
+fn declaration(ast: &Declaration, values: &InitialValues) -> Vec<String> {
+    let comment = declaration_comment(ast);
+    if let Some((name, lo, hi)) = memory_declaration(ast) {
+        let type_name = ident(&format!("{name}_type"));
+        let elements = values
+            .elements
+            .get(&name)
+            .map(|elements| {
+                let mut entries = elements
+                    .iter()
+                    .map(|(address, value)| {
+                        format!("{address} => {}", fit(bit_string(value), ast.width))
+                    })
+                    .collect::<Vec<_>>();
+                entries.push("others => (others => 'X')".into());
+                format!(" := ({})", entries.join(", "))
+            })
+            .unwrap_or_default();
+        return vec![
+            format!(
+                "type {type_name} is array ({lo} to {hi}) of {};",
+                vector_type(&ast.width)
+            ),
+            format!("signal {} : {type_name}{elements};{comment}", ident(&name)),
+        ];
+    }
+    vec![format!(
+        "signal {} : {}{};{comment}",
+        ident(&ast.name),
+        vector_type(&ast.width),
+        initial_value(values.signals.get(&ast.name), &ast.width)
+    )]
+}
+
+fn entity(ast: &Module, entities: &HashMap<String, &Module>) -> String {
+    let mut context = Context::new(entities);
+    let values = initial_values(ast);
+    for port in &ast.ports {
+        context.declare(&port.name, port.width, Class::Signal);
+    }
+    for decl in &ast.declarations {
+        match memory_declaration(decl) {
+            Some((name, _, _)) => context.declare(&name, decl.width, Class::Memory),
+            None => context.declare(&decl.name, decl.width, Class::Signal),
+        }
+    }
+    context.functions = ast
+        .functions
+        .iter()
+        .map(|func| (func.name.clone(), func))
+        .collect();
+    let name = ident(&ast.name);
+    let mut lines = if ast.description.is_empty() {
+        vec![]
+    } else {
+        comment(&ast.description)
+    };
+    lines.extend(CONTEXT.lines().map(String::from));
+    lines.push(String::new());
+    lines.push(format!("entity {name} is"));
+    if !ast.ports.is_empty() {
+        let count = ast.ports.len();
+        lines.push("    port (".into());
+        lines.extend(ast.ports.iter().enumerate().map(|(ndx, x)| {
+            let sep = if ndx + 1 < count { ";" } else { "" };
+            format!("        {}{sep}", port(x, &values))
+        }));
+        lines.push("    );".into());
+    }
+    lines.push(format!("end entity {name};"));
+    lines.push(String::new());
+    let mut functions = vec![];
+    for func in &ast.functions {
+        functions.extend(function_text(&mut context, func));
+    }
+    let statements = ast
+        .statements
+        .iter()
+        .enumerate()
+        .filter(|(ndx, _)| !values.folded.contains(ndx))
+        .flat_map(|(_, statement)| {
+            let mut lines = context.statement(statement);
+            if !matches!(statement, Statement::Comment(_)) {
+                lines.push(String::new());
+            }
+            lines
+        })
+        .collect::<Vec<_>>();
+    let statements = statements
+        .strip_suffix(&[String::new()])
+        .unwrap_or(&statements)
+        .to_vec();
+    lines.push(format!("architecture rtl of {name} is"));
+    lines.extend(indent(
+        ast.declarations
+            .iter()
+            .flat_map(|decl| declaration(decl, &values))
+            .collect(),
+    ));
+    lines.extend(
+        context
+            .temporaries
+            .iter()
+            .map(|(name, width)| format!("    signal {name} : {};", vector_type(width))),
+    );
+    lines.extend(indent(functions));
+    lines.push("begin".into());
+    lines.extend(indent(statements));
+    lines.push("end architecture rtl;".into());
+    lines
+        .into_iter()
+        .map(|line| line.trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+// The modules in the order they must be analyzed (the submodules
+// before the modules that instantiate them).
+fn collect<'a>(ast: &'a Module, order: &mut Vec<&'a Module>) {
+    for submodule in &ast.submodules {
+        collect(submodule, order);
+    }
+    if !order.iter().any(|module| module.name == ast.name) {
+        order.push(ast);
+    }
+}
+
+// Find any `Custom` statements, which have no VHDL equivalent
+fn check_statements(ast: &[Statement]) -> Result<(), RHDLError> {
+    for statement in ast {
+        match statement {
+            Statement::Custom(ast) => {
+                return Err(RHDLError::VhdlExportError(format!(
+                    "the Verilog statement `{ast}` has no VHDL equivalent"
+                )));
+            }
+            Statement::Initial(ast) => check_statements(&ast.block)?,
+            Statement::Always(ast) => check_statements(&ast.block)?,
+            Statement::If(ast) => {
+                check_statements(&ast.true_expr)?;
+                check_statements(&ast.false_expr)?;
+            }
+            Statement::Case(ast) => {
+                for (_, statement) in &ast.cases {
+                    check_statements(std::slice::from_ref(statement))?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn check_module(ast: &Module) -> Result<(), RHDLError> {
+    check_statements(&ast.statements)?;
+    for function in &ast.functions {
+        check_statements(&function.block)?;
+    }
+    Ok(())
+}
+
+pub fn function(ast: &Function) -> Result<String, RHDLError> {
+    check_statements(&ast.block)?;
+    let entities = HashMap::new();
+    let mut context = Context::new(&entities);
+    context.functions.insert(ast.name.clone(), ast);
+    Ok(function_text(&mut context, ast).join("\n") + "\n")
+}
+
+pub fn module(ast: &Module) -> Result<String, RHDLError> {
+    let mut order = vec![];
+    collect(ast, &mut order);
+    for module in &order {
+        check_module(module)?;
+    }
+    let entities = order
+        .iter()
+        .map(|module| (module.name.clone(), *module))
+        .collect::<HashMap<_, _>>();
+    Ok(std::iter::once(PACKAGE.to_string())
+        .chain(order.iter().map(|module| entity(module, &entities)))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
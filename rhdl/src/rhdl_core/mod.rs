@@ -57,7 +57,9 @@ pub use error::RHDLError;
 //pub mod flow_graph;
 pub mod rtl;
 pub use circuit::circuit_descriptor::build_descriptor;
+pub use circuit::circuit_descriptor::build_descriptor_with;
 pub use circuit::circuit_descriptor::build_synchronous_descriptor;
+pub use circuit::circuit_descriptor::build_synchronous_descriptor_with;
 pub use circuit::hdl_backend::build_hdl;
 pub use circuit::hdl_backend::build_synchronous_hdl;
 pub use compiler::CompilationMode;
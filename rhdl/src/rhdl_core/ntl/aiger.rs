@@ -0,0 +1,257 @@
+//! Export of a netlist as an And-Inverter Graph in the ASCII AIGER
+//! format (`aag`), for use with ABC and hardware model checkers.
+//!
+//! The netlist is first lowered to single bit gates with the
+//! [LowerToGates](crate::rhdl_core::compiler::LowerToGates) pass.  The
+//! gates then become and gates with inverted inputs and outputs, and the
+//! register banks become latches.  AIGER has a single implicit clock, so
+//! the clock input is left unconnected, and each latch loads its reset
+//! value when the reset input is asserted.  The latches start with their
+//! reset value (or uninitialized if they have none).  Black boxes other
+//! than register banks cannot be exported.
+//!
+//! The inputs and outputs are named in the symbol table as in the Verilog
+//! output of the netlist (`arg_0[0]`, ... and `out[0]`, ...).
+//! ```ignore
+//! let aag = ntl::aiger::netlist_aiger(&uut.descriptor("top")?.ntl)?;
+//! std::fs::write("top.aag", aag)?;
+//! ```
+use std::collections::HashMap;
+
+use crate::{
+    prelude::RHDLError,
+    rhdl_core::{
+        BitX,
+        ast::source::source_location::SourceLocation,
+        common::symtab::RegisterId,
+        compiler::{LowerToGates, NtlPipeline},
+        error::rhdl_error,
+        ntl::{
+            Object,
+            error::{NetListError, NetListICE},
+            spec::{self, BinaryOp, OpCode, Wire, WireKind},
+        },
+    },
+};
+
+// A literal is twice the variable index, plus one if it is inverted
+const FALSE: usize = 0;
+const TRUE: usize = 1;
+
+struct Latch {
+    lit: usize,
+    next: usize,
+    init: Option<BitX>,
+}
+
+struct AigerBuilder<'a> {
+    ntl: &'a Object,
+    first_and: usize,
+    lits: HashMap<RegisterId<WireKind>, usize>,
+    ands: Vec<(usize, usize)>,
+    latches: Vec<Latch>,
+}
+
+impl<'a> AigerBuilder<'a> {
+    fn new(ntl: &'a Object) -> Self {
+        let mut lits = HashMap::new();
+        let mut var = 1;
+        for reg in ntl.inputs.iter().flatten() {
+            lits.insert(*reg, 2 * var);
+            var += 1;
+        }
+        // The latches are numbered after the inputs, so they can be read
+        // before the op that drives them
+        for lop in &ntl.ops {
+            if let OpCode::BlackBox(black_box) = &lop.op
+                && ntl.black_boxes[black_box.code.raw()].register.is_some()
+            {
+                for reg in black_box.lhs.iter().filter_map(|wire| wire.reg()) {
+                    lits.insert(reg, 2 * var);
+                    var += 1;
+                }
+            }
+        }
+        Self {
+            ntl,
+            first_and: var,
+            lits,
+            ands: vec![],
+            latches: vec![],
+        }
+    }
+    fn raise_ice(&self, cause: NetListICE, location: Option<SourceLocation>) -> RHDLError {
+        rhdl_error(NetListError {
+            cause,
+            src: self.ntl.code.source(),
+            elements: location
+                .map(|loc| self.ntl.code.span(loc).into())
+                .into_iter()
+                .collect(),
+        })
+    }
+    fn lit(&self, wire: Wire, location: Option<SourceLocation>) -> Result<usize, RHDLError> {
+        match wire {
+            Wire::Literal(lid) => Ok(match self.ntl.symtab[lid] {
+                BitX::One => TRUE,
+                BitX::Zero | BitX::X => FALSE,
+            }),
+            Wire::Register(rid) => self
+                .lits
+                .get(&rid)
+                .copied()
+                .ok_or_else(|| self.raise_ice(NetListICE::UndrivenNetlistNode, location)),
+        }
+    }
+    fn define(
+        &mut self,
+        wire: Wire,
+        lit: usize,
+        location: Option<SourceLocation>,
+    ) -> Result<(), RHDLError> {
+        let Some(reg) = wire.reg() else {
+            return Err(self.raise_ice(NetListICE::ExpectedRegisterNotConstant, location));
+        };
+        self.lits.insert(reg, lit);
+        Ok(())
+    }
+    fn and(&mut self, a: usize, b: usize) -> usize {
+        if a == FALSE || b == FALSE || a == b ^ 1 {
+            return FALSE;
+        }
+        if a == TRUE || a == b {
+            return b;
+        }
+        if b == TRUE {
+            return a;
+        }
+        self.ands.push((a, b));
+        2 * (self.first_and + self.ands.len() - 1)
+    }
+    fn or(&mut self, a: usize, b: usize) -> usize {
+        self.and(a ^ 1, b ^ 1) ^ 1
+    }
+    fn mux(&mut self, s: usize, t: usize, f: usize) -> usize {
+        let t = self.and(s, t);
+        let f = self.and(s ^ 1, f);
+        self.or(t, f)
+    }
+    fn black_box_op(
+        &mut self,
+        op: &spec::BlackBox,
+        location: Option<SourceLocation>,
+    ) -> Result<(), RHDLError> {
+        let bb_core = &self.ntl.black_boxes[op.code.raw()];
+        let (Some(register), [clock_reset, d]) = (&bb_core.register, op.arg.as_slice()) else {
+            return Err(self.raise_ice(
+                NetListICE::UnsupportedBlackBox(bb_core.code.name.clone()),
+                location,
+            ));
+        };
+        let reset = self.lit(clock_reset[1], location)?;
+        for (ndx, (q, d)) in op.lhs.iter().zip(d).enumerate() {
+            let lit = self.lit(*q, location)?;
+            let d = self.lit(*d, location)?;
+            let init = register.reset.as_ref().map(|value| value[ndx]);
+            let next = match init {
+                Some(BitX::One) => self.or(reset, d),
+                Some(BitX::Zero) => self.and(reset ^ 1, d),
+                _ => d,
+            };
+            self.latches.push(Latch { lit, next, init });
+        }
+        Ok(())
+    }
+    fn op_code(&mut self, op: &OpCode, location: Option<SourceLocation>) -> Result<(), RHDLError> {
+        match op {
+            OpCode::Noop | OpCode::Comment(_) => {}
+            OpCode::Assign(assign) => {
+                let rhs = self.lit(assign.rhs, location)?;
+                self.define(assign.lhs, rhs, location)?;
+            }
+            OpCode::Binary(binary) => {
+                let a = self.lit(binary.arg1, location)?;
+                let b = self.lit(binary.arg2, location)?;
+                let y = match binary.op {
+                    BinaryOp::And => self.and(a, b),
+                    BinaryOp::Or => self.or(a, b),
+                    BinaryOp::Xor => self.mux(a, b ^ 1, b),
+                };
+                self.define(binary.lhs, y, location)?;
+            }
+            OpCode::Not(not) => {
+                let a = self.lit(not.arg, location)?;
+                self.define(not.lhs, a ^ 1, location)?;
+            }
+            OpCode::Select(select) => {
+                let s = self.lit(select.selector, location)?;
+                let t = self.lit(select.true_case, location)?;
+                let f = self.lit(select.false_case, location)?;
+                let y = self.mux(s, t, f);
+                self.define(select.lhs, y, location)?;
+            }
+            OpCode::BlackBox(black_box) => self.black_box_op(black_box, location)?,
+            OpCode::Vector(_) | OpCode::Case(_) | OpCode::Rom(_) | OpCode::Unary(_) => {
+                return Err(self.raise_ice(NetListICE::UnloweredWordOp, location));
+            }
+        }
+        Ok(())
+    }
+    fn build(mut self) -> Result<String, RHDLError> {
+        let ntl = self.ntl;
+        for lop in &ntl.ops {
+            self.op_code(&lop.op, lop.loc)?;
+        }
+        let outputs = ntl
+            .outputs
+            .iter()
+            .map(|wire| self.lit(*wire, None))
+            .collect::<Result<Vec<_>, _>>()?;
+        let inputs = ntl.inputs.iter().flatten().count();
+        let mut aag = format!(
+            "aag {} {} {} {} {}\n",
+            self.first_and + self.ands.len() - 1,
+            inputs,
+            self.latches.len(),
+            outputs.len(),
+            self.ands.len()
+        );
+        for var in 1..=inputs {
+            aag.push_str(&format!("{}\n", 2 * var));
+        }
+        for latch in &self.latches {
+            match latch.init {
+                Some(BitX::Zero) => aag.push_str(&format!("{} {}\n", latch.lit, latch.next)),
+                Some(BitX::One) => aag.push_str(&format!("{} {} 1\n", latch.lit, latch.next)),
+                // An init value equal to the latch itself means uninitialized
+                _ => aag.push_str(&format!("{} {} {}\n", latch.lit, latch.next, latch.lit)),
+            }
+        }
+        for output in &outputs {
+            aag.push_str(&format!("{output}\n"));
+        }
+        for (ndx, (a, b)) in self.ands.iter().enumerate() {
+            aag.push_str(&format!("{} {a} {b}\n", 2 * (self.first_and + ndx)));
+        }
+        let mut ndx = 0;
+        for (arg, regs) in ntl.inputs.iter().enumerate() {
+            for bit in 0..regs.len() {
+                aag.push_str(&format!("i{ndx} arg_{arg}[{bit}]\n"));
+                ndx += 1;
+            }
+        }
+        for bit in 0..outputs.len() {
+            aag.push_str(&format!("o{bit} out[{bit}]\n"));
+        }
+        aag.push_str(&format!("c\n{}\n", ntl.name));
+        Ok(aag)
+    }
+}
+
+/// Lower the netlist to gates and write it in the ASCII AIGER format.
+pub fn netlist_aiger(ntl: &Object) -> Result<String, RHDLError> {
+    let ntl = NtlPipeline::default()
+        .before_fixpoint(LowerToGates::default())
+        .run(ntl.clone())?;
+    AigerBuilder::new(&ntl).build()
+}
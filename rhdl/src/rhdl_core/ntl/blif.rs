@@ -0,0 +1,252 @@
+//! Export of a netlist in the Berkeley Logic Interchange Format (BLIF),
+//! for use with ABC and other logic synthesis tools.
+//!
+//! The netlist is first lowered to single bit gates with the
+//! [LowerToGates](crate::rhdl_core::compiler::LowerToGates) pass.  Each
+//! gate then becomes a `.names` cover.  Register banks become rising edge
+//! `.latch`es on the clock input, with a cover in front of them that loads
+//! the reset value when the reset input is asserted.  Other black boxes
+//! become a `.subckt` of a `.blackbox` model with the name of the black box.
+//!
+//! The ports of the model are named as in the Verilog output of the
+//! netlist (`arg_0[0]`, ... and `out[0]`, ...).
+//! ```ignore
+//! let blif = ntl::blif::netlist_blif("top", &uut.descriptor("top")?.ntl)?;
+//! std::fs::write("top.blif", blif)?;
+//! ```
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    prelude::RHDLError,
+    rhdl_core::{
+        BitX,
+        ast::source::source_location::SourceLocation,
+        common::symtab::RegisterId,
+        compiler::{LowerToGates, NtlPipeline},
+        error::rhdl_error,
+        ntl::{
+            Object,
+            error::{NetListError, NetListICE},
+            object::BlackBoxMode,
+            spec::{self, BinaryOp, OpCode, Wire, WireKind},
+        },
+    },
+};
+
+// The nets that carry constant values
+const FALSE: &str = "$false";
+const TRUE: &str = "$true";
+
+struct BlifBuilder<'a> {
+    ntl: &'a Object,
+    // The input ports are named after the port, and not the register
+    names: HashMap<RegisterId<WireKind>, String>,
+    body: Vec<String>,
+    models: BTreeMap<String, String>,
+}
+
+impl<'a> BlifBuilder<'a> {
+    fn new(ntl: &'a Object) -> Self {
+        let names = ntl
+            .inputs
+            .iter()
+            .enumerate()
+            .flat_map(|(arg, regs)| {
+                regs.iter()
+                    .enumerate()
+                    .map(move |(bit, reg)| (*reg, format!("arg_{arg}[{bit}]")))
+            })
+            .collect();
+        Self {
+            ntl,
+            names,
+            body: vec![],
+            models: BTreeMap::new(),
+        }
+    }
+    fn raise_ice(&self, cause: NetListICE, location: Option<SourceLocation>) -> RHDLError {
+        rhdl_error(NetListError {
+            cause,
+            src: self.ntl.code.source(),
+            elements: location
+                .map(|loc| self.ntl.code.span(loc).into())
+                .into_iter()
+                .collect(),
+        })
+    }
+    fn net(&self, wire: Wire) -> String {
+        match wire {
+            Wire::Literal(lid) => match self.ntl.symtab[lid] {
+                BitX::One => TRUE.into(),
+                BitX::Zero | BitX::X => FALSE.into(),
+            },
+            Wire::Register(rid) => self
+                .names
+                .get(&rid)
+                .cloned()
+                .unwrap_or_else(|| rid.to_string()),
+        }
+    }
+    // A single output cover, with one line per cube of the on set
+    fn names(&mut self, args: &[Wire], lhs: &str, cubes: &[&str]) {
+        let mut names = String::from(".names");
+        for arg in args {
+            names.push(' ');
+            names.push_str(&self.net(*arg));
+        }
+        names.push(' ');
+        names.push_str(lhs);
+        for cube in cubes {
+            names.push('\n');
+            if !args.is_empty() {
+                names.push_str(cube);
+                names.push(' ');
+            }
+            names.push('1');
+        }
+        self.body.push(names);
+    }
+    fn black_box_op(&mut self, op: &spec::BlackBox) {
+        let bb_core = &self.ntl.black_boxes[op.code.raw()];
+        if let (Some(register), [clock_reset, d]) = (&bb_core.register, op.arg.as_slice()) {
+            let clock = self.net(clock_reset[0]);
+            for (ndx, (q, d)) in op.lhs.iter().zip(d).enumerate() {
+                let q = self.net(*q);
+                let next = format!("{q}$next");
+                let init = register.reset.as_ref().map(|value| value[ndx]);
+                let cubes: &[&str] = match init {
+                    Some(BitX::One) => &["1-", "-1"],
+                    Some(BitX::Zero) => &["01"],
+                    _ => &["-1"],
+                };
+                self.names(&[clock_reset[1], *d], &next, cubes);
+                let init = match init {
+                    Some(BitX::Zero) => 0,
+                    Some(BitX::One) => 1,
+                    _ => 3,
+                };
+                self.body
+                    .push(format!(".latch {next} {q} re {clock} {init}"));
+            }
+            return;
+        }
+        let names: &[&str] = match bb_core.mode {
+            BlackBoxMode::Asynchronous => &["i"],
+            BlackBoxMode::Synchronous => &["clock_reset", "i"],
+        };
+        let bit_ports = |name: &str, wires: &[Wire]| {
+            wires
+                .iter()
+                .enumerate()
+                .map(|(bit, wire)| (format!("{name}[{bit}]"), *wire))
+                .collect::<Vec<_>>()
+        };
+        let inputs = names
+            .iter()
+            .zip(&op.arg)
+            .flat_map(|(name, wires)| bit_ports(name, wires))
+            .collect::<Vec<_>>();
+        let outputs = bit_ports("o", &op.lhs);
+        let model = bb_core.code.name.clone();
+        self.models.entry(model.clone()).or_insert_with(|| {
+            let ports = |ports: &[(String, Wire)]| {
+                ports
+                    .iter()
+                    .map(|(port, _)| port.clone())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            format!(
+                ".model {model}\n.inputs {}\n.outputs {}\n.blackbox\n.end\n",
+                ports(&inputs),
+                ports(&outputs)
+            )
+        });
+        let connections = inputs
+            .iter()
+            .chain(&outputs)
+            .map(|(port, wire)| format!("{port}={}", self.net(*wire)))
+            .collect::<Vec<_>>();
+        self.body
+            .push(format!(".subckt {model} {}", connections.join(" ")));
+    }
+    fn op_code(&mut self, op: &OpCode, location: Option<SourceLocation>) -> Result<(), RHDLError> {
+        match op {
+            OpCode::Noop | OpCode::Comment(_) => {}
+            OpCode::Assign(assign) => {
+                let lhs = self.net(assign.lhs);
+                self.names(&[assign.rhs], &lhs, &["1"]);
+            }
+            OpCode::Binary(binary) => {
+                let lhs = self.net(binary.lhs);
+                let cubes: &[&str] = match binary.op {
+                    BinaryOp::And => &["11"],
+                    BinaryOp::Or => &["1-", "-1"],
+                    BinaryOp::Xor => &["10", "01"],
+                };
+                self.names(&[binary.arg1, binary.arg2], &lhs, cubes);
+            }
+            OpCode::Not(not) => {
+                let lhs = self.net(not.lhs);
+                self.names(&[not.arg], &lhs, &["0"]);
+            }
+            OpCode::Select(select) => {
+                let lhs = self.net(select.lhs);
+                self.names(
+                    &[select.selector, select.true_case, select.false_case],
+                    &lhs,
+                    &["11-", "0-1"],
+                );
+            }
+            OpCode::BlackBox(black_box) => self.black_box_op(black_box),
+            OpCode::Vector(_) | OpCode::Case(_) | OpCode::Rom(_) | OpCode::Unary(_) => {
+                return Err(self.raise_ice(NetListICE::UnloweredWordOp, location));
+            }
+        }
+        Ok(())
+    }
+    fn build(mut self, module_name: &str) -> Result<String, RHDLError> {
+        let ntl = self.ntl;
+        let inputs = ntl
+            .inputs
+            .iter()
+            .flatten()
+            .map(|reg| self.names[reg].clone())
+            .collect::<Vec<_>>();
+        let outputs = (0..ntl.outputs.len())
+            .map(|bit| format!("out[{bit}]"))
+            .collect::<Vec<_>>();
+        self.body.push(format!(".names {FALSE}"));
+        self.body.push(format!(".names {TRUE}\n1"));
+        for lop in &ntl.ops {
+            self.op_code(&lop.op, lop.loc)?;
+        }
+        for (output, wire) in outputs.iter().zip(&ntl.outputs) {
+            self.names(&[*wire], output, &["1"]);
+        }
+        let mut blif = format!(".model {module_name}\n");
+        if !inputs.is_empty() {
+            blif.push_str(&format!(".inputs {}\n", inputs.join(" ")));
+        }
+        blif.push_str(&format!(".outputs {}\n", outputs.join(" ")));
+        for line in &self.body {
+            blif.push_str(line);
+            blif.push('\n');
+        }
+        blif.push_str(".end\n");
+        for model in self.models.values() {
+            blif.push('\n');
+            blif.push_str(model);
+        }
+        Ok(blif)
+    }
+}
+
+/// Lower the netlist to gates and write it as a BLIF model with the given
+/// name, followed by a `.blackbox` model for each kind of black box it uses.
+pub fn netlist_blif(module_name: &str, ntl: &Object) -> Result<String, RHDLError> {
+    let ntl = NtlPipeline::default()
+        .before_fixpoint(LowerToGates::default())
+        .run(ntl.clone())?;
+    BlifBuilder::new(&ntl).build(module_name)
+}
@@ -7,9 +7,9 @@ use crate::{
     prelude::{RHDLError, Synchronous},
     rhdl_core::{
         ast::source::spanned_source_set::SpannedSourceSet,
-        compiler::optimize_ntl,
+        compiler::NtlPipeline,
         ntl::{
-            object::{BlackBox, BlackBoxMode, LocatedOpCode, Object},
+            object::{BlackBox, BlackBoxMode, LocatedOpCode, Object, RegisterBank},
             spec::{OpCode, Wire},
         },
     },
@@ -65,7 +65,15 @@ impl Builder {
         self.object.outputs = ret.clone();
         ret.into_iter().flat_map(Wire::reg).collect()
     }
-    pub fn build(mut self, mode: BuilderMode) -> Result<Object, RHDLError> {
+    pub fn build(self, mode: BuilderMode) -> Result<Object, RHDLError> {
+        self.build_with(mode, &NtlPipeline::default())
+    }
+    // Build the netlist, and optimize it with the given pipeline
+    pub fn build_with(
+        mut self,
+        mode: BuilderMode,
+        pipeline: &NtlPipeline,
+    ) -> Result<Object, RHDLError> {
         match mode {
             BuilderMode::Asynchronous => {
                 if self.object.inputs.is_empty() {
@@ -79,7 +87,7 @@ impl Builder {
                 }
             }
         }
-        optimize_ntl(self.object)
+        pipeline.run(self.object)
     }
     pub fn import(&mut self, other: &Object) -> impl Fn(Wire) -> Wire + use<> {
         self.add_code(&other.code);
@@ -104,6 +112,7 @@ pub fn circuit_black_box<C: Circuit>(circuit: &C, name: &str) -> Result<Object,
     builder.object.black_boxes.push(BlackBox {
         code: hdl,
         mode: BlackBoxMode::Asynchronous,
+        register: None,
     });
     let arg0 = arg0.into_iter().map(Wire::Register).collect();
     let lhs = out.iter().copied().map(Wire::Register).collect();
@@ -119,6 +128,27 @@ pub fn circuit_black_box<C: Circuit>(circuit: &C, name: &str) -> Result<Object,
 }
 
 pub fn synchronous_black_box<S: Synchronous>(circuit: &S, name: &str) -> Result<Object, RHDLError> {
+    synchronous_box(circuit, name, None)
+}
+
+/// A black box for a circuit that is a register holding its input,
+/// and that loads `reset` when reset is asserted.
+pub fn register_black_box<S: Synchronous>(
+    circuit: &S,
+    name: &str,
+    reset: S::O,
+) -> Result<Object, RHDLError> {
+    let register = RegisterBank {
+        reset: Some(reset.bin()),
+    };
+    synchronous_box(circuit, name, Some(register))
+}
+
+fn synchronous_box<S: Synchronous>(
+    circuit: &S,
+    name: &str,
+    register: Option<RegisterBank>,
+) -> Result<Object, RHDLError> {
     let mut builder = Builder::new(name);
     let hdl = circuit.hdl(name)?;
     // This is the Clock/Reset input
@@ -128,6 +158,7 @@ pub fn synchronous_black_box<S: Synchronous>(circuit: &S, name: &str) -> Result<
     builder.object.black_boxes.push(BlackBox {
         code: hdl,
         mode: BlackBoxMode::Synchronous,
+        register,
     });
     let arg0 = arg0.into_iter().map(Wire::Register).collect();
     let arg1 = arg1.into_iter().map(Wire::Register).collect();
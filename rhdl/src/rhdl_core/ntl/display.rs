@@ -68,6 +68,17 @@ impl std::fmt::Debug for OpCode {
                 }
                 writeln!(f, " }}")
             }
+            OpCode::Rom(rom) => {
+                write!(f, " ")?;
+                vec_disp(f, &rom.lhs)?;
+                write!(f, " <- rom ")?;
+                vec_disp(f, &rom.address)?;
+                writeln!(f, " {{")?;
+                for (ndx, val) in rom.table.iter().enumerate() {
+                    writeln!(f, "          {ndx} => {val:?}")?;
+                }
+                writeln!(f, " }}")
+            }
             OpCode::Comment(comment) => {
                 write!(f, "// {comment}")
             }
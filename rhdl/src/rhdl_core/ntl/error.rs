@@ -16,6 +16,15 @@ pub enum NetListICE {
     LogicLoop,
     #[error("Net list contains an undriven node")]
     UndrivenNetlistNode,
+    #[error("Division should be lowered to multiplication by the RTL passes")]
+    UnloweredDivision,
+    #[error("Word level ops should be lowered to gates before the netlist is exported")]
+    UnloweredWordOp,
+    #[error("Black box {0} cannot be written in this netlist format")]
+    #[diagnostic(help(
+        "Only black boxes that are plain register banks (like the DFF) can be exported.  Replace the black box with the equivalent logic."
+    ))]
+    UnsupportedBlackBox(String),
 }
 
 #[derive(Debug, Error)]
@@ -3,7 +3,11 @@ use std::collections::HashMap;
 use crate::core::ntl;
 use crate::core::rtl;
 use crate::prelude::BitX;
+use crate::rhdl_core::Kind;
+use crate::rhdl_core::RHDLError;
 use crate::rhdl_core::ast::source::source_location::SourceLocation;
+use crate::rhdl_core::error::rhdl_error;
+use crate::rhdl_core::ntl::error::{NetListError, NetListICE};
 use crate::rhdl_core::ntl::object::WireDetails;
 use crate::rhdl_core::ntl::spec::Binary;
 use crate::rhdl_core::ntl::spec::Not;
@@ -21,10 +25,10 @@ struct NtlBuilder<'a> {
     operand_map: HashMap<tl::Operand, Vec<bt::Wire>>,
 }
 
-pub fn build_ntl_from_rtl(object: &rtl::Object) -> ntl::object::Object {
+pub fn build_ntl_from_rtl(object: &rtl::Object) -> Result<ntl::object::Object, RHDLError> {
     let mut bob = NtlBuilder::new(object);
     for lop in object.ops.iter() {
-        bob.op(lop);
+        bob.op(lop)?;
     }
     let inputs = object
         .arguments
@@ -34,18 +38,18 @@ pub fn build_ntl_from_rtl(object: &rtl::Object) -> ntl::object::Object {
                 .operand(tl::Operand::Register(*id))
                 .iter()
                 .map(|x| match x {
-                    bt::Wire::Register(rid) => *rid,
-                    _ => panic!("Argument mapped to a constant!"),
+                    bt::Wire::Register(rid) => Ok(*rid),
+                    _ => Err(bob.raise_ice(NetListICE::ExpectedRegisterNotConstant, None)),
                 })
                 .collect(),
-            None => vec![],
+            None => Ok(vec![]),
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
     let outputs = bob.operand(object.return_register);
     bob.btl.inputs = inputs;
     bob.btl.outputs = outputs;
     log::debug!("NTL built: {ntl:?}", ntl = &bob.btl);
-    bob.btl
+    Ok(bob.btl)
 }
 
 impl<'a> NtlBuilder<'a> {
@@ -61,6 +65,16 @@ impl<'a> NtlBuilder<'a> {
             operand_map: HashMap::default(),
         }
     }
+    fn raise_ice(&self, cause: NetListICE, location: Option<SourceLocation>) -> RHDLError {
+        rhdl_error(NetListError {
+            cause,
+            src: self.btl.code.source(),
+            elements: location
+                .map(|loc| self.btl.code.span(loc).into())
+                .into_iter()
+                .collect(),
+        })
+    }
     fn reg(&mut self, details: impl Into<WireDetails>) -> Wire {
         self.btl.symtab.reg((), details.into())
     }
@@ -72,13 +86,14 @@ impl<'a> NtlBuilder<'a> {
             .ops
             .push(ntl::object::LocatedOpCode { loc: Some(loc), op });
     }
-    fn op(&mut self, lop: &rtl::object::LocatedOpCode) {
+    fn op(&mut self, lop: &rtl::object::LocatedOpCode) -> Result<(), RHDLError> {
         let loc = lop.loc;
         match &lop.op {
             tl::OpCode::Noop => {}
             tl::OpCode::Assign(assign) => self.build_assign(loc, assign),
-            tl::OpCode::Binary(binary) => self.build_binary(loc, binary),
+            tl::OpCode::Binary(binary) => return self.build_binary(loc, binary),
             tl::OpCode::Case(case) => self.build_case(loc, case),
+            tl::OpCode::Rom(rom) => self.build_rom(loc, rom),
             tl::OpCode::Cast(cast) => self.build_cast(loc, cast),
             tl::OpCode::Comment(comment) => self.lop(loc, bt::OpCode::Comment(comment.clone())),
             tl::OpCode::Concat(concat) => self.build_concat(loc, concat),
@@ -87,6 +102,7 @@ impl<'a> NtlBuilder<'a> {
             tl::OpCode::Splice(splice) => self.build_splice(loc, splice),
             tl::OpCode::Unary(unary) => self.build_unary(loc, unary),
         }
+        Ok(())
     }
     fn operand(&mut self, operand: tl::Operand) -> Vec<bt::Wire> {
         if let Some(port) = self.operand_map.get(&operand) {
@@ -134,14 +150,21 @@ impl<'a> NtlBuilder<'a> {
             self.lop(loc, bt::assign(lhs, rhs));
         }
     }
-    fn build_binary(&mut self, loc: SourceLocation, binary: &tl::Binary) {
+    fn build_binary(&mut self, loc: SourceLocation, binary: &tl::Binary) -> Result<(), RHDLError> {
+        if let Some(op) = binary.op.unchecked() {
+            self.build_checked_binary(loc, binary, op);
+            return Ok(());
+        }
         let arg1 = self.operand(binary.arg1);
         let arg2 = self.operand(binary.arg2);
         let lhs = self.operand(binary.lhs);
         let signed = self.object.kind(binary.lhs).is_signed()
             || (self.object.kind(binary.arg1).is_signed()
                 && self.object.kind(binary.arg2).is_signed());
-        match classify_binary(binary.op) {
+        let Some(class) = classify_binary(binary.op) else {
+            return Err(self.raise_ice(NetListICE::UnloweredDivision, Some(loc)));
+        };
+        match class {
             BinOpClass::Bitwise(binop) => {
                 for (&lhs, (&arg1, &arg2)) in lhs.iter().zip(arg1.iter().zip(arg2.iter())) {
                     self.lop(
@@ -166,6 +189,155 @@ impl<'a> NtlBuilder<'a> {
                 }),
             ),
         }
+        Ok(())
+    }
+    // The saturating and overflowing operators compute the operation at full
+    // precision (N+1 bits for add and sub, 2N bits for mul).  The overflow flag
+    // is then derived from the bits that do not fit in the N bit result.
+    fn build_checked_binary(
+        &mut self,
+        loc: SourceLocation,
+        binary: &tl::Binary,
+        op: tl::AluBinary,
+    ) {
+        let kind = self.object.kind(binary.arg1);
+        let len = kind.bits();
+        let signed = kind.is_signed();
+        let full_len = if op == tl::AluBinary::Mul {
+            2 * len
+        } else {
+            len + 1
+        };
+        let lhs = self.operand(binary.lhs);
+        let arg1 = self.operand(binary.arg1);
+        let arg2 = self.operand(binary.arg2);
+        let full_kind = if signed {
+            Kind::Signed(full_len)
+        } else {
+            Kind::Bits(full_len)
+        };
+        let details = |bit| WireDetails {
+            source_details: Some(loc.into()),
+            kind: full_kind,
+            bit,
+        };
+        let zero = self.lit(BitX::Zero, details(0));
+        let one = self.lit(BitX::One, details(0));
+        let extend = |arg: Vec<Wire>| {
+            let fill = if signed { arg[len - 1] } else { zero };
+            let padding = std::iter::repeat_n(fill, full_len - len);
+            arg.into_iter().chain(padding).collect::<Vec<_>>()
+        };
+        let (arg1, arg2) = (extend(arg1), extend(arg2));
+        let full = (0..full_len)
+            .map(|bit| self.reg(details(bit)))
+            .collect::<Vec<_>>();
+        let vector_op = match op {
+            tl::AluBinary::Add => bt::VectorOp::Add,
+            tl::AluBinary::Sub => bt::VectorOp::Sub,
+            _ => bt::VectorOp::Mul,
+        };
+        self.lop(
+            loc,
+            bt::OpCode::Vector(Vector {
+                op: vector_op,
+                lhs: full.clone(),
+                arg1,
+                arg2,
+                signed,
+            }),
+        );
+        let flag = self.reg(details(0));
+        match (signed, op) {
+            // Any of the high bits are set
+            (false, tl::AluBinary::Mul) => self.lop(
+                loc,
+                bt::OpCode::Unary(Unary {
+                    op: bt::UnaryOp::Any,
+                    lhs: vec![flag],
+                    arg: full[len..].to_vec(),
+                }),
+            ),
+            // The carry (or borrow) out of the top bit
+            (false, _) => self.lop(loc, bt::assign(flag, full[len])),
+            // The high bits are not a sign extension of the result
+            (true, tl::AluBinary::Mul) => {
+                let any = self.reg(details(0));
+                let all = self.reg(details(0));
+                let not_all = self.reg(details(0));
+                for (op, lhs) in [(bt::UnaryOp::Any, any), (bt::UnaryOp::All, all)] {
+                    self.lop(
+                        loc,
+                        bt::OpCode::Unary(Unary {
+                            op,
+                            lhs: vec![lhs],
+                            arg: full[len - 1..].to_vec(),
+                        }),
+                    );
+                }
+                self.lop(
+                    loc,
+                    bt::OpCode::Not(Not {
+                        lhs: not_all,
+                        arg: all,
+                    }),
+                );
+                self.lop(
+                    loc,
+                    bt::OpCode::Binary(Binary {
+                        op: bt::BinaryOp::And,
+                        lhs: flag,
+                        arg1: any,
+                        arg2: not_all,
+                    }),
+                );
+            }
+            // The sign bit and the carry into it disagree
+            (true, _) => self.lop(
+                loc,
+                bt::OpCode::Binary(Binary {
+                    op: bt::BinaryOp::Xor,
+                    lhs: flag,
+                    arg1: full[len],
+                    arg2: full[len - 1],
+                }),
+            ),
+        }
+        if binary.op.is_overflowing() {
+            for (&lhs, &rhs) in lhs.iter().zip(full[..len].iter().chain([&flag])) {
+                self.lop(loc, bt::assign(lhs, rhs));
+            }
+            return;
+        }
+        // Saturate in the direction of the sign of the full precision result
+        let sign = full[full_len - 1];
+        let not_sign = self.reg(details(0));
+        if signed {
+            self.lop(
+                loc,
+                bt::OpCode::Not(Not {
+                    lhs: not_sign,
+                    arg: sign,
+                }),
+            );
+        }
+        for (bit, &lhs) in lhs.iter().enumerate() {
+            let limit = match (signed, op) {
+                (true, _) if bit == len - 1 => sign,
+                (true, _) => not_sign,
+                (false, tl::AluBinary::Sub) => zero,
+                (false, _) => one,
+            };
+            self.lop(
+                loc,
+                bt::OpCode::Select(Select {
+                    lhs,
+                    selector: flag,
+                    true_case: limit,
+                    false_case: full[bit],
+                }),
+            );
+        }
     }
     fn build_case(&mut self, loc: SourceLocation, case: &tl::Case) {
         let lhs = self.operand(case.lhs);
@@ -204,6 +376,18 @@ impl<'a> NtlBuilder<'a> {
             );
         }
     }
+    fn build_rom(&mut self, loc: SourceLocation, rom: &tl::Rom) {
+        let lhs = self.operand(rom.lhs);
+        let address = self.operand(rom.address);
+        self.lop(
+            loc,
+            bt::OpCode::Rom(bt::Rom {
+                lhs,
+                address,
+                table: rom.table.clone(),
+            }),
+        );
+    }
     fn build_cast(&mut self, loc: SourceLocation, cast: &tl::Cast) {
         let lhs = self.operand(cast.lhs);
         let arg = self.operand(cast.arg);
@@ -329,8 +513,10 @@ enum BinOpClass {
     Vector(bt::VectorOp),
 }
 
-fn classify_binary(op: tl::AluBinary) -> BinOpClass {
-    match op {
+// Division is lowered to multiplication by the RTL passes, and has no
+// netlist equivalent.
+fn classify_binary(op: tl::AluBinary) -> Option<BinOpClass> {
+    Some(match op {
         tl::AluBinary::BitAnd => BinOpClass::Bitwise(bt::BinaryOp::And),
         tl::AluBinary::BitXor => BinOpClass::Bitwise(bt::BinaryOp::Xor),
         tl::AluBinary::BitOr => BinOpClass::Bitwise(bt::BinaryOp::Or),
@@ -345,5 +531,13 @@ fn classify_binary(op: tl::AluBinary) -> BinOpClass {
         tl::AluBinary::Ne => BinOpClass::Vector(bt::VectorOp::Ne),
         tl::AluBinary::Ge => BinOpClass::Vector(bt::VectorOp::Ge),
         tl::AluBinary::Gt => BinOpClass::Vector(bt::VectorOp::Gt),
-    }
+        tl::AluBinary::Div | tl::AluBinary::Rem => return None,
+        // The checked operators are built separately, at full precision
+        tl::AluBinary::SaturatingAdd
+        | tl::AluBinary::SaturatingSub
+        | tl::AluBinary::SaturatingMul
+        | tl::AluBinary::OverflowingAdd
+        | tl::AluBinary::OverflowingSub
+        | tl::AluBinary::OverflowingMul => return None,
+    })
 }
@@ -202,6 +202,16 @@ impl<'a> NetListHDLBuilder<'a> {
         self.push_body(ast::case(discriminant, table));
         Ok(())
     }
+    fn rom_op(
+        &mut self,
+        op: &spec::Rom,
+        location: Option<SourceLocation>,
+    ) -> Result<(), RHDLError> {
+        let target = self.reg_v(&op.lhs, location)?;
+        let address = self.opex_v(&op.address);
+        self.push_body(ast::rom(&target, address, op.address.len(), &op.table));
+        Ok(())
+    }
     fn unary_op(
         &mut self,
         op: &spec::Unary,
@@ -254,6 +264,7 @@ impl<'a> NetListHDLBuilder<'a> {
             spec::OpCode::Binary(binary) => self.binary_op(binary, location),
             spec::OpCode::Vector(vector) => self.vector_op(vector, location),
             spec::OpCode::Case(case) => self.case_op(case, location),
+            spec::OpCode::Rom(rom) => self.rom_op(rom, location),
             spec::OpCode::Comment(comment) => {
                 self.push_body(ast::comment(comment));
                 Ok(())
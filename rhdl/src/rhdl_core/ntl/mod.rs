@@ -1,3 +1,5 @@
+pub mod aiger;
+pub mod blif;
 pub mod builder;
 pub mod display;
 pub mod from_rtl;
@@ -9,3 +11,5 @@ pub use object::Object;
 pub mod error;
 pub mod graph;
 pub mod hdl;
+pub mod retime;
+pub mod yosys;
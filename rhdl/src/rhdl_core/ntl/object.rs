@@ -14,27 +14,39 @@ use std::hash::Hash;
 use std::hash::Hasher;
 
 use fnv::FnvHasher;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Hash, PartialEq, Copy, Debug)]
+#[derive(Clone, Hash, PartialEq, Copy, Debug, Serialize, Deserialize)]
 pub enum BlackBoxMode {
     Synchronous,
     Asynchronous,
 }
 
-#[derive(Clone, Hash)]
+/// A black box that is a bank of plain registers, which load `i` into
+/// `o` on the rising edge of the clock.  Tools that know about registers
+/// can use this in place of the code of the black box.
+#[derive(Clone, Hash, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RegisterBank {
+    /// The value loaded (synchronously) when reset is asserted, or `None`
+    /// if the registers ignore reset.
+    pub reset: Option<Vec<BitX>>,
+}
+
+#[derive(Clone, Hash, Serialize, Deserialize)]
 pub struct BlackBox {
     pub code: HDLDescriptor,
     pub mode: BlackBoxMode,
+    pub register: Option<RegisterBank>,
 }
 
-#[derive(Clone, Hash)]
+#[derive(Clone, Hash, Serialize, Deserialize)]
 pub struct WireDetails {
     pub source_details: Option<SourceDetails>,
     pub kind: Kind,
     pub bit: usize,
 }
 
-#[derive(Clone, Default, Hash)]
+#[derive(Clone, Default, Hash, Serialize, Deserialize)]
 pub struct Object {
     pub name: String,
     pub inputs: Vec<Vec<RegisterId<WireKind>>>,
@@ -78,7 +90,7 @@ impl Object {
     }
 }
 
-#[derive(Clone, Hash)]
+#[derive(Clone, Hash, Serialize, Deserialize)]
 pub struct LocatedOpCode {
     pub op: OpCode,
     pub loc: Option<SourceLocation>,
@@ -0,0 +1,165 @@
+//! Pipelining of combinational netlists.
+//!
+//! The netlist of a combinational (synchronous mode) kernel is split
+//! into stages of roughly equal delay, and a bank of registers is
+//! inserted at the end of each stage.  Every path from the inputs to
+//! the outputs crosses the same number of registers, so the pipelined
+//! netlist computes the same function, delayed by the number of stages.
+use std::collections::HashMap;
+
+use crate::{
+    prelude::{HDLDescriptor, RHDLError},
+    rhdl_core::{
+        circuit::timing::{Arrivals, DelayModel, arrival_times},
+        compiler::optimize_ntl,
+        hdl::ast::{
+            Direction, Events, HDLKind, Module, always, continuous_assignment, declaration, id,
+            index_bit, non_blocking_assignment, port, unsigned_width,
+        },
+        ntl::{
+            Object,
+            graph::{GraphMode, WriteSource},
+            object::{BlackBox, BlackBoxMode, LocatedOpCode, RegisterBank},
+            spec::{self, BlackBoxId, OpCode, Wire},
+            visit::{visit_wires, visit_wires_mut},
+        },
+    },
+};
+
+// The Verilog for a bank of pipeline registers.  The registers have
+// no reset, since the contents of the pipeline are flushed by the
+// inputs anyway.
+fn register_bank(name: &str, width: usize) -> HDLDescriptor {
+    let module = Module {
+        name: name.into(),
+        description: format!("Pipeline register bank of {width} bits"),
+        ports: vec![
+            port(
+                "clock_reset",
+                Direction::Input,
+                HDLKind::Wire,
+                unsigned_width(2),
+            ),
+            port("i", Direction::Input, HDLKind::Wire, unsigned_width(width)),
+            port("o", Direction::Output, HDLKind::Reg, unsigned_width(width)),
+        ],
+        declarations: vec![declaration(HDLKind::Wire, "clock", unsigned_width(1), None)],
+        statements: vec![
+            continuous_assignment("clock", index_bit("clock_reset", 0)),
+            always(
+                vec![Events::Posedge("clock".into())],
+                vec![non_blocking_assignment("o", id("i"))],
+            ),
+        ],
+        ..Default::default()
+    };
+    HDLDescriptor {
+        name: name.into(),
+        body: module,
+        children: Default::default(),
+    }
+}
+
+/// Pipeline the netlist of a synchronous kernel into `stages` stages,
+/// balanced using the given delay model.  The outputs are registered,
+/// so the latency of the result is `stages` clocks.  The register banks
+/// are named `{name}_stage_{k}`.
+pub fn retime(
+    ntl: &Object,
+    name: &str,
+    stages: usize,
+    model: &impl DelayModel,
+) -> Result<Object, RHDLError> {
+    if stages == 0 {
+        return Ok(ntl.clone());
+    }
+    let Arrivals { dep, arrival, .. } = arrival_times(ntl, GraphMode::Synchronous, model)?;
+    let depth = ntl
+        .outputs
+        .iter()
+        .flat_map(|wire| wire.reg())
+        .filter_map(|reg| match dep.reg_map.get(&reg) {
+            Some(WriteSource::OpCode(ndx)) => Some(arrival[dep.op_nodes[*ndx].index()]),
+            _ => None,
+        })
+        .max()
+        .unwrap_or_default();
+    // Each op is placed in the stage in which its inputs are all available.
+    // Stage `k` is the logic between the `k`th and `k+1`th register banks.
+    let stage = ntl
+        .ops
+        .iter()
+        .enumerate()
+        .map(|(ndx, lop)| {
+            let start = arrival[dep.op_nodes[ndx].index()] - model.delay(&lop.op);
+            (start * stages)
+                .checked_div(depth)
+                .unwrap_or_default()
+                .min(stages - 1)
+        })
+        .collect::<Vec<_>>();
+    let mut object = ntl.clone();
+    let mut time = HashMap::new();
+    for (ndx, lop) in ntl.ops.iter().enumerate() {
+        visit_wires(&lop.op, |sense, wire| {
+            if sense.is_write() {
+                time.insert(*wire, stage[ndx]);
+            }
+        });
+    }
+    // The wires that cross each register bank, as (input, output) pairs
+    let mut banks: Vec<Vec<(Wire, Wire)>> = vec![vec![]; stages];
+    let mut delayed: HashMap<(Wire, usize), Wire> = HashMap::new();
+    let mut delay = |object: &mut Object, wire: Wire, target: usize| -> Wire {
+        if wire.is_lit() {
+            return wire;
+        }
+        let start = time.get(&wire).copied().unwrap_or_default();
+        let mut current = wire;
+        for (ndx, bank) in banks.iter_mut().enumerate().take(target).skip(start) {
+            current = *delayed.entry((wire, ndx + 1)).or_insert_with(|| {
+                let details = object.symtab[wire].clone();
+                let next = object.symtab.reg((), details);
+                bank.push((current, next));
+                next
+            });
+        }
+        current
+    };
+    for (ndx, lop) in object.ops.clone().into_iter().enumerate() {
+        let mut op = lop.op;
+        visit_wires_mut(&mut op, |sense, wire| {
+            if sense.is_read() {
+                *wire = delay(&mut object, *wire, stage[ndx]);
+            }
+        });
+        object.ops[ndx].op = op;
+    }
+    object.outputs = ntl
+        .outputs
+        .iter()
+        .map(|wire| delay(&mut object, *wire, stages))
+        .collect();
+    let clock_reset: Vec<Wire> = ntl.inputs[0].iter().copied().map(Wire::Register).collect();
+    for (bank, wires) in banks.into_iter().enumerate() {
+        if wires.is_empty() {
+            continue;
+        }
+        let code = BlackBoxId::new(object.black_boxes.len());
+        object.black_boxes.push(BlackBox {
+            code: register_bank(&format!("{name}_stage_{}", bank + 1), wires.len()),
+            mode: BlackBoxMode::Synchronous,
+            register: Some(RegisterBank { reset: None }),
+        });
+        let (arg, lhs) = wires.into_iter().unzip();
+        object.ops.push(LocatedOpCode {
+            op: OpCode::BlackBox(spec::BlackBox {
+                lhs,
+                arg: vec![clock_reset.clone(), arg],
+                code,
+            }),
+            loc: None,
+        });
+    }
+    optimize_ntl(object)
+}
@@ -2,8 +2,9 @@ use crate::{
     prelude::BitString,
     rhdl_core::common::symtab::{Symbol, SymbolKind},
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, PartialEq, Hash)]
+#[derive(Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub enum OpCode {
     Noop,
     // lhs <- arg
@@ -14,6 +15,8 @@ pub enum OpCode {
     Vector(Vector),
     // lhs <- case [arg1] {pattern0 : arg0, pattern1: arg1, ...}
     Case(Case),
+    // [lhs...] <- table[address...], where table is a constant
+    Rom(Rom),
     // Comment
     Comment(String),
     // lhs <- cond ? true_value : false_value
@@ -26,14 +29,14 @@ pub enum OpCode {
     Unary(Unary),
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct BlackBox {
     pub lhs: Vec<Wire>,
     pub arg: Vec<Vec<Wire>>,
     pub code: BlackBoxId,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Select {
     pub lhs: Wire,
     pub selector: Wire,
@@ -41,20 +44,27 @@ pub struct Select {
     pub false_case: Wire,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Case {
     pub lhs: Wire,
     pub discriminant: Vec<Wire>,
     pub entries: Vec<(CaseEntry, Wire)>,
 }
 
-#[derive(Debug, Clone, Hash, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Rom {
+    pub lhs: Vec<Wire>,
+    pub address: Vec<Wire>,
+    pub table: Vec<BitString>,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Serialize, Deserialize)]
 pub enum CaseEntry {
     Literal(BitString),
     WildCard,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Vector {
     pub op: VectorOp,
     pub lhs: Vec<Wire>,
@@ -63,14 +73,14 @@ pub struct Vector {
     pub signed: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Unary {
     pub op: UnaryOp,
     pub lhs: Vec<Wire>,
     pub arg: Vec<Wire>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash, Serialize, Deserialize)]
 pub enum VectorOp {
     Add,
     Sub,
@@ -85,7 +95,7 @@ pub enum VectorOp {
     Shr,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash, Serialize, Deserialize)]
 pub enum UnaryOp {
     All,
     Any,
@@ -93,13 +103,13 @@ pub enum UnaryOp {
     Xor,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Not {
     pub lhs: Wire,
     pub arg: Wire,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Binary {
     pub op: BinaryOp,
     pub lhs: Wire,
@@ -107,14 +117,14 @@ pub struct Binary {
     pub arg2: Wire,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Hash, Serialize, Deserialize)]
 pub enum BinaryOp {
     Xor,
     And,
     Or,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Assign {
     pub lhs: Wire,
     pub rhs: Wire,
@@ -124,7 +134,7 @@ pub fn assign(lhs: Wire, rhs: Wire) -> OpCode {
     OpCode::Assign(Assign { lhs, rhs })
 }
 
-#[derive(Hash, Eq, Ord, PartialOrd, PartialEq, Copy, Clone, Default)]
+#[derive(Hash, Eq, Ord, PartialOrd, PartialEq, Copy, Clone, Default, Serialize, Deserialize)]
 pub struct WireKind {}
 
 impl SymbolKind for WireKind {
@@ -133,7 +143,7 @@ impl SymbolKind for WireKind {
 
 pub type Wire = Symbol<WireKind>;
 
-#[derive(Copy, Clone, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[derive(Copy, Clone, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct BlackBoxId(usize);
 
 impl std::fmt::Debug for BlackBoxId {
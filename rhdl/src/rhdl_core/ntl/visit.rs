@@ -45,6 +45,14 @@ pub fn visit_wires<F: FnMut(Sense, &Wire)>(op: &OpCode, mut f: F) {
                 f(Sense::Read, entry);
             }
         }
+        OpCode::Rom(Rom {
+            lhs,
+            address,
+            table: _,
+        }) => {
+            vec_v(&mut f, Sense::Write, lhs);
+            vec_v(&mut f, Sense::Read, address);
+        }
         OpCode::Comment(_) => {}
         OpCode::Select(Select {
             lhs,
@@ -119,6 +127,14 @@ pub fn visit_wires_mut<F: FnMut(Sense, &mut Wire)>(op: &mut OpCode, mut f: F) {
                 f(Sense::Read, entry);
             }
         }
+        OpCode::Rom(Rom {
+            lhs,
+            address,
+            table: _,
+        }) => {
+            vec_m(&mut f, Sense::Write, lhs);
+            vec_m(&mut f, Sense::Read, address);
+        }
         OpCode::Comment(_comment) => {}
         OpCode::Select(Select {
             lhs,
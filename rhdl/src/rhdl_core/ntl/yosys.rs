@@ -0,0 +1,525 @@
+//! Export of a netlist in the JSON format written by `yosys -o x.json`.
+//!
+//! The netlist is written directly as Yosys internal cells, so it can be
+//! read by `nextpnr`, `netlistsvg` and friends without going through
+//! Verilog.  The ops map onto cells as follows:
+//!   - `Binary` and `Not` become `$and`, `$or`, `$xor` and `$not`
+//!   - `Select` becomes a `$mux`
+//!   - `Vector` ops become the arithmetic, comparison and shift cells
+//!     (`$add`, `$lt`, `$sshr`, etc.)
+//!   - `Unary` ops become `$reduce_and`, `$reduce_or`, `$reduce_xor`
+//!     and `$neg`
+//!   - `Case` becomes a chain of `$mux` cells, one per entry, each driven
+//!     by an `$eq`, so that the first entry that matches wins
+//!   - `Rom` becomes a `$pmux` driven by one `$eq` per entry (the
+//!     addresses are distinct, so at most one of them matches)
+//!   - black boxes that are register banks become `$dff` (or `$sdff`
+//!     when they have a reset value)
+//!   - other black boxes become instances of a module with the name of
+//!     the black box, which is declared with the `blackbox` attribute
+//!
+//! `Assign` ops do not create cells.  The two wires are simply the same
+//! net.  The ports of the module are named as in the Verilog output
+//! of the netlist (`arg_0`, `arg_1`, ... and `out`).
+//! ```ignore
+//! let json = ntl::yosys::netlist_json("top", &uut.descriptor("top")?.ntl);
+//! std::fs::write("top.json", json)?;
+//! ```
+use std::collections::HashMap;
+
+use serde_json::{Map, Value, json};
+
+use crate::rhdl_core::{
+    BitX,
+    common::symtab::RegisterId,
+    ntl::{
+        Object,
+        object::BlackBoxMode,
+        spec::{self, BinaryOp, CaseEntry, OpCode, UnaryOp, VectorOp, Wire, WireKind},
+    },
+};
+
+// Yosys writes integer parameters as 32 bit binary strings
+fn int_param(value: usize) -> Value {
+    Value::String(format!("{value:032b}"))
+}
+
+// A constant parameter, with the most significant bit first
+fn const_param(bits: &[BitX]) -> Value {
+    Value::String(bits.iter().rev().map(|bit| bit.to_string()).collect())
+}
+
+fn const_bits(bits: impl IntoIterator<Item = BitX>) -> Vec<Value> {
+    bits.into_iter()
+        .map(|bit| Value::String(bit.to_string()))
+        .collect()
+}
+
+// The bits of `value` as an unsigned number of the given width
+fn index_bits(value: usize, width: usize) -> Vec<Value> {
+    const_bits((0..width).map(|bit| (value >> bit & 1 == 1).into()))
+}
+
+#[derive(Clone, Copy)]
+enum Dir {
+    Input,
+    Output,
+}
+
+impl Dir {
+    fn name(self) -> &'static str {
+        match self {
+            Dir::Input => "input",
+            Dir::Output => "output",
+        }
+    }
+}
+
+struct JsonBuilder<'a> {
+    ntl: &'a Object,
+    // Registers that are the same net as another wire
+    aliases: HashMap<RegisterId<WireKind>, Wire>,
+    nets: HashMap<RegisterId<WireKind>, usize>,
+    next_net: usize,
+    cells: Map<String, Value>,
+    modules: Map<String, Value>,
+}
+
+impl<'a> JsonBuilder<'a> {
+    fn new(ntl: &'a Object) -> Self {
+        let mut aliases = HashMap::new();
+        for lop in &ntl.ops {
+            match &lop.op {
+                OpCode::Assign(assign) => {
+                    if let Some(lhs) = assign.lhs.reg() {
+                        aliases.insert(lhs, assign.rhs);
+                    }
+                }
+                // A case that starts with the default entry is also just a wire
+                OpCode::Case(case) => {
+                    if let (Some(lhs), Some((CaseEntry::WildCard, value))) =
+                        (case.lhs.reg(), case.entries.first())
+                    {
+                        aliases.insert(lhs, *value);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self {
+            ntl,
+            aliases,
+            nets: HashMap::new(),
+            // Nets 0 and 1 are reserved by Yosys
+            next_net: 2,
+            cells: Map::new(),
+            modules: Map::new(),
+        }
+    }
+    fn fresh(&mut self) -> Value {
+        let net = self.next_net;
+        self.next_net += 1;
+        json!(net)
+    }
+    fn bit(&mut self, mut wire: Wire) -> Value {
+        while let Some(alias) = wire.reg().and_then(|reg| self.aliases.get(&reg)) {
+            wire = *alias;
+        }
+        match wire {
+            Wire::Literal(lid) => Value::String(self.ntl.symtab[lid].to_string()),
+            Wire::Register(rid) => {
+                let next_net = &mut self.next_net;
+                let net = *self.nets.entry(rid).or_insert_with(|| {
+                    *next_net += 1;
+                    *next_net - 1
+                });
+                json!(net)
+            }
+        }
+    }
+    fn bits(&mut self, wires: &[Wire]) -> Vec<Value> {
+        wires.iter().map(|wire| self.bit(*wire)).collect()
+    }
+    fn cell(
+        &mut self,
+        kind: &str,
+        parameters: Vec<(&str, Value)>,
+        ports: Vec<(&str, Dir, Vec<Value>)>,
+    ) {
+        let name = format!("{kind}${}", self.cells.len());
+        let port_directions = ports
+            .iter()
+            .map(|(port, dir, _)| (port.to_string(), json!(dir.name())))
+            .collect::<Map<_, _>>();
+        let connections = ports
+            .into_iter()
+            .map(|(port, _, bits)| (port.to_string(), Value::Array(bits)))
+            .collect::<Map<_, _>>();
+        let parameters = parameters
+            .into_iter()
+            .map(|(param, value)| (param.to_string(), value))
+            .collect::<Map<_, _>>();
+        self.cells.insert(
+            name,
+            json!({
+                "hide_name": 1,
+                "type": kind,
+                "parameters": parameters,
+                "attributes": {},
+                "port_directions": port_directions,
+                "connections": connections,
+            }),
+        );
+    }
+    fn binary_op(&mut self, op: &spec::Binary) {
+        let kind = match op.op {
+            BinaryOp::Xor => "$xor",
+            BinaryOp::And => "$and",
+            BinaryOp::Or => "$or",
+        };
+        let (a, b, y) = (self.bit(op.arg1), self.bit(op.arg2), self.bit(op.lhs));
+        self.cell(
+            kind,
+            vec![
+                ("A_SIGNED", int_param(0)),
+                ("A_WIDTH", int_param(1)),
+                ("B_SIGNED", int_param(0)),
+                ("B_WIDTH", int_param(1)),
+                ("Y_WIDTH", int_param(1)),
+            ],
+            vec![
+                ("A", Dir::Input, vec![a]),
+                ("B", Dir::Input, vec![b]),
+                ("Y", Dir::Output, vec![y]),
+            ],
+        );
+    }
+    fn not_op(&mut self, op: &spec::Not) {
+        let (a, y) = (self.bit(op.arg), self.bit(op.lhs));
+        self.cell(
+            "$not",
+            vec![
+                ("A_SIGNED", int_param(0)),
+                ("A_WIDTH", int_param(1)),
+                ("Y_WIDTH", int_param(1)),
+            ],
+            vec![("A", Dir::Input, vec![a]), ("Y", Dir::Output, vec![y])],
+        );
+    }
+    fn select_op(&mut self, op: &spec::Select) {
+        let a = self.bit(op.false_case);
+        let b = self.bit(op.true_case);
+        let s = self.bit(op.selector);
+        let y = self.bit(op.lhs);
+        self.cell(
+            "$mux",
+            vec![("WIDTH", int_param(1))],
+            vec![
+                ("A", Dir::Input, vec![a]),
+                ("B", Dir::Input, vec![b]),
+                ("S", Dir::Input, vec![s]),
+                ("Y", Dir::Output, vec![y]),
+            ],
+        );
+    }
+    fn vector_op(&mut self, op: &spec::Vector) {
+        let kind = match op.op {
+            VectorOp::Add => "$add",
+            VectorOp::Sub => "$sub",
+            VectorOp::Mul => "$mul",
+            VectorOp::Eq => "$eq",
+            VectorOp::Ne => "$ne",
+            VectorOp::Lt => "$lt",
+            VectorOp::Le => "$le",
+            VectorOp::Gt => "$gt",
+            VectorOp::Ge => "$ge",
+            VectorOp::Shl => "$shl",
+            VectorOp::Shr if op.signed => "$sshr",
+            VectorOp::Shr => "$shr",
+        };
+        // The shift amount is always unsigned
+        let b_signed = op.signed && !matches!(op.op, VectorOp::Shl | VectorOp::Shr);
+        let a = self.bits(&op.arg1);
+        let b = self.bits(&op.arg2);
+        let y = self.bits(&op.lhs);
+        self.cell(
+            kind,
+            vec![
+                ("A_SIGNED", int_param(op.signed as usize)),
+                ("A_WIDTH", int_param(a.len())),
+                ("B_SIGNED", int_param(b_signed as usize)),
+                ("B_WIDTH", int_param(b.len())),
+                ("Y_WIDTH", int_param(y.len())),
+            ],
+            vec![
+                ("A", Dir::Input, a),
+                ("B", Dir::Input, b),
+                ("Y", Dir::Output, y),
+            ],
+        );
+    }
+    fn unary_op(&mut self, op: &spec::Unary) {
+        let kind = match op.op {
+            UnaryOp::All => "$reduce_and",
+            UnaryOp::Any => "$reduce_or",
+            UnaryOp::Xor => "$reduce_xor",
+            UnaryOp::Neg => "$neg",
+        };
+        let a = self.bits(&op.arg);
+        let y = self.bits(&op.lhs);
+        self.cell(
+            kind,
+            vec![
+                ("A_SIGNED", int_param(0)),
+                ("A_WIDTH", int_param(a.len())),
+                ("Y_WIDTH", int_param(y.len())),
+            ],
+            vec![("A", Dir::Input, a), ("Y", Dir::Output, y)],
+        );
+    }
+    // A comparison of the selector with a constant pattern
+    fn eq(&mut self, selector: &[Value], pattern: Vec<Value>) -> Value {
+        let matched = self.fresh();
+        self.cell(
+            "$eq",
+            vec![
+                ("A_SIGNED", int_param(0)),
+                ("A_WIDTH", int_param(selector.len())),
+                ("B_SIGNED", int_param(0)),
+                ("B_WIDTH", int_param(pattern.len())),
+                ("Y_WIDTH", int_param(1)),
+            ],
+            vec![
+                ("A", Dir::Input, selector.to_vec()),
+                ("B", Dir::Input, pattern),
+                ("Y", Dir::Output, vec![matched.clone()]),
+            ],
+        );
+        matched
+    }
+    // A parallel mux that selects the value of the entry that matches the
+    // selector, or the default if none match.  The `$pmux` cell requires
+    // that at most one entry matches.
+    fn pmux(
+        &mut self,
+        selector: &[Value],
+        entries: Vec<(Vec<Value>, Vec<Value>)>,
+        default: Vec<Value>,
+        y: Vec<Value>,
+    ) {
+        let mut s = vec![];
+        let mut b = vec![];
+        for (pattern, value) in entries {
+            s.push(self.eq(selector, pattern));
+            b.extend(value);
+        }
+        self.cell(
+            "$pmux",
+            vec![
+                ("WIDTH", int_param(y.len())),
+                ("S_WIDTH", int_param(s.len())),
+            ],
+            vec![
+                ("A", Dir::Input, default),
+                ("B", Dir::Input, b),
+                ("S", Dir::Input, s),
+                ("Y", Dir::Output, y),
+            ],
+        );
+    }
+    // A chain of muxes that selects the value of the first entry that
+    // matches the selector, or the default if none match.  The chain is
+    // built from the last entry, so the first entry drives the output.
+    // (A case that starts with the default is an alias, so there is at
+    // least one entry.)
+    fn priority_mux(
+        &mut self,
+        selector: &[Value],
+        entries: Vec<(Vec<Value>, Value)>,
+        default: Value,
+        y: Value,
+    ) {
+        let count = entries.len();
+        let mut next = default;
+        for (ndx, (pattern, value)) in entries.into_iter().rev().enumerate() {
+            let matched = self.eq(selector, pattern);
+            let out = if ndx + 1 == count {
+                y.clone()
+            } else {
+                self.fresh()
+            };
+            self.cell(
+                "$mux",
+                vec![("WIDTH", int_param(1))],
+                vec![
+                    ("A", Dir::Input, vec![next]),
+                    ("B", Dir::Input, vec![value]),
+                    ("S", Dir::Input, vec![matched]),
+                    ("Y", Dir::Output, vec![out.clone()]),
+                ],
+            );
+            next = out;
+        }
+    }
+    fn case_op(&mut self, op: &spec::Case) {
+        if op
+            .lhs
+            .reg()
+            .is_some_and(|lhs| self.aliases.contains_key(&lhs))
+        {
+            return;
+        }
+        let selector = self.bits(&op.discriminant);
+        let mut default = Value::String("x".into());
+        let mut entries = vec![];
+        for (entry, value) in &op.entries {
+            let value = self.bit(*value);
+            match entry {
+                CaseEntry::Literal(pattern) => {
+                    entries.push((const_bits(pattern.bits().iter().copied()), value))
+                }
+                // The entries after the default can never be selected
+                CaseEntry::WildCard => {
+                    default = value;
+                    break;
+                }
+            }
+        }
+        let y = self.bit(op.lhs);
+        self.priority_mux(&selector, entries, default, y);
+    }
+    fn rom_op(&mut self, op: &spec::Rom) {
+        let selector = self.bits(&op.address);
+        let entries = op
+            .table
+            .iter()
+            .enumerate()
+            .map(|(ndx, value)| {
+                (
+                    index_bits(ndx, selector.len()),
+                    const_bits(value.bits().iter().copied()),
+                )
+            })
+            .collect();
+        let default = const_bits(vec![BitX::X; op.lhs.len()]);
+        let y = self.bits(&op.lhs);
+        self.pmux(&selector, entries, default, y);
+    }
+    fn black_box_op(&mut self, op: &spec::BlackBox) {
+        let bb_core = &self.ntl.black_boxes[op.code.raw()];
+        let o = self.bits(&op.lhs);
+        let args = op.arg.iter().map(|arg| self.bits(arg)).collect::<Vec<_>>();
+        if let (Some(register), [clock_reset, d]) = (&bb_core.register, args.as_slice()) {
+            let (clock, reset) = (clock_reset[0].clone(), clock_reset[1].clone());
+            let mut parameters = vec![
+                ("CLK_POLARITY", int_param(1)),
+                ("WIDTH", int_param(o.len())),
+            ];
+            let mut ports = vec![("CLK", Dir::Input, vec![clock])];
+            let kind = if let Some(value) = &register.reset {
+                parameters.push(("SRST_POLARITY", int_param(1)));
+                parameters.push(("SRST_VALUE", const_param(value)));
+                ports.push(("SRST", Dir::Input, vec![reset]));
+                "$sdff"
+            } else {
+                "$dff"
+            };
+            ports.push(("D", Dir::Input, d.clone()));
+            ports.push(("Q", Dir::Output, o));
+            self.cell(kind, parameters, ports);
+            return;
+        }
+        let names: &[&str] = match bb_core.mode {
+            BlackBoxMode::Asynchronous => &["i"],
+            BlackBoxMode::Synchronous => &["clock_reset", "i"],
+        };
+        let ports = names
+            .iter()
+            .zip(args)
+            .map(|(name, bits)| (*name, Dir::Input, bits))
+            .chain(std::iter::once(("o", Dir::Output, o)))
+            .filter(|(_, _, bits)| !bits.is_empty())
+            .collect::<Vec<_>>();
+        let module = bb_core.code.name.clone();
+        if !self.modules.contains_key(&module) {
+            let mut next = 2;
+            let mut module_ports = Map::new();
+            for (name, dir, bits) in &ports {
+                let bits = (next..next + bits.len()).collect::<Vec<_>>();
+                next += bits.len();
+                module_ports.insert(
+                    name.to_string(),
+                    json!({"direction": dir.name(), "bits": bits}),
+                );
+            }
+            self.modules.insert(
+                module.clone(),
+                json!({
+                    "attributes": {"blackbox": int_param(1)},
+                    "ports": module_ports,
+                    "cells": {},
+                    "netnames": {},
+                }),
+            );
+        }
+        self.cell(&module, vec![], ports);
+    }
+    fn op_code(&mut self, op: &OpCode) {
+        match op {
+            OpCode::Noop | OpCode::Comment(_) | OpCode::Assign(_) => {}
+            OpCode::Binary(binary) => self.binary_op(binary),
+            OpCode::Vector(vector) => self.vector_op(vector),
+            OpCode::Case(case) => self.case_op(case),
+            OpCode::Rom(rom) => self.rom_op(rom),
+            OpCode::Select(select) => self.select_op(select),
+            OpCode::Not(not) => self.not_op(not),
+            OpCode::BlackBox(black_box) => self.black_box_op(black_box),
+            OpCode::Unary(unary) => self.unary_op(unary),
+        }
+    }
+    fn build(mut self, module_name: &str) -> Value {
+        let mut ports = Map::new();
+        let mut netnames = Map::new();
+        let inputs = self
+            .ntl
+            .inputs
+            .iter()
+            .enumerate()
+            .filter(|(_, arg)| !arg.is_empty())
+            .map(|(ndx, arg)| {
+                let wires = arg.iter().copied().map(Wire::Register).collect::<Vec<_>>();
+                (format!("arg_{ndx}"), Dir::Input, wires)
+            })
+            .collect::<Vec<_>>();
+        let output = (String::from("out"), Dir::Output, self.ntl.outputs.clone());
+        for (name, dir, wires) in inputs.into_iter().chain(std::iter::once(output)) {
+            let bits = self.bits(&wires);
+            ports.insert(name.clone(), json!({"direction": dir.name(), "bits": bits}));
+            netnames.insert(
+                name,
+                json!({"hide_name": 0, "bits": bits, "attributes": {}}),
+            );
+        }
+        for lop in &self.ntl.ops {
+            self.op_code(&lop.op);
+        }
+        let top = json!({
+            "attributes": {"top": int_param(1)},
+            "ports": ports,
+            "cells": self.cells,
+            "netnames": netnames,
+        });
+        self.modules.insert(module_name.into(), top);
+        json!({
+            "creator": "rhdl",
+            "modules": self.modules,
+        })
+    }
+}
+
+/// Write the netlist as a Yosys JSON netlist, with the given name for the
+/// top module, and one (empty) module for each kind of black box it uses.
+pub fn netlist_json(module_name: &str, ntl: &Object) -> String {
+    let json = JsonBuilder::new(ntl).build(module_name);
+    serde_json::to_string_pretty(&json).expect("netlist is valid JSON")
+}
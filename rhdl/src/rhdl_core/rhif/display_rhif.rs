@@ -1,7 +1,7 @@
 use crate::rhdl_core::{
     rhif::spec::{
         AluBinary, AluUnary, Array, Assign, Binary, Case, CaseArgument, Cast, Enum, Exec,
-        FieldValue, Index, Member, OpCode, Repeat, Splice, Struct, Tuple, Unary,
+        FieldValue, Index, Member, OpCode, Repeat, Rom, Splice, Struct, Tuple, Unary,
     },
     util::splice,
 };
@@ -62,6 +62,17 @@ impl std::fmt::Debug for OpCode {
                 }
                 writeln!(f, " }}")
             }
+            OpCode::Rom(Rom {
+                lhs,
+                address,
+                table,
+            }) => {
+                writeln!(f, " {lhs} <- rom {address} {{")?;
+                for (ndx, val) in table.iter().enumerate() {
+                    writeln!(f, "         {ndx} => {val:?}")?;
+                }
+                writeln!(f, " }}")
+            }
             OpCode::Exec(Exec { lhs, id, args }) => {
                 write!(f, " {lhs} <- {:?}({})", id, splice(args, ", "))
             }
@@ -162,6 +173,8 @@ impl std::fmt::Debug for AluBinary {
             AluBinary::Add => write!(f, "+"),
             AluBinary::Sub => write!(f, "-"),
             AluBinary::Mul => write!(f, "*"),
+            AluBinary::Div => write!(f, "/"),
+            AluBinary::Rem => write!(f, "%"),
             AluBinary::BitAnd => write!(f, "&"),
             AluBinary::BitOr => write!(f, "|"),
             AluBinary::BitXor => write!(f, "^"),
@@ -176,6 +189,14 @@ impl std::fmt::Debug for AluBinary {
             AluBinary::XAdd => write!(f, "xadd"),
             AluBinary::XSub => write!(f, "xsub"),
             AluBinary::XMul => write!(f, "xmul"),
+            AluBinary::SaturatingAdd => write!(f, "sadd"),
+            AluBinary::SaturatingSub => write!(f, "ssub"),
+            AluBinary::SaturatingMul => write!(f, "smul"),
+            AluBinary::OverflowingAdd => write!(f, "oadd"),
+            AluBinary::OverflowingSub => write!(f, "osub"),
+            AluBinary::OverflowingMul => write!(f, "omul"),
+            AluBinary::RotateLeft => write!(f, "rotl"),
+            AluBinary::RotateRight => write!(f, "rotr"),
         }
     }
 }
@@ -202,6 +223,17 @@ impl std::fmt::Debug for AluUnary {
             }
             AluUnary::XNeg => write!(f, "xneg "),
             AluUnary::XSgn => write!(f, "xsgn "),
+            AluUnary::Quantize(from, to, rounding) => {
+                write!(f, "quantize<F{from}->F{to}, {rounding:?}> ")
+            }
+            AluUnary::Saturate(from, to) => {
+                write!(f, "saturate<I{from}->I{to}> ")
+            }
+            AluUnary::CountOnes => write!(f, "popcnt "),
+            AluUnary::LeadingZeros => write!(f, "clz "),
+            AluUnary::TrailingZeros => write!(f, "ctz "),
+            AluUnary::ReverseBits => write!(f, "bitrev "),
+            AluUnary::SwapBytes => write!(f, "bswap "),
         }
     }
 }
@@ -19,8 +19,9 @@ use crate::rhdl_core::{
 
 use super::spec::FuncId;
 use super::spec::OpCode;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Hash, Default)]
+#[derive(Debug, Clone, Hash, Default, Serialize, Deserialize)]
 pub struct SymbolMap {
     pub source_set: SpannedSourceSet,
 }
@@ -37,7 +38,7 @@ impl SymbolMap {
     }
 }
 
-#[derive(Clone, Hash)]
+#[derive(Clone, Hash, Serialize, Deserialize)]
 pub struct LocatedOpCode {
     pub op: OpCode,
     pub loc: SourceLocation,
@@ -64,7 +65,7 @@ impl From<(OpCode, SourceLocation)> for LocatedOpCode {
     }
 }
 
-#[derive(Clone, Hash, Debug)]
+#[derive(Clone, Hash, Debug, Serialize, Deserialize)]
 pub struct SourceDetails {
     pub location: SourceLocation,
     pub name: Option<String>,
@@ -79,7 +80,7 @@ impl From<SourceLocation> for SourceDetails {
     }
 }
 
-#[derive(Clone, Hash)]
+#[derive(Clone, Hash, Serialize, Deserialize)]
 pub struct Object {
     pub symbols: SymbolMap,
     pub symtab: SymbolTable<TypedBits, Kind, SourceDetails, SlotKind>,
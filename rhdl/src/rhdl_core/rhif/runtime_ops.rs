@@ -1,5 +1,6 @@
-use num_bigint::BigInt;
+use num_bigint::{BigInt, BigUint, Sign};
 
+use crate::rhdl_bits::Rounding;
 use crate::rhdl_core::bitx::dyn_bit_manip::{from_bigint, from_biguint, to_bigint, to_biguint};
 use crate::rhdl_core::error::rhdl_error;
 use crate::rhdl_core::types::error::DynamicTypeError;
@@ -157,6 +158,230 @@ fn xmul(a: TypedBits, b: TypedBits) -> Result<TypedBits, RHDLError> {
     }
 }
 
+// Computes `a op b` at full precision, and returns the result wrapped
+// to the width of the arguments, the overflow flag, and the result
+// clamped to the range of the arguments.
+fn checked(
+    op: fn(BigInt, BigInt) -> BigInt,
+    a: TypedBits,
+    b: TypedBits,
+) -> Result<(TypedBits, bool, TypedBits), RHDLError> {
+    if a.kind != b.kind {
+        return Err(rhdl_error(
+            DynamicTypeError::BinaryOperationRequiresCompatibleType {
+                lhs: a.kind,
+                rhs: b.kind,
+            },
+        ));
+    }
+    let to_int = |x: &TypedBits| {
+        if x.kind.is_signed() {
+            to_bigint(&x.bits)
+        } else {
+            to_biguint(&x.bits).map(BigInt::from)
+        }
+        .ok_or_else(|| rhdl_error(DynamicTypeError::CannotConvertUninitToInt { value: x.clone() }))
+    };
+    let len = a.bits.len();
+    let (min, max) = if a.kind.is_signed() {
+        let half = BigInt::from(1) << (len - 1);
+        (-half.clone(), half - 1)
+    } else {
+        (BigInt::ZERO, (BigInt::from(1) << len) - 1)
+    };
+    let result = op(to_int(&a)?, to_int(&b)?);
+    let overflow = result < min || result > max;
+    let clamped = result.clone().clamp(min, max);
+    Ok((
+        TypedBits {
+            bits: from_bigint(&result, len),
+            kind: a.kind,
+        },
+        overflow,
+        TypedBits {
+            bits: from_bigint(&clamped, len),
+            kind: a.kind,
+        },
+    ))
+}
+
+fn saturating(
+    op: fn(BigInt, BigInt) -> BigInt,
+    a: TypedBits,
+    b: TypedBits,
+) -> Result<TypedBits, RHDLError> {
+    let (_, _, saturated) = checked(op, a, b)?;
+    Ok(saturated)
+}
+
+fn overflowing(
+    op: fn(BigInt, BigInt) -> BigInt,
+    a: TypedBits,
+    b: TypedBits,
+) -> Result<TypedBits, RHDLError> {
+    let (wrapped, overflow, _) = checked(op, a, b)?;
+    Ok(tuple(&[wrapped, overflow.typed_bits()]))
+}
+
+fn fixed_value(x: &TypedBits) -> Result<BigInt, RHDLError> {
+    if x.kind.is_signed() {
+        to_bigint(&x.bits)
+    } else {
+        to_biguint(&x.bits).map(BigInt::from)
+    }
+    .ok_or_else(|| rhdl_error(DynamicTypeError::CannotConvertUninitToInt { value: x.clone() }))
+}
+
+fn fixed_kind(signed: bool, len: usize) -> Kind {
+    if signed {
+        Kind::make_signed(len)
+    } else {
+        Kind::make_bits(len)
+    }
+}
+
+// Changes the number of fractional bits of a fixed point value from `from`
+// to `to`.  Dropped bits are rounded as in `Fixed::quantize`, and the result
+// wraps if rounding overflows it.
+fn quantize(
+    arg: TypedBits,
+    from: usize,
+    to: usize,
+    rounding: Rounding,
+) -> Result<TypedBits, RHDLError> {
+    if to >= from {
+        return arg.xshl(to - from);
+    }
+    let shift = from - to;
+    let len = arg.bits.len() - shift;
+    let val = fixed_value(&arg)?;
+    let quotient = &val >> shift;
+    let remainder = &val - (&quotient << shift);
+    let half = BigInt::from(1) << (shift - 1);
+    let round_up = match rounding {
+        Rounding::Truncate => false,
+        Rounding::TowardZero => val.sign() == Sign::Minus && remainder != BigInt::ZERO,
+        Rounding::HalfUp => remainder >= half,
+        Rounding::HalfEven => remainder > half || (remainder == half && quotient.bit(0)),
+    };
+    let result = quotient + BigInt::from(round_up as u8);
+    Ok(TypedBits {
+        bits: from_bigint(&result, len),
+        kind: fixed_kind(arg.kind.is_signed(), len),
+    })
+}
+
+// Changes the number of integer bits of a fixed point value from `from`
+// to `to`, clamping the value to the range of the result.
+fn saturate(arg: TypedBits, from: usize, to: usize) -> Result<TypedBits, RHDLError> {
+    let len = arg.bits.len() + to - from;
+    if to >= from {
+        return arg.resize(len);
+    }
+    let signed = arg.kind.is_signed();
+    let (min, max) = if signed {
+        let half = BigInt::from(1) << (len - 1);
+        (-half.clone(), half - 1)
+    } else {
+        (BigInt::ZERO, (BigInt::from(1) << len) - 1)
+    };
+    let clamped = fixed_value(&arg)?.clamp(min, max);
+    Ok(TypedBits {
+        bits: from_bigint(&clamped, len),
+        kind: fixed_kind(signed, len),
+    })
+}
+
+// Truncating division (or remainder), wrapped to the width of the arguments.
+fn divide(a: TypedBits, b: TypedBits, remainder: bool) -> Result<TypedBits, RHDLError> {
+    if a.kind != b.kind {
+        return Err(rhdl_error(
+            DynamicTypeError::BinaryOperationRequiresCompatibleType {
+                lhs: a.kind,
+                rhs: b.kind,
+            },
+        ));
+    }
+    let to_int = |x: &TypedBits| {
+        if x.kind.is_signed() {
+            to_bigint(&x.bits)
+        } else {
+            to_biguint(&x.bits).map(BigInt::from)
+        }
+        .ok_or_else(|| rhdl_error(DynamicTypeError::CannotConvertUninitToInt { value: x.clone() }))
+    };
+    let (a_bi, b_bi) = (to_int(&a)?, to_int(&b)?);
+    if b_bi == BigInt::ZERO {
+        return Err(rhdl_error(DynamicTypeError::DivisionByZero { value: a }));
+    }
+    let result = if remainder { a_bi % b_bi } else { a_bi / b_bi };
+    Ok(TypedBits {
+        bits: from_bigint(&result, a.bits.len()),
+        kind: a.kind,
+    })
+}
+
+// Computes a count (e.g., the number of ones) over the bits of the argument,
+// and returns it as a value of the same kind.
+fn bit_count(arg: TypedBits, count: fn(&[bool]) -> usize) -> Result<TypedBits, RHDLError> {
+    let Some(bits) = arg
+        .bits
+        .iter()
+        .map(|x| x.to_bool())
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Err(rhdl_error(DynamicTypeError::CannotConvertUninitToInt {
+            value: arg,
+        }));
+    };
+    let count = BigUint::from(count(&bits));
+    Ok(TypedBits {
+        bits: from_biguint(&count, bits.len()),
+        kind: arg.kind,
+    })
+}
+
+fn swap_bytes(arg: TypedBits) -> Result<TypedBits, RHDLError> {
+    if !arg.bits.len().is_multiple_of(8) {
+        return Err(rhdl_error(DynamicTypeError::SwapBytesRequiresWholeBytes {
+            value: arg,
+        }));
+    }
+    let bits = arg.bits.chunks(8).rev().flatten().copied().collect();
+    Ok(TypedBits {
+        bits,
+        kind: arg.kind,
+    })
+}
+
+fn rotate(arg: TypedBits, amount: TypedBits, left: bool) -> Result<TypedBits, RHDLError> {
+    if amount.kind.is_signed() {
+        return Err(rhdl_error(DynamicTypeError::ShiftAmountMustBeUnsigned {
+            value: amount,
+        }));
+    }
+    let Some(amount_bi) = to_biguint(&amount.bits) else {
+        return Err(rhdl_error(DynamicTypeError::CannotConvertUninitToInt {
+            value: amount,
+        }));
+    };
+    let mut bits = arg.bits;
+    if !bits.is_empty() {
+        let shift = usize::try_from(amount_bi % bits.len()).unwrap_or_default();
+        // The bits are stored LSB first, so rotating the vector to the right
+        // moves each bit towards the MSB.
+        if left {
+            bits.rotate_right(shift);
+        } else {
+            bits.rotate_left(shift);
+        }
+    }
+    Ok(TypedBits {
+        bits,
+        kind: arg.kind,
+    })
+}
+
 pub fn binary(
     op: crate::rhdl_core::rhif::spec::AluBinary,
     arg1: TypedBits,
@@ -177,9 +402,19 @@ pub fn binary(
         AluBinary::Gt => Ok((arg1 > arg2).typed_bits()),
         AluBinary::Ge => Ok((arg1 >= arg2).typed_bits()),
         AluBinary::Mul => mul(arg1, arg2),
+        AluBinary::Div => divide(arg1, arg2, false),
+        AluBinary::Rem => divide(arg1, arg2, true),
         AluBinary::XAdd => xadd(arg1, arg2),
         AluBinary::XSub => xsub(arg1, arg2),
         AluBinary::XMul => xmul(arg1, arg2),
+        AluBinary::SaturatingAdd => saturating(|a, b| a + b, arg1, arg2),
+        AluBinary::SaturatingSub => saturating(|a, b| a - b, arg1, arg2),
+        AluBinary::SaturatingMul => saturating(|a, b| a * b, arg1, arg2),
+        AluBinary::OverflowingAdd => overflowing(|a, b| a + b, arg1, arg2),
+        AluBinary::OverflowingSub => overflowing(|a, b| a - b, arg1, arg2),
+        AluBinary::OverflowingMul => overflowing(|a, b| a * b, arg1, arg2),
+        AluBinary::RotateLeft => rotate(arg1, arg2, true),
+        AluBinary::RotateRight => rotate(arg1, arg2, false),
     }
 }
 
@@ -209,6 +444,17 @@ pub fn unary(op: AluUnary, arg1: TypedBits) -> Result<TypedBits, RHDLError> {
             let arg1 = arg1.xext(1)?;
             arg1.as_signed()
         }
+        AluUnary::Quantize(from, to, rounding) => quantize(arg1, from, to, rounding),
+        AluUnary::Saturate(from, to) => saturate(arg1, from, to),
+        AluUnary::CountOnes => bit_count(arg1, |x| x.iter().filter(|b| **b).count()),
+        AluUnary::LeadingZeros => bit_count(arg1, |x| x.iter().rev().take_while(|b| !**b).count()),
+        AluUnary::TrailingZeros => bit_count(arg1, |x| x.iter().take_while(|b| !**b).count()),
+        AluUnary::ReverseBits => {
+            let mut arg1 = arg1;
+            arg1.bits.reverse();
+            Ok(arg1)
+        }
+        AluUnary::SwapBytes => swap_bytes(arg1),
     }
 }
 
@@ -1,14 +1,16 @@
 use internment::Intern;
 
 // RHDL Intermediate Form (RHIF).
+use crate::rhdl_bits::Rounding;
 use crate::rhdl_core::{
     Color, Kind, TypedBits,
     ast::ast_impl::WrapOp,
     common::symtab::{Symbol, SymbolKind},
     types::path::Path,
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, PartialEq, Hash)]
+#[derive(Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub enum OpCode {
     Noop,
     // lhs <- arg1 op arg2
@@ -31,6 +33,8 @@ pub enum OpCode {
     Tuple(Tuple),
     // ROM table
     Case(Case),
+    // lhs <- table[address], where table is a constant
+    Rom(Rom),
     // lhs = @path(args)
     Exec(Exec),
     // x <- [a, b, c, d]
@@ -64,6 +68,7 @@ impl OpCode {
             | OpCode::Struct(Struct { lhs, .. })
             | OpCode::Tuple(Tuple { lhs, .. })
             | OpCode::Case(Case { lhs, .. })
+            | OpCode::Rom(Rom { lhs, .. })
             | OpCode::Exec(Exec { lhs, .. })
             | OpCode::Array(Array { lhs, .. })
             | OpCode::Enum(Enum { lhs, .. })
@@ -77,7 +82,7 @@ impl OpCode {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Wrap {
     pub op: WrapOp,
     pub lhs: Slot,
@@ -85,7 +90,7 @@ pub struct Wrap {
     pub kind: Option<Kind>,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Binary {
     pub op: AluBinary,
     pub lhs: Slot,
@@ -93,14 +98,14 @@ pub struct Binary {
     pub arg2: Slot,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Unary {
     pub op: AluUnary,
     pub lhs: Slot,
     pub arg1: Slot,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Select {
     pub lhs: Slot,
     pub cond: Slot,
@@ -108,20 +113,20 @@ pub struct Select {
     pub false_value: Slot,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Index {
     pub lhs: Slot,
     pub arg: Slot,
     pub path: Path,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Assign {
     pub lhs: Slot,
     pub rhs: Slot,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Splice {
     pub lhs: Slot,
     pub orig: Slot,
@@ -129,14 +134,14 @@ pub struct Splice {
     pub subst: Slot,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Repeat {
     pub lhs: Slot,
     pub value: Slot,
     pub len: u64,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Struct {
     pub lhs: Slot,
     pub fields: Vec<FieldValue>,
@@ -144,49 +149,58 @@ pub struct Struct {
     pub template: TypedBits,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Case {
     pub lhs: Slot,
     pub discriminant: Slot,
     pub table: Vec<(CaseArgument, Slot)>,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Rom {
+    pub lhs: Slot,
+    pub address: Slot,
+    pub table: Vec<TypedBits>,
+}
+
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Array {
     pub lhs: Slot,
     pub elements: Vec<Slot>,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Tuple {
     pub lhs: Slot,
     pub fields: Vec<Slot>,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Exec {
     pub lhs: Slot,
     pub id: FuncId,
     pub args: Vec<Slot>,
 }
 
-#[derive(Clone, PartialEq, Hash)]
+#[derive(Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub enum CaseArgument {
     Slot(Slot),
     Wild,
 }
 
-#[derive(Clone, PartialEq, Hash)]
+#[derive(Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct FieldValue {
     pub member: Member,
     pub value: Slot,
 }
 
-#[derive(Clone, Copy, PartialEq, Hash)]
+#[derive(Clone, Copy, PartialEq, Hash, Serialize, Deserialize)]
 pub enum AluBinary {
     Add,
     Sub,
     Mul,
+    Div,
+    Rem,
     BitXor,
     BitAnd,
     BitOr,
@@ -201,6 +215,14 @@ pub enum AluBinary {
     XAdd,
     XSub,
     XMul,
+    SaturatingAdd,
+    SaturatingSub,
+    SaturatingMul,
+    OverflowingAdd,
+    OverflowingSub,
+    OverflowingMul,
+    RotateLeft,
+    RotateRight,
 }
 
 impl AluBinary {
@@ -217,11 +239,21 @@ impl AluBinary {
     }
 
     pub(crate) fn is_shift(&self) -> bool {
-        matches!(self, AluBinary::Shl | AluBinary::Shr)
+        matches!(
+            self,
+            AluBinary::Shl | AluBinary::Shr | AluBinary::RotateLeft | AluBinary::RotateRight
+        )
+    }
+
+    pub fn is_overflowing(&self) -> bool {
+        matches!(
+            self,
+            AluBinary::OverflowingAdd | AluBinary::OverflowingSub | AluBinary::OverflowingMul
+        )
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Hash)]
+#[derive(Clone, Copy, PartialEq, Hash, Serialize, Deserialize)]
 pub enum AluUnary {
     Neg,
     Not,
@@ -236,9 +268,20 @@ pub enum AluUnary {
     XShr(usize),
     XNeg,
     XSgn,
+    // Change the number of fractional bits of a fixed point value
+    // from the first count to the second, rounding if bits are dropped
+    Quantize(usize, usize, Rounding),
+    // Change the number of integer bits of a fixed point value
+    // from the first count to the second, clamping if it does not fit
+    Saturate(usize, usize),
+    CountOnes,
+    LeadingZeros,
+    TrailingZeros,
+    ReverseBits,
+    SwapBytes,
 }
 
-#[derive(Hash, Eq, Ord, PartialOrd, PartialEq, Copy, Clone, Default)]
+#[derive(Hash, Eq, Ord, PartialOrd, PartialEq, Copy, Clone, Default, Serialize, Deserialize)]
 pub struct SlotKind {}
 
 impl SymbolKind for SlotKind {
@@ -247,13 +290,13 @@ impl SymbolKind for SlotKind {
 
 pub type Slot = Symbol<SlotKind>;
 
-#[derive(Clone, PartialEq, Hash)]
+#[derive(Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub enum Member {
     Named(Intern<String>),
     Unnamed(u32),
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct FuncId(usize);
 
 impl std::fmt::Debug for FuncId {
@@ -268,21 +311,21 @@ impl From<usize> for FuncId {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Enum {
     pub lhs: Slot,
     pub fields: Vec<FieldValue>,
     pub template: TypedBits,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Cast {
     pub lhs: Slot,
     pub arg: Slot,
     pub len: Option<usize>,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Retime {
     pub lhs: Slot,
     pub arg: Slot,
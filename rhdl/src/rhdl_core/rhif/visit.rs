@@ -90,6 +90,14 @@ pub fn visit_slots<F: FnMut(Sense, &Slot)>(op: &OpCode, mut f: F) {
                 }
             }
         }
+        OpCode::Rom(Rom {
+            lhs,
+            address,
+            table: _,
+        }) => {
+            f(Sense::Write, lhs);
+            f(Sense::Read, address);
+        }
         OpCode::Exec(Exec { lhs, id: _, args }) => {
             f(Sense::Write, lhs);
             for arg in args {
@@ -223,6 +231,14 @@ pub fn visit_slots_mut<F: FnMut(Sense, &mut Slot)>(op: &mut OpCode, mut f: F) {
                 }
             }
         }
+        OpCode::Rom(Rom {
+            lhs,
+            address,
+            table: _,
+        }) => {
+            f(Sense::Write, lhs);
+            f(Sense::Read, address);
+        }
         OpCode::Exec(Exec { lhs, id: _, args }) => {
             f(Sense::Write, lhs);
             for arg in args {
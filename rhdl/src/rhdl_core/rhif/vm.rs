@@ -6,7 +6,7 @@ use crate::rhdl_core::error::rhdl_error;
 use crate::rhdl_core::rhif::object::{Object, SourceDetails};
 use crate::rhdl_core::rhif::spec::{
     Array, Assign, Binary, Case, CaseArgument, Cast, Enum, Exec, Index, Member, OpCode, Repeat,
-    Slot, Struct, Tuple, Unary,
+    Rom, Slot, Struct, Tuple, Unary,
 };
 use crate::rhdl_core::types::path::Path;
 use crate::rhdl_core::{BitX, TypedBits};
@@ -202,6 +202,22 @@ fn execute_block(ops: &[LocatedOpCode], state: &mut VMState) -> Result<()> {
                 };
                 state.write(*lhs, arm, loc)?;
             }
+            OpCode::Rom(Rom {
+                lhs,
+                address,
+                table,
+            }) => {
+                let lhs_kind = state.obj.kind(*lhs);
+                let address = state.read(*address, loc)?;
+                let value = address
+                    .as_i64()
+                    .ok()
+                    .and_then(|address| usize::try_from(address).ok())
+                    .and_then(|address| table.get(address))
+                    .cloned()
+                    .unwrap_or_else(|| TypedBits::dont_care_from_kind(lhs_kind));
+                state.write(*lhs, value, loc)?;
+            }
             OpCode::AsBits(Cast { lhs, arg, len }) => {
                 let arg = state.read(*arg, loc)?;
                 let len = len.ok_or(state.raise_ice(ICE::BitCastMissingRequiredLength, loc))?;
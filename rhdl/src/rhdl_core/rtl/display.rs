@@ -1,7 +1,8 @@
 use crate::rhdl_core::{rtl::spec::CastKind, util::splice};
 
 use super::spec::{
-    AluBinary, AluUnary, Assign, Binary, Case, Cast, Concat, Index, OpCode, Select, Splice, Unary,
+    AluBinary, AluUnary, Assign, Binary, Case, Cast, Concat, Index, OpCode, Rom, Select, Splice,
+    Unary,
 };
 
 impl std::fmt::Debug for AluBinary {
@@ -10,6 +11,8 @@ impl std::fmt::Debug for AluBinary {
             AluBinary::Add => write!(f, "+"),
             AluBinary::Sub => write!(f, "-"),
             AluBinary::Mul => write!(f, "*"),
+            AluBinary::Div => write!(f, "/"),
+            AluBinary::Rem => write!(f, "%"),
             AluBinary::BitAnd => write!(f, "&"),
             AluBinary::BitOr => write!(f, "|"),
             AluBinary::BitXor => write!(f, "^"),
@@ -21,6 +24,12 @@ impl std::fmt::Debug for AluBinary {
             AluBinary::Le => write!(f, "<="),
             AluBinary::Gt => write!(f, ">"),
             AluBinary::Ge => write!(f, ">="),
+            AluBinary::SaturatingAdd => write!(f, "sadd"),
+            AluBinary::SaturatingSub => write!(f, "ssub"),
+            AluBinary::SaturatingMul => write!(f, "smul"),
+            AluBinary::OverflowingAdd => write!(f, "oadd"),
+            AluBinary::OverflowingSub => write!(f, "osub"),
+            AluBinary::OverflowingMul => write!(f, "omul"),
         }
     }
 }
@@ -72,6 +81,17 @@ impl std::fmt::Debug for OpCode {
                 }
                 write!(f, "}}")
             }
+            OpCode::Rom(Rom {
+                lhs,
+                address,
+                table,
+            }) => {
+                writeln!(f, " {lhs} <- rom {address} {{")?;
+                for (ndx, val) in table.iter().enumerate() {
+                    writeln!(f, "         {ndx} => {val:?}")?;
+                }
+                write!(f, "}}")
+            }
             OpCode::Cast(Cast {
                 lhs,
                 arg,
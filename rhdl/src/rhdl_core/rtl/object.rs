@@ -16,8 +16,9 @@ use crate::rhdl_core::types::bit_string::BitString;
 
 use super::spec::{OpCode, Operand};
 use super::symbols::SymbolMap;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Hash)]
+#[derive(Clone, Hash, Serialize, Deserialize)]
 pub struct LocatedOpCode {
     pub op: OpCode,
     pub loc: SourceLocation,
@@ -42,7 +43,7 @@ pub fn lop(op: OpCode, id: NodeId, func: FunctionId) -> LocatedOpCode {
     LocatedOpCode::new(op, id, func)
 }
 
-#[derive(Clone, Hash)]
+#[derive(Clone, Hash, Serialize, Deserialize)]
 pub struct Object {
     pub symbols: SymbolMap,
     pub symtab: SymbolTable<TypedBits, Kind, SourceDetails, OperandKind>,
@@ -1,11 +1,12 @@
 use std::ops::Range;
 
 use crate::{
-    prelude::Path,
+    prelude::{BitString, Path},
     rhdl_core::common::symtab::{LiteralId, Symbol, SymbolKind},
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, PartialEq, Hash)]
+#[derive(Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub enum OpCode {
     Noop,
     // lhs <- arg
@@ -14,6 +15,8 @@ pub enum OpCode {
     Binary(Binary),
     // lhs <- table[slot]
     Case(Case),
+    // lhs <- table[address], where table is a constant
+    Rom(Rom),
     // lhs <- cast(slot) as signed/unsigned
     Cast(Cast),
     // Comment
@@ -30,7 +33,7 @@ pub enum OpCode {
     Unary(Unary),
 }
 
-#[derive(Hash, Eq, Ord, PartialOrd, PartialEq, Copy, Clone, Default)]
+#[derive(Hash, Eq, Ord, PartialOrd, PartialEq, Copy, Clone, Default, Serialize, Deserialize)]
 pub struct OperandKind {}
 
 impl SymbolKind for OperandKind {
@@ -39,11 +42,13 @@ impl SymbolKind for OperandKind {
 
 pub type Operand = Symbol<OperandKind>;
 
-#[derive(Clone, Copy, PartialEq, Hash)]
+#[derive(Clone, Copy, PartialEq, Hash, Serialize, Deserialize)]
 pub enum AluBinary {
     Add,
     Sub,
     Mul,
+    Div,
+    Rem,
     BitXor,
     BitAnd,
     BitOr,
@@ -55,6 +60,31 @@ pub enum AluBinary {
     Ne,
     Ge,
     Gt,
+    SaturatingAdd,
+    SaturatingSub,
+    SaturatingMul,
+    OverflowingAdd,
+    OverflowingSub,
+    OverflowingMul,
+}
+
+impl AluBinary {
+    // The saturating and overflowing operators compute the plain operator
+    // at full precision, and check the bits that do not fit in the result.
+    pub(crate) fn unchecked(&self) -> Option<AluBinary> {
+        match self {
+            AluBinary::SaturatingAdd | AluBinary::OverflowingAdd => Some(AluBinary::Add),
+            AluBinary::SaturatingSub | AluBinary::OverflowingSub => Some(AluBinary::Sub),
+            AluBinary::SaturatingMul | AluBinary::OverflowingMul => Some(AluBinary::Mul),
+            _ => None,
+        }
+    }
+    pub(crate) fn is_overflowing(&self) -> bool {
+        matches!(
+            self,
+            AluBinary::OverflowingAdd | AluBinary::OverflowingSub | AluBinary::OverflowingMul
+        )
+    }
 }
 
 impl From<AluBinary> for crate::rhdl_core::rhif::spec::AluBinary {
@@ -63,6 +93,8 @@ impl From<AluBinary> for crate::rhdl_core::rhif::spec::AluBinary {
             AluBinary::Add => crate::rhdl_core::rhif::spec::AluBinary::Add,
             AluBinary::Sub => crate::rhdl_core::rhif::spec::AluBinary::Sub,
             AluBinary::Mul => crate::rhdl_core::rhif::spec::AluBinary::Mul,
+            AluBinary::Div => crate::rhdl_core::rhif::spec::AluBinary::Div,
+            AluBinary::Rem => crate::rhdl_core::rhif::spec::AluBinary::Rem,
             AluBinary::BitXor => crate::rhdl_core::rhif::spec::AluBinary::BitXor,
             AluBinary::BitAnd => crate::rhdl_core::rhif::spec::AluBinary::BitAnd,
             AluBinary::BitOr => crate::rhdl_core::rhif::spec::AluBinary::BitOr,
@@ -74,11 +106,17 @@ impl From<AluBinary> for crate::rhdl_core::rhif::spec::AluBinary {
             AluBinary::Ne => crate::rhdl_core::rhif::spec::AluBinary::Ne,
             AluBinary::Ge => crate::rhdl_core::rhif::spec::AluBinary::Ge,
             AluBinary::Gt => crate::rhdl_core::rhif::spec::AluBinary::Gt,
+            AluBinary::SaturatingAdd => crate::rhdl_core::rhif::spec::AluBinary::SaturatingAdd,
+            AluBinary::SaturatingSub => crate::rhdl_core::rhif::spec::AluBinary::SaturatingSub,
+            AluBinary::SaturatingMul => crate::rhdl_core::rhif::spec::AluBinary::SaturatingMul,
+            AluBinary::OverflowingAdd => crate::rhdl_core::rhif::spec::AluBinary::OverflowingAdd,
+            AluBinary::OverflowingSub => crate::rhdl_core::rhif::spec::AluBinary::OverflowingSub,
+            AluBinary::OverflowingMul => crate::rhdl_core::rhif::spec::AluBinary::OverflowingMul,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Binary {
     pub op: AluBinary,
     pub lhs: Operand,
@@ -86,7 +124,7 @@ pub struct Binary {
     pub arg2: Operand,
 }
 
-#[derive(Clone, Copy, PartialEq, Hash)]
+#[derive(Clone, Copy, PartialEq, Hash, Serialize, Deserialize)]
 pub enum AluUnary {
     Neg,
     Not,
@@ -113,14 +151,14 @@ impl From<AluUnary> for crate::rhdl_core::rhif::spec::AluUnary {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Unary {
     pub op: AluUnary,
     pub lhs: Operand,
     pub arg1: Operand,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Select {
     pub lhs: Operand,
     pub cond: Operand,
@@ -128,13 +166,13 @@ pub struct Select {
     pub false_value: Operand,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Concat {
     pub lhs: Operand,
     pub args: Vec<Operand>,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Index {
     pub lhs: Operand,
     pub arg: Operand,
@@ -142,7 +180,7 @@ pub struct Index {
     pub path: Path,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Splice {
     pub lhs: Operand,
     pub orig: Operand,
@@ -151,26 +189,33 @@ pub struct Splice {
     pub path: Path,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Assign {
     pub lhs: Operand,
     pub rhs: Operand,
 }
 
-#[derive(Clone, Debug, PartialEq, Hash)]
+#[derive(Clone, Debug, PartialEq, Hash, Serialize, Deserialize)]
 pub enum CaseArgument {
     Literal(LiteralId<OperandKind>),
     Wild,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Case {
     pub lhs: Operand,
     pub discriminant: Operand,
     pub table: Vec<(CaseArgument, Operand)>,
 }
 
-#[derive(Debug, Clone, PartialEq, Hash)]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Rom {
+    pub lhs: Operand,
+    pub address: Operand,
+    pub table: Vec<BitString>,
+}
+
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Cast {
     pub lhs: Operand,
     pub arg: Operand,
@@ -178,7 +223,7 @@ pub struct Cast {
     pub kind: CastKind,
 }
 
-#[derive(Debug, Clone, PartialEq, Copy, Hash)]
+#[derive(Debug, Clone, PartialEq, Copy, Hash, Serialize, Deserialize)]
 pub enum CastKind {
     Signed,
     Unsigned,
@@ -7,8 +7,9 @@ use crate::rhdl_core::{
         source::{source_location::SourceLocation, spanned_source_set::SpannedSourceSet},
     },
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Default, Hash)]
+#[derive(Debug, Clone, Default, Hash, Serialize, Deserialize)]
 pub struct SymbolMap {
     pub source_set: SpannedSourceSet,
 }
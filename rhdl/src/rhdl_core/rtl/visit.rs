@@ -34,6 +34,14 @@ pub fn visit_operands<F: FnMut(Sense, &Operand)>(op: &OpCode, mut f: F) {
                 }
             }
         }
+        OpCode::Rom(Rom {
+            lhs,
+            address,
+            table: _,
+        }) => {
+            f(Sense::Write, lhs);
+            f(Sense::Read, address);
+        }
         OpCode::Cast(Cast {
             lhs,
             arg,
@@ -124,6 +132,14 @@ pub fn visit_operands_mut<F: FnMut(Sense, &mut Operand)>(op: &mut OpCode, mut f:
                 }
             }
         }
+        OpCode::Rom(Rom {
+            lhs,
+            address,
+            table: _,
+        }) => {
+            f(Sense::Write, lhs);
+            f(Sense::Read, address);
+        }
         OpCode::Cast(Cast {
             lhs,
             arg,
@@ -7,8 +7,8 @@ use crate::rhdl_core::{
     error::rhdl_error,
     rhif::object::SourceDetails,
     rtl::spec::{
-        AluBinary, AluUnary, Case, CaseArgument, Cast, CastKind, Concat, Index, Select, Splice,
-        Unary,
+        AluBinary, AluUnary, Case, CaseArgument, Cast, CastKind, Concat, Index, Rom, Select,
+        Splice, Unary,
     },
     types::bit_string::BitString,
 };
@@ -85,7 +85,13 @@ fn execute_block(ops: &[LocatedOpCode], state: &mut VMState) -> Result<()> {
         match op {
             OpCode::Noop => {}
             OpCode::Assign(Assign { lhs, rhs }) => {
+                // An assignment is a copy of the bits, so it can change signedness
                 let rhs = state.read(*rhs, loc)?;
+                let rhs = if state.obj.kind(*lhs).is_signed() {
+                    BitString::Signed(rhs.bits().to_vec())
+                } else {
+                    BitString::Unsigned(rhs.bits().to_vec())
+                };
                 state.write(*lhs, rhs, loc)?;
             }
             OpCode::Binary(Binary {
@@ -123,6 +129,20 @@ fn execute_block(ops: &[LocatedOpCode], state: &mut VMState) -> Result<()> {
                 };
                 state.write(*lhs, arm, loc)?;
             }
+            OpCode::Rom(Rom {
+                lhs,
+                address,
+                table,
+            }) => {
+                let lhs_kind = state.obj.kind(*lhs);
+                let address = state.read(*address, loc)?;
+                let value = address
+                    .as_address()
+                    .and_then(|address| table.get(address))
+                    .cloned()
+                    .unwrap_or_else(|| BitString::dont_care_from_kind(lhs_kind));
+                state.write(*lhs, value, loc)?;
+            }
             OpCode::Cast(Cast {
                 lhs,
                 arg,
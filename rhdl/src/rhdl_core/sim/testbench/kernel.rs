@@ -3,10 +3,7 @@ use std::iter::once;
 
 use crate::rhdl_core::{
     Digital, DigitalFn, RHDLError, TypedBits,
-    compiler::{
-        driver::{compile_design_stage1, compile_design_stage2},
-        optimize_ntl,
-    },
+    compiler::{cache::CompiledDesign, driver::compile_design_artifacts},
     hdl::{
         ast::{
             Declaration, Function, HDLKind, Module, Statement, assert, assign, bit_string,
@@ -15,7 +12,7 @@ use crate::rhdl_core::{
         },
         builder::generate_verilog,
     },
-    ntl::{from_rtl::build_ntl_from_rtl, hdl::generate_hdl},
+    ntl::hdl::generate_hdl,
     sim::test_module::TestModule,
     types::bit_string::BitString,
 };
@@ -380,8 +377,12 @@ where
     K: DigitalFn,
     Args: TestArg,
 {
-    let design = compile_design_stage1::<K>(mode)?;
-    let rtl = compile_design_stage2(&design)?;
+    let CompiledDesign {
+        rhif: design,
+        rtl,
+        ntl,
+        ..
+    } = compile_design_artifacts::<K>(mode)?;
     let vm_inputs = vals.clone();
     debug!("Testing kernel function");
     debug!("----- RHIF -----");
@@ -416,9 +417,6 @@ where
     debug!("{}", hdl.as_verilog());
     let tm = test_module(&uut, hdl, vals.clone());
     tm.run_iverilog()?;
-    debug!("Generating netlist from rtl");
-    let ntl = build_ntl_from_rtl(&rtl);
-    let ntl = optimize_ntl(ntl)?;
     debug!("{rtl:?}");
     debug!("{ntl:?}");
     let desc = generate_hdl("dut", &ntl)?;
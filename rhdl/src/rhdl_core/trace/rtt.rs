@@ -14,6 +14,8 @@ impl From<crate::rhdl_core::Color> for rtt::Color {
             crate::rhdl_core::Color::Blue => rtt::Color::Blue,
             crate::rhdl_core::Color::Indigo => rtt::Color::Indigo,
             crate::rhdl_core::Color::Violet => rtt::Color::Violet,
+            // Both sides share the interned path
+            crate::rhdl_core::Color::Named(path) => rtt::Color::Named(path),
         }
     }
 }
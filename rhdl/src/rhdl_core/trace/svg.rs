@@ -2,9 +2,12 @@ use crate::{
     prelude::{BitX, Digital, Kind, Path},
     rhdl_core::{
         Color, TypedBits,
-        types::path::{PathElement, sub_kind},
+        bitx::dyn_bit_manip::{to_bigint, to_biguint},
+        types::path::{PathElement, sub_kind, sub_trace_type},
     },
 };
+use rhdl_trace_type::TraceType;
+use std::collections::{HashMap, HashSet};
 
 // We want to take a series of time/bool values and turn it into an SVG thing.
 // The underlying time series is a set of time/impl Digital values.  So the
@@ -210,30 +213,75 @@ fn rewrite_trace_names_into_tree(mut traces: Box<[Trace]>) -> Box<[Trace]> {
     traces
 }
 
-// The Clock to color map
-fn stroke_color(color: TraceColor) -> &'static str {
-    match color {
-        TraceColor::Single(Color::Red) => "#D62246",
-        TraceColor::Single(Color::Orange) => "#FF7F11",
-        TraceColor::Single(Color::Yellow) => "#F7B32B",
-        TraceColor::Single(Color::Green) => "#56C126",
-        TraceColor::Single(Color::Blue) => "#5C95FF",
-        TraceColor::Single(Color::Indigo) => "#9000B3",
-        TraceColor::Single(Color::Violet) => "#672856",
-        TraceColor::MultiColor => "#E7ECEF",
-    }
+// The stroke and fill colors of a trace
+type Swatch = (&'static str, &'static str);
+
+const COLORS: [Color; 7] = [
+    Color::Red,
+    Color::Orange,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::Indigo,
+    Color::Violet,
+];
+
+// The swatches of the seven colors (in the same order), followed by
+// extra swatches that are only used for named domains
+const SWATCHES: [Swatch; 12] = [
+    ("#D62246", "#470B17"),
+    ("#FF7F11", "#552A05"),
+    ("#F7B32B", "#523B0E"),
+    ("#56C126", "#1C400C"),
+    ("#5C95FF", "#1E3155"),
+    ("#9000B3", "#30003B"),
+    ("#672856", "#220D1C"),
+    ("#1FC8B4", "#0A423C"),
+    ("#F25CA2", "#501E36"),
+    ("#A8D930", "#384810"),
+    ("#B08050", "#3A2A1A"),
+    ("#40C8F0", "#154250"),
+];
+
+const MULTI_COLOR: Swatch = ("#E7ECEF", "#4D4E4F");
+
+// The swatches used by the domains in a document.  The seven colors
+// keep their own swatches.  The named domains are given the extra
+// swatches, and then those of the colors that are not in the document,
+// in order of their paths.  So no two domains in a document share a
+// swatch, unless there are more domains than swatches.
+struct Palette {
+    named: HashMap<Color, usize>,
 }
 
-fn fill_color(color: TraceColor) -> &'static str {
-    match color {
-        TraceColor::Single(Color::Red) => "#470B17",
-        TraceColor::Single(Color::Orange) => "#552A05",
-        TraceColor::Single(Color::Yellow) => "#523B0E",
-        TraceColor::Single(Color::Green) => "#1C400C",
-        TraceColor::Single(Color::Blue) => "#1E3155",
-        TraceColor::Single(Color::Indigo) => "#30003B",
-        TraceColor::Single(Color::Violet) => "#220D1C",
-        TraceColor::MultiColor => "#4D4E4F",
+impl Palette {
+    fn new(regions: &[SvgRegion]) -> Self {
+        let used = regions
+            .iter()
+            .filter_map(|region| match region.color {
+                TraceColor::Single(color) => Some(color),
+                TraceColor::MultiColor => None,
+            })
+            .collect::<HashSet<_>>();
+        let mut named = used
+            .iter()
+            .filter(|color| matches!(color, Color::Named(_)))
+            .copied()
+            .collect::<Vec<_>>();
+        named.sort_by_key(|color| color.path());
+        let free = (COLORS.len()..SWATCHES.len())
+            .chain((0..COLORS.len()).filter(|ndx| !used.contains(&COLORS[*ndx])))
+            .chain((0..SWATCHES.len()).cycle());
+        Self {
+            named: named.into_iter().zip(free).collect(),
+        }
+    }
+    fn swatch(&self, color: TraceColor) -> Swatch {
+        match color {
+            TraceColor::Single(color @ Color::Named(_)) => SWATCHES[self.named[&color]],
+            TraceColor::Single(color) => SWATCHES[COLORS.iter().position(|c| *c == color).unwrap()],
+            TraceColor::MultiColor => MULTI_COLOR,
+        }
     }
 }
 
@@ -271,6 +319,7 @@ pub fn render_traces_as_svg_document(
     let time_delta = select_time_delta(options);
     let traces = rewrite_trace_names_into_tree(traces);
     let mut regions = render_traces_to_svg(&traces, options);
+    let palette = Palette::new(&regions);
     // Shift the traces down so we can fit the timeline in at the top
     regions
         .iter_mut()
@@ -359,8 +408,7 @@ pub fn render_traces_as_svg_document(
         let width = region.width;
         let height = options.spacing();
         let shim = options.shim;
-        let fill_color = fill_color(region.color);
-        let stroke_color = stroke_color(region.color);
+        let (stroke_color, fill_color) = palette.swatch(region.color);
         let text = region.tag.clone();
         let tip = region.full_tag.clone();
         let text_x = if matches!(region.kind, RegionKind::Label) {
@@ -543,9 +591,11 @@ pub fn build_time_trace<T: Digital>(
     path: &Path,
     time_set: std::ops::RangeInclusive<u64>,
 ) -> Box<[Region]> {
+    // The trace type is used to format fixed point values
+    let trace = sub_trace_type(T::static_trace_type(), path).ok();
     slice_by_path_and_bucketize(data, path, time_set)
         .iter()
-        .map(map_bucket_to_region)
+        .map(|bucket| map_bucket_to_region(bucket, trace.as_ref()))
         .collect()
 }
 
@@ -562,7 +612,7 @@ fn slice_by_path_and_bucketize<T: Digital>(
     bucketize(sliced, time_set, trace_color)
 }
 
-fn map_bucket_to_region(bucket: &Bucket) -> Region {
+fn map_bucket_to_region(bucket: &Bucket, trace: Option<&TraceType>) -> Region {
     let kind = match bucket.data.bits.len() {
         1 => match bucket.data.bits[0] {
             BitX::Zero => RegionKind::False,
@@ -574,7 +624,7 @@ fn map_bucket_to_region(bucket: &Bucket) -> Region {
     Region {
         start: bucket.start,
         end: bucket.end,
-        tag: format_as_label(&bucket.data),
+        tag: format_as_traced_label(&bucket.data, trace),
         kind,
         color: bucket.color,
     }
@@ -636,7 +686,13 @@ fn bucketize(
 }
 
 pub fn format_as_label(t: &TypedBits) -> Option<String> {
-    (t.bits.len() != 1).then(|| format_as_label_inner(t))?
+    format_as_traced_label(t, None)
+}
+
+// Like [format_as_label], but uses the trace type (if available)
+// to show fixed point values as real numbers.
+fn format_as_traced_label(t: &TypedBits, trace: Option<&TraceType>) -> Option<String> {
+    (t.bits.len() != 1).then(|| format_as_label_inner(t, trace))?
 }
 
 // Construct the leaf paths of the current object.  This version is a customized
@@ -749,12 +805,42 @@ pub fn try_path(t: &TypedBits, path: &Path) -> Option<TypedBits> {
     Some(t)
 }
 
-fn format_as_label_inner(t: &TypedBits) -> Option<String> {
+// TODO - handle other BitX values
+fn known_bits(bits: &[BitX]) -> Vec<BitX> {
+    bits.iter()
+        .map(|b| match b {
+            BitX::One => BitX::One,
+            _ => BitX::Zero,
+        })
+        .collect()
+}
+
+fn format_as_fixed_label(t: &TypedBits, fraction: usize) -> Option<String> {
+    let bits = known_bits(&t.bits);
+    let val = if t.kind.is_signed() {
+        i128::try_from(to_bigint(&bits)?).ok()? as f64
+    } else {
+        u128::try_from(to_biguint(&bits)?).ok()? as f64
+    };
+    Some(format!("{}", val / (fraction as f64).exp2()))
+}
+
+fn format_as_label_inner(t: &TypedBits, trace: Option<&TraceType>) -> Option<String> {
+    match trace {
+        Some(TraceType::Fixed(_, fraction)) | Some(TraceType::SignedFixed(_, fraction)) => {
+            return format_as_fixed_label(t, *fraction);
+        }
+        _ => {}
+    }
     match t.kind {
         Kind::Array(inner) => {
+            let base = match trace {
+                Some(TraceType::Array(array)) => Some(array.base.as_ref()),
+                _ => None,
+            };
             let vals = (0..inner.size)
                 .flat_map(|i| t.path(&Path::default().index(i)).ok())
-                .flat_map(|element| format_as_label_inner(&element))
+                .flat_map(|element| format_as_label_inner(&element, base))
                 .collect::<Vec<_>>()
                 .join(", ");
             Some(format!("[{vals}]"))
@@ -764,8 +850,14 @@ fn format_as_label_inner(t: &TypedBits) -> Option<String> {
                 .elements
                 .iter()
                 .enumerate()
-                .flat_map(|(i, _)| t.path(&Path::default().tuple_index(i)).ok())
-                .flat_map(|element| format_as_label_inner(&element))
+                .flat_map(|(i, _)| {
+                    let element = t.path(&Path::default().tuple_index(i)).ok()?;
+                    let trace = match trace {
+                        Some(TraceType::Tuple(tuple)) => tuple.elements.get(i),
+                        _ => None,
+                    };
+                    format_as_label_inner(&element, trace)
+                })
                 .collect::<Vec<_>>()
                 .join(", ");
             Some(format!("({vals})"))
@@ -779,7 +871,17 @@ fn format_as_label_inner(t: &TypedBits) -> Option<String> {
                         .map(|x| (field, x))
                         .ok()
                 })
-                .flat_map(|(name, field)| format_as_label_inner(&field).map(|x| (name, x)))
+                .flat_map(|(name, field)| {
+                    let trace = match trace {
+                        Some(TraceType::Struct(strukt)) => strukt
+                            .fields
+                            .iter()
+                            .find(|f| f.name == *name.name)
+                            .map(|f| &f.ty),
+                        _ => None,
+                    };
+                    format_as_label_inner(&field, trace).map(|x| (name, x))
+                })
                 .map(|(name, val)| format!("{}: {}", name.name, val))
                 .collect::<Vec<_>>()
                 .join(", ");
@@ -794,9 +896,22 @@ fn format_as_label_inner(t: &TypedBits) -> Option<String> {
             let payload = t
                 .path(&Path::default().payload_by_value(discriminant))
                 .ok()?;
-            let payload = format_as_label_inner(&payload).unwrap_or_default();
+            let trace = match trace {
+                Some(TraceType::Enum(enumerate)) => enumerate
+                    .variants
+                    .iter()
+                    .find(|v| v.discriminant == discriminant)
+                    .map(|v| &v.ty),
+                _ => None,
+            };
+            let payload = format_as_label_inner(&payload, trace).unwrap_or_default();
             Some(format!("{}{}", variant.name, payload))
         }
+        Kind::Bits(inner) if inner > 128 => {
+            let val = to_biguint(&known_bits(&t.bits))?;
+            let num_nibbles = inner / 4 + if inner % 4 == 0 { 0 } else { 1 };
+            Some(format!("{val:0num_nibbles$x}"))
+        }
         Kind::Bits(inner) => {
             let mut val: u128 = 0;
             for ndx in 0..inner {
@@ -810,6 +925,10 @@ fn format_as_label_inner(t: &TypedBits) -> Option<String> {
             // number of nibbles, with left padding of zeros
             Some(format!("{val:0num_nibbles$x}"))
         }
+        Kind::Signed(inner) if inner > 128 => {
+            let val = to_bigint(&known_bits(&t.bits))?;
+            Some(format!("{val}"))
+        }
         Kind::Signed(inner) => {
             let mut val: i128 = 0;
             for ndx in 0..inner {
@@ -825,7 +944,11 @@ fn format_as_label_inner(t: &TypedBits) -> Option<String> {
         }
         Kind::Signal(_inner, color) => {
             let val = &t.val();
-            let val = format_as_label_inner(val)?;
+            let trace = match trace {
+                Some(TraceType::Signal(base, _)) => Some(base.as_ref()),
+                _ => None,
+            };
+            let val = format_as_label_inner(val, trace)?;
             Some(format!("{color:?}@({val})"))
         }
         Kind::Empty => None,
@@ -1047,6 +1170,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fixed_point_labels() {
+        use crate::prelude::{Fixed, SignedFixed, U4, fixed, signed_fixed};
+        let x: Fixed<U4, U4> = fixed(2.75);
+        let y: SignedFixed<U4, U4> = signed_fixed(-1.125);
+        let data = [(0, (x, y, b8(9))), (5, (x, -y, b8(10)))];
+        let tags = |path: &Path| {
+            build_time_trace(&data, path, 0..=10)
+                .iter()
+                .map(|r| r.tag.clone().unwrap_or_default())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(tags(&Path::default().tuple_index(0)), ["2.75"]);
+        assert_eq!(tags(&Path::default().tuple_index(1)), ["-1.125", "1.125"]);
+        assert_eq!(
+            tags(&Path::default()),
+            ["(2.75, -1.125, 09)", "(2.75, 1.125, 0a)"]
+        );
+    }
+
     #[test]
     fn test_parent_map() {
         let sample_paths = &[
@@ -1122,4 +1265,37 @@ mod tests {
             Some(TraceColor::Single(Color::Blue))
         );
     }
+
+    #[test]
+    fn test_named_domains_get_distinct_swatches() {
+        let region = |color| SvgRegion {
+            start_x: 0,
+            start_y: 0,
+            width: 0,
+            tag: String::new(),
+            full_tag: String::new(),
+            kind: RegionKind::Multibit,
+            color: TraceColor::Single(color),
+        };
+        let colors = [Color::Red, Color::named("a::PixelClk")]
+            .into_iter()
+            .chain((0..SWATCHES.len()).map(|ndx| Color::named(&format!("b::Clk{ndx:02}"))))
+            .collect::<Vec<_>>();
+        // With few enough domains, no two share a swatch
+        let palette = Palette::new(
+            &colors[0..SWATCHES.len()]
+                .iter()
+                .map(|c| region(*c))
+                .collect::<Vec<_>>(),
+        );
+        let swatches = colors[0..SWATCHES.len()]
+            .iter()
+            .map(|c| palette.swatch(TraceColor::Single(*c)))
+            .collect::<HashSet<_>>();
+        assert_eq!(swatches.len(), SWATCHES.len());
+        assert_eq!(palette.swatch(TraceColor::Single(Color::Red)), SWATCHES[0]);
+        // The swatches are reused once they run out
+        let palette = Palette::new(&colors.iter().map(|c| region(*c)).collect::<Vec<_>>());
+        assert_eq!(palette.named.len(), colors.len() - 1);
+    }
 }
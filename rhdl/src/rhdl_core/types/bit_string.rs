@@ -2,8 +2,9 @@ use crate::rhdl_core::{
     Kind, RHDLError, TypedBits,
     bitx::{BitX, bitx_string},
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, PartialEq, Hash)]
+#[derive(Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub enum BitString {
     Signed(Vec<BitX>),
     Unsigned(Vec<BitX>),
@@ -89,6 +90,19 @@ impl BitString {
         BitString::Unsigned(std::iter::repeat_n(BitX::Zero, shift_amount).collect())
     }
 
+    // Interpret the bits as an unsigned address into a table.  Returns
+    // `None` if any bit is undefined, or the value does not fit in a `usize`.
+    pub(crate) fn as_address(&self) -> Option<usize> {
+        self.bits()
+            .iter()
+            .enumerate()
+            .try_fold(0_usize, |acc, (ndx, bit)| match bit {
+                BitX::Zero => Some(acc),
+                BitX::One => (ndx < usize::BITS as usize).then(|| acc | (1 << ndx)),
+                BitX::X => None,
+            })
+    }
+
     pub(crate) fn dont_care(&self) -> BitString {
         match self {
             BitString::Signed(bits) => BitString::Signed(bits.iter().map(|_| BitX::X).collect()),
@@ -1,6 +1,9 @@
 use std::marker::PhantomData;
 
-use crate::rhdl_bits::{consts::U128, consts::U32, consts::U64, BitWidth, Bits, SignedBits};
+use crate::rhdl_bits::{
+    consts::U128, consts::U32, consts::U64, BitWidth, Bits, Fixed, SignedBits, SignedFixed,
+    SignedWideBits, WideBits,
+};
 
 use crate::rhdl_core::{
     bitx::{bitx_vec, BitX},
@@ -445,6 +448,70 @@ impl<N: BitWidth> Digital for SignedBits<N> {
     }
 }
 
+impl<const N: usize> Digital for WideBits<N> {
+    const BITS: usize = N;
+    fn static_kind() -> Kind {
+        Kind::make_bits(N)
+    }
+    fn static_trace_type() -> rhdl_trace_type::TraceType {
+        rtt::TraceType::Bits(N)
+    }
+    fn bin(self) -> Vec<BitX> {
+        bitx_vec(&self.to_bools())
+    }
+    fn dont_care() -> Self {
+        Self::default()
+    }
+}
+
+impl<const N: usize> Digital for SignedWideBits<N> {
+    const BITS: usize = N;
+    fn static_kind() -> Kind {
+        Kind::make_signed(N)
+    }
+    fn static_trace_type() -> rhdl_trace_type::TraceType {
+        rtt::TraceType::Signed(N)
+    }
+    fn bin(self) -> Vec<BitX> {
+        bitx_vec(&self.to_bools())
+    }
+    fn dont_care() -> Self {
+        Self::default()
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> Digital for Fixed<I, F> {
+    const BITS: usize = I::BITS + F::BITS;
+    fn static_kind() -> Kind {
+        Kind::make_bits(Self::BITS)
+    }
+    fn static_trace_type() -> rhdl_trace_type::TraceType {
+        rtt::TraceType::Fixed(I::BITS, F::BITS)
+    }
+    fn bin(self) -> Vec<BitX> {
+        bitx_vec(&self.to_bools())
+    }
+    fn dont_care() -> Self {
+        Self::default()
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> Digital for SignedFixed<I, F> {
+    const BITS: usize = I::BITS + F::BITS;
+    fn static_kind() -> Kind {
+        Kind::make_signed(Self::BITS)
+    }
+    fn static_trace_type() -> rhdl_trace_type::TraceType {
+        rtt::TraceType::SignedFixed(I::BITS, F::BITS)
+    }
+    fn bin(self) -> Vec<BitX> {
+        bitx_vec(&self.to_bools())
+    }
+    fn dont_care() -> Self {
+        Self::default()
+    }
+}
+
 // Use the seq! macro to generate an implementation for a tuple of size N
 macro_rules! impl_tuple_for_digital {
     ($size: expr) => {
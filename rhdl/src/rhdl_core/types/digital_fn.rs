@@ -1,6 +1,7 @@
 #![allow(clippy::type_complexity)]
 
-use crate::rhdl_bits::BitWidth;
+use crate::rhdl_bits::{BitWidth, Fixed, SignedFixed};
+use crate::rhdl_core::ast::ast_impl::PatKind;
 
 pub use crate::rhdl_core::{kernel::KernelFnKind, Digital, Kind};
 
@@ -12,6 +13,65 @@ pub trait DigitalFn {
 
 impl DigitalFn for () {}
 
+// The kernel macro does not know the type of the receiver of a method
+// call.  Instead, it binds the receiver to a probe in code that is never
+// run, and lets the Rust type checker infer the type of the probe.  The
+// probe can then stand in for the receiver when looking up the kernel
+// for the method.
+pub struct MethodProbe<T>(std::marker::PhantomData<T>);
+
+impl<T> Default for MethodProbe<T> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+impl<T> MethodProbe<T> {
+    pub fn bind(&self, _receiver: &T) {}
+}
+
+impl<T: Digital> MethodProbe<T> {
+    pub fn receiver(&self) -> T {
+        T::dont_care()
+    }
+}
+
+// The fixed point methods need to know where the binary point of the
+// receiver is, as the count of integer and fractional bits.
+impl<I: BitWidth, F: BitWidth> MethodProbe<Fixed<I, F>> {
+    pub fn binary_point(&self) -> (usize, usize) {
+        (I::BITS, F::BITS)
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> MethodProbe<SignedFixed<I, F>> {
+    pub fn binary_point(&self) -> (usize, usize) {
+        (I::BITS, F::BITS)
+    }
+}
+
+// The signature of a kernel, as seen by a caller.
+pub fn kernel_signature(code: &Option<KernelFnKind>) -> DigitalSignature {
+    match code {
+        Some(KernelFnKind::Kernel(kernel)) => DigitalSignature {
+            arguments: kernel
+                .inner()
+                .inputs
+                .iter()
+                .map(|pat| match &pat.kind {
+                    PatKind::Type(pat) => pat.kind,
+                    _ => Kind::Empty,
+                })
+                .collect(),
+            ret: kernel.inner().ret,
+        },
+        _ => DigitalSignature {
+            arguments: vec![],
+            ret: Kind::Empty,
+        },
+    }
+}
+
 pub trait DigitalFn0 {
     type O: Digital;
     fn func() -> fn() -> Self::O;
@@ -202,3 +262,15 @@ where
         Some(KernelFnKind::SignedBitsConstructor(N::BITS))
     }
 }
+
+impl<const N: usize> DigitalFn for crate::rhdl_bits::wide_bits<N> {
+    fn kernel_fn() -> Option<KernelFnKind> {
+        Some(KernelFnKind::BitConstructor(N))
+    }
+}
+
+impl<const N: usize> DigitalFn for crate::rhdl_bits::wide_signed<N> {
+    fn kernel_fn() -> Option<KernelFnKind> {
+        Some(KernelFnKind::SignedBitsConstructor(N))
+    }
+}
@@ -1,3 +1,6 @@
+use internment::Intern;
+use serde::{Deserialize, Serialize};
+
 pub trait Domain: Copy + PartialEq + 'static + Default {
     fn color() -> Color;
 }
@@ -25,7 +28,34 @@ macro_rules! decl_domain {
     };
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+/// Declare a named clock domain, for designs that need more (or more
+/// descriptive) domains than the seven colors.  The domain can be used
+/// anywhere a color can, as in `Signal<b8, PixelClk>`.  Domains are
+/// identified by their full path, so two crates (or modules) can each
+/// declare a `PixelClk` without the two being treated as the same domain.
+/// ```
+/// use rhdl::prelude::*;
+///
+/// domain!(PixelClk);
+///
+/// assert_eq!(PixelClk::color().name(), "PixelClk");
+/// assert!(PixelClk::color().path().ends_with("::PixelClk"));
+/// ```
+#[macro_export]
+macro_rules! domain {
+    ($name: ident) => {
+        #[derive(Copy, Clone, Debug, PartialEq, Default)]
+        pub struct $name;
+
+        impl $crate::core::Domain for $name {
+            fn color() -> $crate::core::Color {
+                $crate::core::Color::named(concat!(module_path!(), "::", stringify!($name)))
+            }
+        }
+    };
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Color {
     Red,
     Orange,
@@ -34,11 +64,46 @@ pub enum Color {
     Blue,
     Indigo,
     Violet,
+    /// A domain declared with [domain!], holding the full path of the domain
+    Named(Intern<String>),
+}
+
+impl Color {
+    /// A named domain, given the full path of the type that declares it
+    pub fn named(path: &str) -> Self {
+        Color::Named(Intern::from_ref(path))
+    }
+    /// The short name of the domain, for display
+    pub fn name(&self) -> &'static str {
+        match self {
+            Color::Red => "Red",
+            Color::Orange => "Orange",
+            Color::Yellow => "Yellow",
+            Color::Green => "Green",
+            Color::Blue => "Blue",
+            Color::Indigo => "Indigo",
+            Color::Violet => "Violet",
+            Color::Named(path) => short_name(Intern::as_ref(*path).as_str()),
+        }
+    }
+    /// The full path of the domain, which identifies it.  For the seven
+    /// colors this is the same as the name.
+    pub fn path(&self) -> &'static str {
+        match self {
+            Color::Named(path) => Intern::as_ref(*path).as_str(),
+            _ => self.name(),
+        }
+    }
+}
+
+fn short_name(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
 }
 
 impl std::fmt::Debug for Color {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // Print only first letter in lower case
+        // Print only first letter in lower case, or the name of a
+        // named domain
         match self {
             Color::Red => write!(f, "r"),
             Color::Orange => write!(f, "o"),
@@ -47,6 +112,7 @@ impl std::fmt::Debug for Color {
             Color::Blue => write!(f, "b"),
             Color::Indigo => write!(f, "i"),
             Color::Violet => write!(f, "v"),
+            Color::Named(_) => write!(f, "{}", self.name()),
         }
     }
 }
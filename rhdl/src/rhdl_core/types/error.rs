@@ -93,4 +93,8 @@ pub enum DynamicTypeError {
     XshlFailed { value: TypedBits, len: usize },
     #[error("Cannot right shift the value {value:?} by {len:?}")]
     XshrFailed { value: TypedBits, len: usize },
+    #[error("Cannot swap the bytes of {value:?} - it is not a whole number of bytes")]
+    SwapBytesRequiresWholeBytes { value: TypedBits },
+    #[error("Cannot divide {value:?} by zero")]
+    DivisionByZero { value: TypedBits },
 }
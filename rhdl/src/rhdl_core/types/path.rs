@@ -12,6 +12,7 @@ use crate::rhdl_core::error::RHDLError;
 use crate::rhdl_core::error::rhdl_error;
 use crate::rhdl_core::rhif::spec::Member;
 use crate::rhdl_core::rhif::spec::Slot;
+use serde::{Deserialize, Serialize};
 
 #[derive(Error, Debug, Diagnostic)]
 pub enum PathError {
@@ -62,15 +63,18 @@ pub enum PathError {
     EnumPayloadByValueNotValid { kind: Kind },
     #[error("Dynamic indices must be resolved {path:?} before calling bit_range")]
     DynamicIndicesNotResolved { path: Path },
+    #[error("Indices from the end of an array must be resolved {path:?} before calling bit_range")]
+    IndicesFromEndNotResolved { path: Path },
     #[error("Unsupported path type {path:?} for trace {trace:?}")]
     UnsupportedPathTypeForTrace { path: Path, trace: TraceType },
 }
 
 type Result<T> = std::result::Result<T, RHDLError>;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PathElement {
     Index(usize),
+    IndexFromEnd(usize),
     TupleIndex(usize),
     Field(Intern<String>),
     EnumDiscriminant,
@@ -80,7 +84,7 @@ pub enum PathElement {
     SignalValue,
 }
 
-#[derive(Clone, PartialEq, Hash, Default)]
+#[derive(Clone, PartialEq, Hash, Default, Serialize, Deserialize)]
 pub struct Path {
     elements: Vec<PathElement>,
 }
@@ -98,6 +102,7 @@ impl std::fmt::Debug for Path {
         for e in self.elements.iter() {
             match e {
                 PathElement::Index(i) => write!(f, "[{i}]")?,
+                PathElement::IndexFromEnd(i) => write!(f, "[-{i}]")?,
                 PathElement::TupleIndex(i) => write!(f, ".{i}")?,
                 PathElement::Field(s) => write!(f, ".{s}")?,
                 PathElement::EnumDiscriminant => write!(f, "#")?,
@@ -137,6 +142,15 @@ impl Path {
         self.elements.push(PathElement::Index(index));
         self
     }
+    // An index counted back from the end of an array, so that
+    // `index_from_end(1)` is the last element.  These come from
+    // patterns like `[first, .., last]`, and are replaced with
+    // regular indices by `resolve_indices_from_end` once the
+    // length of the array is known.
+    pub fn index_from_end(mut self, index: usize) -> Self {
+        self.elements.push(PathElement::IndexFromEnd(index));
+        self
+    }
     pub fn tuple_index(mut self, ndx: usize) -> Self {
         self.elements.push(PathElement::TupleIndex(ndx));
         self
@@ -189,6 +203,32 @@ impl Path {
             .iter()
             .any(|e| matches!(e, PathElement::DynamicIndex(_)))
     }
+    pub fn any_from_end(&self) -> bool {
+        self.elements
+            .iter()
+            .any(|e| matches!(e, PathElement::IndexFromEnd(_)))
+    }
+    pub fn resolve_indices_from_end(&self, kind: Kind) -> Result<Path> {
+        let mut resolved = Path::default();
+        for element in &self.elements {
+            if let PathElement::IndexFromEnd(ndx) = element {
+                let (_, sub_kind) = bit_range(kind, &resolved)?;
+                let Kind::Array(array) = &sub_kind else {
+                    return Err(rhdl_error(PathError::IndexingNotAllowed { kind: sub_kind }));
+                };
+                if *ndx == 0 || *ndx > array.size {
+                    return Err(rhdl_error(PathError::ArrayIndexOutOfBounds {
+                        ndx: *ndx,
+                        kind: sub_kind,
+                    }));
+                }
+                resolved = resolved.index(array.size - ndx);
+            } else {
+                resolved.elements.push(*element);
+            }
+        }
+        Ok(resolved)
+    }
     pub fn remap_slots<F: FnMut(Slot) -> Slot>(self, mut f: F) -> Path {
         Path {
             elements: self
@@ -619,6 +659,11 @@ pub fn bit_range(kind: Kind, path: &Path) -> Result<(Range<usize>, Kind)> {
                     path: path.clone(),
                 }));
             }
+            PathElement::IndexFromEnd(_) => {
+                return Err(rhdl_error(PathError::IndicesFromEndNotResolved {
+                    path: path.clone(),
+                }));
+            }
         }
     }
     Ok((range, kind))
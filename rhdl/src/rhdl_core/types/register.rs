@@ -1,4 +1,4 @@
-use crate::rhdl_bits::{BitWidth, Bits, SignedBits};
+use crate::rhdl_bits::{BitWidth, Bits, Fixed, SignedBits, SignedFixed, SignedWideBits, WideBits};
 
 use crate::rhdl_core::{Digital, Kind};
 
@@ -12,6 +12,14 @@ impl<N: BitWidth> Register for Bits<N> {}
 
 impl<N: BitWidth> Register for SignedBits<N> {}
 
+impl<const N: usize> Register for WideBits<N> {}
+
+impl<const N: usize> Register for SignedWideBits<N> {}
+
+impl<I: BitWidth, F: BitWidth> Register for Fixed<I, F> {}
+
+impl<I: BitWidth, F: BitWidth> Register for SignedFixed<I, F> {}
+
 pub trait SignedRegister: Digital {
     fn static_kind() -> Kind {
         <Self as Digital>::static_kind()
@@ -19,3 +27,7 @@ pub trait SignedRegister: Digital {
 }
 
 impl<N: BitWidth> SignedRegister for SignedBits<N> {}
+
+impl<const N: usize> SignedRegister for SignedWideBits<N> {}
+
+impl<I: BitWidth, F: BitWidth> SignedRegister for SignedFixed<I, F> {}
@@ -7,6 +7,7 @@ use crate::rhdl_core::ast::ast_impl::WrapOp;
 use crate::rhdl_core::bitx::dyn_bit_manip::bits_shr_signed;
 use crate::rhdl_core::bitx::dyn_bit_manip::{
     bit_neg, bit_not, bits_and, bits_or, bits_shl, bits_shr, bits_xor, full_add, full_sub,
+    to_bigint, to_biguint,
 };
 use crate::rhdl_core::bitx::{BitX, bitx_string};
 use crate::rhdl_core::error::{RHDLError, rhdl_error};
@@ -20,10 +21,11 @@ use super::kind::Array;
 use super::kind::Enum;
 use super::kind::Struct;
 use super::kind::Tuple;
+use serde::{Deserialize, Serialize};
 
 type Result<T> = std::result::Result<T, RHDLError>;
 
-#[derive(Clone, PartialEq, Hash)]
+#[derive(Clone, PartialEq, Hash, Serialize, Deserialize)]
 pub struct TypedBits {
     pub bits: Vec<BitX>,
     pub kind: Kind,
@@ -713,6 +715,15 @@ impl std::cmp::PartialOrd for TypedBits {
         } else {
             self.kind.is_unsigned()
         };
+        // Values wider than 128 bits do not fit into the native
+        // integers, and need the slower arbitrary precision path.
+        if self.bits.len() > 128 {
+            return if is_unsigned {
+                to_biguint(&self.bits)?.partial_cmp(&to_biguint(&other.bits)?)
+            } else {
+                to_bigint(&self.bits)?.partial_cmp(&to_bigint(&other.bits)?)
+            };
+        }
         if is_unsigned {
             let mut a_as_u128 = 0;
             let mut b_as_u128 = 0;
@@ -856,7 +867,12 @@ fn write_bits(bits: &[BitX], f: &mut std::fmt::Formatter<'_>) -> std::fmt::Resul
             }
         );
     }
-    // We know that the bits array will fit into a u128.
+    if bits.len() > 128 {
+        return match to_biguint(bits) {
+            Some(val) => write!(f, "{:x}_b{}", val, bits.len()),
+            None => write!(f, "{}_b{}", bitx_string(bits), bits.len()),
+        };
+    }
     let val = bits
         .iter()
         .rev()
@@ -876,7 +892,12 @@ fn write_signed(bits: &[BitX], f: &mut std::fmt::Formatter<'_>) -> std::fmt::Res
             }
         );
     }
-    // We know that the bits array will fit into a i128.
+    if bits.len() > 128 {
+        return match to_bigint(bits) {
+            Some(val) => write!(f, "{}_s{}", val, bits.len()),
+            None => write!(f, "{}_s{}", bitx_string(bits), bits.len()),
+        };
+    }
     let bit_len = bits.len();
     let sign_bit = bits.last().cloned().unwrap_or(BitX::Zero);
     let val = std::iter::repeat_n(&sign_bit, 128 - bit_len)
@@ -557,6 +557,288 @@ fn test_error_about_for_loop() -> miette::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_while_loop() -> miette::Result<()> {
+    #[kernel]
+    fn leading_zeros(a: Signal<b8, Red>) -> Signal<b4, Red> {
+        let a = a.val();
+        let mut count = b4(0);
+        let mut found = false;
+        let mut i = b4(8);
+        while i > 0 {
+            i -= 1;
+            if !found && (a & (b8(1) << i) != 0) {
+                found = true;
+            }
+            if !found {
+                count += 1;
+            }
+        }
+        signal(count)
+    }
+    test_kernel_vm_and_verilog::<leading_zeros, _, _, _>(leading_zeros, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_while_loop_counting_up() -> miette::Result<()> {
+    #[kernel]
+    fn fold_nibbles(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        let mut acc = b8(0);
+        let mut i = b4(0);
+        while i < 8 {
+            acc ^= a >> i;
+            i += 4;
+        }
+        signal(acc)
+    }
+    test_kernel_vm_and_verilog::<fold_nibbles, _, _, _>(fold_nibbles, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::assign_op_pattern)]
+fn test_while_loop_inclusive_bound_with_assign_step() -> miette::Result<()> {
+    #[kernel]
+    fn sum_shifts(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        let mut acc = b8(0);
+        let mut i = b4(1);
+        while i <= 7 {
+            acc += a >> i;
+            i = i + 2;
+        }
+        signal(acc)
+    }
+    test_kernel_vm_and_verilog::<sum_shifts, _, _, _>(sum_shifts, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_while_loop_variable_on_right() -> miette::Result<()> {
+    #[kernel]
+    fn count_down(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        let mut acc = b8(0);
+        let mut i = b4(0);
+        while 8 > i {
+            acc = (acc << 1) | ((a >> i) & 1);
+            i += 1;
+        }
+        signal(acc)
+    }
+    test_kernel_vm_and_verilog::<count_down, _, _, _>(count_down, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_while_loop_shift_step_wraps() -> miette::Result<()> {
+    #[kernel]
+    fn count_ones(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        let mut count = b8(0);
+        let mut mask = b8(1);
+        while mask != 0 {
+            if a & mask != 0 {
+                count += 1;
+            }
+            mask <<= 1;
+        }
+        signal(count)
+    }
+    test_kernel_vm_and_verilog::<count_ones, _, _, _>(count_ones, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_while_loop_add_step_wraps() -> miette::Result<()> {
+    #[kernel]
+    fn wrap_once(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        let mut acc = a;
+        let mut i = b4(15);
+        while i != 0 {
+            acc += 1;
+            i += 1;
+        }
+        signal(acc)
+    }
+    test_kernel_vm_and_verilog::<wrap_once, _, _, _>(wrap_once, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_error_about_while_loop_initial_value() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b4, Red>) -> Signal<b4, Red> {
+        let a = a.val();
+        let mut i = a;
+        while i != 0 {
+            i -= 1;
+        }
+        signal(i)
+    }
+    let Err(RHDLError::RHDLSyntaxError(err)) =
+        compile_design::<do_stuff>(CompilationMode::Asynchronous)
+    else {
+        panic!("Expected syntax error");
+    };
+    assert!(matches!(
+        err.cause,
+        rhdl::core::compiler::mir::error::Syntax::WhileLoopNonConstantInitialValue { .. }
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_error_about_while_loop_unbounded_condition() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b4, Red>) -> Signal<b4, Red> {
+        let a = a.val();
+        let mut i = b4(0);
+        while i < a {
+            i += 1;
+        }
+        signal(i)
+    }
+    let Err(RHDLError::RHDLSyntaxError(err)) =
+        compile_design::<do_stuff>(CompilationMode::Asynchronous)
+    else {
+        panic!("Expected syntax error");
+    };
+    assert!(matches!(
+        err.cause,
+        rhdl::core::compiler::mir::error::Syntax::WhileLoopUnboundedCondition
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_error_about_while_loop_no_constant_step() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b4, Red>) -> Signal<b4, Red> {
+        let a = a.val();
+        let mut i = b4(0);
+        while i < 8 {
+            i += a;
+        }
+        signal(i)
+    }
+    let Err(RHDLError::RHDLSyntaxError(err)) =
+        compile_design::<do_stuff>(CompilationMode::Asynchronous)
+    else {
+        panic!("Expected syntax error");
+    };
+    assert!(matches!(
+        err.cause,
+        rhdl::core::compiler::mir::error::Syntax::WhileLoopNoConstantStep { .. }
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_error_about_while_loop_multiple_updates() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b4, Red>) -> Signal<b4, Red> {
+        let a = a.val();
+        let mut i = b4(0);
+        while i < 8 {
+            if a != 0 {
+                i += 1;
+            }
+            i += 1;
+        }
+        signal(i)
+    }
+    let Err(RHDLError::RHDLSyntaxError(err)) =
+        compile_design::<do_stuff>(CompilationMode::Asynchronous)
+    else {
+        panic!("Expected syntax error");
+    };
+    assert!(matches!(
+        err.cause,
+        rhdl::core::compiler::mir::error::Syntax::WhileLoopMultipleUpdates { .. }
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_error_about_while_loop_too_many_iterations() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b4, Red>) -> Signal<b4, Red> {
+        let a = a.val();
+        let mut b = a;
+        let mut i = b4(1);
+        while i >= 0 {
+            b += 1;
+            i -= 1;
+        }
+        signal(b)
+    }
+    let Err(RHDLError::RHDLSyntaxError(err)) =
+        compile_design::<do_stuff>(CompilationMode::Asynchronous)
+    else {
+        panic!("Expected syntax error");
+    };
+    assert!(matches!(
+        err.cause,
+        rhdl::core::compiler::mir::error::Syntax::WhileLoopTooManyIterations { .. }
+    ));
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::while_immutable_condition)]
+fn test_error_about_while_loop_shadowed_variable() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b4, Red>) -> Signal<b4, Red> {
+        let a = a.val();
+        let mut b = a;
+        let mut i = b4(0);
+        while i < 8 {
+            let i = b4(3);
+            b += i;
+        }
+        signal(b)
+    }
+    let Err(RHDLError::RHDLSyntaxError(err)) =
+        compile_design::<do_stuff>(CompilationMode::Asynchronous)
+    else {
+        panic!("Expected syntax error");
+    };
+    assert!(matches!(
+        err.cause,
+        rhdl::core::compiler::mir::error::Syntax::WhileLoopVariableShadowed { .. }
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_error_about_while_loop_variable_out_of_range() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b4, Red>) -> Signal<b4, Red> {
+        let a = a.val();
+        let mut b = a;
+        let mut i = 0;
+        while i < 8 {
+            b += 1;
+            i += 3;
+        }
+        signal(b)
+    }
+    let Err(RHDLError::RHDLSyntaxError(err)) =
+        compile_design::<do_stuff>(CompilationMode::Asynchronous)
+    else {
+        panic!("Expected syntax error");
+    };
+    assert!(matches!(
+        err.cause,
+        rhdl::core::compiler::mir::error::Syntax::WhileLoopVariableOutOfRange { .. }
+    ));
+    Ok(())
+}
+
 #[test]
 fn test_match_scrutinee_bits() {
     let z = b4(0b1010);
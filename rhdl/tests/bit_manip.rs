@@ -0,0 +1,77 @@
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl::core::sim::testbench::kernel::test_kernel_vm_and_verilog;
+
+#[test]
+fn test_bit_counts() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<(b8, b8, b8), Red> {
+        let a = a.val();
+        signal((a.count_ones(), a.leading_zeros(), a.trailing_zeros()))
+    }
+
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_bit_counts_odd_width() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b5, Red>) -> Signal<(b5, b5, b5), Red> {
+        let a = a.val();
+        signal((a.count_ones(), a.leading_zeros(), a.trailing_zeros()))
+    }
+
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_reverse_and_swap() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b16, Red>) -> Signal<(b16, b16), Red> {
+        let a = a.val();
+        signal((a.reverse_bits(), a.swap_bytes()))
+    }
+
+    let args = exhaustive::<U8>()
+        .into_iter()
+        .map(|x| (red(bits::<U16>((x.raw() * 0x0101) ^ 0x00f0)),));
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_rotate() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b6, Red>, n: Signal<b4, Red>) -> Signal<(b6, b6), Red> {
+        let a = a.val();
+        let n = n.val();
+        signal((a.rotate_left(n), a.rotate_right(n)))
+    }
+
+    let args = exhaustive::<U6>().into_iter().flat_map(|a| {
+        exhaustive::<U4>()
+            .into_iter()
+            .map(move |n| (red(a), red(n)))
+    });
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_rotate_by_constant() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        signal(a.rotate_left(b3(3)) ^ a.rotate_right(b2(1)))
+    }
+
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_exhaustive_red())?;
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use rhdl::prelude::*;
+
+mod common;
+use common::{aiger::Aiger, counter::Counter, to_bools};
+
+#[kernel]
+fn datapath(_cr: ClockReset, i: (b8, b8, b8)) -> b8 {
+    let (a, b, c) = i;
+    let x = a + b;
+    let y = x ^ c;
+    let z = y + a;
+    let w = z & b;
+    w - c
+}
+
+#[test]
+fn test_pipelined_blif_and_aiger() -> miette::Result<()> {
+    let uut = Pipelined::<(b8, b8, b8), b8, U1>::try_new::<datapath>()?;
+    let ntl = uut.descriptor("top")?.ntl;
+    let blif = rhdl::core::ntl::blif::netlist_blif("top", &ntl)?;
+    assert!(blif.starts_with(".model top\n"));
+    assert!(!blif.contains(".subckt"));
+    // The pipeline registers have no reset, so they start out unknown
+    let latches = blif
+        .lines()
+        .filter(|line| line.starts_with(".latch"))
+        .collect::<Vec<_>>();
+    assert!(!latches.is_empty());
+    assert!(latches.iter().all(|line| line.ends_with(" re arg_0[0] 3")));
+    // Every net that is read is driven by an input, a cover or a latch
+    let mut driven = std::collections::HashSet::new();
+    let mut read = vec![];
+    for line in blif.lines() {
+        let fields = line.split(' ').collect::<Vec<_>>();
+        match fields[0] {
+            ".inputs" => driven.extend(fields[1..].iter().copied()),
+            ".names" => {
+                driven.insert(fields[fields.len() - 1]);
+                read.extend(fields[1..fields.len() - 1].iter().copied());
+            }
+            ".latch" => {
+                driven.insert(fields[2]);
+                read.push(fields[1]);
+            }
+            _ => {}
+        }
+    }
+    for net in read {
+        assert!(driven.contains(net), "net {net} is not driven");
+    }
+    let aag = rhdl::core::ntl::aiger::netlist_aiger(&ntl)?;
+    let header = aag.lines().next().unwrap().split(' ').collect::<Vec<_>>();
+    assert_eq!(header[0], "aag");
+    assert_eq!(header[2], "26");
+    assert_eq!(header[3], latches.len().to_string());
+    assert_eq!(header[4], "8");
+    Ok(())
+}
+
+#[test]
+fn test_counter_blif_and_aiger() -> miette::Result<()> {
+    let uut: Counter<U6> = Counter::default();
+    let ntl = uut.descriptor("top")?.ntl;
+    let blif = rhdl::core::ntl::blif::netlist_blif("top", &ntl)?;
+    let latches = blif.lines().filter(|line| line.starts_with(".latch"));
+    assert_eq!(latches.filter(|line| line.ends_with(" 0")).count(), 6);
+    // Run the AIGER model with reset held low and the input held high
+    let mut aiger = Aiger::new(&rhdl::core::ntl::aiger::netlist_aiger(&ntl)?);
+    assert_eq!(aiger.num_latches(), 6);
+    let cr = clock_reset(clock(false), reset(false));
+    let mut inputs = to_bools(cr.bin());
+    inputs.push(true);
+    for step in 0..70 {
+        let count = aiger
+            .step(&inputs)
+            .iter()
+            .enumerate()
+            .map(|(bit, value)| (*value as usize) << bit)
+            .sum::<usize>();
+        assert_eq!(count, step % 64);
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use rhdl::core::compiler::{
+    cache::{CompiledDesign, set_cache_dir},
+    driver::compile_design_artifacts,
+    optimize_ntl,
+};
+use rhdl::prelude::*;
+
+#[kernel]
+fn add_one(a: b8) -> b8 {
+    a + 1
+}
+
+#[kernel]
+fn add_two(a: b8) -> b8 {
+    a + 2
+}
+
+#[kernel]
+fn calls_add_one(a: Signal<b8, Red>) -> Signal<b8, Red> {
+    signal(add_one(a.val()) ^ 0x55)
+}
+
+#[kernel]
+fn calls_add_two(a: Signal<b8, Red>) -> Signal<b8, Red> {
+    signal(add_two(a.val()) ^ 0x55)
+}
+
+fn entries(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect()
+}
+
+// The cache directory is global, so everything is checked in one test.
+#[test]
+fn test_compiled_kernels_are_cached() -> miette::Result<()> {
+    let dir = tempfile::tempdir().unwrap();
+    set_cache_dir(Some(dir.path().to_owned()));
+    let first = compile_design_artifacts::<calls_add_one>(CompilationMode::Asynchronous)?;
+    let files = entries(dir.path());
+    assert_eq!(files.len(), 1);
+    let text = std::fs::read_to_string(&files[0]).unwrap();
+    assert!(ron::de::from_str::<CompiledDesign>(&text).is_ok());
+    // A second compile is served from the cache, and matches the original
+    let second = compile_design_artifacts::<calls_add_one>(CompilationMode::Asynchronous)?;
+    assert_eq!(entries(dir.path()).len(), 1);
+    assert_eq!(format!("{:?}", first.rhif), format!("{:?}", second.rhif));
+    assert_eq!(format!("{:?}", first.rtl), format!("{:?}", second.rtl));
+    assert_eq!(format!("{:?}", first.ntl), format!("{:?}", second.ntl));
+    // The netlist is cached after it has been optimized
+    let optimized = optimize_ntl(second.ntl.clone())?;
+    assert_eq!(format!("{optimized:?}"), format!("{:?}", second.ntl));
+    let arg = signal::<_, Red>(b8(0x10));
+    let args = vec![arg.typed_bits()];
+    let expected = calls_add_one(arg).typed_bits();
+    assert_eq!(rhdl::core::rhif::vm::execute(&second.rhif, args)?, expected);
+    // The mode and the called kernels are part of the key
+    compile_design_artifacts::<calls_add_one>(CompilationMode::Synchronous)?;
+    compile_design_artifacts::<calls_add_two>(CompilationMode::Asynchronous)?;
+    assert_eq!(entries(dir.path()).len(), 3);
+    // A damaged entry is ignored and rewritten
+    std::fs::write(&files[0], "garbage").unwrap();
+    let third = compile_design_artifacts::<calls_add_one>(CompilationMode::Asynchronous)?;
+    assert_eq!(format!("{:?}", first.rtl), format!("{:?}", third.rtl));
+    assert_ne!(std::fs::read_to_string(&files[0]).unwrap(), "garbage");
+    set_cache_dir(None);
+    Ok(())
+}
@@ -522,3 +522,48 @@ fn test_tuple_unused_variable() -> miette::Result<()> {
     compile_design::<do_stuff>(Asynchronous)?;
     Ok(())
 }
+
+domain!(PixelClk);
+domain!(EthRxClk);
+
+#[test]
+fn test_named_clock_domains() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff<C: Domain, D: Domain>(a: Signal<b8, C>, b: Signal<b8, D>) -> Signal<b8, C> {
+        signal(a.val() + b.val())
+    }
+
+    compile_design::<do_stuff<PixelClk, PixelClk>>(Asynchronous)?;
+    let err = compile_design::<do_stuff<PixelClk, EthRxClk>>(Asynchronous).unwrap_err();
+    let report = format!("{:?}", miette::Report::new(err));
+    assert!(report.contains("PixelClk"));
+    assert!(report.contains("EthRxClk"));
+    assert_ne!(PixelClk::color(), Red::color());
+    let color = rhdl::rtt::Color::from(EthRxClk::color());
+    assert_eq!(color, rhdl::rtt::Color::named("clock::EthRxClk"));
+    assert_eq!(color.name(), "EthRxClk");
+    Ok(())
+}
+
+mod other {
+    use rhdl::prelude::*;
+
+    domain!(PixelClk);
+}
+
+#[test]
+fn test_named_clock_domains_are_identified_by_path() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff<C: Domain, D: Domain>(a: Signal<b8, C>, b: Signal<b8, D>) -> Signal<b8, C> {
+        signal(a.val() + b.val())
+    }
+
+    assert_ne!(PixelClk::color(), other::PixelClk::color());
+    assert_eq!(PixelClk::color().name(), other::PixelClk::color().name());
+    assert!(compile_design::<do_stuff<PixelClk, other::PixelClk>>(Asynchronous).is_err());
+    let color = rhdl::rtt::Color::from(other::PixelClk::color());
+    let copy = color;
+    assert_eq!(copy, color);
+    assert_eq!(color, rhdl::rtt::Color::named("clock::other::PixelClk"));
+    Ok(())
+}
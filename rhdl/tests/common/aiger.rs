@@ -0,0 +1,68 @@
+// A simulator for ASCII AIGER files, as written by `netlist_aiger`.
+pub struct Aiger {
+    inputs: Vec<usize>,
+    // The literal of each latch, and of its next state
+    latches: Vec<(usize, usize)>,
+    outputs: Vec<usize>,
+    ands: Vec<(usize, usize, usize)>,
+    values: Vec<bool>,
+}
+
+fn numbers(line: &str) -> Vec<usize> {
+    line.split(' ').map(|n| n.parse().unwrap()).collect()
+}
+
+impl Aiger {
+    // The latches start out at their initial values (uninitialized
+    // latches start out false)
+    pub fn new(aag: &str) -> Self {
+        let lines = aag.lines().collect::<Vec<_>>();
+        let [max_var, num_inputs, num_latches, num_outputs, num_ands] =
+            numbers(lines[0].strip_prefix("aag ").expect("bad header"))[..]
+        else {
+            panic!("bad header");
+        };
+        let mut lines = lines[1..].iter().map(|line| numbers(line));
+        let inputs = lines.by_ref().take(num_inputs).map(|n| n[0]).collect();
+        let latch_lines = lines.by_ref().take(num_latches).collect::<Vec<_>>();
+        let outputs = lines.by_ref().take(num_outputs).map(|n| n[0]).collect();
+        let ands = lines.take(num_ands).map(|n| (n[0], n[1], n[2])).collect();
+        let mut values = vec![false; max_var + 1];
+        for latch in &latch_lines {
+            values[latch[0] / 2] = latch.get(2) == Some(&1);
+        }
+        Self {
+            inputs,
+            latches: latch_lines.iter().map(|n| (n[0], n[1])).collect(),
+            outputs,
+            ands,
+            values,
+        }
+    }
+    fn lit(&self, lit: usize) -> bool {
+        self.values[lit / 2] ^ (lit % 2 == 1)
+    }
+    // Compute the outputs for the given inputs, and then clock the latches
+    pub fn step(&mut self, inputs: &[bool]) -> Vec<bool> {
+        assert_eq!(inputs.len(), self.inputs.len());
+        for (lit, value) in self.inputs.iter().zip(inputs) {
+            self.values[lit / 2] = *value;
+        }
+        for (lhs, a, b) in self.ands.clone() {
+            self.values[lhs / 2] = self.lit(a) && self.lit(b);
+        }
+        let outputs = self.outputs.iter().map(|lit| self.lit(*lit)).collect();
+        let next = self
+            .latches
+            .iter()
+            .map(|(_, next)| self.lit(*next))
+            .collect::<Vec<_>>();
+        for ((lit, _), value) in self.latches.iter().zip(next) {
+            self.values[lit / 2] = value;
+        }
+        outputs
+    }
+    pub fn num_latches(&self) -> usize {
+        self.latches.len()
+    }
+}
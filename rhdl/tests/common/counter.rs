@@ -0,0 +1,156 @@
+// A counter built on a register with a reset value, so that the
+// exports and estimates can be tested on a design with state.
+use rhdl::core::hdl::ast::{
+    Declaration, Direction, Events, HDLKind, Module, always, assign, bit_string, id, if_statement,
+    index_bit, initial, non_blocking_assignment, port, unsigned_width,
+};
+use rhdl::core::types::bit_string::BitString;
+use rhdl::prelude::*;
+
+// A positive edge triggered register that loads `reset` on reset
+#[derive(Clone)]
+pub struct Register<N: BitWidth> {
+    reset: Bits<N>,
+}
+
+impl<N: BitWidth> SynchronousIO for Register<N> {
+    type I = Bits<N>;
+    type O = Bits<N>;
+    type Kernel = NoKernel3<ClockReset, Bits<N>, (), (Bits<N>, ())>;
+}
+
+impl<N: BitWidth> SynchronousDQ for Register<N> {
+    type D = ();
+    type Q = ();
+}
+
+#[derive(PartialEq, Debug, Digital)]
+pub struct S<N: BitWidth> {
+    cr: ClockReset,
+    reset: Reset,
+    current: Bits<N>,
+    next: Bits<N>,
+}
+
+impl<N: BitWidth> Synchronous for Register<N> {
+    type S = S<N>;
+
+    fn init(&self) -> Self::S {
+        Self::S::dont_care()
+    }
+
+    fn sim(&self, clock_reset: ClockReset, input: Self::I, state: &mut Self::S) -> Self::O {
+        let clock = clock_reset.clock;
+        if !clock.raw() {
+            state.next = input;
+            state.reset = clock_reset.reset;
+        }
+        if clock.raw() && !state.cr.clock.raw() {
+            state.current = if state.reset.raw() {
+                self.reset
+            } else {
+                state.next
+            };
+        }
+        state.cr = clock_reset;
+        state.current
+    }
+
+    fn description(&self) -> String {
+        "Register with a reset value".into()
+    }
+
+    fn hdl(&self, name: &str) -> Result<HDLDescriptor, RHDLError> {
+        let mut module = Module {
+            name: name.into(),
+            ..Default::default()
+        };
+        let init: BitString = self.reset.typed_bits().into();
+        module.ports = vec![
+            port(
+                "clock_reset",
+                Direction::Input,
+                HDLKind::Wire,
+                unsigned_width(2),
+            ),
+            port(
+                "i",
+                Direction::Input,
+                HDLKind::Wire,
+                unsigned_width(N::BITS),
+            ),
+            port(
+                "o",
+                Direction::Output,
+                HDLKind::Reg,
+                unsigned_width(N::BITS),
+            ),
+        ];
+        for wire in ["clock", "reset"] {
+            module.declarations.push(Declaration {
+                kind: HDLKind::Wire,
+                name: wire.into(),
+                width: unsigned_width(1),
+                alias: None,
+            });
+        }
+        module.statements = vec![
+            initial(vec![assign("o", bit_string(&init))]),
+            continuous_assignment("clock", index_bit("clock_reset", 0)),
+            continuous_assignment("reset", index_bit("clock_reset", 1)),
+            always(
+                vec![Events::Posedge("clock".into())],
+                vec![if_statement(
+                    id("reset"),
+                    vec![non_blocking_assignment("o", bit_string(&init))],
+                    vec![non_blocking_assignment("o", id("i"))],
+                )],
+            ),
+        ];
+        Ok(HDLDescriptor {
+            name: name.into(),
+            body: module,
+            children: Default::default(),
+        })
+    }
+
+    fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError> {
+        let ntl = rhdl::core::ntl::builder::register_black_box(self, name, self.reset)?;
+        Ok(CircuitDescriptor {
+            unique_name: name.to_string(),
+            input_kind: Self::I::static_kind(),
+            output_kind: Self::O::static_kind(),
+            ntl,
+            ..Default::default()
+        })
+    }
+}
+
+// Counts the clocks in which the input is true
+#[derive(Clone, Synchronous, SynchronousDQ)]
+pub struct Counter<N: BitWidth> {
+    count: Register<N>,
+}
+
+impl<N: BitWidth> Default for Counter<N> {
+    fn default() -> Self {
+        Self {
+            count: Register {
+                reset: Bits::<N>::default(),
+            },
+        }
+    }
+}
+
+impl<N: BitWidth> SynchronousIO for Counter<N> {
+    type I = bool;
+    type O = Bits<N>;
+    type Kernel = counter<N>;
+}
+
+#[kernel]
+pub fn counter<N: BitWidth>(cr: ClockReset, enable: bool, q: Q<N>) -> (Bits<N>, D<N>) {
+    let next_count = if enable { q.count + 1 } else { q.count };
+    let next_count = if cr.reset.any() { bits(0) } else { next_count };
+    (q.count, D::<N> { count: next_count })
+}
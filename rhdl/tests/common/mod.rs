@@ -1,4 +1,7 @@
 #![allow(dead_code)]
+pub mod aiger;
+pub mod counter;
+
 use rhdl::prelude::*;
 
 pub fn exhaustive<N: BitWidth>() -> Vec<Bits<N>> {
@@ -45,3 +48,8 @@ pub fn s8_red() -> impl Iterator<Item = (Signal<s8, Red>,)> + Clone {
 pub fn red<T: Digital>(x: T) -> Signal<T, Red> {
     signal(x)
 }
+
+// The bits of a value, with the unknown bits as false
+pub fn to_bools(bits: Vec<BitX>) -> Vec<bool> {
+    bits.into_iter().map(|bit| bit == BitX::One).collect()
+}
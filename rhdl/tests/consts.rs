@@ -0,0 +1,110 @@
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl::core::sim::testbench::kernel::test_kernel_vm_and_verilog;
+
+const fn log2_ceil(x: usize) -> usize {
+    let mut n = 0;
+    while (1 << n) < x {
+        n += 1;
+    }
+    n
+}
+
+const OFFSET: b8 = b8(3);
+// Not Digital, so this is an untyped integer like `3`
+const STEPS: u32 = 3;
+
+#[test]
+fn test_const_items() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<(b8, b8), Red> {
+        let a = a.val();
+        let mut b = a;
+        for _i in 0..STEPS {
+            b += 1;
+        }
+        signal((a + OFFSET, b))
+    }
+
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_associated_consts() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff<N: BitWidth>(a: Signal<Bits<N>, Red>) -> Signal<Bits<N>, Red> {
+        let a = a.val();
+        signal(a + bits(N::BITS as u128))
+    }
+
+    test_kernel_vm_and_verilog::<do_stuff<U8>, _, _, _>(do_stuff::<U8>, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_const_fn_blocks() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff<N: BitWidth>(a: Signal<Bits<N>, Red>) -> Signal<(Bits<N>, bool), Red> {
+        let a = a.val();
+        let shift = const { log2_ceil(N::BITS) as u128 };
+        signal((a >> shift, const { N::BITS > 4 }))
+    }
+
+    test_kernel_vm_and_verilog::<do_stuff<U8>, _, _, _>(do_stuff::<U8>, tuple_exhaustive_red())?;
+    test_kernel_vm_and_verilog::<do_stuff<U4>, _, _, _>(do_stuff::<U4>, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_const_fn_calls() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff<N: BitWidth>(a: Signal<Bits<N>, Red>) -> Signal<Bits<N>, Red> {
+        let a = a.val();
+        signal(a >> log2_ceil(N::BITS) as u128)
+    }
+
+    test_kernel_vm_and_verilog::<do_stuff<U8>, _, _, _>(do_stuff::<U8>, tuple_exhaustive_red())?;
+    test_kernel_vm_and_verilog::<do_stuff<U4>, _, _, _>(do_stuff::<U4>, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_kernel_calls_with_const_args() -> miette::Result<()> {
+    #[kernel]
+    fn shift<N: BitWidth>(a: Bits<N>, n: u128) -> Bits<N> {
+        a >> n
+    }
+
+    #[kernel]
+    fn do_stuff<N: BitWidth>(a: Signal<Bits<N>, Red>) -> Signal<Bits<N>, Red> {
+        let a = a.val();
+        // A kernel called with constant arguments is still a kernel call
+        signal(shift::<N>(a, 2) + shift::<N>(bits(N::BITS as u128), 1))
+    }
+
+    test_kernel_vm_and_verilog::<do_stuff<U8>, _, _, _>(do_stuff::<U8>, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_typed_consts_keep_their_type() -> miette::Result<()> {
+    const WIDE: u128 = 5;
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        signal(a + WIDE)
+    }
+
+    // A u128 constant is Digital, and so is 128 bits wide
+    assert!(matches!(
+        compile_design::<do_stuff>(CompilationMode::Asynchronous),
+        Err(RHDLError::RHDLTypeCheckError(_))
+    ));
+    Ok(())
+}
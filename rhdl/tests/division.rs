@@ -0,0 +1,128 @@
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl::core::sim::testbench::kernel::test_kernel_vm_and_verilog;
+
+#[test]
+fn test_divide_by_constant_unsigned() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<[b8; 8], Red> {
+        let a = a.val();
+        signal([
+            a / b8(10),
+            a % b8(10),
+            a / b8(3),
+            a % b8(7),
+            a / b8(8),
+            a % b8(8),
+            a / b8(1),
+            a / b8(255),
+        ])
+    }
+
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_divide_by_constant_signed() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s8, Red>) -> Signal<[s8; 8], Red> {
+        let a = a.val();
+        signal([
+            a / s8(3),
+            a % s8(3),
+            a / s8(-7),
+            a % s8(-7),
+            a / s8(4),
+            a % s8(-4),
+            a / s8(-1),
+            a % s8(-128),
+        ])
+    }
+
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, s8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_binary_to_bcd() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<(b4, b4, b4), Red> {
+        let mut a = a.val();
+        let ones = a % 10;
+        a /= 10;
+        let tens = a % 10;
+        a /= 10;
+        signal((a.resize(), tens.resize(), ones.resize()))
+    }
+
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_divide_by_non_constant_is_an_error() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<b8, Red> {
+        signal(a.val() / b.val())
+    }
+
+    let Err(RHDLError::RHDLSyntaxError(err)) =
+        compile_design::<do_stuff>(CompilationMode::Asynchronous)
+    else {
+        panic!("Expected syntax error");
+    };
+    assert!(matches!(
+        err.cause,
+        rhdl::core::compiler::mir::error::Syntax::DivisionByNonConstant
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_divide_by_zero_is_an_error() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        signal(a.val() % b8(0))
+    }
+
+    let Err(RHDLError::RHDLSyntaxError(err)) =
+        compile_design::<do_stuff>(CompilationMode::Asynchronous)
+    else {
+        panic!("Expected syntax error");
+    };
+    assert!(matches!(
+        err.cause,
+        rhdl::core::compiler::mir::error::Syntax::DivisionByZero
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_netlist_rejects_division() -> miette::Result<()> {
+    use rhdl::core::ntl::{error::NetListICE, from_rtl::build_ntl_from_rtl};
+    use rhdl::core::rtl::spec::{AluBinary, OpCode};
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<b8, Red> {
+        signal(a.val() + b.val())
+    }
+
+    // RTL that did not go through the compiler (and so still divides)
+    // is an internal error, and not a panic
+    let mut rtl = compile_design::<do_stuff>(CompilationMode::Asynchronous)?;
+    for lop in &mut rtl.ops {
+        if let OpCode::Binary(binary) = &mut lop.op {
+            binary.op = AluBinary::Div;
+        }
+    }
+    let Err(RHDLError::NetListError(err)) = build_ntl_from_rtl(&rtl) else {
+        panic!("Expected a netlist error");
+    };
+    assert!(matches!(err.cause, NetListICE::UnloweredDivision));
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use rhdl::core::compiler::{dump::set_dump_dir, optimize_ntl};
+use rhdl::core::ntl::from_rtl::build_ntl_from_rtl;
+use rhdl::prelude::*;
+
+#[kernel]
+fn dumped(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<b8, Red> {
+    let c = a.val() + b.val();
+    signal(if c > 4 { c } else { b8(4) })
+}
+
+// The dump directory is global, so everything is checked in one test.
+#[test]
+fn test_per_pass_dumps_are_written() -> miette::Result<()> {
+    let dir = tempfile::tempdir().unwrap();
+    set_dump_dir(Some(dir.path().to_owned()));
+    let rtl = compile_design::<dumped>(CompilationMode::Asynchronous)?;
+    optimize_ntl(build_ntl_from_rtl(&rtl)?)?;
+    set_dump_dir(None);
+    for stage in ["rhif", "rtl", "ntl"] {
+        let stage_dir = dir.path().join("dumped").join(stage);
+        let summary = std::fs::read_to_string(stage_dir.join("summary.txt")).unwrap();
+        let lines = summary.lines().collect::<Vec<_>>();
+        assert!(lines.len() > 1);
+        assert!(lines[0].starts_with("000 Input"));
+        // One file per line of the summary, plus the summary itself
+        let files = std::fs::read_dir(&stage_dir).unwrap().count();
+        assert_eq!(files, lines.len() + 1);
+        let first = std::fs::read_to_string(stage_dir.join("000_Input.txt")).unwrap();
+        assert!(first.contains("dumped"));
+    }
+    let summary = std::fs::read_to_string(dir.path().join("dumped/rtl/summary.txt")).unwrap();
+    assert!(summary.contains("ConstantPropagationPass"));
+    Ok(())
+}
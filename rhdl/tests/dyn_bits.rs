@@ -276,3 +276,25 @@ fn test_xsgn_is_trapped_as_signed() -> miette::Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn test_xext_up_to_128_bits() {
+    let x = b64::MAX.dyn_bits().xext::<U64>();
+    assert_eq!(x.bits, 128);
+    assert_eq!(x.as_bits::<U128>(), b128(u64::MAX as u128));
+    let x = s64::MIN.dyn_bits().xext::<U64>();
+    assert_eq!(x.bits, 128);
+    assert_eq!(x.as_signed_bits::<U128>(), s128(i64::MIN as i128));
+}
+
+#[test]
+#[should_panic(expected = "DynBits is limited to 128 bits")]
+fn test_xext_past_128_bits_panics() {
+    let _ = b128::MAX.dyn_bits().xext::<U1>();
+}
+
+#[test]
+#[should_panic(expected = "SignedDynBits is limited to 128 bits")]
+fn test_signed_xshl_past_128_bits_panics() {
+    let _ = s128::MAX.dyn_bits().xshl::<U1>();
+}
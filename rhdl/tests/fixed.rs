@@ -0,0 +1,259 @@
+#![allow(unused_variables)]
+#![allow(unused_assignments)]
+#![allow(unused_mut)]
+#![allow(unreachable_code)]
+#![allow(unused_must_use)]
+#![allow(dead_code)]
+
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+use common::{aiger, to_bools};
+use rhdl::core::compiler::driver::compile_design_artifacts;
+use rhdl::core::ntl::aiger::netlist_aiger;
+use rhdl::core::sim::testbench::kernel::test_kernel_vm_and_verilog_synchronous;
+
+fn unsigned_samples() -> Vec<Fixed<U4, U4>> {
+    [0.0, 0.0625, 1.5, 2.75, 7.9375, 8.0, 12.3125, 15.9375]
+        .into_iter()
+        .map(fixed)
+        .collect()
+}
+
+fn signed_samples() -> Vec<SignedFixed<U4, U4>> {
+    [-8.0, -7.9375, -2.75, -0.0625, 0.0, 0.0625, 1.5, 7.9375]
+        .into_iter()
+        .map(signed_fixed)
+        .collect()
+}
+
+#[test]
+fn test_fixed_exact_arithmetic() -> miette::Result<()> {
+    #[kernel]
+    fn foo(
+        a: Fixed<U4, U4>,
+        b: Fixed<U4, U4>,
+    ) -> (Fixed<U5, U4>, SignedFixed<U5, U4>, Fixed<U8, U8>) {
+        (a.xadd(b), a.xsub(b), a.xmul(b))
+    }
+
+    let samples = unsigned_samples();
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        samples
+            .iter()
+            .flat_map(|&a| samples.iter().map(move |&b| (a, b))),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_signed_fixed_arithmetic() -> miette::Result<()> {
+    #[kernel]
+    fn foo(
+        a: SignedFixed<U4, U4>,
+        b: SignedFixed<U4, U4>,
+    ) -> (SignedFixed<U4, U4>, SignedFixed<U8, U8>, bool) {
+        (a - b, a.xmul(b), a >= b)
+    }
+
+    let samples = signed_samples();
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        samples
+            .iter()
+            .flat_map(|&a| samples.iter().map(move |&b| (a, b))),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_fixed_sign_changes() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: Fixed<U4, U4>, b: SignedFixed<U4, U4>) -> (SignedFixed<U5, U4>, Fixed<U4, U4>, bool) {
+        (a.xneg(), b.as_unsigned(), a < b.as_unsigned())
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        unsigned_samples().into_iter().zip(signed_samples()),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_fixed_binary_point_moves() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: Fixed<U4, U4>) -> (Fixed<U4, U2>, Fixed<U4, U6>, Fixed<U6, U4>) {
+        (a.xshr::<U2>(), a.xshl::<U2>(), a.xext::<U2>())
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        unsigned_samples().into_iter().map(|a| (a,)),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_fixed_struct_register() -> miette::Result<()> {
+    #[derive(PartialEq, Debug, Digital, Default)]
+    pub struct Accum {
+        sum: SignedFixed<U8, U4>,
+        last: SignedFixed<U4, U4>,
+    }
+
+    #[kernel]
+    fn foo(acc: Accum, x: SignedFixed<U4, U4>) -> Accum {
+        Accum {
+            sum: acc.sum + x.xext::<U4>(),
+            last: x,
+        }
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        signed_samples().into_iter().map(|x| {
+            (
+                Accum {
+                    sum: signed_fixed(-100.5),
+                    last: x,
+                },
+                x,
+            )
+        }),
+    )?;
+    Ok(())
+}
+
+// The value quantized with each rounding mode, quantized to more
+// fractional bits, and saturated to fewer and to more integer bits
+type Resized = (
+    [Fixed<U4, U2>; 4],
+    Fixed<U4, U6>,
+    Fixed<U2, U4>,
+    Fixed<U6, U4>,
+);
+type SignedResized = (
+    [SignedFixed<U4, U1>; 4],
+    SignedFixed<U4, U3>,
+    SignedFixed<U2, U4>,
+    SignedFixed<U6, U4>,
+);
+
+#[test]
+fn test_fixed_quantize_and_saturate() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: Fixed<U4, U4>) -> Resized {
+        (
+            [
+                a.quantize::<U2>(Rounding::Truncate),
+                a.quantize::<U2>(Rounding::TowardZero),
+                a.quantize::<U2>(Rounding::HalfUp),
+                a.quantize::<U2>(Rounding::HalfEven),
+            ],
+            a.quantize::<U6>(Rounding::HalfUp),
+            a.saturate::<U2>(),
+            a.saturate::<U6>(),
+        )
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        common::exhaustive::<U8>()
+            .into_iter()
+            .map(|x| (Fixed::from_bits(x),)),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_signed_fixed_quantize_and_saturate() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: SignedFixed<U4, U4>) -> SignedResized {
+        (
+            [
+                a.quantize::<U1>(Rounding::Truncate),
+                a.quantize::<U1>(Rounding::TowardZero),
+                a.quantize::<U1>(Rounding::HalfUp),
+                a.quantize::<U1>(Rounding::HalfEven),
+            ],
+            a.quantize::<U3>(Rounding::HalfEven),
+            a.saturate::<U2>(),
+            a.saturate::<U6>(),
+        )
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        common::exhaustive_signed::<U8>()
+            .into_iter()
+            .map(|x| (SignedFixed::from_signed_bits(x),)),
+    )?;
+    Ok(())
+}
+
+// The value quantized with each rounding mode, and saturated
+type Narrowed = ([Fixed<U4, U1>; 4], Fixed<U2, U4>);
+type SignedNarrowed = ([SignedFixed<U4, U2>; 4], SignedFixed<U2, U4>);
+
+// Simulate the netlist of the kernel, and check it against the kernel
+// itself for every argument
+fn check_netlist<K: DigitalFn, A: Digital, T: Digital>(
+    args: impl Iterator<Item = A>,
+    kernel: impl Fn(A) -> T,
+) -> miette::Result<()> {
+    let ntl = compile_design_artifacts::<K>(CompilationMode::Asynchronous)?.ntl;
+    let mut aiger = aiger::Aiger::new(&netlist_aiger(&ntl)?);
+    for a in args {
+        let expected = to_bools(kernel(a).typed_bits().bits);
+        assert_eq!(aiger.step(&to_bools(a.typed_bits().bits)), expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_quantize_and_saturate_netlist() -> miette::Result<()> {
+    #[kernel]
+    fn unsigned(a: Signal<Fixed<U4, U4>, Red>) -> Signal<Narrowed, Red> {
+        let a = a.val();
+        signal((
+            [
+                a.quantize::<U1>(Rounding::Truncate),
+                a.quantize::<U1>(Rounding::TowardZero),
+                a.quantize::<U1>(Rounding::HalfUp),
+                a.quantize::<U1>(Rounding::HalfEven),
+            ],
+            a.saturate::<U2>(),
+        ))
+    }
+
+    #[kernel]
+    fn signed(a: Signal<SignedFixed<U4, U4>, Red>) -> Signal<SignedNarrowed, Red> {
+        let a = a.val();
+        signal((
+            [
+                a.quantize::<U2>(Rounding::Truncate),
+                a.quantize::<U2>(Rounding::TowardZero),
+                a.quantize::<U2>(Rounding::HalfUp),
+                a.quantize::<U2>(Rounding::HalfEven),
+            ],
+            a.saturate::<U2>(),
+        ))
+    }
+
+    check_netlist::<unsigned, _, _>(
+        common::exhaustive::<U8>()
+            .into_iter()
+            .map(|x| signal(Fixed::from_bits(x))),
+        unsigned,
+    )?;
+    check_netlist::<signed, _, _>(
+        common::exhaustive_signed::<U8>()
+            .into_iter()
+            .map(|x| signal(SignedFixed::from_signed_bits(x))),
+        signed,
+    )?;
+    Ok(())
+}
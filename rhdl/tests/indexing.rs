@@ -379,3 +379,29 @@ fn test_link_to_bits_fn() -> miette::Result<()> {
     test_kernel_vm_and_verilog::<add<Red>, _, _, _>(add::<Red>, tuple_exhaustive_red())?;
     Ok(())
 }
+
+#[test]
+fn test_dynamic_index_into_const_table() -> miette::Result<()> {
+    const fn square_table() -> [b8; 16] {
+        let mut table = [b8(0); 16];
+        let mut ndx = 0;
+        while ndx < 16 {
+            table[ndx] = b8((ndx * ndx) as u128);
+            ndx += 1;
+        }
+        table
+    }
+
+    const SQUARES: [b8; 16] = square_table();
+
+    #[kernel]
+    fn foo(a: Signal<b8, Red>) -> Signal<(b8, b8), Red> {
+        let a = a.val();
+        let lo = a.resize::<U4>();
+        let hi = (a >> 4).resize::<U4>();
+        signal((SQUARES[lo], SQUARES[hi] + SQUARES[lo]))
+    }
+
+    test_kernel_vm_and_verilog::<foo, _, _, _>(foo, tuple_exhaustive_red())?;
+    Ok(())
+}
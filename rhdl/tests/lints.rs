@@ -0,0 +1,228 @@
+use rhdl::core::ast::Lint;
+use rhdl::core::compiler::{cache::set_cache_dir, lints::collect_lints};
+use rhdl::prelude::*;
+
+fn lints_of<F: FnOnce() -> Result<(), RHDLError>>(f: F) -> Vec<Lint> {
+    let guard = collect_lints();
+    f().unwrap();
+    guard.take().into_iter().map(|lint| lint.lint).collect()
+}
+
+#[kernel]
+fn shrink(a: Signal<b8, Red>) -> Signal<b4, Red> {
+    signal(a.val().resize())
+}
+
+#[kernel]
+fn shrink_literal(a: Signal<b4, Red>) -> Signal<b4, Red> {
+    signal(a.val() + b8(3).resize::<U4>())
+}
+
+#[kernel]
+#[rhdl(allow(lossy_resize))]
+fn shrink_allowed(a: Signal<b8, Red>) -> Signal<b4, Red> {
+    signal(a.val().resize())
+}
+
+#[kernel]
+#[rhdl(deny(lossy_resize))]
+fn shrink_denied(a: Signal<b8, Red>) -> Signal<b4, Red> {
+    signal(a.val().resize())
+}
+
+#[test]
+fn test_lossy_resize_lint() {
+    let compile = |f: fn(CompilationMode) -> Result<_, RHDLError>| {
+        lints_of(|| f(CompilationMode::Asynchronous).map(|_| ()))
+    };
+    assert_eq!(compile(compile_design::<shrink>), vec![Lint::LossyResize]);
+    assert!(compile(compile_design::<shrink_literal>).is_empty());
+    assert!(compile(compile_design::<shrink_allowed>).is_empty());
+    let err = compile_design::<shrink_denied>(CompilationMode::Asynchronous).unwrap_err();
+    assert!(matches!(err, RHDLError::RHDLLint(_)));
+}
+
+#[kernel]
+fn covered(a: Signal<b2, Red>) -> Signal<b4, Red> {
+    signal(match a.val().raw() {
+        0 => b4(1),
+        1 => b4(2),
+        2 => b4(4),
+        3 => b4(8),
+        _ => b4(0),
+    })
+}
+
+#[kernel]
+fn not_covered(a: Signal<b3, Red>) -> Signal<b4, Red> {
+    signal(match a.val().raw() {
+        0 => b4(1),
+        1 => b4(2),
+        2 => b4(4),
+        3 => b4(8),
+        _ => b4(0),
+    })
+}
+
+#[test]
+fn test_unreachable_arm_lint() {
+    let lints = lints_of(|| compile_design::<covered>(CompilationMode::Asynchronous).map(|_| ()));
+    assert_eq!(lints, vec![Lint::UnreachableArm]);
+    let lints =
+        lints_of(|| compile_design::<not_covered>(CompilationMode::Asynchronous).map(|_| ()));
+    assert!(lints.is_empty());
+}
+
+mod inverter {
+    use rhdl::prelude::*;
+
+    #[derive(Clone, Debug, Synchronous, Default)]
+    pub struct U;
+
+    impl SynchronousIO for U {
+        type I = bool;
+        type O = bool;
+        type Kernel = inverter;
+    }
+
+    impl SynchronousDQ for U {
+        type D = ();
+        type Q = ();
+    }
+
+    #[kernel]
+    pub fn inverter(_cr: ClockReset, i: bool, _q: ()) -> (bool, ()) {
+        (!i, ())
+    }
+}
+
+#[derive(PartialEq, Digital, Default)]
+pub struct D {
+    left: bool,
+    right: bool,
+}
+
+#[derive(PartialEq, Digital, Default)]
+pub struct Q {
+    left: bool,
+    right: bool,
+}
+
+#[derive(Clone, Debug, Synchronous, Default)]
+pub struct Partial {
+    left: inverter::U,
+    right: inverter::U,
+}
+
+impl SynchronousIO for Partial {
+    type I = bool;
+    type O = bool;
+    type Kernel = partial;
+}
+
+impl SynchronousDQ for Partial {
+    type D = D;
+    type Q = Q;
+}
+
+// The right inverter is never driven, and its output is never used
+#[kernel]
+pub fn partial(_cr: ClockReset, i: bool, q: Q) -> (bool, D) {
+    let d = D {
+        left: i,
+        ..D::default()
+    };
+    (q.left, d)
+}
+
+#[derive(Clone, Debug, Synchronous, Default)]
+pub struct Constant {
+    left: inverter::U,
+    right: inverter::U,
+}
+
+impl SynchronousIO for Constant {
+    type I = bool;
+    type O = bool;
+    type Kernel = constant;
+}
+
+impl SynchronousDQ for Constant {
+    type D = D;
+    type Q = Q;
+}
+
+#[kernel]
+#[rhdl(allow(unread_q))]
+pub fn constant(_cr: ClockReset, i: bool, _q: Q) -> (bool, D) {
+    let mut d = D::default();
+    if i {
+        d.left = true;
+    } else {
+        d.right = true;
+    }
+    (true, d)
+}
+
+#[test]
+fn test_circuit_lints() -> miette::Result<()> {
+    let guard = collect_lints();
+    Partial::default().descriptor("uut")?;
+    let lints = guard.take();
+    let fields = lints
+        .iter()
+        .map(|lint| (lint.lint, lint.cause.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        vec![
+            (
+                Lint::UnwrittenD,
+                "Field `right` of the D type is never written".to_string()
+            ),
+            (
+                Lint::UnreadQ,
+                "Field `right` of the Q type is never read".to_string()
+            ),
+        ]
+    );
+    let lints = lints_of(|| Constant::default().descriptor("uut").map(|_| ()));
+    assert_eq!(lints, vec![Lint::ConstantOutput]);
+    Ok(())
+}
+
+#[test]
+fn test_descriptors_keep_their_lints() -> miette::Result<()> {
+    let descriptor = Partial::default().descriptor("uut")?;
+    let lints = descriptor
+        .lints
+        .iter()
+        .map(|lint| lint.lint)
+        .collect::<Vec<_>>();
+    assert_eq!(lints, vec![Lint::UnwrittenD, Lint::UnreadQ]);
+    assert!(descriptor.children["left"].lints.is_empty());
+    Ok(())
+}
+
+// Compiling the kernel of a circuit as a plain function first must not
+// let the cache skip the circuit lints, and a cached kernel still reports
+// its lints.
+#[test]
+fn test_cached_kernels_keep_their_lints() -> miette::Result<()> {
+    let dir = tempfile::tempdir().unwrap();
+    set_cache_dir(Some(dir.path().to_owned()));
+    compile_design::<partial>(CompilationMode::Synchronous)?;
+    for _ in 0..2 {
+        let guard = collect_lints();
+        let descriptor = Partial::default().descriptor("uut")?;
+        let lints = guard
+            .take()
+            .into_iter()
+            .map(|lint| lint.lint)
+            .collect::<Vec<_>>();
+        assert_eq!(lints, vec![Lint::UnwrittenD, Lint::UnreadQ]);
+        assert_eq!(descriptor.lints.len(), 2);
+    }
+    set_cache_dir(None);
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rhdl::core::compiler::{LowerToGates, NtlPass, NtlPipeline};
+use rhdl::core::ntl::{
+    Object,
+    object::LocatedOpCode,
+    spec::{OpCode, Unary, UnaryOp},
+};
+use rhdl::prelude::*;
+
+mod common;
+use common::{aiger::Aiger, counter::Counter, to_bools};
+
+type Trace<T> = Rc<RefCell<Vec<T>>>;
+
+// Records its tag each time it runs
+struct Tag {
+    tag: &'static str,
+    trace: Trace<&'static str>,
+}
+
+impl NtlPass for Tag {
+    fn run(&self, input: Object) -> Result<Object, RHDLError> {
+        self.trace.borrow_mut().push(self.tag);
+        Ok(input)
+    }
+    fn description(&self) -> &str {
+        "Records a tag in the trace"
+    }
+}
+
+// Records the number of ops in each netlist it sees
+struct CountOps {
+    trace: Trace<usize>,
+}
+
+impl NtlPass for CountOps {
+    fn run(&self, input: Object) -> Result<Object, RHDLError> {
+        self.trace.borrow_mut().push(input.ops.len());
+        Ok(input)
+    }
+    fn description(&self) -> &str {
+        "Records the number of ops in the netlist"
+    }
+}
+
+#[kernel]
+fn masked(_cr: ClockReset, a: b8) -> b8 {
+    (a & 0x0F) | (a & 0xF0)
+}
+
+#[test]
+fn test_user_ntl_passes_run_in_place() -> miette::Result<()> {
+    let uut = Func::try_new::<masked>()?;
+    let trace = Trace::default();
+    let tag = |tag| Tag {
+        tag,
+        trace: trace.clone(),
+    };
+    let pipeline = NtlPipeline::default()
+        .after_fixpoint(tag("after"))
+        .in_fixpoint(tag("during"))
+        .before_fixpoint(tag("before"));
+    let with = uut.netlist_hdl_with("top", &pipeline)?;
+    let seen = trace.take();
+    assert_eq!(seen.first(), Some(&"before"));
+    assert_eq!(seen.last(), Some(&"after"));
+    assert!(seen[1..seen.len() - 1].iter().all(|t| *t == "during"));
+    assert!(seen.len() > 2);
+    // Passes that leave the netlist alone do not change the output
+    let without = uut.netlist_hdl_with("top", &NtlPipeline::default())?;
+    assert!(trace.borrow().is_empty());
+    assert_eq!(with.as_verilog(), without.as_verilog());
+    Ok(())
+}
+
+#[test]
+fn test_user_ntl_passes_run_on_each_design() -> miette::Result<()> {
+    let uut = Counter::<U6>::default();
+    let trace = Trace::default();
+    let pipeline = NtlPipeline::default().before_fixpoint(CountOps {
+        trace: trace.clone(),
+    });
+    let with = uut.descriptor_with("top", &pipeline)?;
+    let without = uut.descriptor("top")?;
+    // The pass sees the register, and then the counter before it is optimized
+    let seen = trace.take();
+    assert_eq!(seen.len(), 2);
+    assert!(seen[1] > without.ntl.ops.len());
+    assert_eq!(with.ntl.ops.len(), without.ntl.ops.len());
+    Ok(())
+}
+
+const SQUARES: [b4; 4] = [b4(0), b4(1), b4(4), b4(9)];
+
+type Words = (
+    (b4, b4, b4, b4),
+    (bool, bool, bool, bool, bool),
+    (b4, s4, s4, b4),
+);
+
+#[kernel]
+fn words(_cr: ClockReset, (a, b, c, d): (b4, b4, s4, s4)) -> Words {
+    let arith = (a + b, a - b, a * b, SQUARES[a.resize::<U2>()]);
+    let cmp = (a < b, a >= b, a == b, c < d, c >= d);
+    let code = match a.raw() {
+        0 => b4(1),
+        3 => b4(2),
+        _ => b4(5),
+    };
+    let misc = (a << b, c >> b, -c, code);
+    (arith, cmp, misc)
+}
+
+#[test]
+fn test_lowered_gates_match_the_kernel() -> miette::Result<()> {
+    let uut = Func::try_new::<words>()?;
+    let ntl = uut.descriptor("top")?.ntl;
+    let lowered = NtlPipeline::default()
+        .before_fixpoint(LowerToGates::default())
+        .run(ntl.clone())?;
+    assert!(lowered.ops.iter().all(|lop| matches!(
+        lop.op,
+        OpCode::Comment(_)
+            | OpCode::Assign(_)
+            | OpCode::Binary(_)
+            | OpCode::Not(_)
+            | OpCode::Select(_)
+    )));
+    let mut aiger = Aiger::new(&rhdl::core::ntl::aiger::netlist_aiger(&ntl)?);
+    assert_eq!(aiger.num_latches(), 0);
+    let cr = clock_reset(clock(false), reset(false));
+    for (a, b, c, d) in itertools::iproduct!(0..16, 0..16, -8..8, -8..8) {
+        let (a, b, c, d) = (b4(a), b4(b), s4(c), s4(d));
+        let mut inputs = to_bools(cr.bin());
+        inputs.extend(to_bools((a, b, c, d).bin()));
+        let expected = to_bools(words(cr, (a, b, c, d)).bin());
+        assert_eq!(aiger.step(&inputs), expected, "{a:?} {b:?} {c:?} {d:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_lowered_gates_drop_ops_without_outputs() -> miette::Result<()> {
+    let uut = Func::try_new::<masked>()?;
+    let mut ntl = uut.descriptor("top")?.ntl;
+    let arg = ntl.inputs[1].iter().map(|&reg| reg.into()).collect();
+    ntl.ops.push(LocatedOpCode {
+        op: OpCode::Unary(Unary {
+            op: UnaryOp::Any,
+            lhs: vec![],
+            arg,
+        }),
+        loc: None,
+    });
+    let lowered = NtlPipeline::default()
+        .before_fixpoint(LowerToGates::default())
+        .run(ntl.clone())?;
+    assert!(
+        !lowered
+            .ops
+            .iter()
+            .any(|lop| matches!(lop.op, OpCode::Unary(_)))
+    );
+    // The exports lower the netlist themselves, so they accept it too
+    rhdl::core::ntl::aiger::netlist_aiger(&ntl)?;
+    rhdl::core::ntl::blif::netlist_blif("top", &ntl)?;
+    Ok(())
+}
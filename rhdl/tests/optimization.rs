@@ -1,5 +1,6 @@
 use rhdl::core::{
-    rtl::spec::AluBinary, rtl::spec::Binary, sim::testbench::kernel::test_kernel_vm_and_verilog,
+    compiler::optimize_ntl, ntl::from_rtl::build_ntl_from_rtl, rtl::spec::AluBinary,
+    rtl::spec::Binary, sim::testbench::kernel::test_kernel_vm_and_verilog,
 };
 use rhdl::prelude::*;
 #[cfg(test)]
@@ -554,3 +555,82 @@ fn test_empty_case_dropped() -> miette::Result<()> {
     assert!(no_empty_operands(&rtl));
     Ok(())
 }
+
+#[test]
+fn test_dynamic_index_into_const_table_is_a_rom() -> miette::Result<()> {
+    const TABLE: [b8; 8] = [b8(1), b8(2), b8(4), b8(8), b8(16), b8(32), b8(64), b8(128)];
+    #[kernel]
+    fn foo(a: b3) -> b8 {
+        TABLE[a]
+    }
+    let rtl = compile_design::<foo>(CompilationMode::Synchronous)?;
+    assert!(
+        rtl.ops
+            .iter()
+            .any(|op| matches!(op.op, rhdl::core::rtl::spec::OpCode::Rom(_)))
+    );
+    rtl.ops
+        .iter()
+        .for_each(|op| assert!(!matches!(op.op, rhdl::core::rtl::spec::OpCode::Case(_))));
+    Ok(())
+}
+
+#[test]
+fn test_repeated_expressions_are_computed_once() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        let b = b.val();
+        let c = if a > b { a + b } else { (b + a) ^ 0x55 };
+        let d = if b < a { c } else { c + 1 };
+        signal(d)
+    }
+    let rtl = compile_design::<foo>(CompilationMode::Asynchronous)?;
+    let count = |alu: fn(&AluBinary) -> bool| {
+        rtl.ops
+            .iter()
+            .filter(|lop| match &lop.op {
+                rhdl::core::rtl::spec::OpCode::Binary(binary) => alu(&binary.op),
+                _ => false,
+            })
+            .count()
+    };
+    // a + b and b + a share an adder, and c + 1 needs another
+    assert_eq!(count(|op| matches!(op, AluBinary::Add)), 2);
+    // a > b and b < a share a comparator
+    assert_eq!(count(|op| matches!(op, AluBinary::Gt | AluBinary::Lt)), 1);
+    test_kernel_vm_and_verilog::<foo, _, _, _>(foo, tuple_pair_b8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_repeated_gates_in_netlist_are_computed_once() -> miette::Result<()> {
+    use rhdl::core::ntl::spec::{Binary, BinaryOp, OpCode};
+    #[kernel]
+    fn foo(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        let b = b.val();
+        let x = a & b;
+        // Different widths in RTL, but the same 8 gates in the netlist
+        let y: b16 = a.resize() & b.resize();
+        signal(x + y.resize())
+    }
+    let rtl = compile_design::<foo>(CompilationMode::Asynchronous)?;
+    let ntl = optimize_ntl(build_ntl_from_rtl(&rtl)?)?;
+    let ands = ntl
+        .ops
+        .iter()
+        .filter(|op| {
+            matches!(
+                op.op,
+                OpCode::Binary(Binary {
+                    op: BinaryOp::And,
+                    ..
+                })
+            )
+        })
+        .count();
+    assert_eq!(ands, 8);
+    test_kernel_vm_and_verilog::<foo, _, _, _>(foo, tuple_pair_b8_red())?;
+    Ok(())
+}
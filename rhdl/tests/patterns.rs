@@ -0,0 +1,151 @@
+#![allow(unused_variables)]
+#![allow(unused_assignments)]
+#![allow(unused_mut)]
+#![allow(unreachable_code)]
+#![allow(unused_must_use)]
+#![allow(dead_code)]
+
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+#[cfg(test)]
+use common::*;
+use rhdl::core::sim::testbench::kernel::test_kernel_vm_and_verilog_synchronous;
+
+#[test]
+fn test_match_guard_on_value() -> miette::Result<()> {
+    const MAX: b8 = bits(255);
+    #[kernel]
+    fn foo(a: b8) -> b8 {
+        match a {
+            x if x < 16 => x,
+            MAX => bits(1),
+            x if x > 200 => x - 200,
+            _ => bits(2),
+        }
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        exhaustive().into_iter().map(|a| (a,)),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_match_guard_on_option() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: b8, valid: bool) -> b8 {
+        let a: Option<b8> = if valid { Some(a) } else { None };
+        match a {
+            Some(x) if x > 4 => x,
+            None => bits(3),
+            Some(x) => x + 1,
+        }
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        exhaustive()
+            .into_iter()
+            .flat_map(|a| [(a, false), (a, true)]),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_match_guard_state_machine() -> miette::Result<()> {
+    #[derive(PartialEq, Debug, Digital, Default)]
+    pub enum State {
+        #[default]
+        Idle,
+        Run(b4),
+        Done,
+    }
+
+    #[kernel]
+    fn foo(state: State, start: bool) -> (State, bool) {
+        let mut busy = false;
+        let next = match state {
+            State::Idle if start => State::Run(bits(0)),
+            State::Run(count) if count == 15 => State::Done,
+            State::Run(count) => {
+                busy = true;
+                State::Run(count + 1)
+            }
+            State::Done if !start => State::Idle,
+            _ => state,
+        };
+        (next, busy)
+    }
+
+    let states = [State::Idle, State::Done]
+        .into_iter()
+        .chain(exhaustive::<U4>().into_iter().map(State::Run))
+        .collect::<Vec<_>>();
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        states
+            .into_iter()
+            .flat_map(|state| [(state, false), (state, true)]),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_slice_pattern_in_let() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: [b4; 4]) -> (b4, b4, b4) {
+        let [first, .., last] = a;
+        let [_, second, ..] = a;
+        (first, second, last)
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        exhaustive::<U4>()
+            .into_iter()
+            .map(|x| ([x, x + 1, !x, x + 3],)),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_slice_pattern_in_match_with_guard() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: [b4; 3]) -> b4 {
+        match a {
+            [first, .., last] if first == last => first,
+            [first, middle, _] if middle > first => middle - first,
+            [.., last] => last,
+        }
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        exhaustive::<U4>().into_iter().flat_map(|x| {
+            exhaustive::<U4>()
+                .into_iter()
+                .map(move |y| ([x, y, x ^ (y & bits(1))],))
+        }),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_tuple_pattern_in_match() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: b8, b: b8) -> b8 {
+        match (a, b) {
+            (x, y) if x > y => x - y,
+            (x, y) => y - x,
+        }
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        exhaustive().into_iter().map(|a: b8| (a, !a + 3)),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use rhdl::prelude::*;
+
+#[kernel]
+fn datapath(_cr: ClockReset, i: (b8, b8, b8)) -> b8 {
+    let (a, b, c) = i;
+    let x = a + b;
+    let y = x ^ c;
+    let z = y + a;
+    let w = z & b;
+    w - c
+}
+
+type Datapath = Pipelined<(b8, b8, b8), b8, U2>;
+
+fn inputs() -> impl Iterator<Item = TimedSample<(ClockReset, (b8, b8, b8))>> + Clone {
+    (0..50)
+        .map(|n| (b8(n * 7 % 256), b8(n * 13 % 256), b8(n * 29 % 256)))
+        .with_reset(1)
+        .clock_pos_edge(100)
+}
+
+// The outputs sampled just before each rising edge of the clock
+fn outputs<T: Synchronous<I = (b8, b8, b8), O = b8>>(uut: &T) -> miette::Result<Vec<b8>> {
+    Ok(uut
+        .run(inputs())?
+        .filter(|sample| !sample.value.0.clock.raw())
+        .map(|sample| sample.value.2)
+        .collect())
+}
+
+#[test]
+fn test_pipelined_latency() -> miette::Result<()> {
+    assert_eq!(Datapath::LATENCY, 2);
+    let comb = outputs(&Func::try_new::<datapath>()?)?;
+    let piped = outputs(&Datapath::try_new::<datapath>()?)?;
+    assert_eq!(comb[..comb.len() - 2], piped[2..]);
+    let piped = outputs(&Func::try_new::<datapath>()?.pipelined::<U2>())?;
+    assert_eq!(comb[..comb.len() - 2], piped[2..]);
+    Ok(())
+}
+
+// Drive the simulation directly, a clock at a time, and compare the
+// outputs just before each rising edge against the kernel.  The reset is
+// asserted part way through, and does not clear the values in flight.
+#[test]
+fn test_pipelined_latency_in_simulation() -> miette::Result<()> {
+    let uut = Datapath::try_new::<datapath>()?;
+    let mut state = uut.init();
+    let inputs = (0..20)
+        .map(|n| (b8(n * 7 % 256), b8(n * 13 % 256), b8(n * 29 % 256)))
+        .collect::<Vec<_>>();
+    let mut outputs = vec![];
+    for (n, &input) in inputs.iter().enumerate() {
+        let rst = reset((8..10).contains(&n));
+        outputs.push(uut.sim(clock_reset(clock(false), rst), input, &mut state));
+        uut.sim(clock_reset(clock(true), rst), input, &mut state);
+    }
+    let cr = clock_reset(clock(false), reset(false));
+    for (n, output) in outputs.iter().enumerate().skip(Datapath::LATENCY) {
+        assert_eq!(*output, datapath(cr, inputs[n - Datapath::LATENCY]));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_pipelined_stages_are_balanced() -> miette::Result<()> {
+    let comb = timing::critical_path(&Func::try_new::<datapath>()?, "top")?;
+    let uut = Datapath::try_new::<datapath>()?;
+    let piped = timing::critical_path(&uut, "top")?;
+    // Three 8 bit adders and two gates.  The ops are not split, so the
+    // first stage holds two of the adders.
+    assert_eq!(comb.depth, 8 + 1 + 8 + 1 + 8);
+    assert_eq!(piped.depth, 8 + 1 + 8);
+    let usage = resources::estimate_resources(&uut.descriptor("top")?.ntl);
+    assert!(usage.flip_flops >= 16);
+    Ok(())
+}
+
+#[test]
+fn test_pipelined_hdl() -> miette::Result<()> {
+    let uut = Datapath::try_new::<datapath>()?;
+    let test_bench = uut.run(inputs())?.collect::<SynchronousTestBench<_, _>>();
+    // The outputs are not defined until the pipeline fills
+    let options = TestBenchOptions::default().skip(2 * Datapath::LATENCY + 2);
+    let tm_rtl = test_bench.rtl(&uut, &options)?;
+    tm_rtl.run_iverilog()?;
+    let tm_ntl = test_bench.ntl(&uut, &options)?;
+    tm_ntl.run_iverilog()?;
+    Ok(())
+}
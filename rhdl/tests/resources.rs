@@ -0,0 +1,130 @@
+use expect_test::expect;
+use rhdl::prelude::*;
+
+mod common;
+use common::counter::Counter;
+
+#[kernel]
+fn arith(_cr: ClockReset, (a, b): (b8, b8)) -> (b8, b8, bool, bool) {
+    (a + b, a * b, a < b, a == b)
+}
+
+#[kernel]
+fn sum(_cr: ClockReset, (a, b): (b8, b8)) -> b8 {
+    a + b
+}
+
+#[kernel]
+fn and4(_cr: ClockReset, (a, b, c, d): (bool, bool, bool, bool)) -> bool {
+    a & b & c & d
+}
+
+#[kernel]
+fn and7(_cr: ClockReset, i: (bool, bool, bool, bool, bool, bool, bool)) -> bool {
+    i.0 & i.1 & i.2 & i.3 & i.4 & i.5 & i.6
+}
+
+#[kernel]
+fn shift(_cr: ClockReset, (a, b): (b8, b4)) -> b8 {
+    a << b
+}
+
+#[kernel]
+fn same(_cr: ClockReset, (a, b): (b8, b8)) -> bool {
+    a == b
+}
+
+// An adder next to a comparator
+#[derive(Clone, Synchronous, SynchronousDQ)]
+pub struct AddAndCompare {
+    adder: Func<(b8, b8), b8>,
+    compare: Func<(b8, b8), bool>,
+}
+
+impl SynchronousIO for AddAndCompare {
+    type I = (b8, b8);
+    type O = (b8, bool);
+    type Kernel = add_and_compare;
+}
+
+#[kernel]
+pub fn add_and_compare(_cr: ClockReset, i: (b8, b8), q: Q) -> ((b8, bool), D) {
+    let d = D {
+        adder: i,
+        compare: i,
+    };
+    ((q.adder, q.compare), d)
+}
+
+#[test]
+fn test_operators_are_counted_by_width() -> miette::Result<()> {
+    let uut = Func::try_new::<arith>()?;
+    let usage = resources::estimate_resources(&uut.descriptor("top")?.ntl);
+    assert_eq!(usage.flip_flops, 0);
+    assert_eq!(usage.adders, [(8, 1)].into());
+    assert_eq!(usage.multipliers, [(8, 1)].into());
+    // Comparators are counted by the width of their arguments
+    assert_eq!(usage.comparators, [(8, 2)].into());
+    assert_eq!(usage.muxes, 0);
+    // Wider LUTs need fewer of them
+    assert!(usage.lut6 < usage.lut4);
+    Ok(())
+}
+
+#[test]
+fn test_logic_is_packed_into_luts() -> miette::Result<()> {
+    let usage = resources::estimate_resources(&Func::try_new::<and4>()?.descriptor("top")?.ntl);
+    assert_eq!((usage.lut4, usage.lut6), (1, 1));
+    // Seven inputs need a second LUT of either size
+    let usage = resources::estimate_resources(&Func::try_new::<and7>()?.descriptor("top")?.ntl);
+    assert_eq!((usage.lut4, usage.lut6), (2, 2));
+    Ok(())
+}
+
+#[test]
+fn test_lut_size_is_modelled() -> miette::Result<()> {
+    // A barrel shifter takes one LUT per bit for each level of muxes, and
+    // a 6 input LUT holds a 4:1 mux where a 4 input LUT holds a 2:1 mux
+    let usage = resources::estimate_resources(&Func::try_new::<shift>()?.descriptor("top")?.ntl);
+    assert_eq!((usage.lut4, usage.lut6), (8 * 4, 8 * 2));
+    // The carry chain of an adder needs one LUT per bit of either size
+    let usage = resources::estimate_resources(&Func::try_new::<sum>()?.descriptor("top")?.ntl);
+    assert_eq!((usage.lut4, usage.lut6), (8, 8));
+    Ok(())
+}
+
+#[test]
+fn test_report_follows_the_hierarchy() -> miette::Result<()> {
+    let uut = AddAndCompare {
+        adder: Func::try_new::<sum>()?,
+        compare: Func::try_new::<same>()?,
+    };
+    let report = resources::resource_report(&uut.descriptor("top")?);
+    assert_eq!(report.children["adder"].usage.adders, [(8, 1)].into());
+    assert_eq!(
+        report.children["compare"].usage.comparators,
+        [(8, 1)].into()
+    );
+    let expect = expect![[r#"
+        circuit    ff  lut4  lut6  mux  add  mul  cmp
+        top         0    13    12    0  1x8    -  1x8
+          adder     0     8     8    0  1x8    -    -
+          compare   0     5     4    0    -    -  1x8
+    "#]];
+    expect.assert_eq(&report.to_string());
+    Ok(())
+}
+
+#[test]
+fn test_registers_are_counted() -> miette::Result<()> {
+    let uut: Counter<U6> = Counter::default();
+    let report = resources::resource_report(&uut.descriptor("top")?);
+    assert_eq!(report.usage.flip_flops, 6);
+    let expect = expect![[r#"
+        circuit  ff  lut4  lut6  mux  add  mul  cmp
+        top       6    12    12   12  1x6    -    -
+          count   6     0     0    0    -    -    -
+    "#]];
+    expect.assert_eq(&report.to_string());
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl::core::compiler::driver::compile_design_artifacts;
+use rhdl::core::ntl::aiger::netlist_aiger;
+use rhdl::core::sim::testbench::kernel::test_kernel_vm_and_verilog;
+
+#[test]
+fn test_saturating_unsigned() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a1: Signal<b4, Red>, a2: Signal<b4, Red>) -> Signal<(b4, b4, b4), Red> {
+        let a1 = a1.val();
+        let a2 = a2.val();
+        signal((
+            a1.saturating_add(a2),
+            a1.saturating_sub(a2),
+            a1.saturating_mul(a2),
+        ))
+    }
+
+    let args = exhaustive::<U4>().into_iter().flat_map(|a1| {
+        exhaustive::<U4>()
+            .into_iter()
+            .map(move |a2| (red(a1), red(a2)))
+    });
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_saturating_signed() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a1: Signal<s4, Red>, a2: Signal<s4, Red>) -> Signal<(s4, s4, s4), Red> {
+        let a1 = a1.val();
+        let a2 = a2.val();
+        signal((
+            a1.saturating_add(a2),
+            a1.saturating_sub(a2),
+            a1.saturating_mul(a2),
+        ))
+    }
+
+    let args = exhaustive_signed::<U4>().into_iter().flat_map(|a1| {
+        exhaustive_signed::<U4>()
+            .into_iter()
+            .map(move |a2| (red(a1), red(a2)))
+    });
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_overflowing_unsigned() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a1: Signal<b4, Red>, a2: Signal<b4, Red>) -> Signal<[(b4, bool); 3], Red> {
+        let a1 = a1.val();
+        let a2 = a2.val();
+        signal([
+            a1.overflowing_add(a2),
+            a1.overflowing_sub(a2),
+            a1.overflowing_mul(a2),
+        ])
+    }
+
+    let args = exhaustive::<U4>().into_iter().flat_map(|a1| {
+        exhaustive::<U4>()
+            .into_iter()
+            .map(move |a2| (red(a1), red(a2)))
+    });
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_overflowing_signed() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a1: Signal<s4, Red>, a2: Signal<s4, Red>) -> Signal<[(s4, bool); 3], Red> {
+        let a1 = a1.val();
+        let a2 = a2.val();
+        signal([
+            a1.overflowing_add(a2),
+            a1.overflowing_sub(a2),
+            a1.overflowing_mul(a2),
+        ])
+    }
+
+    let args = exhaustive_signed::<U4>().into_iter().flat_map(|a1| {
+        exhaustive_signed::<U4>()
+            .into_iter()
+            .map(move |a2| (red(a1), red(a2)))
+    });
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_saturating_accumulator() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(acc: Signal<s8, Red>, x: Signal<s8, Red>) -> Signal<(s8, bool), Red> {
+        let (sum, overflow) = acc.val().overflowing_add(x.val());
+        if overflow {
+            signal((acc.val().saturating_add(x.val()), true))
+        } else {
+            signal((sum, false))
+        }
+    }
+
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_pair_s8_red())?;
+    Ok(())
+}
+
+// The saturating results, and the overflowing ones
+type Checked<T> = ([T; 3], [(T, bool); 3]);
+
+// Simulate the netlist of the kernel, and check it against the kernel
+// itself for every pair of arguments
+fn check_netlist<K: DigitalFn, A: Digital, T: Digital>(
+    args: impl Iterator<Item = (A, A)>,
+    kernel: impl Fn(A, A) -> T,
+) -> miette::Result<()> {
+    let ntl = compile_design_artifacts::<K>(CompilationMode::Asynchronous)?.ntl;
+    let mut aiger = aiger::Aiger::new(&netlist_aiger(&ntl)?);
+    for (a1, a2) in args {
+        let mut inputs = a1.typed_bits().bits;
+        inputs.extend(a2.typed_bits().bits);
+        let expected = to_bools(kernel(a1, a2).typed_bits().bits);
+        assert_eq!(aiger.step(&to_bools(inputs)), expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_checked_arithmetic_netlist() -> miette::Result<()> {
+    #[kernel]
+    fn unsigned(a1: Signal<b4, Red>, a2: Signal<b4, Red>) -> Signal<Checked<b4>, Red> {
+        let a1 = a1.val();
+        let a2 = a2.val();
+        signal((
+            [
+                a1.saturating_add(a2),
+                a1.saturating_sub(a2),
+                a1.saturating_mul(a2),
+            ],
+            [
+                a1.overflowing_add(a2),
+                a1.overflowing_sub(a2),
+                a1.overflowing_mul(a2),
+            ],
+        ))
+    }
+
+    #[kernel]
+    fn signed(a1: Signal<s4, Red>, a2: Signal<s4, Red>) -> Signal<Checked<s4>, Red> {
+        let a1 = a1.val();
+        let a2 = a2.val();
+        signal((
+            [
+                a1.saturating_add(a2),
+                a1.saturating_sub(a2),
+                a1.saturating_mul(a2),
+            ],
+            [
+                a1.overflowing_add(a2),
+                a1.overflowing_sub(a2),
+                a1.overflowing_mul(a2),
+            ],
+        ))
+    }
+
+    let args = exhaustive::<U4>().into_iter().flat_map(|a1| {
+        exhaustive::<U4>()
+            .into_iter()
+            .map(move |a2| (red(a1), red(a2)))
+    });
+    check_netlist::<unsigned, _, _>(args, unsigned)?;
+    let args = exhaustive_signed::<U4>().into_iter().flat_map(|a1| {
+        exhaustive_signed::<U4>()
+            .into_iter()
+            .map(move |a2| (red(a1), red(a2)))
+    });
+    check_netlist::<signed, _, _>(args, signed)?;
+    Ok(())
+}
@@ -11,7 +11,9 @@ use rhdl::prelude::*;
 mod common;
 #[cfg(test)]
 use common::*;
-use rhdl::core::sim::testbench::kernel::test_kernel_vm_and_verilog;
+use rhdl::core::sim::testbench::kernel::{
+    test_kernel_vm_and_verilog, test_kernel_vm_and_verilog_synchronous,
+};
 
 #[test]
 fn test_struct_expr_not_adt() -> miette::Result<()> {
@@ -93,3 +95,100 @@ fn test_struct_rest_syntax() -> miette::Result<()> {
     test_kernel_vm_and_verilog::<foo, _, _, _>(foo, tuple_pair_b8_red())?;
     Ok(())
 }
+
+#[test]
+fn test_struct_methods() -> miette::Result<()> {
+    #[derive(PartialEq, Debug, Digital)]
+    pub struct Accum {
+        sum: b8,
+        count: b4,
+    }
+
+    #[kernel]
+    impl Accum {
+        fn add(self, x: b8) -> Self {
+            Self {
+                sum: self.sum + x,
+                count: self.count + 1,
+            }
+        }
+        fn is_empty(&self) -> bool {
+            self.count == 0
+        }
+        fn total(self) -> b8 {
+            if self.is_empty() { bits(0) } else { self.sum }
+        }
+    }
+
+    #[kernel]
+    fn foo(a: b8, b: b8) -> (b8, bool) {
+        let acc = Accum {
+            sum: a,
+            count: bits(0),
+        };
+        let empty = acc.is_empty();
+        let acc = acc.add(b).add(a);
+        (acc.total(), empty)
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        exhaustive().into_iter().map(|a: b8| (a, !a)),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_generic_struct_methods() -> miette::Result<()> {
+    #[derive(PartialEq, Debug, Digital)]
+    pub struct Pair<T: Digital> {
+        a: T,
+        b: T,
+    }
+
+    #[kernel]
+    impl<T: Digital> Pair<T> {
+        fn swap(self) -> Self {
+            Self {
+                a: self.b,
+                b: self.a,
+            }
+        }
+    }
+
+    #[kernel]
+    fn foo(a: b8, b: b8) -> b8 {
+        let p = Pair::<b8> { a, b }.swap();
+        p.a - p.b
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        exhaustive().into_iter().map(|a: b8| (a, a >> 1)),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_signed_field_as_argument() -> miette::Result<()> {
+    #[derive(PartialEq, Debug, Digital)]
+    pub struct Acc {
+        sum: s4,
+    }
+    #[kernel]
+    fn g(a: s4, b: s4) -> s4 {
+        a + b
+    }
+    #[kernel]
+    fn foo(a: s4, b: s4) -> s4 {
+        let acc = Acc { sum: a };
+        g(acc.sum, b)
+    }
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        exhaustive_signed::<U4>()
+            .into_iter()
+            .flat_map(|a| exhaustive_signed::<U4>().into_iter().map(move |b| (a, b))),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+#![allow(dead_code, unused_variables)]
+use rhdl::prelude::*;
+
+#[derive(PartialEq, Debug, Digital, Default)]
+enum Cmd {
+    #[default]
+    Nop,
+    Read(b4),
+    Write {
+        addr: b4,
+        data: b8,
+    },
+}
+
+#[derive(PartialEq, Debug, Digital, Default)]
+enum Mode {
+    #[default]
+    Idle,
+    Busy = 3,
+    Error = -1,
+}
+
+#[derive(PartialEq, Debug, Digital)]
+struct In {
+    cmd: Cmd,
+    input: b8,
+}
+
+#[derive(PartialEq, Debug, Digital)]
+struct Out {
+    mode: Mode,
+    data: Option<b8>,
+    flags: [bool; 2],
+}
+
+#[kernel]
+fn decode(_cr: ClockReset, i: In) -> Out {
+    let data = match i.cmd {
+        Cmd::Nop => None,
+        Cmd::Read(_addr) => Some(i.input),
+        Cmd::Write { addr: _, data } => Some(data),
+    };
+    let mode = if i.input == b8(0) {
+        Mode::Idle
+    } else {
+        Mode::Busy
+    };
+    Out {
+        mode,
+        data,
+        flags: [i.input == b8(0), i.input == b8(1)],
+    }
+}
+
+#[test]
+fn test_systemverilog_typed_ports() -> miette::Result<()> {
+    let uut = Func::try_new::<decode>()?;
+    let expect = expect_test::expect_file!["systemverilog.sv.expect"];
+    expect.assert_eq(&systemverilog::synchronous(&uut, "top")?);
+    Ok(())
+}
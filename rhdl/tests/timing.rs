@@ -0,0 +1,91 @@
+use rhdl::core::circuit::async_func::AsyncFunc;
+use rhdl::core::ntl::{graph::GraphMode, spec::OpCode};
+use rhdl::prelude::*;
+
+mod common;
+use common::counter::Counter;
+
+#[kernel]
+fn chain(_cr: ClockReset, (a, b, c): (b8, b8, b8)) -> b8 {
+    let x = a + b;
+    let y = x ^ c;
+    y + a
+}
+
+#[kernel]
+fn wires(_cr: ClockReset, (a, b): (b8, b8)) -> (b8, b8) {
+    (b, a)
+}
+
+#[kernel]
+fn async_chain(i: Signal<(b8, b8, b8), Red>) -> Signal<b8, Red> {
+    let (a, b, c) = i.val();
+    signal((a + b) ^ c)
+}
+
+// Counts each op as one level, whatever its width
+fn ops(op: &OpCode) -> usize {
+    timing::DelayModel::delay(&timing::LogicLevels, op).min(1)
+}
+
+#[test]
+fn test_logic_levels_count_the_ops_on_the_path() -> miette::Result<()> {
+    let uut = Func::try_new::<chain>()?;
+    let path = timing::critical_path(&uut, "top")?;
+    // The adders count one level per bit of their carry chains
+    assert_eq!(path.depth, 8 + 1 + 8);
+    // The path is reported from the start, with the depth at each step
+    // (the wiring in front of the logic is free)
+    let depths = path
+        .elements
+        .iter()
+        .map(|(depth, _)| *depth)
+        .filter(|depth| *depth > 0)
+        .collect::<Vec<_>>();
+    assert_eq!(depths, [8, 9, 17]);
+    let report = format!("{:?}", miette::Report::new(path));
+    assert!(report.contains("x ^ c"));
+    assert!(report.contains("depth 17"));
+    Ok(())
+}
+
+#[test]
+fn test_wiring_has_no_depth() -> miette::Result<()> {
+    let path = timing::critical_path(&Func::try_new::<wires>()?, "top")?;
+    assert_eq!(path.depth, 0);
+    assert!(path.elements.iter().all(|(depth, _)| *depth == 0));
+    Ok(())
+}
+
+#[test]
+fn test_custom_delay_model() -> miette::Result<()> {
+    let uut = Func::try_new::<chain>()?;
+    let path = timing::critical_path_with(&uut, "top", &ops)?;
+    assert_eq!(path.depth, 3);
+    // The same analysis can be run on a netlist directly
+    let ntl = uut.descriptor("top")?.ntl;
+    let path = timing::logic_depth(&ntl, GraphMode::Synchronous, &ops)?;
+    assert_eq!(path.depth, 3);
+    Ok(())
+}
+
+#[test]
+fn test_asynchronous_critical_path() -> miette::Result<()> {
+    let uut = AsyncFunc::new::<async_chain>()?;
+    let path = timing::asynchronous_critical_path(&uut, "top")?;
+    assert_eq!(path.depth, 8 + 1);
+    let path = timing::asynchronous_critical_path_with(&uut, "top", &ops)?;
+    assert_eq!(path.depth, 2);
+    Ok(())
+}
+
+#[test]
+fn test_paths_end_at_registers() -> miette::Result<()> {
+    let uut: Counter<U6> = Counter::default();
+    // The adder of the counter, then the enable and reset muxes
+    let path = timing::critical_path(&uut, "top")?;
+    assert_eq!(path.depth, 6 + 2);
+    let path = timing::critical_path_with(&uut, "top", &ops)?;
+    assert_eq!(path.depth, 3);
+    Ok(())
+}
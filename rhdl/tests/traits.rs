@@ -0,0 +1,100 @@
+#![allow(unused_variables)]
+#![allow(unused_assignments)]
+#![allow(unused_mut)]
+#![allow(unreachable_code)]
+#![allow(unused_must_use)]
+#![allow(dead_code)]
+
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+#[cfg(test)]
+use common::*;
+use rhdl::core::sim::testbench::kernel::test_kernel_vm_and_verilog_synchronous;
+
+#[kernel]
+pub trait MyArith: Digital {
+    fn mul(a: Self, b: Self) -> Self;
+    fn mac(self, b: Self, c: Self) -> Self;
+}
+
+#[kernel]
+impl<N: BitWidth> MyArith for Bits<N> {
+    fn mul(a: Self, b: Self) -> Self {
+        a * b
+    }
+    fn mac(self, b: Self, c: Self) -> Self {
+        self + Self::mul(b, c)
+    }
+}
+
+#[kernel]
+impl<N: BitWidth> MyArith for SignedBits<N> {
+    fn mul(a: Self, b: Self) -> Self {
+        a * b
+    }
+    fn mac(self, b: Self, c: Self) -> Self {
+        self + Self::mul(b, c)
+    }
+}
+
+#[kernel]
+pub fn filter<T: MyArith>(a: T, b: T) -> T {
+    let y = T::mul(a, b);
+    let z = <T as MyArith>::mul(y, a);
+    z.mac(a, b)
+}
+
+#[test]
+fn test_trait_method_on_bits() -> miette::Result<()> {
+    test_kernel_vm_and_verilog_synchronous::<filter<b4>, _, _, _>(
+        filter::<b4>,
+        exhaustive::<U4>()
+            .into_iter()
+            .flat_map(|a| exhaustive::<U4>().into_iter().map(move |b| (a, b))),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_trait_method_on_signed_bits() -> miette::Result<()> {
+    test_kernel_vm_and_verilog_synchronous::<filter<s4>, _, _, _>(
+        filter::<s4>,
+        exhaustive_signed::<U4>()
+            .into_iter()
+            .flat_map(|a| exhaustive_signed::<U4>().into_iter().map(move |b| (a, b))),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_trait_method_in_struct_impl() -> miette::Result<()> {
+    #[derive(PartialEq, Debug, Digital)]
+    pub struct Acc<T: MyArith> {
+        sum: T,
+    }
+
+    #[kernel]
+    impl<T: MyArith> Acc<T> {
+        fn step(self, a: T, b: T) -> Self {
+            Self {
+                sum: self.sum.mac(a, b),
+            }
+        }
+    }
+
+    #[kernel]
+    fn foo(a: s4, b: s4) -> s4 {
+        let acc = Acc::<s4> { sum: a };
+        acc.step(a, b).step(b, a).sum
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        exhaustive_signed::<U4>()
+            .into_iter()
+            .flat_map(|a| exhaustive_signed::<U4>().into_iter().map(move |b| (a, b))),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use expect_test::expect_file;
+use rhdl::prelude::*;
+
+mod common;
+use common::counter::Counter;
+
+#[kernel]
+fn datapath(_cr: ClockReset, i: (b8, b8, b8)) -> b8 {
+    let (a, b, c) = i;
+    let x = a + b;
+    let y = x ^ c;
+    let z = y + a;
+    let w = z & b;
+    w - c
+}
+
+#[kernel]
+fn shifts(_cr: ClockReset, (a, b, n): (b8, s8, b40)) -> (b8, s8) {
+    (a << n, b >> n)
+}
+
+// Analyze the VHDL with ghdl, if it is installed
+fn ghdl_check(vhdl: &str) -> miette::Result<()> {
+    let dir = tempfile::tempdir().map_err(RHDLError::from)?;
+    std::fs::write(dir.path().join("top.vhd"), vhdl).map_err(RHDLError::from)?;
+    let output = std::process::Command::new("ghdl")
+        .args(["-a", "--std=08", "top.vhd"])
+        .current_dir(dir.path())
+        .output();
+    match output {
+        Ok(output) => assert!(
+            output.status.success(),
+            "ghdl failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            eprintln!("ghdl is not installed, so the VHDL was not analyzed");
+        }
+        Err(err) => return Err(RHDLError::from(err).into()),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_pipelined_vhdl() -> miette::Result<()> {
+    let uut = Pipelined::<(b8, b8, b8), b8, U1>::try_new::<datapath>()?;
+    let vhdl = uut.hdl("top")?.as_module().as_vhdl()?;
+    let expect = expect_file!["pipelined.vhd.expect"];
+    expect.assert_eq(&vhdl);
+    ghdl_check(&vhdl)
+}
+
+#[test]
+fn test_counter_vhdl() -> miette::Result<()> {
+    let uut: Counter<U6> = Counter::default();
+    let vhdl = uut.hdl("top")?.as_module().as_vhdl()?;
+    let expect = expect_file!["counter.vhd.expect"];
+    expect.assert_eq(&vhdl);
+    ghdl_check(&vhdl)
+}
+
+#[test]
+fn test_shift_amounts_are_clamped() -> miette::Result<()> {
+    let uut = Func::try_new::<shifts>()?;
+    let vhdl = uut.hdl("top")?.as_module().as_vhdl()?;
+    // The 40 bit amount would overflow `to_integer`
+    assert!(vhdl.contains("rhdl_shl("));
+    assert!(vhdl.contains("rhdl_shr("));
+    assert!(!vhdl.contains("shift_left(or"));
+    ghdl_check(&vhdl)
+}
+
+#[test]
+fn test_vhdl_rejects_verilog_statements() -> miette::Result<()> {
+    use rhdl::core::hdl::ast::{dump_file, initial};
+    let uut = Pipelined::<(b8, b8, b8), b8, U1>::try_new::<datapath>()?;
+    let mut module = uut.hdl("top")?.as_module();
+    module.statements.push(initial(vec![dump_file("top.vcd")]));
+    let err = module.as_vhdl().unwrap_err();
+    assert!(err.to_string().contains("$dumpfile"));
+    Ok(())
+}
@@ -0,0 +1,140 @@
+#![allow(unused_variables)]
+#![allow(unused_assignments)]
+#![allow(unused_mut)]
+#![allow(unreachable_code)]
+#![allow(unused_must_use)]
+#![allow(dead_code)]
+
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+use rhdl::core::sim::testbench::kernel::test_kernel_vm_and_verilog_synchronous;
+
+fn wide_samples() -> Vec<WideBits<256>> {
+    let seeds = [
+        0_u128,
+        1,
+        0xDEAD_BEEF,
+        u128::MAX,
+        0x8000_0000_0000_0000_0000_0000_0000_0001,
+    ];
+    seeds
+        .iter()
+        .flat_map(|&x| {
+            let x: WideBits<256> = wide_bits(x);
+            [x, x << 100_u128, x << 128_u128, !x]
+        })
+        .collect()
+}
+
+#[test]
+fn test_wide_arithmetic() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: WideBits<256>, b: WideBits<256>) -> (WideBits<256>, WideBits<256>, bool, bool) {
+        let c = a + b - 1;
+        let d = (a ^ b) & !(b | wide_bits(0xFF));
+        (c, d, a > b, a == b)
+    }
+
+    let samples = wide_samples();
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        samples
+            .iter()
+            .flat_map(|&a| samples.iter().map(move |&b| (a, b))),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_wide_shifts() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: WideBits<256>, n: b8) -> (WideBits<256>, WideBits<256>, SignedWideBits<256>) {
+        (a << n, a >> n, a.as_signed() >> n)
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        wide_samples()
+            .into_iter()
+            .flat_map(|a| [0, 1, 7, 64, 127, 128, 200, 255].map(|n| (a, b8(n)))),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_wide_and_narrow_conversions() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: b64, b: s32) -> (b64, s32, WideBits<200>, SignedWideBits<256>) {
+        let w = a.wide::<256>() << 150;
+        let s: SignedWideBits<256> = b.wide();
+        let s = s - wide_signed(5);
+        (
+            (w >> 150).narrow::<U64>(),
+            s.narrow::<U32>(),
+            w.resize::<200>(),
+            s * s,
+        )
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        [0, 1, 0xDEAD_BEEF_CAFE_BABE, u64::MAX as u128]
+            .into_iter()
+            .flat_map(|a| [0, 1, -1, i32::MIN as i128, i32::MAX as i128].map(|b| (b64(a), s32(b)))),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_wide_signed_compare() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: SignedWideBits<256>, b: SignedWideBits<256>) -> (bool, bool, SignedWideBits<256>) {
+        (a < b, a >= b, -a)
+    }
+
+    let samples = wide_samples()
+        .into_iter()
+        .map(|x| x.as_signed())
+        .collect::<Vec<_>>();
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        samples
+            .iter()
+            .flat_map(|&a| samples.iter().map(move |&b| (a, b))),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_wide_struct_register() -> miette::Result<()> {
+    #[derive(PartialEq, Debug, Digital, Default)]
+    pub struct Hash {
+        state: WideBits<512>,
+        count: b8,
+    }
+
+    #[kernel]
+    fn foo(h: Hash, word: b32) -> Hash {
+        let state = (h.state << 32) | word.wide::<512>();
+        Hash {
+            state: state ^ (state >> 480),
+            count: h.count + 1,
+        }
+    }
+
+    test_kernel_vm_and_verilog_synchronous::<foo, _, _, _>(
+        foo,
+        [0, 1, 0xFFFF_FFFF, 0x1234_5678].into_iter().map(|w| {
+            (
+                Hash {
+                    state: wide_bits::<512>(u128::MAX) << 400_u128,
+                    count: b8(w & 0xFF),
+                },
+                b32(w),
+            )
+        }),
+    )?;
+    Ok(())
+}
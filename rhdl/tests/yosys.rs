@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use rhdl::core::ntl::spec::OpCode;
+use rhdl::prelude::*;
+use serde_json::Value;
+
+mod common;
+use common::{counter::Counter, to_bools};
+
+#[kernel]
+fn datapath(_cr: ClockReset, i: (b8, b8, b8)) -> b8 {
+    let (a, b, c) = i;
+    let x = a + b;
+    let y = x ^ c;
+    let z = y + a;
+    let w = z & b;
+    w - c
+}
+
+#[kernel]
+fn choose(_cr: ClockReset, (sel, x, y, z): (b2, bool, bool, bool)) -> bool {
+    match sel.raw() {
+        0 => x,
+        1 => y,
+        _ => z,
+    }
+}
+
+fn parse(json: &str) -> Value {
+    let json: Value = serde_json::from_str(json).unwrap();
+    json["modules"]["top"].clone()
+}
+
+fn port_bits(top: &Value, port: &str) -> Vec<Value> {
+    top["ports"][port]["bits"].as_array().unwrap().clone()
+}
+
+// Evaluate a module made of single bit `$eq`, `$mux` and `$pmux` cells
+// (and the gates), given the values of its input ports.
+fn eval_json(top: &Value, inputs: &[(&str, Vec<bool>)]) -> Vec<bool> {
+    let mut nets: HashMap<u64, bool> = HashMap::new();
+    for (port, bits) in inputs {
+        for (net, bit) in port_bits(top, port).iter().zip(bits) {
+            nets.insert(net.as_u64().unwrap(), *bit);
+        }
+    }
+    let value = |nets: &HashMap<u64, bool>, net: &Value| match net {
+        Value::String(bit) => Some(bit == "1"),
+        _ => nets.get(&net.as_u64().unwrap()).copied(),
+    };
+    let cells = top["cells"].as_object().unwrap();
+    // Evaluate the cells whose inputs are known until nothing changes
+    loop {
+        let known = nets.len();
+        for cell in cells.values() {
+            let conn = &cell["connections"];
+            let port = |name: &str| -> Option<Vec<bool>> {
+                conn[name]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|net| value(&nets, net))
+                    .collect()
+            };
+            let y = match cell["type"].as_str().unwrap() {
+                "$eq" => port("A").zip(port("B")).map(|(a, b)| vec![a == b]),
+                "$mux" => port("S").zip(port("A").zip(port("B"))).map(
+                    |(s, (a, b))| {
+                        if s[0] { b } else { a }
+                    },
+                ),
+                "$pmux" => port("S").zip(port("A").zip(port("B"))).map(|(s, (a, b))| {
+                    let width = a.len();
+                    let hot = (0..s.len()).filter(|ndx| s[*ndx]).collect::<Vec<_>>();
+                    match hot[..] {
+                        [] => a,
+                        [ndx] => b[ndx * width..][..width].to_vec(),
+                        _ => panic!("more than one select of a $pmux is set"),
+                    }
+                }),
+                "$and" => port("A").zip(port("B")).map(|(a, b)| vec![a[0] & b[0]]),
+                "$or" => port("A").zip(port("B")).map(|(a, b)| vec![a[0] | b[0]]),
+                "$xor" => port("A").zip(port("B")).map(|(a, b)| vec![a[0] ^ b[0]]),
+                "$not" => port("A").map(|a| vec![!a[0]]),
+                kind => panic!("cannot evaluate a {kind} cell"),
+            };
+            for (net, bit) in conn["Y"].as_array().unwrap().iter().zip(y.iter().flatten()) {
+                nets.insert(net.as_u64().unwrap(), *bit);
+            }
+        }
+        if nets.len() == known {
+            break;
+        }
+    }
+    port_bits(top, "out")
+        .iter()
+        .map(|net| value(&nets, net).expect("output is not driven"))
+        .collect()
+}
+
+#[test]
+fn test_pipelined_yosys_json() -> miette::Result<()> {
+    let uut = Pipelined::<(b8, b8, b8), b8, U1>::try_new::<datapath>()?;
+    let json = rhdl::core::ntl::yosys::netlist_json("top", &uut.descriptor("top")?.ntl);
+    let top = parse(&json);
+    let cells = top["cells"].as_object().unwrap();
+    let count = |kind: &str| cells.values().filter(|cell| cell["type"] == kind).count();
+    assert_eq!(count("$dff"), 1);
+    assert_eq!(count("$add"), 2);
+    assert_eq!(count("$sub"), 1);
+    // Every net that is read is driven by an input port or a cell
+    let mut driven = port_bits(&top, "arg_1");
+    driven.extend(port_bits(&top, "arg_0"));
+    for cell in cells.values() {
+        for (port, dir) in cell["port_directions"].as_object().unwrap() {
+            if dir == "output" {
+                driven.extend(cell["connections"][port].as_array().unwrap().clone());
+            }
+        }
+    }
+    let read = cells
+        .values()
+        .flat_map(|cell| {
+            let dirs = cell["port_directions"].as_object().unwrap();
+            dirs.iter()
+                .filter(|(_, dir)| *dir == "input")
+                .flat_map(|(port, _)| cell["connections"][port].as_array().unwrap().clone())
+                .collect::<Vec<_>>()
+        })
+        .chain(port_bits(&top, "out"));
+    for bit in read.filter(|bit| bit.is_number()) {
+        assert!(driven.contains(&bit), "net {bit} is not driven");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_registers_with_reset_are_sdffs() -> miette::Result<()> {
+    let uut: Counter<U6> = Counter::default();
+    let json = rhdl::core::ntl::yosys::netlist_json("top", &uut.descriptor("top")?.ntl);
+    assert!(json.contains(r#""type": "$sdff""#));
+    assert!(json.contains(r#""SRST_VALUE": "000000""#));
+    assert!(json.contains(r#""type": "$add""#));
+    Ok(())
+}
+
+#[test]
+fn test_case_takes_the_first_match() -> miette::Result<()> {
+    let uut = Func::try_new::<choose>()?;
+    let mut ntl = uut.descriptor("top")?.ntl;
+    // Repeat the first pattern of the case with the value of the second
+    // entry.  It must never be selected, since the first entry wins.
+    let case = ntl
+        .ops
+        .iter_mut()
+        .find_map(|lop| match &mut lop.op {
+            OpCode::Case(case) => Some(case),
+            _ => None,
+        })
+        .unwrap();
+    let repeat = (case.entries[0].0.clone(), case.entries[1].1);
+    case.entries.insert(2, repeat);
+    let top = parse(&rhdl::core::ntl::yosys::netlist_json("top", &ntl));
+    let cr = clock_reset(clock(false), reset(false));
+    for (sel, x, y, z) in itertools::iproduct!(0..4, [false, true], [false, true], [false, true]) {
+        let input = (b2(sel), x, y, z);
+        let output = eval_json(
+            &top,
+            &[
+                ("arg_0", to_bools(cr.bin())),
+                ("arg_1", to_bools(input.bin())),
+            ],
+        );
+        assert_eq!(output, to_bools(choose(cr, input).bin()), "{input:?}");
+    }
+    Ok(())
+}